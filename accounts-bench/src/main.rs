@@ -10,11 +10,90 @@ use {
         accounts_db::AccountShrinkThreshold,
         accounts_index::AccountSecondaryIndexes,
         ancestors::Ancestors,
+        append_vec::{AppendVec, StoredMeta},
+    },
+    paychains_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        genesis_config::ClusterType,
+        hash::Hash,
+        pubkey::Pubkey,
+    },
+    std::{
+        env, fs,
+        path::{Path, PathBuf},
     },
-    paychains_sdk::{genesis_config::ClusterType, pubkey::Pubkey},
-    std::{env, fs, path::PathBuf},
 };
 
+/// Write `num_accounts` accounts with `data_size` bytes of data each into an uncompressed and a
+/// compressed AppendVec, then report the size of each and the time to read all accounts back.
+fn bench_compression(path: &Path, num_accounts: usize, data_size: usize, iterations: usize) {
+    fs::create_dir_all(path).unwrap();
+    let accounts: Vec<_> = (0..num_accounts)
+        .map(|i| {
+            // partially compressible, like most account data
+            let data: Vec<u8> = (0..data_size)
+                .map(|j| if j % 4 == 0 { (i * j % 251) as u8 } else { 0 })
+                .collect();
+            let mut account = AccountSharedData::new(i as u64 + 1, 0, &Pubkey::default());
+            account.set_data(data);
+            let stored_meta = StoredMeta {
+                write_version: i as u64,
+                pubkey: paychains_sdk::pubkey::new_rand(),
+                data_len: data_size as u64,
+            };
+            (stored_meta, account)
+        })
+        .collect();
+    let to_store: Vec<_> = accounts
+        .iter()
+        .map(|(stored_meta, account)| (stored_meta.clone(), Some(account)))
+        .collect();
+    let hashes = vec![Hash::default(); num_accounts];
+    let size = num_accounts * (data_size + 256) + 4096;
+
+    let mut uncompressed_len = 0;
+    for compressed in [false, true] {
+        let file = path.join(format!("compression-{}", compressed));
+        let append_vec = if compressed {
+            AppendVec::new_compressed(&file, size)
+        } else {
+            AppendVec::new(&file, true, size)
+        };
+        let mut write_time = Measure::start("write");
+        let offsets = append_vec.append_accounts(&to_store, &hashes);
+        write_time.stop();
+        assert_eq!(offsets.len(), num_accounts + 1);
+
+        let mut read_us = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let mut read_time = Measure::start("read");
+            let loaded: usize = append_vec
+                .accounts(0)
+                .iter()
+                .map(|account| account.clone_account().data().len())
+                .sum();
+            read_time.stop();
+            assert_eq!(loaded, num_accounts * data_size);
+            read_us.push(read_time.as_us());
+        }
+        read_us.sort_unstable();
+
+        let len = append_vec.len();
+        if !compressed {
+            uncompressed_len = len;
+        }
+        println!(
+            "compressed: {}, len: {}, ratio: {:.3}, write: {}, read median(us): {}, read max(us): {}",
+            compressed,
+            len,
+            uncompressed_len as f64 / len as f64,
+            write_time,
+            read_us[read_us.len() / 2],
+            read_us[read_us.len() - 1],
+        );
+    }
+}
+
 fn main() {
     paychains_logger::setup();
 
@@ -48,6 +127,19 @@ fn main() {
                 .takes_value(false)
                 .help("Run clean"),
         )
+        .arg(
+            Arg::with_name("compression")
+                .long("compression")
+                .takes_value(false)
+                .help("Compare the size and read latency of compressed and uncompressed storages"),
+        )
+        .arg(
+            Arg::with_name("data_size")
+                .long("data_size")
+                .takes_value(true)
+                .value_name("BYTES")
+                .help("Data size of each account, used with --compression"),
+        )
         .get_matches();
 
     let num_slots = value_t!(matches, "num_slots", usize).unwrap_or(4);
@@ -62,6 +154,11 @@ fn main() {
     if fs::remove_dir_all(path.clone()).is_err() {
         println!("Warning: Couldn't remove {:?}", path);
     }
+    if matches.is_present("compression") {
+        let data_size = value_t!(matches, "data_size", usize).unwrap_or(1024);
+        bench_compression(&path, num_accounts, data_size, iterations.max(1));
+        return;
+    }
     let accounts = Accounts::new_with_config_for_benches(
         vec![path],
         &ClusterType::Testnet,
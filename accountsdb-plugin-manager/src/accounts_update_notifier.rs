@@ -119,7 +119,7 @@ impl AccountsUpdateNotifierImpl {
             owner: stored_account_meta.account_meta.owner.as_ref(),
             executable: stored_account_meta.account_meta.executable,
             rent_epoch: stored_account_meta.account_meta.rent_epoch,
-            data: stored_account_meta.data,
+            data: &stored_account_meta.data,
             write_version: stored_account_meta.meta.write_version,
         })
     }
@@ -54,7 +54,11 @@ Create TYPE "TransactionErrorCode" AS ENUM (
     'InvalidAddressLookupTableOwner',
     'InvalidAddressLookupTableData',
     'InvalidAddressLookupTableIndex',
-    'InvalidRentPayingAccount'
+    'InvalidRentPayingAccount',
+    'MaxAccountsDataAllocationsExceeded',
+    'WouldExceedAccountDataBlockLimit',
+    'InsufficientFundsForRent',
+    'ProgramExecutionTemporarilyRestricted'
 );
 
 CREATE TYPE "TransactionError" AS (
@@ -337,6 +337,10 @@ pub enum DbTransactionErrorCode {
     InvalidAddressLookupTableData,
     InvalidAddressLookupTableIndex,
     InvalidRentPayingAccount,
+    MaxAccountsDataAllocationsExceeded,
+    WouldExceedAccountDataBlockLimit,
+    InsufficientFundsForRent,
+    ProgramExecutionTemporarilyRestricted,
 }
 
 impl From<&TransactionError> for DbTransactionErrorCode {
@@ -378,6 +382,16 @@ impl From<&TransactionError> for DbTransactionErrorCode {
                 Self::InvalidAddressLookupTableIndex
             }
             TransactionError::InvalidRentPayingAccount => Self::InvalidRentPayingAccount,
+            TransactionError::MaxAccountsDataAllocationsExceeded => {
+                Self::MaxAccountsDataAllocationsExceeded
+            }
+            TransactionError::WouldExceedAccountDataBlockLimit => {
+                Self::WouldExceedAccountDataBlockLimit
+            }
+            TransactionError::InsufficientFundsForRent { .. } => Self::InsufficientFundsForRent,
+            TransactionError::ProgramExecutionTemporarilyRestricted { .. } => {
+                Self::ProgramExecutionTemporarilyRestricted
+            }
         }
     }
 }
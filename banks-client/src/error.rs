@@ -1,4 +1,5 @@
 use {
+    paychains_banks_interface::BanksRequestError,
     paychains_sdk::{transaction::TransactionError, transport::TransportError},
     std::io,
     tarpc::client::RpcError,
@@ -17,6 +18,9 @@ pub enum BanksClientError {
     #[error(transparent)]
     RpcError(#[from] RpcError),
 
+    #[error(transparent)]
+    RequestError(#[from] BanksRequestError),
+
     #[error("transport transaction error: {0}")]
     TransactionError(#[from] TransactionError),
 
@@ -44,6 +48,9 @@ impl From<BanksClientError> for io::Error {
             BanksClientError::ClientError(err) => Self::new(io::ErrorKind::Other, err.to_string()),
             BanksClientError::Io(err) => err,
             BanksClientError::RpcError(err) => Self::new(io::ErrorKind::Other, err.to_string()),
+            BanksClientError::RequestError(err) => {
+                Self::new(io::ErrorKind::InvalidInput, err.to_string())
+            }
             BanksClientError::TransactionError(err) => {
                 Self::new(io::ErrorKind::Other, err.to_string())
             }
@@ -66,6 +73,9 @@ impl From<BanksClientError> for TransportError {
             BanksClientError::RpcError(err) => {
                 Self::IoError(io::Error::new(io::ErrorKind::Other, err.to_string()))
             }
+            BanksClientError::RequestError(err) => {
+                Self::IoError(io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))
+            }
             BanksClientError::TransactionError(err) => Self::TransactionError(err),
             BanksClientError::SimulationError { err, .. } => Self::TransactionError(err),
         }
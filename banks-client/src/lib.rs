@@ -5,12 +5,17 @@
 //! but they are undocumented, may change over time, and are generally more
 //! cumbersome to use.
 
-pub use paychains_banks_interface::{BanksClient as TarpcClient, TransactionStatus};
+pub use paychains_banks_interface::{
+    BanksClient as TarpcClient, BanksRequestError, BanksTransactionResultWithMetadata,
+    BanksTransactionResultWithSimulation, InnerInstruction, TransactionMetadata,
+    TransactionSimulationDetails, TransactionStatus, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+    MAX_MULTIPLE_ACCOUNTS,
+};
 use {
     crate::error::BanksClientError,
     borsh::BorshDeserialize,
     futures::{future::join_all, Future, FutureExt, TryFutureExt},
-    paychains_banks_interface::{BanksRequest, BanksResponse, BanksTransactionResultWithSimulation},
+    paychains_banks_interface::{BanksRequest, BanksResponse},
     paychains_program::{
         clock::Slot, fee_calculator::FeeCalculator, hash::Hash, program_pack::Pack, pubkey::Pubkey,
         rent::Rent, sysvar::Sysvar,
@@ -147,6 +152,54 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub fn process_transaction_with_metadata_and_context(
+        &mut self,
+        ctx: Context,
+        transaction: Transaction,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithMetadata, BanksClientError>> + '_
+    {
+        self.inner
+            .process_transaction_with_metadata_and_context(ctx, transaction)
+            .map(|result| {
+                result?.ok_or(BanksClientError::ClientError(
+                    "processing transactions with metadata is only supported by local servers",
+                ))
+            })
+    }
+
+    pub fn simulate_transaction_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        transaction: Transaction,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulation, BanksClientError>> + '_
+    {
+        self.inner
+            .simulate_transaction_with_commitment_and_context(ctx, transaction, commitment)
+            .map_err(Into::into)
+    }
+
+    pub fn get_multiple_accounts_with_commitment_and_context(
+        &mut self,
+        ctx: Context,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Vec<Option<Account>>, BanksClientError>> + '_ {
+        self.inner
+            .get_multiple_accounts_with_commitment_and_context(ctx, addresses, commitment)
+            .map(|result| Ok(result??))
+    }
+
+    pub fn get_transaction_statuses_with_context(
+        &mut self,
+        ctx: Context,
+        signatures: Vec<Signature>,
+    ) -> impl Future<Output = Result<Vec<Option<TransactionStatus>>, BanksClientError>> + '_ {
+        self.inner
+            .get_transaction_statuses_with_context(ctx, signatures)
+            .map(|result| Ok(result??))
+    }
+
     /// Send a transaction and return immediately. The server will resend the
     /// transaction until either it is accepted by the cluster or the transaction's
     /// blockhash expires.
@@ -273,6 +326,47 @@ impl BanksClient {
         self.process_transaction_with_commitment(transaction, CommitmentLevel::default())
     }
 
+    /// Process a transaction in the working bank and return its result along with the logs,
+    /// cross-program invocations, compute units consumed and return data it produced.
+    /// Only servers started with `start_local_server` support this; others return an error.
+    pub fn process_transaction_with_metadata(
+        &mut self,
+        transaction: Transaction,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithMetadata, BanksClientError>> + '_
+    {
+        let mut ctx = context::current();
+        ctx.deadline += Duration::from_secs(50);
+        self.process_transaction_with_metadata_and_context(ctx, transaction)
+    }
+
+    /// Simulate a transaction against the bank at the slot corresponding to the given
+    /// commitment level, without committing it. The result includes the logs and compute
+    /// units consumed by the simulation. Signatures are not verified, so unsigned or
+    /// partially-signed transactions can be simulated; a successful simulation does not
+    /// imply the transaction would be accepted.
+    pub fn simulate_transaction_with_commitment(
+        &mut self,
+        transaction: Transaction,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulation, BanksClientError>> + '_
+    {
+        self.simulate_transaction_with_commitment_and_context(
+            context::current(),
+            transaction,
+            commitment,
+        )
+    }
+
+    /// Simulate a transaction against the bank at the time of the most recent root slot,
+    /// without committing it. Signatures are not verified.
+    pub fn simulate_transaction(
+        &mut self,
+        transaction: Transaction,
+    ) -> impl Future<Output = Result<BanksTransactionResultWithSimulation, BanksClientError>> + '_
+    {
+        self.simulate_transaction_with_commitment(transaction, CommitmentLevel::default())
+    }
+
     pub async fn process_transactions_with_commitment(
         &mut self,
         transactions: Vec<Transaction>,
@@ -330,6 +424,29 @@ impl BanksClient {
         self.get_account_with_commitment(address, CommitmentLevel::default())
     }
 
+    /// Return the accounts at the given addresses at the slot corresponding to the given
+    /// commitment level. Accounts that are not found are returned as None.
+    pub fn get_multiple_accounts_with_commitment(
+        &mut self,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> impl Future<Output = Result<Vec<Option<Account>>, BanksClientError>> + '_ {
+        self.get_multiple_accounts_with_commitment_and_context(
+            context::current(),
+            addresses,
+            commitment,
+        )
+    }
+
+    /// Return the accounts at the given addresses at the time of the most recent root slot.
+    /// Accounts that are not found are returned as None.
+    pub fn get_multiple_accounts(
+        &mut self,
+        addresses: Vec<Pubkey>,
+    ) -> impl Future<Output = Result<Vec<Option<Account>>, BanksClientError>> + '_ {
+        self.get_multiple_accounts_with_commitment(addresses, CommitmentLevel::default())
+    }
+
     /// Return the unpacked account data at the given address
     /// If the account is not found, an error is returned
     pub fn get_packed_account_data<T: Pack>(
@@ -387,25 +504,12 @@ impl BanksClient {
         self.get_transaction_status_with_context(context::current(), signature)
     }
 
-    /// Same as get_transaction_status, but for multiple transactions.
-    pub async fn get_transaction_statuses(
+    /// Same as get_transaction_status, but for multiple transactions, in a single request.
+    pub fn get_transaction_statuses(
         &mut self,
         signatures: Vec<Signature>,
-    ) -> Result<Vec<Option<TransactionStatus>>, BanksClientError> {
-        // tarpc futures oddly hold a mutable reference back to the client so clone the client upfront
-        let mut clients_and_signatures: Vec<_> = signatures
-            .into_iter()
-            .map(|signature| (self.clone(), signature))
-            .collect();
-
-        let futs = clients_and_signatures
-            .iter_mut()
-            .map(|(client, signature)| client.get_transaction_status(*signature));
-
-        let statuses = join_all(futs).await;
-
-        // Convert Vec<Result<_, _>> to Result<Vec<_>>
-        statuses.into_iter().collect()
+    ) -> impl Future<Output = Result<Vec<Option<TransactionStatus>>, BanksClientError>> + '_ {
+        self.get_transaction_statuses_with_context(context::current(), signatures)
     }
 
     pub fn get_latest_blockhash(
@@ -474,7 +578,9 @@ mod tests {
             bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache,
             genesis_utils::create_genesis_config,
         },
-        paychains_sdk::{message::Message, signature::Signer, system_instruction},
+        paychains_sdk::{
+            message::Message, signature::Signer, system_instruction, transaction::TransactionError,
+        },
         std::sync::{Arc, RwLock},
         tarpc::transport,
         tokio::{runtime::Runtime, time::sleep},
@@ -566,4 +672,139 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_banks_server_transfer_with_metadata() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+
+        let bob_pubkey = paychains_sdk::pubkey::new_rand();
+        let mint_pubkey = genesis.mint_keypair.pubkey();
+        let instruction = system_instruction::transfer(&mint_pubkey, &bob_pubkey, 1);
+        let message = Message::new(&[instruction], Some(&mint_pubkey));
+
+        Runtime::new()?.block_on(async {
+            let client_transport =
+                start_local_server(bank_forks, block_commitment_cache, Duration::from_millis(1))
+                    .await;
+            let mut banks_client = start_client(client_transport).await?;
+
+            let recent_blockhash = banks_client.get_latest_blockhash().await?;
+            let transaction = Transaction::new(&[&genesis.mint_keypair], message, recent_blockhash);
+            let BanksTransactionResultWithMetadata { result, metadata } = banks_client
+                .process_transaction_with_metadata(transaction.clone())
+                .await?;
+            assert_eq!(result, Ok(()));
+            let metadata = metadata.unwrap();
+            assert_eq!(metadata.inner_instructions, vec![vec![]]);
+            assert_eq!(metadata.return_data, None);
+            assert_eq!(banks_client.get_balance(bob_pubkey).await?, 1);
+
+            // a replayed transaction is rejected without metadata
+            let BanksTransactionResultWithMetadata { result, metadata } = banks_client
+                .process_transaction_with_metadata(transaction)
+                .await?;
+            assert_eq!(result, Err(TransactionError::AlreadyProcessed));
+            assert_eq!(metadata, None);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_banks_server_simulate_and_batch_queries() -> Result<(), BanksClientError> {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+
+        let bob_pubkey = paychains_sdk::pubkey::new_rand();
+        let mint_pubkey = genesis.mint_keypair.pubkey();
+        let instruction = system_instruction::transfer(&mint_pubkey, &bob_pubkey, 1);
+        let message = Message::new(&[instruction], Some(&mint_pubkey));
+
+        Runtime::new()?.block_on(async {
+            let client_transport =
+                start_local_server(bank_forks, block_commitment_cache, Duration::from_millis(1))
+                    .await;
+            let mut banks_client = start_client(client_transport).await?;
+
+            let recent_blockhash = banks_client.get_latest_blockhash().await?;
+            let transaction = Transaction::new(&[&genesis.mint_keypair], message, recent_blockhash);
+            let signature = transaction.signatures[0];
+
+            // simulation does not commit the transfer
+            let BanksTransactionResultWithSimulation {
+                result,
+                simulation_details,
+            } = banks_client
+                .simulate_transaction(transaction.clone())
+                .await?;
+            assert_eq!(result, Some(Ok(())));
+            assert!(simulation_details.is_some());
+            assert_eq!(
+                banks_client
+                    .get_multiple_accounts(vec![mint_pubkey, bob_pubkey])
+                    .await?
+                    .into_iter()
+                    .map(|account| account.is_some())
+                    .collect::<Vec<_>>(),
+                vec![true, false]
+            );
+            assert_eq!(
+                banks_client
+                    .get_transaction_statuses(vec![signature])
+                    .await?,
+                vec![None]
+            );
+
+            banks_client.process_transaction(transaction).await.unwrap();
+            let accounts = banks_client.get_multiple_accounts(vec![bob_pubkey]).await?;
+            assert_eq!(
+                accounts[0].as_ref().map(|account| account.lamports),
+                Some(1)
+            );
+            let statuses = banks_client
+                .get_transaction_statuses(vec![signature, Signature::default()])
+                .await?;
+            assert_eq!(statuses.len(), 2);
+            assert!(statuses[0].as_ref().unwrap().err.is_none());
+            assert_eq!(statuses[1], None);
+
+            // oversized batches are rejected rather than served
+            let err = banks_client
+                .get_multiple_accounts(vec![bob_pubkey; MAX_MULTIPLE_ACCOUNTS + 1])
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                BanksClientError::RequestError(BanksRequestError::TooManyItems {
+                    limit: MAX_MULTIPLE_ACCOUNTS,
+                    ..
+                })
+            ));
+            let err = banks_client
+                .get_transaction_statuses(vec![
+                    signature;
+                    MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS + 1
+                ])
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                BanksClientError::RequestError(BanksRequestError::TooManyItems {
+                    limit: MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
+                    ..
+                })
+            ));
+            Ok(())
+        })
+    }
 }
@@ -12,9 +12,36 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         transaction::{self, Transaction, TransactionError},
+        transaction_context::TransactionReturnData,
     },
+    std::fmt,
 };
 
+/// Maximum number of addresses accepted by `get_multiple_accounts`, same as the RPC limit
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// Maximum number of signatures accepted by `get_transaction_statuses`, same as the RPC limit
+pub const MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS: usize = 256;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BanksRequestError {
+    /// The request asked for more items than the server accepts in one call
+    TooManyItems { requested: usize, limit: usize },
+}
+
+impl fmt::Display for BanksRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BanksRequestError::TooManyItems { requested, limit } => write!(
+                f,
+                "too many items requested: {}, the maximum is {}",
+                requested, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BanksRequestError {}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TransactionConfirmationStatus {
     Processed,
@@ -43,6 +70,33 @@ pub struct BanksTransactionResultWithSimulation {
     pub simulation_details: Option<TransactionSimulationDetails>,
 }
 
+/// An instruction issued through a cross-program invocation
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InnerInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+    /// Invocation stack height, where the transaction's own instructions are at height 1
+    pub stack_height: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionMetadata {
+    pub log_messages: Vec<String>,
+    /// Cross-program invocations, grouped by the transaction instruction that made them
+    pub inner_instructions: Vec<Vec<InnerInstruction>>,
+    pub compute_units_consumed: u64,
+    pub return_data: Option<TransactionReturnData>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BanksTransactionResultWithMetadata {
+    pub result: transaction::Result<()>,
+    pub metadata: Option<TransactionMetadata>,
+}
+
 #[tarpc::service]
 pub trait Banks {
     async fn send_transaction_with_context(transaction: Transaction);
@@ -77,6 +131,20 @@ pub trait Banks {
         commitment: CommitmentLevel,
         message: Message,
     ) -> Option<u64>;
+    async fn process_transaction_with_metadata_and_context(
+        transaction: Transaction,
+    ) -> Option<BanksTransactionResultWithMetadata>;
+    async fn simulate_transaction_with_commitment_and_context(
+        transaction: Transaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithSimulation;
+    async fn get_multiple_accounts_with_commitment_and_context(
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Result<Vec<Option<Account>>, BanksRequestError>;
+    async fn get_transaction_statuses_with_context(
+        signatures: Vec<Signature>,
+    ) -> Result<Vec<Option<TransactionStatus>>, BanksRequestError>;
 }
 
 #[cfg(test)]
@@ -3,11 +3,13 @@ use {
     crossbeam_channel::{unbounded, Receiver, Sender},
     futures::{future, prelude::stream::StreamExt},
     paychains_banks_interface::{
-        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithSimulation,
-        TransactionConfirmationStatus, TransactionSimulationDetails, TransactionStatus,
+        Banks, BanksRequest, BanksRequestError, BanksResponse, BanksTransactionResultWithMetadata,
+        BanksTransactionResultWithSimulation, InnerInstruction, TransactionConfirmationStatus,
+        TransactionMetadata, TransactionSimulationDetails, TransactionStatus,
+        MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_MULTIPLE_ACCOUNTS,
     },
     paychains_runtime::{
-        bank::{Bank, TransactionSimulationResult},
+        bank::{Bank, TransactionExecutionResult, TransactionSimulationResult},
         bank_forks::BankForks,
         commitment::BlockCommitmentCache,
     },
@@ -52,6 +54,9 @@ struct BanksServer {
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     transaction_sender: Sender<TransactionInfo>,
     poll_signature_status_sleep_duration: Duration,
+    /// Whether transactions may be executed directly against the working bank,
+    /// bypassing the leader. Only the in-process server used for testing allows it.
+    allow_direct_execution: bool,
 }
 
 impl BanksServer {
@@ -64,12 +69,14 @@ impl BanksServer {
         block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
         transaction_sender: Sender<TransactionInfo>,
         poll_signature_status_sleep_duration: Duration,
+        allow_direct_execution: bool,
     ) -> Self {
         Self {
             bank_forks,
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            allow_direct_execution,
         }
     }
 
@@ -112,6 +119,7 @@ impl BanksServer {
             block_commitment_cache,
             transaction_sender,
             poll_signature_status_sleep_duration,
+            true,
         )
     }
 
@@ -126,6 +134,38 @@ impl BanksServer {
         self.bank_forks.read().unwrap()[self.slot(commitment)].clone()
     }
 
+    fn transaction_status(&self, signature: &Signature) -> Option<TransactionStatus> {
+        let bank = self.bank(CommitmentLevel::Processed);
+        let (slot, status) = bank.get_signature_status_slot(signature)?;
+        let r_block_commitment_cache = self.block_commitment_cache.read().unwrap();
+
+        let optimistically_confirmed_bank = self.bank(CommitmentLevel::Confirmed);
+        let optimistically_confirmed =
+            optimistically_confirmed_bank.get_signature_status_slot(signature);
+
+        let confirmations = if r_block_commitment_cache.root() >= slot
+            && r_block_commitment_cache.highest_confirmed_root() >= slot
+        {
+            None
+        } else {
+            r_block_commitment_cache
+                .get_confirmation_count(slot)
+                .or(Some(0))
+        };
+        Some(TransactionStatus {
+            slot,
+            confirmations,
+            err: status.err(),
+            confirmation_status: if confirmations.is_none() {
+                Some(TransactionConfirmationStatus::Finalized)
+            } else if optimistically_confirmed.is_some() {
+                Some(TransactionConfirmationStatus::Confirmed)
+            } else {
+                Some(TransactionConfirmationStatus::Processed)
+            },
+        })
+    }
+
     async fn poll_signature_status(
         self,
         signature: &Signature,
@@ -206,35 +246,7 @@ impl Banks for BanksServer {
         _: Context,
         signature: Signature,
     ) -> Option<TransactionStatus> {
-        let bank = self.bank(CommitmentLevel::Processed);
-        let (slot, status) = bank.get_signature_status_slot(&signature)?;
-        let r_block_commitment_cache = self.block_commitment_cache.read().unwrap();
-
-        let optimistically_confirmed_bank = self.bank(CommitmentLevel::Confirmed);
-        let optimistically_confirmed =
-            optimistically_confirmed_bank.get_signature_status_slot(&signature);
-
-        let confirmations = if r_block_commitment_cache.root() >= slot
-            && r_block_commitment_cache.highest_confirmed_root() >= slot
-        {
-            None
-        } else {
-            r_block_commitment_cache
-                .get_confirmation_count(slot)
-                .or(Some(0))
-        };
-        Some(TransactionStatus {
-            slot,
-            confirmations,
-            err: status.err(),
-            confirmation_status: if confirmations.is_none() {
-                Some(TransactionConfirmationStatus::Finalized)
-            } else if optimistically_confirmed.is_some() {
-                Some(TransactionConfirmationStatus::Confirmed)
-            } else {
-                Some(TransactionConfirmationStatus::Processed)
-            },
-        })
+        self.transaction_status(&signature)
     }
 
     async fn get_slot_with_context(self, _: Context, commitment: CommitmentLevel) -> Slot {
@@ -350,6 +362,139 @@ impl Banks for BanksServer {
         let sanitized_message = SanitizedMessage::try_from(message).ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn process_transaction_with_metadata_and_context(
+        self,
+        _: Context,
+        transaction: Transaction,
+    ) -> Option<BanksTransactionResultWithMetadata> {
+        if !self.allow_direct_execution {
+            return None;
+        }
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        if let Err(err) = verify_transaction(&transaction, &bank.feature_set) {
+            return Some(BanksTransactionResultWithMetadata {
+                result: Err(err),
+                metadata: None,
+            });
+        }
+
+        let account_keys = transaction.message.account_keys.clone();
+        let get_account_key = |index: u8| -> Pubkey {
+            account_keys
+                .get(index as usize)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let result = match bank.process_transaction_with_metadata(transaction) {
+            TransactionExecutionResult::NotExecuted(err) => BanksTransactionResultWithMetadata {
+                result: Err(err),
+                metadata: None,
+            },
+            TransactionExecutionResult::Executed(details) => {
+                let inner_instructions = details
+                    .inner_instructions
+                    .unwrap_or_default()
+                    .into_iter()
+                    .zip(details.inner_instruction_stack_heights.unwrap_or_default())
+                    .map(|(instructions, stack_heights)| {
+                        instructions
+                            .into_iter()
+                            .zip(stack_heights)
+                            .map(|(instruction, stack_height)| InnerInstruction {
+                                program_id: get_account_key(instruction.program_id_index),
+                                accounts: instruction
+                                    .accounts
+                                    .iter()
+                                    .map(|index| get_account_key(*index))
+                                    .collect(),
+                                data: instruction.data,
+                                stack_height,
+                            })
+                            .collect()
+                    })
+                    .collect();
+                BanksTransactionResultWithMetadata {
+                    result: details.status,
+                    metadata: Some(TransactionMetadata {
+                        log_messages: details.log_messages.unwrap_or_default(),
+                        inner_instructions,
+                        compute_units_consumed: details.executed_units,
+                        return_data: details.return_data,
+                    }),
+                }
+            }
+        };
+        Some(result)
+    }
+
+    async fn simulate_transaction_with_commitment_and_context(
+        self,
+        _: Context,
+        transaction: Transaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithSimulation {
+        let sanitized_transaction =
+            match SanitizedTransaction::try_from_legacy_transaction(transaction) {
+                Err(err) => {
+                    return BanksTransactionResultWithSimulation {
+                        result: Some(Err(err)),
+                        simulation_details: None,
+                    };
+                }
+                Ok(tx) => tx,
+            };
+        // Like the RPC `simulateTransaction` default, signatures are not verified. The bank at
+        // `commitment` may not be frozen yet, hence the unchecked simulation.
+        let TransactionSimulationResult {
+            result,
+            logs,
+            post_simulation_accounts: _,
+            units_consumed,
+        } = self
+            .bank(commitment)
+            .simulate_transaction_unchecked(sanitized_transaction);
+        BanksTransactionResultWithSimulation {
+            result: Some(result),
+            simulation_details: Some(TransactionSimulationDetails {
+                logs,
+                units_consumed,
+            }),
+        }
+    }
+
+    async fn get_multiple_accounts_with_commitment_and_context(
+        self,
+        _: Context,
+        addresses: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+    ) -> Result<Vec<Option<Account>>, BanksRequestError> {
+        check_request_len(addresses.len(), MAX_MULTIPLE_ACCOUNTS)?;
+        let bank = self.bank(commitment);
+        Ok(addresses
+            .iter()
+            .map(|address| bank.get_account(address).map(Account::from))
+            .collect())
+    }
+
+    async fn get_transaction_statuses_with_context(
+        self,
+        _: Context,
+        signatures: Vec<Signature>,
+    ) -> Result<Vec<Option<TransactionStatus>>, BanksRequestError> {
+        check_request_len(signatures.len(), MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS)?;
+        Ok(signatures
+            .iter()
+            .map(|signature| self.transaction_status(signature))
+            .collect())
+    }
+}
+
+fn check_request_len(requested: usize, limit: usize) -> Result<(), BanksRequestError> {
+    if requested > limit {
+        return Err(BanksRequestError::TooManyItems { requested, limit });
+    }
+    Ok(())
 }
 
 pub async fn start_local_server(
@@ -406,6 +551,7 @@ pub async fn start_tcp_server(
                 block_commitment_cache.clone(),
                 sender,
                 Duration::from_millis(200),
+                false,
             );
             chan.execute(server.serve())
         })
@@ -416,3 +562,70 @@ pub async fn start_tcp_server(
     server.await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        paychains_runtime::genesis_utils::create_genesis_config,
+        paychains_sdk::{signature::Signer, system_transaction},
+        tarpc::context,
+        tokio::runtime::Runtime,
+    };
+
+    #[test]
+    fn test_process_transaction_with_metadata_requires_local_server() {
+        let genesis = create_genesis_config(10);
+        let bank = Bank::new_for_tests(&genesis.genesis_config);
+        let slot = bank.slot();
+        let recent_blockhash = bank.last_blockhash();
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let block_commitment_cache = Arc::new(RwLock::new(
+            BlockCommitmentCache::new_for_tests_with_slots(slot, slot),
+        ));
+        let transaction = system_transaction::transfer(
+            &genesis.mint_keypair,
+            &paychains_sdk::pubkey::new_rand(),
+            1,
+            recent_blockhash,
+        );
+
+        Runtime::new().unwrap().block_on(async {
+            let (transaction_sender, _transaction_receiver) = unbounded();
+            let remote_server = BanksServer::new(
+                bank_forks.clone(),
+                block_commitment_cache.clone(),
+                transaction_sender,
+                Duration::from_millis(1),
+                false,
+            );
+            assert!(remote_server
+                .process_transaction_with_metadata_and_context(
+                    context::current(),
+                    transaction.clone()
+                )
+                .await
+                .is_none());
+            assert_eq!(
+                bank_forks
+                    .read()
+                    .unwrap()
+                    .working_bank()
+                    .get_balance(&genesis.mint_keypair.pubkey()),
+                10
+            );
+
+            let local_server = BanksServer::new_loopback(
+                bank_forks.clone(),
+                block_commitment_cache,
+                Duration::from_millis(1),
+            );
+            let result = local_server
+                .process_transaction_with_metadata_and_context(context::current(), transaction)
+                .await
+                .unwrap();
+            assert_eq!(result.result, Ok(()));
+            assert!(result.metadata.is_some());
+        });
+    }
+}
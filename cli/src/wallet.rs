@@ -9,6 +9,7 @@ use {
         spend_utils::{resolve_spend_tx_and_check_account_balances, SpendAmount},
     },
     clap::{value_t_or_exit, App, Arg, ArgMatches, SubCommand},
+    log::*,
     paychains_account_decoder::{UiAccount, UiAccountEncoding},
     paychains_clap_utils::{
         fee_payer::*,
@@ -632,6 +633,44 @@ pub fn process_create_address_with_seed(
     Ok(address.to_string())
 }
 
+/// Warn if a transfer would leave the recipient with a non-zero balance below its rent-exempt
+/// minimum; such transactions fail once rent-paying accounts are no longer allowed.  The check is
+/// advisory, the transfer goes ahead if it cannot be made.
+fn warn_if_recipient_rent_paying(
+    rpc_client: &RpcClient,
+    recipient: &Pubkey,
+    lamports: u64,
+    commitment: CommitmentConfig,
+) {
+    let balances = rpc_client
+        .get_account_with_commitment(recipient, commitment)
+        .and_then(|response| {
+            let (balance, data_len) = response
+                .value
+                .map(|account| (account.lamports, account.data.len()))
+                .unwrap_or_default();
+            let minimum_balance = rpc_client.get_minimum_balance_for_rent_exemption(data_len)?;
+            Ok((balance.saturating_add(lamports), minimum_balance))
+        });
+    match balances {
+        Ok((post_balance, minimum_balance)) => {
+            if post_balance > 0 && post_balance < minimum_balance {
+                eprintln!(
+                    "Warning: the recipient address ({}) would hold {}, less than the rent-exempt \
+                     minimum of {}; the transfer will fail if rent-paying accounts are not allowed",
+                    recipient,
+                    build_balance_message(post_balance, false, true),
+                    build_balance_message(minimum_balance, false, true),
+                );
+            }
+        }
+        Err(err) => warn!(
+            "Unable to check the rent-exempt minimum of the recipient address ({}): {}",
+            recipient, err
+        ),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn process_transfer(
     rpc_client: &RpcClient,
@@ -710,7 +749,7 @@ pub fn process_transfer(
         }
     };
 
-    let (message, _) = resolve_spend_tx_and_check_account_balances(
+    let (message, lamports) = resolve_spend_tx_and_check_account_balances(
         rpc_client,
         sign_only,
         amount,
@@ -720,6 +759,9 @@ pub fn process_transfer(
         build_message,
         config.commitment,
     )?;
+    if !sign_only {
+        warn_if_recipient_rent_paying(rpc_client, to, lamports, config.commitment);
+    }
     let mut tx = Transaction::new_unsigned(message);
 
     if sign_only {
@@ -15,6 +15,7 @@ use {
         },
         rpc_sender::*,
     },
+    async_trait::async_trait,
     serde_json::{json, Number, Value},
     paychains_account_decoder::{UiAccount, UiAccountEncoding},
     paychains_sdk::{
@@ -87,12 +88,17 @@ impl MockSender {
     }
 }
 
+#[async_trait]
 impl RpcSender for MockSender {
     fn get_transport_stats(&self) -> RpcTransportStats {
         RpcTransportStats::default()
     }
 
-    fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value> {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
         if let Some(value) = self.mocks.write().unwrap().remove(&request) {
             return Ok(value);
         }
@@ -107,6 +113,10 @@ impl RpcSender for MockSender {
                 context: RpcResponseContext { slot: 1 },
                 value: Value::Null,
             })?,
+            "getAccountsDataSize" => serde_json::to_value(Response {
+                context: RpcResponseContext { slot: 1 },
+                value: Value::Number(Number::from(1234)),
+            })?,
             "getBalance" => serde_json::to_value(Response {
                 context: RpcResponseContext { slot: 1 },
                 value: Value::Number(Number::from(50)),
@@ -379,6 +389,7 @@ impl RpcSender for MockSender {
                     meta: None,
                 }],
                 rewards: Rewards::new(),
+                num_partitions: None,
                 block_time: None,
                 block_height: Some(428),
             })?,
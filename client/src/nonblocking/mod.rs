@@ -0,0 +1,3 @@
+pub mod pubsub_client;
+pub mod rpc_client;
+pub mod tpu_client;
@@ -0,0 +1,992 @@
+//! A nonblocking [`PubsubClient`] that multiplexes any number of subscriptions
+//! over a single websocket connection.
+//!
+//! When the connection drops, the client reconnects with exponential backoff and
+//! resubscribes everything that was active. If it was given an [`RpcClient`], it
+//! also replays the slots, roots and signature confirmations that were missed
+//! while disconnected, so `slotSubscribe`, `rootSubscribe` and
+//! `signatureSubscribe` streams don't silently skip state. A subscription that
+//! can't be restored after a reconnect yields an error and then ends.
+
+use {
+    crate::{
+        client_error::Result as ClientResult,
+        nonblocking::rpc_client::RpcClient,
+        rpc_config::{
+            RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
+            RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
+        },
+        rpc_response::{
+            ProcessedSignatureResult, Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount,
+            RpcLogsResponse, RpcResponseContext, RpcSignatureResult, RpcTransactionUpdate, RpcVote,
+            SlotInfo, SlotUpdate,
+        },
+    },
+    futures_util::{
+        future::{ready, BoxFuture, FutureExt},
+        sink::SinkExt,
+        stream::{self, BoxStream, StreamExt},
+    },
+    log::*,
+    serde::de::DeserializeOwned,
+    serde_json::{json, Map, Value},
+    paychains_account_decoder::UiAccount,
+    paychains_sdk::{
+        clock::Slot, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    },
+    std::{
+        collections::{BTreeMap, HashMap},
+        sync::Arc,
+        time::Duration,
+    },
+    thiserror::Error,
+    tokio::{
+        net::TcpStream,
+        sync::{mpsc, oneshot},
+        task::JoinHandle,
+        time::{interval, sleep},
+    },
+    tokio_tungstenite::{
+        connect_async,
+        tungstenite::{
+            protocol::frame::{coding::CloseCode, CloseFrame},
+            Message,
+        },
+        MaybeTlsStream, WebSocketStream,
+    },
+    url::Url,
+};
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+/// Upper bound on the number of slots or roots replayed after a single reconnect
+const MAX_REPLAYED_SLOTS: u64 = 10_000;
+
+pub type PubsubClientResult<T = ()> = Result<T, PubsubClientError>;
+
+#[derive(Debug, Error)]
+pub enum PubsubClientError {
+    #[error("url parse error")]
+    UrlParseError(#[from] url::ParseError),
+
+    #[error("unable to connect to server")]
+    ConnectionError(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("connection closed ({0})")]
+    ConnectionClosed(String),
+
+    #[error("json parse error")]
+    JsonParseError(#[from] serde_json::error::Error),
+
+    #[error("subscribe failed: {reason}")]
+    SubscribeFailed { reason: String, message: String },
+
+    #[error("unexpected message format: {0}")]
+    UnexpectedMessageError(String),
+}
+
+pub type UnsubscribeFn = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+pub type SubscribeResult<'a, T> =
+    PubsubClientResult<(BoxStream<'a, PubsubClientResult<T>>, UnsubscribeFn)>;
+
+/// Reconnect and gap replay settings for [`PubsubClient`]
+#[derive(Clone, Default)]
+pub struct PubsubClientConfig {
+    /// Queried for the state missed while disconnected; nothing is replayed if `None`
+    pub rpc_client: Option<Arc<RpcClient>>,
+    /// Give up after this many consecutive failed reconnects; retry forever if `None`
+    pub max_reconnect_attempts: Option<usize>,
+}
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type NotificationSender = mpsc::UnboundedSender<PubsubClientResult<Value>>;
+type NotificationReceiver = mpsc::UnboundedReceiver<PubsubClientResult<Value>>;
+type SubscribeResponse = PubsubClientResult<(u64, NotificationReceiver)>;
+
+struct SubscribeRequest {
+    operation: &'static str,
+    params: Value,
+    replay: GapReplay,
+    response: oneshot::Sender<SubscribeResponse>,
+}
+
+type UnsubscribeRequest = (u64, oneshot::Sender<()>);
+
+/// State kept per subscription to fill the gap left by a reconnect
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GapReplay {
+    None,
+    Slot {
+        slots: ReplayedSlots,
+    },
+    Root {
+        roots: ReplayedSlots,
+    },
+    Signature {
+        signature: Signature,
+        commitment: CommitmentConfig,
+    },
+}
+
+impl GapReplay {
+    /// Records a notification received from the server. Returns false if the
+    /// notification was already delivered by a replay and should be dropped.
+    fn observe(&mut self, result: &Value) -> bool {
+        match self {
+            GapReplay::Slot { slots } => slots.observe(result.get("slot").and_then(Value::as_u64)),
+            GapReplay::Root { roots } => roots.observe(result.as_u64()),
+            GapReplay::None | GapReplay::Signature { .. } => true,
+        }
+    }
+}
+
+/// The slots or roots notified to a subscription
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ReplayedSlots {
+    /// The highest one notified, a replay starts after it
+    last: Option<Slot>,
+    /// The first and last ones delivered by the replay after the latest reconnect. Live
+    /// notifications between them are duplicates, until the live stream moves past them.
+    replayed: Option<(Slot, Slot)>,
+}
+
+impl ReplayedSlots {
+    /// Records a live notification, returns false if the replay already delivered it.
+    /// Slots are not monotonic across forks, so anything outside of the replay is delivered.
+    fn observe(&mut self, slot: Option<Slot>) -> bool {
+        let slot = match slot {
+            Some(slot) => slot,
+            None => return true,
+        };
+        if let Some((first, last)) = self.replayed {
+            if (first..=last).contains(&slot) {
+                return false;
+            }
+            if slot > last {
+                self.replayed = None;
+            }
+        }
+        self.last = Some(self.last.map_or(slot, |last| last.max(slot)));
+        true
+    }
+
+    /// Records the slots delivered by a replay, oldest first
+    fn replay(&mut self, slots: &[Slot]) {
+        if let (Some(&first), Some(&last)) = (slots.first(), slots.last()) {
+            self.replayed = Some((first, last));
+            self.last = Some(self.last.map_or(last, |previous| previous.max(last)));
+        }
+    }
+}
+
+struct Subscription {
+    operation: &'static str,
+    params: Value,
+    sender: NotificationSender,
+    server_id: Option<u64>,
+    replay: GapReplay,
+    /// Set until the server answers the first subscribe request
+    pending: Option<(oneshot::Sender<SubscribeResponse>, NotificationReceiver)>,
+}
+
+/// A nonblocking pubsub client; see the module documentation
+pub struct PubsubClient {
+    subscribe_sender: mpsc::UnboundedSender<SubscribeRequest>,
+    unsubscribe_sender: mpsc::UnboundedSender<UnsubscribeRequest>,
+    shutdown_sender: oneshot::Sender<()>,
+    ws: JoinHandle<PubsubClientResult>,
+}
+
+impl PubsubClient {
+    pub async fn new(url: &str) -> PubsubClientResult<Self> {
+        Self::new_with_config(url, PubsubClientConfig::default()).await
+    }
+
+    pub async fn new_with_config(
+        url: &str,
+        config: PubsubClientConfig,
+    ) -> PubsubClientResult<Self> {
+        let url = Url::parse(url)?;
+        let (ws, _response) = connect_async(url.clone()).await?;
+
+        let (subscribe_sender, subscribe_receiver) = mpsc::unbounded_channel();
+        let (unsubscribe_sender, unsubscribe_receiver) = mpsc::unbounded_channel();
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+
+        Ok(Self {
+            subscribe_sender,
+            unsubscribe_sender,
+            shutdown_sender,
+            ws: tokio::spawn(Self::run_ws(
+                url,
+                ws,
+                config,
+                subscribe_receiver,
+                unsubscribe_receiver,
+                shutdown_receiver,
+            )),
+        })
+    }
+
+    pub async fn shutdown(self) -> PubsubClientResult {
+        let _ = self.shutdown_sender.send(());
+        self.ws.await.unwrap() // WS future should not be cancelled or panicked
+    }
+
+    async fn subscribe<'a, T>(
+        &self,
+        operation: &'static str,
+        params: Value,
+        replay: GapReplay,
+    ) -> SubscribeResult<'a, T>
+    where
+        T: DeserializeOwned + Send + 'a,
+    {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.subscribe_sender
+            .send(SubscribeRequest {
+                operation,
+                params,
+                replay,
+                response: response_sender,
+            })
+            .map_err(|err| PubsubClientError::ConnectionClosed(err.to_string()))?;
+        let (local_id, notifications) = response_receiver
+            .await
+            .map_err(|err| PubsubClientError::ConnectionClosed(err.to_string()))??;
+
+        let unsubscribe_sender = self.unsubscribe_sender.clone();
+        let unsubscribe: UnsubscribeFn = Box::new(move || {
+            async move {
+                let (done_sender, done_receiver) = oneshot::channel();
+                if unsubscribe_sender.send((local_id, done_sender)).is_ok() {
+                    let _ = done_receiver.await;
+                }
+            }
+            .boxed()
+        });
+
+        let stream = stream::unfold(notifications, |mut notifications| async move {
+            notifications
+                .recv()
+                .await
+                .map(|notification| (notification, notifications))
+        })
+        .filter_map(move |notification| {
+            ready(match notification {
+                Ok(notification) => serde_json::from_value::<T>(notification)
+                    .map_err(|err| warn!("invalid {} notification: {}", operation, err))
+                    .ok()
+                    .map(Ok),
+                Err(err) => Some(Err(err)),
+            })
+        })
+        .boxed();
+
+        Ok((stream, unsubscribe))
+    }
+
+    pub async fn account_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<UiAccount>> {
+        let params = json!([pubkey.to_string(), config]);
+        self.subscribe("account", params, GapReplay::None).await
+    }
+
+    pub async fn block_subscribe(
+        &self,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcBlockUpdate>> {
+        self.subscribe("block", json!([filter, config]), GapReplay::None)
+            .await
+    }
+
+    pub async fn logs_subscribe(
+        &self,
+        filter: RpcTransactionLogsFilter,
+        config: RpcTransactionLogsConfig,
+    ) -> SubscribeResult<'_, RpcResponse<RpcLogsResponse>> {
+        self.subscribe("logs", json!([filter, config]), GapReplay::None)
+            .await
+    }
+
+    pub async fn program_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        config: Option<RpcProgramAccountsConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcKeyedAccount>> {
+        let params = json!([pubkey.to_string(), config]);
+        self.subscribe("program", params, GapReplay::None).await
+    }
+
+    pub async fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcTransactionUpdate>> {
+        self.subscribe("transaction", json!([filter, config]), GapReplay::None)
+            .await
+    }
+
+    pub async fn vote_subscribe(&self) -> SubscribeResult<'_, RpcVote> {
+        self.subscribe("vote", json!([]), GapReplay::None).await
+    }
+
+    pub async fn root_subscribe(&self) -> SubscribeResult<'_, Slot> {
+        let replay = GapReplay::Root {
+            roots: ReplayedSlots::default(),
+        };
+        self.subscribe("root", json!([]), replay).await
+    }
+
+    pub async fn signature_subscribe(
+        &self,
+        signature: &Signature,
+        config: Option<RpcSignatureSubscribeConfig>,
+    ) -> SubscribeResult<'_, RpcResponse<RpcSignatureResult>> {
+        let replay = GapReplay::Signature {
+            signature: *signature,
+            commitment: config
+                .as_ref()
+                .and_then(|config| config.commitment)
+                .unwrap_or_default(),
+        };
+        let params = json!([signature.to_string(), config]);
+        self.subscribe("signature", params, replay).await
+    }
+
+    pub async fn slot_subscribe(&self) -> SubscribeResult<'_, SlotInfo> {
+        let replay = GapReplay::Slot {
+            slots: ReplayedSlots::default(),
+        };
+        self.subscribe("slot", json!([]), replay).await
+    }
+
+    pub async fn slot_updates_subscribe(&self) -> SubscribeResult<'_, SlotUpdate> {
+        self.subscribe("slotsUpdates", json!([]), GapReplay::None)
+            .await
+    }
+
+    async fn run_ws(
+        url: Url,
+        mut ws: WsStream,
+        config: PubsubClientConfig,
+        mut subscribe_receiver: mpsc::UnboundedReceiver<SubscribeRequest>,
+        mut unsubscribe_receiver: mpsc::UnboundedReceiver<UnsubscribeRequest>,
+        mut shutdown_receiver: oneshot::Receiver<()>,
+    ) -> PubsubClientResult {
+        let mut subscriptions = BTreeMap::new();
+        let mut next_local_id = 0;
+        // Nothing was missed on the first connection
+        let mut replay_rpc_client = None;
+        loop {
+            let err = match Self::serve(
+                &mut ws,
+                replay_rpc_client,
+                &mut subscriptions,
+                &mut next_local_id,
+                &mut subscribe_receiver,
+                &mut unsubscribe_receiver,
+                &mut shutdown_receiver,
+            )
+            .await
+            {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            warn!("pubsub connection to {} lost: {}", url, err);
+
+            let mut attempt = 0;
+            ws = loop {
+                tokio::select! {
+                    _ = &mut shutdown_receiver => return Ok(()),
+                    _ = sleep(reconnect_delay(attempt)) => {}
+                }
+                match connect_async(url.clone()).await {
+                    Ok((ws, _response)) => break ws,
+                    Err(err) => {
+                        attempt += 1;
+                        if config
+                            .max_reconnect_attempts
+                            .map(|max_attempts| attempt >= max_attempts)
+                            .unwrap_or_default()
+                        {
+                            // Pending subscribers get an error once their response sender
+                            // is dropped
+                            for subscription in subscriptions.values() {
+                                let _ = subscription.sender.send(Err(
+                                    PubsubClientError::ConnectionClosed(err.to_string()),
+                                ));
+                            }
+                            return Err(err.into());
+                        }
+                        debug!("pubsub reconnect attempt {} failed: {}", attempt, err);
+                    }
+                }
+            };
+            info!("pubsub reconnected to {}", url);
+            replay_rpc_client = config.rpc_client.as_ref();
+        }
+    }
+
+    /// Resubscribes everything in `subscriptions`, replays what was missed while
+    /// disconnected if `replay_rpc_client` is set, and serves the connection until
+    /// shutdown (`Ok`) or until the connection fails (`Err`).
+    async fn serve(
+        ws: &mut WsStream,
+        replay_rpc_client: Option<&Arc<RpcClient>>,
+        subscriptions: &mut BTreeMap<u64, Subscription>,
+        next_local_id: &mut u64,
+        subscribe_receiver: &mut mpsc::UnboundedReceiver<SubscribeRequest>,
+        unsubscribe_receiver: &mut mpsc::UnboundedReceiver<UnsubscribeRequest>,
+        shutdown_receiver: &mut oneshot::Receiver<()>,
+    ) -> PubsubClientResult {
+        let mut request_id = 0;
+        // Outstanding subscribe requests, by request id
+        let mut requests: HashMap<u64, (u64, &'static str)> = HashMap::new();
+        let mut by_server_id = HashMap::new();
+
+        for (local_id, subscription) in subscriptions.iter_mut() {
+            subscription.server_id = None;
+            request_id += 1;
+            requests.insert(request_id, (*local_id, subscription.operation));
+            let method = format!("{}Subscribe", subscription.operation);
+            ws.send(request_message(request_id, &method, &subscription.params))
+                .await?;
+        }
+        // Replay only once resubscribed, so nothing that happens in the meantime is missed.
+        // Live notifications already delivered by the replay are dropped by `GapReplay`.
+        if let Some(rpc_client) = replay_rpc_client {
+            Self::replay_gaps(rpc_client, subscriptions).await;
+        }
+
+        let mut ping = interval(PING_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = &mut *shutdown_receiver => {
+                    let frame = CloseFrame { code: CloseCode::Normal, reason: "".into() };
+                    ws.send(Message::Close(Some(frame))).await?;
+                    ws.flush().await?;
+                    return Ok(());
+                },
+                _ = ping.tick() => {
+                    ws.send(Message::Ping(Vec::new())).await?;
+                },
+                Some(request) = subscribe_receiver.recv() => {
+                    let local_id = *next_local_id;
+                    *next_local_id += 1;
+                    let (sender, receiver) = mpsc::unbounded_channel();
+                    request_id += 1;
+                    requests.insert(request_id, (local_id, request.operation));
+                    let method = format!("{}Subscribe", request.operation);
+                    let message = request_message(request_id, &method, &request.params);
+                    // Track the subscription before sending so a failed send resubscribes it
+                    subscriptions.insert(local_id, Subscription {
+                        operation: request.operation,
+                        params: request.params,
+                        sender,
+                        server_id: None,
+                        replay: request.replay,
+                        pending: Some((request.response, receiver)),
+                    });
+                    ws.send(message).await?;
+                },
+                Some((local_id, done)) = unsubscribe_receiver.recv() => {
+                    let server_id = subscriptions
+                        .remove(&local_id)
+                        .and_then(|subscription| {
+                            subscription.server_id.map(|id| (subscription.operation, id))
+                        });
+                    if let Some((operation, server_id)) = server_id {
+                        by_server_id.remove(&server_id);
+                        request_id += 1;
+                        let method = format!("{}Unsubscribe", operation);
+                        ws.send(request_message(request_id, &method, &json!([server_id]))).await?;
+                    }
+                    let _ = done.send(());
+                },
+                next_msg = ws.next() => {
+                    let msg = match next_msg {
+                        Some(msg) => msg?,
+                        None => {
+                            return Err(PubsubClientError::ConnectionClosed(
+                                "end of stream".into(),
+                            ));
+                        }
+                    };
+                    let text = match msg {
+                        Message::Text(text) => text,
+                        Message::Binary(_data) => continue, // Ignore
+                        Message::Ping(data) => {
+                            ws.send(Message::Pong(data)).await?;
+                            continue
+                        },
+                        Message::Pong(_data) => continue,
+                        Message::Close(frame) => {
+                            return Err(PubsubClientError::ConnectionClosed(format!("{:?}", frame)));
+                        },
+                        Message::Frame(_frame) => continue,
+                    };
+                    let mut json: Map<String, Value> = serde_json::from_str(&text)?;
+
+                    // Subscribe responses
+                    if let Some(id) = json.get("id").and_then(Value::as_u64) {
+                        let (local_id, operation) = match requests.remove(&id) {
+                            Some(request) => request,
+                            None => continue, // Unsubscribe responses
+                        };
+                        let result = match json.get("result").and_then(Value::as_u64) {
+                            Some(server_id) => Ok(server_id),
+                            None => Err(subscribe_failed(&json)),
+                        };
+                        match (subscriptions.get_mut(&local_id), result) {
+                            (Some(subscription), Ok(server_id)) => {
+                                subscription.server_id = Some(server_id);
+                                by_server_id.insert(server_id, local_id);
+                                if let Some((response, receiver)) = subscription.pending.take() {
+                                    let _ = response.send(Ok((local_id, receiver)));
+                                }
+                            }
+                            (Some(_), Err(err)) => {
+                                let subscription = subscriptions.remove(&local_id).unwrap();
+                                match subscription.pending {
+                                    Some((response, _receiver)) => {
+                                        let _ = response.send(Err(err));
+                                    }
+                                    None => {
+                                        warn!("resubscribe failed, ending subscription: {}", err);
+                                        let _ = subscription.sender.send(Err(err));
+                                    }
+                                }
+                            }
+                            // Unsubscribed before the server answered
+                            (None, Ok(server_id)) => {
+                                request_id += 1;
+                                let method = format!("{}Unsubscribe", operation);
+                                let params = json!([server_id]);
+                                ws.send(request_message(request_id, &method, &params)).await?;
+                            }
+                            (None, Err(_)) => (),
+                        }
+                        continue;
+                    }
+
+                    // Notifications
+                    if let Some(Value::Object(params)) = json.get_mut("params") {
+                        let server_id = params.get("subscription").and_then(Value::as_u64);
+                        let result = params.remove("result");
+                        if let (Some(server_id), Some(result)) = (server_id, result) {
+                            if let Some(&local_id) = by_server_id.get(&server_id) {
+                                let subscription = subscriptions.get_mut(&local_id).unwrap();
+                                // `receivedSignature` notifications are the only non-final
+                                // signature notifications
+                                let is_signature =
+                                    matches!(subscription.replay, GapReplay::Signature { .. });
+                                let is_final = is_signature
+                                    && result
+                                        .get("value")
+                                        .map(Value::is_object)
+                                        .unwrap_or_default();
+                                let is_closed = subscription.replay.observe(&result)
+                                    && subscription.sender.send(Ok(result)).is_err();
+                                if is_final || is_closed {
+                                    let subscription = subscriptions.remove(&local_id).unwrap();
+                                    by_server_id.remove(&server_id);
+                                    if is_closed && !is_final {
+                                        request_id += 1;
+                                        let method =
+                                            format!("{}Unsubscribe", subscription.operation);
+                                        let params = json!([server_id]);
+                                        ws.send(request_message(request_id, &method, &params))
+                                            .await?;
+                                    }
+                                }
+                            }
+                            continue;
+                        }
+                    }
+
+                    info!("unknown message: {}", text);
+                }
+            }
+        }
+    }
+
+    async fn replay_gaps(
+        rpc_client: &Arc<RpcClient>,
+        subscriptions: &mut BTreeMap<u64, Subscription>,
+    ) {
+        let gaps: Vec<_> = subscriptions
+            .iter()
+            .filter(|(_, subscription)| {
+                subscription.pending.is_none() && subscription.replay != GapReplay::None
+            })
+            .map(|(local_id, subscription)| (*local_id, subscription.replay))
+            .collect();
+        if gaps.is_empty() {
+            return;
+        }
+
+        for (local_id, mut replay) in gaps {
+            let (notifications, is_final) = missed_notifications(rpc_client, &mut replay)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!("unable to replay missed notifications: {}", err);
+                    (vec![], false)
+                });
+            if let Some(subscription) = subscriptions.get_mut(&local_id) {
+                subscription.replay = replay;
+                let is_closed = notifications
+                    .into_iter()
+                    .any(|notification| subscription.sender.send(Ok(notification)).is_err());
+                if is_final || is_closed {
+                    subscriptions.remove(&local_id);
+                }
+            }
+        }
+    }
+}
+
+fn request_message(id: u64, method: &str, params: &Value) -> Message {
+    Message::Text(
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+        .to_string(),
+    )
+}
+
+fn subscribe_failed(json: &Map<String, Value>) -> PubsubClientError {
+    let reason = json
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string();
+    PubsubClientError::SubscribeFailed {
+        reason,
+        message: Value::Object(json.clone()).to_string(),
+    }
+}
+
+fn reconnect_delay(attempt: usize) -> Duration {
+    INITIAL_RECONNECT_DELAY
+        .saturating_mul(1 << attempt.min(16))
+        .min(MAX_RECONNECT_DELAY)
+}
+
+/// Queries `rpc_client` for the state a subscription missed while disconnected.
+/// Returns the notifications to deliver, oldest first, and whether they end the
+/// subscription.
+async fn missed_notifications(
+    rpc_client: &RpcClient,
+    replay: &mut GapReplay,
+) -> ClientResult<(Vec<Value>, bool)> {
+    match replay {
+        GapReplay::None => Ok((vec![], false)),
+        GapReplay::Slot { slots: replayed } => {
+            let last = match replayed.last {
+                Some(last) => last,
+                None => return Ok((vec![], false)),
+            };
+            let root = rpc_client
+                .get_slot_with_commitment(CommitmentConfig::finalized())
+                .await?;
+            let slots = missed_slots(rpc_client, last, CommitmentConfig::confirmed()).await?;
+            let slot_infos = replayed_slot_infos(last, &slots, root);
+            replayed.replay(&slots);
+            Ok((
+                slot_infos.into_iter().map(|info| json!(info)).collect(),
+                false,
+            ))
+        }
+        GapReplay::Root { roots: replayed } => {
+            let last = match replayed.last {
+                Some(last) => last,
+                None => return Ok((vec![], false)),
+            };
+            let roots = missed_slots(rpc_client, last, CommitmentConfig::finalized()).await?;
+            replayed.replay(&roots);
+            Ok((roots.into_iter().map(|root| json!(root)).collect(), false))
+        }
+        GapReplay::Signature {
+            signature,
+            commitment,
+        } => {
+            let status = rpc_client
+                .get_signature_statuses(&[*signature])
+                .await?
+                .value
+                .pop()
+                .flatten();
+            match status {
+                Some(status) if status.satisfies_commitment(*commitment) => {
+                    let response = RpcResponse {
+                        context: RpcResponseContext { slot: status.slot },
+                        value: RpcSignatureResult::ProcessedSignature(ProcessedSignatureResult {
+                            err: status.err,
+                        }),
+                    };
+                    Ok((vec![json!(response)], true))
+                }
+                _ => Ok((vec![], false)),
+            }
+        }
+    }
+}
+
+/// Returns the blocks after `last_slot` up to the latest slot at `commitment`
+async fn missed_slots(
+    rpc_client: &RpcClient,
+    last_slot: Slot,
+    commitment: CommitmentConfig,
+) -> ClientResult<Vec<Slot>> {
+    let current_slot = rpc_client.get_slot_with_commitment(commitment).await?;
+    if current_slot <= last_slot {
+        return Ok(vec![]);
+    }
+    let start_slot = last_slot
+        .saturating_add(1)
+        .max(current_slot.saturating_sub(MAX_REPLAYED_SLOTS));
+    rpc_client
+        .get_blocks_with_commitment(start_slot, Some(current_slot), commitment)
+        .await
+}
+
+/// Builds slot notifications for replayed blocks. The parent of each slot is the
+/// previous replayed one, which may skip slots that were never confirmed.
+fn replayed_slot_infos(last_slot: Slot, slots: &[Slot], root: Slot) -> Vec<SlotInfo> {
+    let mut parent = last_slot;
+    slots
+        .iter()
+        .map(|&slot| {
+            let slot_info = SlotInfo {
+                slot,
+                parent,
+                root: root.min(slot),
+            };
+            parent = slot;
+            slot_info
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{mock_sender::Mocks, rpc_request::RpcRequest},
+        tokio::{net::TcpListener, time::timeout},
+        tokio_tungstenite::accept_async,
+    };
+
+    type ServerStream = WebSocketStream<TcpStream>;
+
+    async fn accept(listener: &TcpListener) -> ServerStream {
+        let (stream, _addr) = listener.accept().await.unwrap();
+        accept_async(stream).await.unwrap()
+    }
+
+    /// Receives the next request and returns its id and method
+    async fn receive_request(ws: &mut ServerStream) -> (u64, String) {
+        loop {
+            if let Message::Text(text) = ws.next().await.unwrap().unwrap() {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                return (
+                    request["id"].as_u64().unwrap(),
+                    request["method"].as_str().unwrap().to_string(),
+                );
+            }
+        }
+    }
+
+    async fn send_json(ws: &mut ServerStream, json: Value) {
+        ws.send(Message::Text(json.to_string())).await.unwrap();
+    }
+
+    async fn send_root(ws: &mut ServerStream, server_id: u64, root: Slot) {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "rootNotification",
+            "params": { "result": root, "subscription": server_id },
+        });
+        send_json(ws, notification).await;
+    }
+
+    async fn next_root(stream: &mut BoxStream<'_, PubsubClientResult<Slot>>) -> Slot {
+        timeout(Duration::from_secs(10), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resubscribe_and_replay() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let mut mocks = Mocks::default();
+        mocks.insert(RpcRequest::GetSlot, json!(5));
+        mocks.insert(RpcRequest::GetBlocks, json!([3, 4, 5]));
+        let config = PubsubClientConfig {
+            rpc_client: Some(Arc::new(RpcClient::new_mock_with_mocks(
+                "succeeds".to_string(),
+                mocks,
+            ))),
+            max_reconnect_attempts: None,
+        };
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            let (id, method) = receive_request(&mut ws).await;
+            assert_eq!(method, "rootSubscribe");
+            send_json(&mut ws, json!({"jsonrpc": "2.0", "result": 7, "id": id})).await;
+            send_root(&mut ws, 7, 2).await;
+            ws.close(None).await.unwrap();
+
+            // The client resubscribes on the new connection
+            let mut ws = accept(&listener).await;
+            let (id, method) = receive_request(&mut ws).await;
+            assert_eq!(method, "rootSubscribe");
+            send_json(&mut ws, json!({"jsonrpc": "2.0", "result": 8, "id": id})).await;
+            // Root 5 was already replayed from the rpc client
+            send_root(&mut ws, 8, 5).await;
+            send_root(&mut ws, 8, 6).await;
+            ws
+        });
+
+        let client = PubsubClient::new_with_config(&url, config).await.unwrap();
+        let (mut roots, _unsubscribe) = client.root_subscribe().await.unwrap();
+        let mut received = vec![];
+        for _ in 0..5 {
+            received.push(next_root(&mut roots).await);
+        }
+        assert_eq!(received, vec![2, 3, 4, 5, 6]);
+
+        let _ws = server.await.unwrap();
+        drop(roots);
+        client.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resubscribe_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut ws = accept(&listener).await;
+            let (id, _method) = receive_request(&mut ws).await;
+            send_json(&mut ws, json!({"jsonrpc": "2.0", "result": 7, "id": id})).await;
+            send_root(&mut ws, 7, 2).await;
+            ws.close(None).await.unwrap();
+
+            let mut ws = accept(&listener).await;
+            let (id, _method) = receive_request(&mut ws).await;
+            let error = json!({
+                "jsonrpc": "2.0",
+                "error": { "code": -32601, "message": "Method not found" },
+                "id": id,
+            });
+            send_json(&mut ws, error).await;
+            ws
+        });
+
+        let client = PubsubClient::new(&url).await.unwrap();
+        let (mut roots, _unsubscribe) = client.root_subscribe().await.unwrap();
+        assert_eq!(next_root(&mut roots).await, 2);
+        let result = timeout(Duration::from_secs(10), roots.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(PubsubClientError::SubscribeFailed { reason, .. }) if reason == "Method not found"
+        ));
+        assert!(roots.next().await.is_none());
+
+        let _ws = server.await.unwrap();
+        client.shutdown().await.unwrap();
+    }
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(0), INITIAL_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(1), INITIAL_RECONNECT_DELAY * 2);
+        assert_eq!(reconnect_delay(3), INITIAL_RECONNECT_DELAY * 8);
+        assert_eq!(reconnect_delay(100), MAX_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn test_gap_replay_observe() {
+        let mut replay = GapReplay::Slot {
+            slots: ReplayedSlots::default(),
+        };
+        // Without a reconnect, slots of other forks are delivered out of order
+        for slot in [100, 102, 101] {
+            assert!(replay.observe(&json!({"slot": slot, "parent": 99, "root": 1})));
+        }
+        assert_eq!(
+            replay,
+            GapReplay::Slot {
+                slots: ReplayedSlots {
+                    last: Some(102),
+                    replayed: None,
+                }
+            }
+        );
+
+        let mut roots = ReplayedSlots {
+            last: Some(10),
+            replayed: None,
+        };
+        roots.replay(&[11, 13]);
+        assert_eq!(roots.last, Some(13));
+        let mut replay = GapReplay::Root { roots };
+        // Already delivered by the replay
+        assert!(!replay.observe(&json!(11)));
+        assert!(!replay.observe(&json!(12)));
+        assert!(!replay.observe(&json!(13)));
+        // Outside of the replay
+        assert!(replay.observe(&json!(9)));
+        assert!(replay.observe(&json!(14)));
+        // The live stream moved past the replay
+        assert!(replay.observe(&json!(12)));
+        assert_eq!(
+            replay,
+            GapReplay::Root {
+                roots: ReplayedSlots {
+                    last: Some(14),
+                    replayed: None,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_replayed_slot_infos() {
+        assert!(replayed_slot_infos(5, &[], 3).is_empty());
+        assert_eq!(
+            replayed_slot_infos(5, &[6, 8], 7),
+            vec![
+                SlotInfo {
+                    slot: 6,
+                    parent: 5,
+                    root: 6,
+                },
+                SlotInfo {
+                    slot: 8,
+                    parent: 6,
+                    root: 7,
+                },
+            ]
+        );
+    }
+}
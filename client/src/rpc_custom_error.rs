@@ -20,6 +20,8 @@ pub const JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE: i64 = -32011;
 pub const JSON_RPC_SCAN_ERROR: i64 = -32012;
 pub const JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH: i64 = -32013;
 pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
+pub const JSON_RPC_SERVER_ERROR_EPOCH_REWARDS_PERIOD_ACTIVE: i64 = -32015;
+pub const JSON_RPC_SERVER_ERROR_EPOCH_REWARD_PARTITIONS_NOT_AVAILABLE: i64 = -32016;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -57,6 +59,14 @@ pub enum RpcCustomError {
     TransactionSignatureLenMismatch,
     #[error("BlockStatusNotAvailableYet")]
     BlockStatusNotAvailableYet { slot: Slot },
+    #[error("EpochRewardsPeriodActive")]
+    EpochRewardsPeriodActive {
+        slot: Slot,
+        current_block_height: u64,
+        rewards_complete_block_height: u64,
+    },
+    #[error("EpochRewardPartitionsNotAvailable")]
+    EpochRewardPartitionsNotAvailable { slot: Slot },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +75,14 @@ pub struct NodeUnhealthyErrorData {
     pub num_slots_behind: Option<Slot>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochRewardsPeriodActiveErrorData {
+    pub current_block_height: u64,
+    pub rewards_complete_block_height: u64,
+    pub slot: Slot,
+}
+
 impl From<RpcCustomError> for Error {
     fn from(e: RpcCustomError) -> Self {
         match e {
@@ -169,6 +187,33 @@ impl From<RpcCustomError> for Error {
                 message: format!("Block status not yet available for slot {}", slot),
                 data: None,
             },
+            RpcCustomError::EpochRewardsPeriodActive {
+                slot,
+                current_block_height,
+                rewards_complete_block_height,
+            } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_EPOCH_REWARDS_PERIOD_ACTIVE),
+                message: format!(
+                    "Epoch rewards period still active at slot {}: current block height {}, \
+                    rewards complete at block height {}",
+                    slot, current_block_height, rewards_complete_block_height
+                ),
+                data: Some(serde_json::json!(EpochRewardsPeriodActiveErrorData {
+                    current_block_height,
+                    rewards_complete_block_height,
+                    slot,
+                })),
+            },
+            RpcCustomError::EpochRewardPartitionsNotAvailable { slot } => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_EPOCH_REWARD_PARTITIONS_NOT_AVAILABLE,
+                ),
+                message: format!(
+                    "Number of epoch reward partitions not available for slot {}",
+                    slot
+                ),
+                data: None,
+            },
         }
     }
 }
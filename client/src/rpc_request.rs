@@ -13,6 +13,7 @@ pub enum RpcRequest {
     },
     DeregisterNode,
     GetAccountInfo,
+    GetAccountsDataSize,
     GetBalance,
     GetBlock,
     GetBlockHeight,
@@ -120,6 +121,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::Custom { method } => method,
             RpcRequest::DeregisterNode => "deregisterNode",
             RpcRequest::GetAccountInfo => "getAccountInfo",
+            RpcRequest::GetAccountsDataSize => "getAccountsDataSize",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
             RpcRequest::GetBlockHeight => "getBlockHeight",
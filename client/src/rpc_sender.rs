@@ -2,6 +2,7 @@
 
 use {
     crate::{client_error::Result, rpc_request::RpcRequest},
+    async_trait::async_trait,
     std::time::Duration,
 };
 
@@ -22,14 +23,22 @@ pub struct RpcTransportStats {
 ///
 /// `RpcSender` implements the underlying transport of requests to, and
 /// responses from, a PayChains node, and is used primarily by [`RpcClient`].
+/// Requests are sent asynchronously; the blocking
+/// [`rpc_client::RpcClient`](crate::rpc_client::RpcClient) drives them to
+/// completion on its own runtime.
 ///
 /// It is typically implemented by [`HttpSender`] in production, and
 /// [`MockSender`] in unit tests.
 ///
-/// [`RpcClient`]: crate::rpc_client::RpcClient
+/// [`RpcClient`]: crate::nonblocking::rpc_client::RpcClient
 /// [`HttpSender`]: crate::http_sender::HttpSender
 /// [`MockSender`]: crate::mock_sender::MockSender
+#[async_trait]
 pub trait RpcSender {
-    fn send(&self, request: RpcRequest, params: serde_json::Value) -> Result<serde_json::Value>;
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value>;
     fn get_transport_stats(&self) -> RpcTransportStats;
 }
@@ -1,10 +1,11 @@
 use {
     crate::{
         client_error::ClientError,
+        nonblocking,
         pubsub_client::{PubsubClient, PubsubClientError, PubsubClientSubscription},
         rpc_client::RpcClient,
         rpc_request::MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
-        rpc_response::SlotUpdate,
+        rpc_response::{RpcContactInfo, SlotUpdate},
         spinner,
     },
     bincode::serialize,
@@ -36,6 +37,8 @@ use {
 pub enum TpuSenderError {
     #[error("Pubsub error: {0:?}")]
     PubsubError(#[from] PubsubClientError),
+    #[error("Pubsub error: {0:?}")]
+    NonblockingPubsubError(#[from] nonblocking::pubsub_client::PubsubClientError),
     #[error("RPC error: {0:?}")]
     RpcError(#[from] ClientError),
     #[error("IO error: {0:?}")]
@@ -46,7 +49,7 @@ pub enum TpuSenderError {
     Custom(String),
 }
 
-type Result<T> = std::result::Result<T, TpuSenderError>;
+pub(crate) type Result<T> = std::result::Result<T, TpuSenderError>;
 
 /// Default number of slots used to build TPU socket fanout set
 pub const DEFAULT_FANOUT_SLOTS: u64 = 12;
@@ -275,12 +278,12 @@ impl Drop for TpuClient {
     }
 }
 
-struct LeaderTpuCache {
-    first_slot: Slot,
-    leaders: Vec<Pubkey>,
-    leader_tpu_map: HashMap<Pubkey, SocketAddr>,
-    slots_in_epoch: Slot,
-    last_epoch_info_slot: Slot,
+pub(crate) struct LeaderTpuCache {
+    pub(crate) first_slot: Slot,
+    pub(crate) leaders: Vec<Pubkey>,
+    pub(crate) leader_tpu_map: HashMap<Pubkey, SocketAddr>,
+    pub(crate) slots_in_epoch: Slot,
+    pub(crate) last_epoch_info_slot: Slot,
 }
 
 impl LeaderTpuCache {
@@ -298,12 +301,16 @@ impl LeaderTpuCache {
     }
 
     // Last slot that has a cached leader pubkey
-    fn last_slot(&self) -> Slot {
+    pub(crate) fn last_slot(&self) -> Slot {
         self.first_slot + self.leaders.len().saturating_sub(1) as u64
     }
 
     // Get the TPU sockets for the current leader and upcoming leaders according to fanout size
-    fn get_leader_sockets(&self, current_slot: Slot, fanout_slots: u64) -> Vec<SocketAddr> {
+    pub(crate) fn get_leader_sockets(
+        &self,
+        current_slot: Slot,
+        fanout_slots: u64,
+    ) -> Vec<SocketAddr> {
         let mut leader_set = HashSet::new();
         let mut leader_sockets = Vec::new();
         for leader_slot in current_slot..current_slot + fanout_slots {
@@ -340,7 +347,13 @@ impl LeaderTpuCache {
 
     fn fetch_cluster_tpu_sockets(rpc_client: &RpcClient) -> Result<HashMap<Pubkey, SocketAddr>> {
         let cluster_contact_info = rpc_client.get_cluster_nodes()?;
-        Ok(cluster_contact_info
+        Ok(Self::cluster_tpu_sockets(cluster_contact_info))
+    }
+
+    pub(crate) fn cluster_tpu_sockets(
+        cluster_contact_info: Vec<RpcContactInfo>,
+    ) -> HashMap<Pubkey, SocketAddr> {
+        cluster_contact_info
             .into_iter()
             .filter_map(|contact_info| {
                 Some((
@@ -348,7 +361,7 @@ impl LeaderTpuCache {
                     contact_info.tpu?,
                 ))
             })
-            .collect())
+            .collect()
     }
 
     fn fetch_slot_leaders(
@@ -356,8 +369,12 @@ impl LeaderTpuCache {
         start_slot: Slot,
         slots_in_epoch: Slot,
     ) -> Result<Vec<Pubkey>> {
-        let fanout = (2 * MAX_FANOUT_SLOTS).min(slots_in_epoch);
-        Ok(rpc_client.get_slot_leaders(start_slot, fanout)?)
+        Ok(rpc_client.get_slot_leaders(start_slot, Self::slot_leaders_fanout(slots_in_epoch))?)
+    }
+
+    // Number of upcoming slot leaders to fetch at a time
+    pub(crate) fn slot_leaders_fanout(slots_in_epoch: Slot) -> u64 {
+        (2 * MAX_FANOUT_SLOTS).min(slots_in_epoch)
     }
 }
 
@@ -365,15 +382,15 @@ impl LeaderTpuCache {
 const MAX_SLOT_SKIP_DISTANCE: u64 = 48;
 
 #[derive(Clone, Debug)]
-struct RecentLeaderSlots(Arc<RwLock<VecDeque<Slot>>>);
+pub(crate) struct RecentLeaderSlots(Arc<RwLock<VecDeque<Slot>>>);
 impl RecentLeaderSlots {
-    fn new(current_slot: Slot) -> Self {
+    pub(crate) fn new(current_slot: Slot) -> Self {
         let mut recent_slots = VecDeque::new();
         recent_slots.push_back(current_slot);
         Self(Arc::new(RwLock::new(recent_slots)))
     }
 
-    fn record_slot(&self, current_slot: Slot) {
+    pub(crate) fn record_slot(&self, current_slot: Slot) {
         let mut recent_slots = self.0.write().unwrap();
         recent_slots.push_back(current_slot);
         // 12 recent slots should be large enough to avoid a misbehaving
@@ -384,7 +401,7 @@ impl RecentLeaderSlots {
     }
 
     // Estimate the current slot from recent slot notifications.
-    fn estimated_current_slot(&self) -> Slot {
+    pub(crate) fn estimated_current_slot(&self) -> Slot {
         let mut recent_slots: Vec<Slot> = self.0.read().unwrap().iter().cloned().collect();
         assert!(!recent_slots.is_empty());
         recent_slots.sort_unstable();
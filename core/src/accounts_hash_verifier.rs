@@ -25,7 +25,7 @@ use {
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, RwLock,
         },
         thread::{self, Builder, JoinHandle},
         time::Duration,
@@ -42,7 +42,7 @@ impl AccountsHashVerifier {
         pending_snapshot_package: Option<PendingSnapshotPackage>,
         exit: &Arc<AtomicBool>,
         cluster_info: &Arc<ClusterInfo>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         halt_on_known_validators_accounts_hash_mismatch: bool,
         fault_injection_rate_slots: u64,
         snapshot_config: Option<SnapshotConfig>,
@@ -67,6 +67,8 @@ impl AccountsHashVerifier {
                                 thread_pool = Some(accounts_db::make_min_priority_thread_pool());
                             }
 
+                            // Don't hold the lock while hashing; the set may be updated at runtime
+                            let known_validators = known_validators.read().unwrap().clone();
                             Self::process_accounts_package(
                                 accounts_package,
                                 &cluster_info,
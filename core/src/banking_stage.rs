@@ -1525,7 +1525,10 @@ mod tests {
             status,
             log_messages: None,
             inner_instructions: None,
+            inner_instruction_stack_heights: None,
             durable_nonce_fee: None,
+            return_data: None,
+            executed_units: 0,
         })
     }
 
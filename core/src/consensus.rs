@@ -351,6 +351,14 @@ impl Tower {
         self.vote_state.tower()
     }
 
+    pub fn lockouts(&self) -> Vec<Lockout> {
+        self.vote_state.votes.iter().copied().collect()
+    }
+
+    pub fn last_timestamp(&self) -> BlockTimestamp {
+        self.last_timestamp.clone()
+    }
+
     pub fn last_vote_tx_blockhash(&self) -> Hash {
         self.last_vote_tx_blockhash
     }
@@ -122,6 +122,7 @@ impl QosService {
     ) -> Vec<transaction::Result<()>> {
         let mut cost_tracking_time = Measure::start("cost_tracking_time");
         let mut cost_tracker = bank.write_cost_tracker().unwrap();
+        cost_tracker.update_account_data_size(bank.accounts_data_len_allocated());
         let select_results = transactions
             .zip(transactions_costs)
             .map(|(tx, cost)| match cost_tracker.try_add(tx, cost) {
@@ -145,6 +146,10 @@ impl QosService {
                             self.metrics.retried_txs_per_account_data_limit_count.fetch_add(1, Ordering::Relaxed);
                             Err(TransactionError::WouldExceedMaxAccountDataCostLimit)
                         }
+                        CostTrackerError::WouldExceedAccountDataTransactionLimit => {
+                            self.metrics.dropped_txs_per_account_data_transaction_limit_count.fetch_add(1, Ordering::Relaxed);
+                            Err(TransactionError::MaxAccountsDataAllocationsExceeded)
+                        }
                     }
                 }
             })
@@ -260,6 +265,10 @@ struct QosServiceMetrics {
 
     // number of transactions to be queued for retry due to its account data limits
     retried_txs_per_account_data_limit_count: AtomicU64,
+
+    // number of transactions dropped because they allocate more account data than allowed per
+    // transaction
+    dropped_txs_per_account_data_transaction_limit_count: AtomicU64,
 }
 
 impl QosServiceMetrics {
@@ -339,6 +348,12 @@ impl QosServiceMetrics {
                         .swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "dropped_txs_per_account_data_transaction_limit_count",
+                    self.dropped_txs_per_account_data_transaction_limit_count
+                        .swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
             );
             self.slot.store(bank_slot, Ordering::Relaxed);
         }
@@ -174,7 +174,8 @@ pub struct RepairInfo {
     pub cluster_slots: Arc<ClusterSlots>,
     pub epoch_schedule: EpochSchedule,
     pub duplicate_slots_reset_sender: DuplicateSlotsResetSender,
-    pub repair_validators: Option<HashSet<Pubkey>>,
+    /// None = repair from all; may be updated while the validator is running
+    pub repair_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
 }
 
 pub struct RepairSlotRange {
@@ -320,6 +321,7 @@ impl RepairService {
             let mut build_repairs_batch_elapsed = Measure::start("build_repairs_batch_elapsed");
             let batch: Vec<(Vec<u8>, SocketAddr)> = {
                 let mut outstanding_requests = outstanding_requests.write().unwrap();
+                let repair_validators = repair_info.repair_validators.read().unwrap();
                 repairs
                     .iter()
                     .filter_map(|repair_request| {
@@ -329,7 +331,7 @@ impl RepairService {
                                 *repair_request,
                                 &mut peers_cache,
                                 &mut repair_stats,
-                                &repair_info.repair_validators,
+                                &repair_validators,
                                 &mut outstanding_requests,
                             )
                             .ok()?;
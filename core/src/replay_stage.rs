@@ -2932,9 +2932,10 @@ impl ReplayStage {
     fn record_rewards(bank: &Bank, rewards_recorder_sender: &Option<RewardsRecorderSender>) {
         if let Some(rewards_recorder_sender) = rewards_recorder_sender {
             let rewards = bank.rewards.read().unwrap();
-            if !rewards.is_empty() {
+            let num_partitions = bank.num_reward_partitions_calculated();
+            if !rewards.is_empty() || num_partitions.is_some() {
                 rewards_recorder_sender
-                    .send((bank.slot(), rewards.clone()))
+                    .send((bank.slot(), rewards.clone(), num_partitions))
                     .unwrap_or_else(|err| warn!("rewards_recorder_sender failed: {:?}", err));
             }
         }
@@ -14,8 +14,10 @@ use {
     },
 };
 
-pub type RewardsRecorderReceiver = Receiver<(Slot, Vec<(Pubkey, RewardInfo)>)>;
-pub type RewardsRecorderSender = Sender<(Slot, Vec<(Pubkey, RewardInfo)>)>;
+/// The rewards of a slot, and the number of partitions over which the stake rewards calculated in
+/// it are distributed
+pub type RewardsRecorderReceiver = Receiver<(Slot, Vec<(Pubkey, RewardInfo)>, Option<u64>)>;
+pub type RewardsRecorderSender = Sender<(Slot, Vec<(Pubkey, RewardInfo)>, Option<u64>)>;
 
 pub struct RewardsRecorderService {
     thread_hdl: JoinHandle<()>,
@@ -49,7 +51,8 @@ impl RewardsRecorderService {
         rewards_receiver: &RewardsRecorderReceiver,
         blockstore: &Arc<Blockstore>,
     ) -> Result<(), RecvTimeoutError> {
-        let (slot, rewards) = rewards_receiver.recv_timeout(Duration::from_secs(1))?;
+        let (slot, rewards, num_partitions) =
+            rewards_receiver.recv_timeout(Duration::from_secs(1))?;
         let rpc_rewards = rewards
             .into_iter()
             .map(|(pubkey, reward_info)| Reward {
@@ -62,7 +65,7 @@ impl RewardsRecorderService {
             .collect();
 
         blockstore
-            .write_rewards(slot, rpc_rewards)
+            .write_rewards(slot, rpc_rewards, num_partitions)
             .expect("Expect database write to succeed");
         Ok(())
     }
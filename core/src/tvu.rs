@@ -87,8 +87,8 @@ pub struct TvuConfig {
     pub max_ledger_shreds: Option<u64>,
     pub shred_version: u16,
     pub halt_on_known_validators_accounts_hash_mismatch: bool,
-    pub known_validators: Option<HashSet<Pubkey>>,
-    pub repair_validators: Option<HashSet<Pubkey>>,
+    pub known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    pub repair_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
     pub accounts_hash_fault_injection_slots: u64,
     pub accounts_db_caching_enabled: bool,
     pub test_hash_calculation: bool,
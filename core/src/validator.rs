@@ -81,6 +81,7 @@ use {
     },
     paychains_sdk::{
         clock::Slot,
+        compute_budget::ComputeBudget,
         epoch_schedule::MAX_LEADER_SCHEDULE_EPOCH_OFFSET,
         exit::Exit,
         genesis_config::GenesisConfig,
@@ -165,6 +166,7 @@ pub struct ValidatorConfig {
     pub validator_exit: Arc<RwLock<Exit>>,
     pub no_wait_for_vote_to_start_leader: bool,
     pub accounts_shrink_ratio: AccountShrinkThreshold,
+    pub compute_budget: Option<ComputeBudget>,
 }
 
 impl Default for ValidatorConfig {
@@ -224,6 +226,7 @@ impl Default for ValidatorConfig {
             no_wait_for_vote_to_start_leader: true,
             accounts_shrink_ratio: AccountShrinkThreshold::default(),
             accounts_db_config: None,
+            compute_budget: None,
         }
     }
 }
@@ -287,6 +290,10 @@ pub struct Validator {
     tvu: Tvu,
     ip_echo_server: Option<paychains_net_utils::IpEchoServer>,
     pub cluster_info: Arc<ClusterInfo>,
+    pub bank_forks: Arc<RwLock<BankForks>>,
+    pub known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    pub repair_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    pub send_transaction_service_config: Arc<RwLock<send_transaction_service::Config>>,
     accountsdb_repl_service: Option<AccountsDbReplService>,
     accountsdb_plugin_service: Option<AccountsDbPluginService>,
 }
@@ -600,6 +607,12 @@ impl Validator {
         }
         let poh_recorder = Arc::new(Mutex::new(poh_recorder));
 
+        // These may be adjusted through the admin RPC while the validator is running
+        let known_validators = Arc::new(RwLock::new(config.known_validators.clone()));
+        let repair_validators = Arc::new(RwLock::new(config.repair_validators.clone()));
+        let send_transaction_service_config =
+            Arc::new(RwLock::new(config.send_transaction_service_config.clone()));
+
         let rpc_override_health_check = Arc::new(AtomicBool::new(false));
         let (
             json_rpc_service,
@@ -646,10 +659,10 @@ impl Validator {
                     genesis_config.hash(),
                     ledger_path,
                     config.validator_exit.clone(),
-                    config.known_validators.clone(),
+                    known_validators.clone(),
                     rpc_override_health_check.clone(),
                     optimistically_confirmed_bank.clone(),
-                    config.send_transaction_service_config.clone(),
+                    send_transaction_service_config.clone(),
                     max_slots.clone(),
                     leader_schedule_cache.clone(),
                     max_complete_transaction_status_slot,
@@ -853,8 +866,8 @@ impl Validator {
                 halt_on_known_validators_accounts_hash_mismatch: config
                     .halt_on_known_validators_accounts_hash_mismatch,
                 shred_version: node.info.shred_version,
-                known_validators: config.known_validators.clone(),
-                repair_validators: config.repair_validators.clone(),
+                known_validators: known_validators.clone(),
+                repair_validators: repair_validators.clone(),
                 accounts_hash_fault_injection_slots: config.accounts_hash_fault_injection_slots,
                 accounts_db_caching_enabled: config.accounts_db_caching_enabled,
                 test_hash_calculation: config.accounts_db_test_hash_calculation,
@@ -889,7 +902,7 @@ impl Validator {
             &exit,
             node.info.shred_version,
             vote_tracker,
-            bank_forks,
+            bank_forks.clone(),
             verified_vote_sender,
             gossip_verified_vote_hash_sender,
             replay_vote_receiver,
@@ -925,6 +938,10 @@ impl Validator {
             ip_echo_server,
             validator_exit: config.validator_exit.clone(),
             cluster_info,
+            bank_forks,
+            known_validators,
+            repair_validators,
+            send_transaction_service_config,
             accountsdb_repl_service,
             accountsdb_plugin_service,
         }
@@ -1287,6 +1304,7 @@ fn new_banks_from_ledger(
         shrink_ratio: config.accounts_shrink_ratio,
         accounts_db_test_hash_calculation: config.accounts_db_test_hash_calculation,
         accounts_db_skip_shrink: config.accounts_db_skip_shrink,
+        compute_budget: config.compute_budget,
         ..blockstore_processor::ProcessOptions::default()
     };
 
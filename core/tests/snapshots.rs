@@ -937,7 +937,7 @@ mod tests {
             Some(pending_snapshot_package),
             &exit,
             &cluster_info,
-            None,
+            Arc::default(),
             false,
             0,
             Some(snapshot_test_config.snapshot_config.clone()),
@@ -4,19 +4,45 @@ use {
     indicatif::{ProgressBar, ProgressStyle},
     log::*,
     paychains_runtime::{
+        snapshot_chunked_archive::{
+            parse_footer, verify_chunk_in_file, ChunkInfo, ChunkVerifier, ChunkedArchiveManifest,
+            CHUNKED_ARCHIVE_FOOTER_SIZE, CHUNKED_ARCHIVE_MAGIC,
+        },
         snapshot_package::SnapshotType,
         snapshot_utils::{self, ArchiveFormat},
     },
-    paychains_sdk::{clock::Slot, genesis_config::DEFAULT_GENESIS_ARCHIVE, hash::Hash},
+    paychains_sdk::{
+        clock::Slot,
+        genesis_config::DEFAULT_GENESIS_ARCHIVE,
+        hash::{Hash, Hasher},
+    },
     std::{
-        fs::{self, File},
-        io::{self, Read},
+        fs::{self, File, OpenOptions},
+        io::{self, Read, Seek, SeekFrom, Write},
         net::SocketAddr,
         path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            mpsc, Arc, Mutex,
+        },
+        thread,
         time::{Duration, Instant},
     },
 };
 
+/// Number of chunks of a chunked snapshot archive that are downloaded at the same time
+pub const DEFAULT_CHUNK_DOWNLOAD_PARALLELISM: usize = 8;
+/// Number of segments of a file that are downloaded at the same time, when the server supports
+/// range requests
+pub const DEFAULT_DOWNLOAD_SEGMENTS: usize = 4;
+/// Files are not split into segments smaller than this
+const MIN_DOWNLOAD_SEGMENT_SIZE: u64 = 32 * 1024 * 1024;
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+/// Files are downloaded next to their destination under this prefix, and renamed once complete
+const TEMP_DESTINATION_FILE_PREFIX: &str = "tmp-";
+/// The progress of a segmented download is saved next to the partial file with this suffix
+const DOWNLOAD_STATE_FILE_SUFFIX: &str = ".state";
+
 static TRUCK: Emoji = Emoji("🚚 ", "");
 static SPARKLE: Emoji = Emoji("✨ ", "");
 
@@ -55,6 +81,567 @@ pub struct DownloadProgressRecord {
 type DownloadProgressCallback<'a> = Box<dyn FnMut(&DownloadProgressRecord) -> bool + 'a>;
 type DownloadProgressCallbackOption<'a> = Option<DownloadProgressCallback<'a>>;
 
+/// Builds the progress records of downloads that track their progress as a whole, rather
+/// than per read of a single response
+struct ProgressNotifier {
+    start_time: Instant,
+    start_bytes: usize,
+    last_print: Instant,
+    last_print_bytes: usize,
+    notification_count: u64,
+}
+
+impl ProgressNotifier {
+    fn new(current_bytes: usize) -> Self {
+        Self {
+            start_time: Instant::now(),
+            start_bytes: current_bytes,
+            last_print: Instant::now(),
+            last_print_bytes: current_bytes,
+            notification_count: 0,
+        }
+    }
+
+    /// Exclude bytes that were not downloaded, e.g. kept from an earlier attempt, from the
+    /// throughput
+    fn skip(&mut self, bytes: usize) {
+        self.start_bytes += bytes;
+        self.last_print_bytes += bytes;
+    }
+
+    /// Returns a progress record if it is time to notify the caller
+    fn poll(&mut self, current_bytes: usize, total_bytes: usize) -> Option<DownloadProgressRecord> {
+        if self.last_print.elapsed().as_secs() <= 5 {
+            return None;
+        }
+        let last_throughput = current_bytes.saturating_sub(self.last_print_bytes) as f32
+            / self.last_print.elapsed().as_secs_f32();
+        self.notification_count += 1;
+        let progress_record = DownloadProgressRecord {
+            elapsed_time: self.start_time.elapsed(),
+            last_elapsed_time: self.last_print.elapsed(),
+            last_throughput,
+            total_throughput: current_bytes.saturating_sub(self.start_bytes) as f32
+                / self.start_time.elapsed().as_secs_f32(),
+            total_bytes,
+            current_bytes,
+            percentage_done: 100f32 * (current_bytes as f32 / total_bytes.max(1) as f32),
+            estimated_remaining_time: if last_throughput > 0_f32 {
+                (total_bytes as f32 - current_bytes as f32) / last_throughput
+            } else {
+                f32::MAX
+            },
+            notification_count: self.notification_count,
+        };
+        self.last_print = Instant::now();
+        self.last_print_bytes = current_bytes;
+        Some(progress_record)
+    }
+}
+
+/// A byte range of a segmented download, and the hash of the part of it written so far
+#[derive(Clone, Debug, PartialEq)]
+struct Segment {
+    start: u64,
+    end: u64,
+    written: u64,
+    hash: Hash,
+}
+
+impl Segment {
+    fn new(start: u64, end: u64) -> Self {
+        Self {
+            start,
+            end,
+            written: 0,
+            hash: Hasher::default().result(),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.start + self.written >= self.end
+    }
+}
+
+/// The progress of a segmented download, saved next to the partial file so that an
+/// interrupted download can resume
+#[derive(Debug, PartialEq)]
+struct DownloadState {
+    /// Identifies the version of the remote file, see `response_validator()`
+    validator: String,
+    total_size: u64,
+    segments: Vec<Segment>,
+}
+
+impl DownloadState {
+    fn new(validator: String, total_size: u64, max_segments: usize) -> Self {
+        let num_segments = ((total_size + MIN_DOWNLOAD_SEGMENT_SIZE - 1)
+            / MIN_DOWNLOAD_SEGMENT_SIZE)
+            .clamp(1, max_segments.max(1) as u64);
+        let segment_size = (total_size + num_segments - 1) / num_segments;
+        let segments = (0..num_segments)
+            .map(|index| {
+                Segment::new(
+                    (index * segment_size).min(total_size),
+                    ((index + 1) * segment_size).min(total_size),
+                )
+            })
+            .collect();
+        Self {
+            validator,
+            total_size,
+            segments,
+        }
+    }
+
+    fn downloaded(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.written).sum()
+    }
+
+    fn serialize(&self) -> String {
+        let mut state = format!("{}\n{}\n", self.validator, self.total_size);
+        for segment in &self.segments {
+            state.push_str(&format!(
+                "{} {} {} {}\n",
+                segment.start, segment.end, segment.written, segment.hash
+            ));
+        }
+        state
+    }
+
+    fn deserialize(state: &str) -> Option<Self> {
+        let mut lines = state.lines();
+        let validator = lines.next()?.to_string();
+        let total_size = lines.next()?.parse().ok()?;
+        let segments = lines
+            .map(|line| {
+                let mut fields = line.split(' ');
+                let segment = Segment {
+                    start: fields.next()?.parse().ok()?,
+                    end: fields.next()?.parse().ok()?,
+                    written: fields.next()?.parse().ok()?,
+                    hash: fields.next()?.parse().ok()?,
+                };
+                (fields.next().is_none() && segment.start + segment.written <= segment.end)
+                    .then(|| segment)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        // The segments must cover the whole file
+        let mut offset = 0;
+        for segment in &segments {
+            if segment.start != offset {
+                return None;
+            }
+            offset = segment.end;
+        }
+        (!segments.is_empty() && offset == total_size).then(|| Self {
+            validator,
+            total_size,
+            segments,
+        })
+    }
+
+    fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|state| Self::deserialize(&state))
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.serialize())
+    }
+}
+
+#[derive(Debug)]
+enum SegmentError {
+    /// The file changed on the server, the partial download must be discarded
+    SourceChanged(String),
+    Failed(String),
+}
+
+/// Identifies the version of the file the server sent, so that a resumed download can check
+/// that it is still the same
+fn response_validator(response: &reqwest::blocking::Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(reqwest::header::ETAG)
+        .or_else(|| headers.get(reqwest::header::LAST_MODIFIED))
+        .and_then(|validator| validator.to_str().ok())
+        .map(str::to_string)
+}
+
+/// The first byte and the total size of the file from the Content-Range of a partial response
+fn content_range(response: &reqwest::blocking::Response) -> Option<(u64, u64)> {
+    let content_range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?;
+    let (range, total_size) = content_range.split_once('/')?;
+    let (first, _) = range.split_once('-')?;
+    Some((first.parse().ok()?, total_size.parse().ok()?))
+}
+
+fn hash_file_range(file: &mut File, start: u64, len: u64, hasher: &mut Hasher) -> io::Result<()> {
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader = Read::by_ref(file).take(len);
+    let mut buf = vec![0; 1 << 16];
+    let mut read = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.hash(&buf[..n]);
+        read += n as u64;
+    }
+    if read == len {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+/// Download the rest of a segment into its place in the file, hashing the data as it is
+/// written.  The response must be of the same version of the file as the rest of the download
+#[allow(clippy::too_many_arguments)]
+fn fetch_segment(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    validator: &str,
+    total_size: u64,
+    file: &mut File,
+    segment: &Mutex<Segment>,
+    hasher: &mut Hasher,
+    abort: &AtomicBool,
+) -> Result<(), SegmentError> {
+    let (offset, end) = {
+        let segment = segment.lock().unwrap();
+        (segment.start + segment.written, segment.end)
+    };
+    let mut request = client.get(url).header(
+        reqwest::header::RANGE,
+        format!("bytes={}-{}", offset, end - 1),
+    );
+    if !validator.is_empty() {
+        request = request.header(reqwest::header::IF_RANGE, validator);
+    }
+    let mut response = request
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| SegmentError::Failed(err.to_string()))?;
+    // A server whose copy of the file is no longer the one being downloaded sends all of it
+    // instead of the range
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT
+        || content_range(&response) != Some((offset, total_size))
+        || (!validator.is_empty() && response_validator(&response).as_deref() != Some(validator))
+    {
+        return Err(SegmentError::SourceChanged(format!(
+            "{} changed during the download",
+            url
+        )));
+    }
+
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|err| SegmentError::Failed(err.to_string()))?;
+    let mut buf = vec![0; 1 << 16];
+    let mut remaining = end - offset;
+    while remaining > 0 {
+        if abort.load(Ordering::Relaxed) {
+            return Err(SegmentError::Failed("Download is aborted".to_string()));
+        }
+        let len = remaining.min(buf.len() as u64) as usize;
+        let n = response
+            .read(&mut buf[..len])
+            .map_err(|err| SegmentError::Failed(err.to_string()))?;
+        if n == 0 {
+            return Err(SegmentError::Failed(format!(
+                "Connection closed with {} bytes of the range left",
+                remaining
+            )));
+        }
+        file.write_all(&buf[..n])
+            .map_err(|err| SegmentError::Failed(err.to_string()))?;
+        hasher.hash(&buf[..n]);
+        remaining -= n as u64;
+
+        let mut segment = segment.lock().unwrap();
+        segment.written += n as u64;
+        segment.hash = hasher.clone().result();
+    }
+    Ok(())
+}
+
+/// Download a segment into the file at `path`.  The part of the segment that an earlier
+/// attempt wrote is verified against its recorded hash first, and downloaded again if it
+/// does not match
+fn download_segment(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    path: &Path,
+    validator: &str,
+    total_size: u64,
+    segment: &Mutex<Segment>,
+    abort: &AtomicBool,
+) -> Result<(), SegmentError> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|err| SegmentError::Failed(format!("Unable to open {:?}: {:?}", path, err)))?;
+
+    let mut hasher = Hasher::default();
+    let (start, written, hash) = {
+        let segment = segment.lock().unwrap();
+        (segment.start, segment.written, segment.hash)
+    };
+    if written > 0 {
+        let intact = hash_file_range(&mut file, start, written, &mut hasher).is_ok()
+            && hasher.clone().result() == hash;
+        if !intact {
+            warn!(
+                "Downloaded part of {:?} at offset {} is corrupt, downloading it again",
+                path, start
+            );
+            hasher = Hasher::default();
+            let mut segment = segment.lock().unwrap();
+            segment.written = 0;
+            segment.hash = hasher.clone().result();
+        }
+    }
+
+    let mut attempt = 0;
+    while !segment.lock().unwrap().is_complete() {
+        let written = segment.lock().unwrap().written;
+        match fetch_segment(
+            client,
+            url,
+            validator,
+            total_size,
+            &mut file,
+            segment,
+            &mut hasher,
+            abort,
+        ) {
+            Ok(()) => {}
+            Err(SegmentError::Failed(err)) if !abort.load(Ordering::Relaxed) => {
+                if segment.lock().unwrap().written > written {
+                    attempt = 0;
+                }
+                attempt += 1;
+                warn!(
+                    "Attempt {} to download {} at offset {} failed: {}",
+                    attempt,
+                    url,
+                    start + segment.lock().unwrap().written,
+                    err
+                );
+                if attempt >= MAX_DOWNLOAD_ATTEMPTS {
+                    return Err(SegmentError::Failed(err));
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Download `url` in concurrent segments with range requests, resuming the partial download
+/// left by an earlier attempt if the server's copy of the file did not change since
+#[allow(clippy::too_many_arguments)]
+fn download_segments<'a, 'b>(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    destination_file: &Path,
+    total_size: u64,
+    validator: String,
+    max_segments: usize,
+    progress_bar: ProgressBar,
+    use_progress_bar: bool,
+    progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
+) -> Result<(), String> {
+    let download_start = Instant::now();
+    let temp_destination_file = temp_destination_file(destination_file);
+    let state_file = download_state_file(&temp_destination_file);
+
+    let resumed_state = DownloadState::load(&state_file).filter(|state| {
+        !validator.is_empty()
+            && state.validator == validator
+            && state.total_size == total_size
+            && fs::metadata(&temp_destination_file)
+                .map(|metadata| metadata.len() == total_size)
+                .unwrap_or(false)
+    });
+    let state = match resumed_state {
+        Some(state) => {
+            info!(
+                "Resuming download of {} with {} of {} bytes downloaded",
+                url,
+                state.downloaded(),
+                total_size
+            );
+            state
+        }
+        None => {
+            let state = DownloadState::new(validator, total_size, max_segments);
+            File::create(&temp_destination_file)
+                .and_then(|file| file.set_len(total_size))
+                .and_then(|_| state.save(&state_file))
+                .map_err(|err| format!("Unable to write {:?}: {:?}", temp_destination_file, err))?;
+            state
+        }
+    };
+
+    if use_progress_bar {
+        progress_bar.set_length(total_size);
+        progress_bar.set_position(state.downloaded());
+        progress_bar.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green}{msg_wide}[{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+                )
+                .progress_chars("=> "),
+        );
+        progress_bar.set_message(format!("{}Downloading~ {}", TRUCK, url));
+    } else {
+        info!(
+            "Downloading {} bytes in {} segments from {}",
+            total_size,
+            state.segments.len(),
+            url
+        );
+    }
+
+    let DownloadState {
+        validator,
+        total_size,
+        segments,
+    } = state;
+    let validator = Arc::new(validator);
+    let segments: Arc<Vec<_>> = Arc::new(segments.into_iter().map(Mutex::new).collect());
+    let current_state = || DownloadState {
+        validator: validator.to_string(),
+        total_size,
+        segments: segments
+            .iter()
+            .map(|segment| segment.lock().unwrap().clone())
+            .collect(),
+    };
+
+    let abort = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let threads: Vec<_> = (0..segments.len())
+        .filter(|index| !segments[*index].lock().unwrap().is_complete())
+        .map(|index| {
+            let client = client.clone();
+            let url = url.to_string();
+            let path = temp_destination_file.clone();
+            let validator = validator.clone();
+            let segments = segments.clone();
+            let abort = abort.clone();
+            let sender = sender.clone();
+            thread::spawn(move || {
+                let result = download_segment(
+                    &client,
+                    &url,
+                    &path,
+                    &validator,
+                    total_size,
+                    &segments[index],
+                    &abort,
+                );
+                let _ = sender.send(result);
+            })
+        })
+        .collect();
+    drop(sender);
+
+    let mut notifier = ProgressNotifier::new(current_state().downloaded() as usize);
+    let mut error = None;
+    loop {
+        match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(Ok(())) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Ok(Err(err)) => {
+                abort.store(true, Ordering::Relaxed);
+                error.get_or_insert(err);
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        let state = current_state();
+        let current_bytes = state.downloaded() as usize;
+        if use_progress_bar {
+            progress_bar.set_position(current_bytes as u64);
+        }
+        if let Some(progress_record) = notifier.poll(current_bytes, total_size as usize) {
+            if let Err(err) = state.save(&state_file) {
+                warn!("Unable to write {:?}: {:?}", state_file, err);
+            }
+            if !use_progress_bar {
+                info!(
+                    "downloaded {} bytes {:.1}% {:.1} bytes/s",
+                    current_bytes, progress_record.percentage_done, progress_record.last_throughput,
+                );
+            }
+            if let Some(callback) = progress_notify_callback {
+                if !callback(&progress_record) {
+                    info!("Download is aborted by the caller");
+                    abort.store(true, Ordering::Relaxed);
+                    error.get_or_insert_with(|| {
+                        SegmentError::Failed("Download is aborted by the caller".to_string())
+                    });
+                }
+            }
+        }
+    }
+    for thread in threads {
+        let _ = thread.join();
+    }
+    progress_bar.finish_and_clear();
+
+    let state = current_state();
+    match error {
+        Some(SegmentError::SourceChanged(err)) => {
+            let _ = fs::remove_file(&temp_destination_file);
+            let _ = fs::remove_file(&state_file);
+            return Err(err);
+        }
+        Some(SegmentError::Failed(err)) => {
+            // Keep the partial download so that the next attempt resumes it
+            if let Err(err) = state.save(&state_file) {
+                warn!("Unable to write {:?}: {:?}", state_file, err);
+            }
+            return Err(err);
+        }
+        None => {}
+    }
+    for segment in &state.segments {
+        debug!(
+            "Downloaded bytes {}..{} of {}, hash: {}",
+            segment.start, segment.end, url, segment.hash
+        );
+    }
+    info!(
+        "  {}{}",
+        SPARKLE,
+        format!(
+            "Downloaded {} ({} bytes) in {:?}",
+            url,
+            total_size,
+            Instant::now().duration_since(download_start),
+        )
+    );
+
+    let _ = fs::remove_file(&state_file);
+    std::fs::rename(temp_destination_file, destination_file)
+        .map_err(|err| format!("Unable to rename: {:?}", err))?;
+
+    Ok(())
+}
+
+/// Download `url` to `destination_file` in up to `DEFAULT_DOWNLOAD_SEGMENTS` concurrent
+/// segments, see `download_file_with_segments()`
+///
 /// This callback allows the caller to get notified of the download progress modelled by DownloadProgressRecord
 /// Return "true" to continue the download
 /// Return "false" to abort the download
@@ -63,6 +650,37 @@ pub fn download_file<'a, 'b>(
     destination_file: &Path,
     use_progress_bar: bool,
     progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
+) -> Result<(), String> {
+    download_file_with_segments(
+        url,
+        destination_file,
+        DEFAULT_DOWNLOAD_SEGMENTS,
+        use_progress_bar,
+        progress_notify_callback,
+    )
+}
+
+/// Download `url` to `destination_file`.
+///
+/// If the server supports range requests the file is downloaded in up to `segments`
+/// concurrent segments, and a download that is interrupted or aborted resumes from where it
+/// stopped the next time, as long as the server's copy of the file did not change.  The data
+/// of each segment is hashed as it is written, so that a partial download is verified before
+/// it is resumed, and the download is aborted as soon as the server's validator or
+/// Content-Range shows that it sends a different version of the file.  There is no hash of
+/// the whole file to check the data against as it arrives, see `download_chunked_archive()`
+/// for archives that carry one per chunk.  Servers without range support are downloaded from
+/// in a single request.
+///
+/// This callback allows the caller to get notified of the download progress modelled by DownloadProgressRecord
+/// Return "true" to continue the download
+/// Return "false" to abort the download
+pub fn download_file_with_segments<'a, 'b>(
+    url: &str,
+    destination_file: &Path,
+    segments: usize,
+    use_progress_bar: bool,
+    progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
 ) -> Result<(), String> {
     if destination_file.is_file() {
         return Err(format!("{:?} already exists", destination_file));
@@ -72,30 +690,54 @@ pub fn download_file<'a, 'b>(
     fs::create_dir_all(destination_file.parent().expect("parent"))
         .map_err(|err| err.to_string())?;
 
-    let mut temp_destination_file = destination_file.to_path_buf();
-    temp_destination_file.set_file_name(format!(
-        "tmp-{}",
-        destination_file
-            .file_name()
-            .expect("file_name")
-            .to_str()
-            .expect("to_str")
-    ));
+    let temp_destination_file = temp_destination_file(destination_file);
 
     let progress_bar = new_spinner_progress_bar();
     if use_progress_bar {
         progress_bar.set_message(format!("{}Downloading {}...", TRUCK, url));
     }
 
-    let response = reqwest::blocking::Client::new()
+    // Probe for range support with the first byte of the file.  A server that ignores the
+    // range sends the whole file instead, which is downloaded as is
+    let client = reqwest::blocking::Client::new();
+    let response = client
         .get(url)
+        .header(reqwest::header::RANGE, "bytes=0-0")
         .send()
+        .and_then(|response| {
+            if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+                // An empty file has no first byte to probe with
+                client.get(url).send()
+            } else {
+                Ok(response)
+            }
+        })
         .and_then(|response| response.error_for_status())
         .map_err(|err| {
             progress_bar.finish_and_clear();
             err.to_string()
         })?;
 
+    if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        let (_, total_size) = content_range(&response).ok_or_else(|| {
+            progress_bar.finish_and_clear();
+            format!("{} sent an invalid Content-Range", url)
+        })?;
+        let validator = response_validator(&response).unwrap_or_default();
+        drop(response);
+        return download_segments(
+            &client,
+            url,
+            destination_file,
+            total_size,
+            validator,
+            segments,
+            progress_bar,
+            use_progress_bar,
+            progress_notify_callback,
+        );
+    }
+
     let download_size = {
         response
             .headers()
@@ -205,6 +847,7 @@ pub fn download_file<'a, 'b>(
         notification_count: 0,
     };
 
+    let _ = fs::remove_file(download_state_file(&temp_destination_file));
     File::create(&temp_destination_file)
         .and_then(|mut file| std::io::copy(&mut source, &mut file))
         .map_err(|err| format!("Unable to write {:?}: {:?}", temp_destination_file, err))?;
@@ -227,6 +870,368 @@ pub fn download_file<'a, 'b>(
     Ok(())
 }
 
+fn temp_destination_file(destination_file: &Path) -> PathBuf {
+    let mut temp_destination_file = destination_file.to_path_buf();
+    temp_destination_file.set_file_name(format!(
+        "{}{}",
+        TEMP_DESTINATION_FILE_PREFIX,
+        destination_file
+            .file_name()
+            .expect("file_name")
+            .to_str()
+            .expect("to_str")
+    ));
+    temp_destination_file
+}
+
+fn download_state_file(temp_destination_file: &Path) -> PathBuf {
+    let mut state_file = temp_destination_file.as_os_str().to_owned();
+    state_file.push(DOWNLOAD_STATE_FILE_SUFFIX);
+    PathBuf::from(state_file)
+}
+
+/// Fetch a byte range of `url` of at most `max_size` bytes, returning the bytes and the total
+/// size of the remote file
+fn fetch_range(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    range: &str,
+    max_size: u64,
+) -> Result<(Vec<u8>, u64), String> {
+    let response = client
+        .get(url)
+        .header(reqwest::header::RANGE, range)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!("{} does not support range requests", url));
+    }
+    let (_, total_size) =
+        content_range(&response).ok_or_else(|| format!("{} sent an invalid Content-Range", url))?;
+    let mut bytes = vec![];
+    response
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    if bytes.len() as u64 > max_size {
+        return Err(format!("{} sent more than {} bytes", url, max_size));
+    }
+    Ok((bytes, total_size))
+}
+
+/// Whether a chunk that an earlier attempt downloaded into the archive at `path` is intact
+fn chunk_is_present(path: &Path, index: usize, chunk: &ChunkInfo) -> bool {
+    File::open(path)
+        .map(|mut file| verify_chunk_in_file(&mut file, index, chunk).is_ok())
+        .unwrap_or(false)
+}
+
+/// Stream one chunk into its place in the archive at `path`, verifying it as it arrives
+fn fetch_chunk(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    path: &Path,
+    index: usize,
+    chunk: &ChunkInfo,
+) -> Result<(), String> {
+    let mut response = client
+        .get(url)
+        .header(
+            reqwest::header::RANGE,
+            format!(
+                "bytes={}-{}",
+                chunk.offset,
+                (chunk.offset + chunk.size).saturating_sub(1)
+            ),
+        )
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?;
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!("{} does not support range requests", url));
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|mut file| {
+            file.seek(SeekFrom::Start(chunk.offset))?;
+            Ok(file)
+        })
+        .map_err(|err| format!("Unable to write {:?}: {:?}", path, err))?;
+    let mut verifier = ChunkVerifier::new(index, chunk);
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let n = response.read(&mut buf).map_err(|err| err.to_string())?;
+        if n == 0 {
+            break;
+        }
+        verifier.update(&buf[..n]).map_err(|err| err.to_string())?;
+        file.write_all(&buf[..n])
+            .map_err(|err| format!("Unable to write {:?}: {:?}", path, err))?;
+    }
+    verifier.finish().map_err(|err| err.to_string())
+}
+
+/// Download one chunk into its place in the archive at `path`, verifying it against the
+/// manifest.  Returns the size of the chunk
+fn download_chunk(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    path: &Path,
+    index: usize,
+    chunk: &ChunkInfo,
+) -> Result<u64, String> {
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_DOWNLOAD_ATTEMPTS {
+        match fetch_chunk(client, url, path, index, chunk) {
+            Ok(()) => return Ok(chunk.size),
+            Err(err) => {
+                warn!(
+                    "Attempt {} to download chunk {} of {} failed: {}",
+                    attempt, index, url, err
+                );
+                last_error = err;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// Download a chunked snapshot archive, fetching up to `parallelism` chunks at the same time
+/// with range requests and verifying every chunk against the archive's manifest.  A partial
+/// download of the same archive, possibly from a different server, is resumed keeping the
+/// chunks that verify.
+///
+/// The progress callback is notified as chunks complete, return "false" to abort the download
+pub fn download_chunked_archive<'a, 'b>(
+    url: &str,
+    destination_file: &Path,
+    parallelism: usize,
+    use_progress_bar: bool,
+    progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
+) -> Result<(), String> {
+    if destination_file.is_file() {
+        return Err(format!("{:?} already exists", destination_file));
+    }
+    let download_start = Instant::now();
+
+    fs::create_dir_all(destination_file.parent().expect("parent"))
+        .map_err(|err| err.to_string())?;
+    let temp_destination_file = temp_destination_file(destination_file);
+
+    let client = reqwest::blocking::Client::new();
+    let (footer, archive_size) = fetch_range(
+        &client,
+        url,
+        &format!("bytes=-{}", CHUNKED_ARCHIVE_FOOTER_SIZE),
+        CHUNKED_ARCHIVE_FOOTER_SIZE,
+    )?;
+    let manifest_size = parse_footer(&footer).map_err(|err| err.to_string())?;
+    let manifest_offset = archive_size
+        .checked_sub(CHUNKED_ARCHIVE_FOOTER_SIZE + manifest_size)
+        .ok_or_else(|| format!("{} is not a chunked archive", url))?;
+    let (manifest_bytes, _) = fetch_range(
+        &client,
+        url,
+        &format!(
+            "bytes={}-{}",
+            manifest_offset,
+            archive_size - CHUNKED_ARCHIVE_FOOTER_SIZE - 1
+        ),
+        manifest_size,
+    )?;
+    let manifest =
+        ChunkedArchiveManifest::deserialize(&manifest_bytes).map_err(|err| err.to_string())?;
+    if manifest.manifest_offset() != manifest_offset {
+        return Err(format!("{} has an inconsistent manifest", url));
+    }
+
+    // The manifest identifies the archive, so a partial download with the same manifest is of
+    // the same archive
+    let resume = File::open(&temp_destination_file)
+        .and_then(|mut file| {
+            if file.metadata()?.len() != archive_size {
+                return Ok(false);
+            }
+            // Bounded by the manifest size limit `parse_footer` enforces
+            let mut tail = vec![0; manifest_bytes.len() + footer.len()];
+            file.seek(SeekFrom::Start(manifest_offset))?;
+            file.read_exact(&mut tail)?;
+            Ok(tail == [manifest_bytes.as_slice(), footer.as_slice()].concat())
+        })
+        .unwrap_or(false);
+    if resume {
+        info!("Resuming download of {}", url);
+    } else {
+        // Everything but the chunks is known already
+        File::create(&temp_destination_file)
+            .and_then(|mut file| {
+                file.set_len(archive_size)?;
+                file.write_all(CHUNKED_ARCHIVE_MAGIC)?;
+                file.seek(SeekFrom::Start(manifest_offset))?;
+                file.write_all(&manifest_bytes)?;
+                file.write_all(&footer)
+            })
+            .map_err(|err| format!("Unable to write {:?}: {:?}", temp_destination_file, err))?;
+    }
+
+    let download_size = manifest.chunks.iter().map(|chunk| chunk.size).sum::<u64>();
+    let progress_bar = new_spinner_progress_bar();
+    if use_progress_bar {
+        progress_bar.set_length(download_size);
+        progress_bar.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green}{msg_wide}[{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+                )
+                .progress_chars("=> "),
+        );
+        progress_bar.set_message(format!("{}Downloading~ {}", TRUCK, url));
+    } else {
+        info!(
+            "Downloading {} bytes in {} chunks from {}",
+            download_size,
+            manifest.chunks.len(),
+            url
+        );
+    }
+
+    let manifest = Arc::new(manifest);
+    let next_chunk = Arc::new(AtomicUsize::new(0));
+    let abort = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let threads: Vec<_> = (0..parallelism.max(1).min(manifest.chunks.len()))
+        .map(|_| {
+            let client = client.clone();
+            let url = url.to_string();
+            let path = temp_destination_file.clone();
+            let manifest = manifest.clone();
+            let next_chunk = next_chunk.clone();
+            let abort = abort.clone();
+            let sender = sender.clone();
+            thread::spawn(move || {
+                while !abort.load(Ordering::Relaxed) {
+                    let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                    let chunk = match manifest.chunks.get(index) {
+                        Some(chunk) => chunk,
+                        None => break,
+                    };
+                    let result = if resume && chunk_is_present(&path, index, chunk) {
+                        Ok((chunk.size, true))
+                    } else {
+                        download_chunk(&client, &url, &path, index, chunk)
+                            .map(|chunk_size| (chunk_size, false))
+                    };
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+
+    let mut notifier = ProgressNotifier::new(0);
+    let mut current_bytes = 0;
+    let mut error = None;
+    for result in receiver {
+        let (chunk_size, is_present) = match result {
+            Ok(result) => result,
+            Err(err) => {
+                abort.store(true, Ordering::Relaxed);
+                error.get_or_insert(err);
+                continue;
+            }
+        };
+        current_bytes += chunk_size as usize;
+        if is_present {
+            notifier.skip(chunk_size as usize);
+        }
+        if use_progress_bar {
+            progress_bar.inc(chunk_size);
+        }
+        if let Some(progress_record) = notifier.poll(current_bytes, download_size as usize) {
+            if !use_progress_bar {
+                info!(
+                    "downloaded {} bytes {:.1}% {:.1} bytes/s",
+                    current_bytes, progress_record.percentage_done, progress_record.last_throughput,
+                );
+            }
+            if let Some(callback) = progress_notify_callback {
+                if !callback(&progress_record) {
+                    info!("Download is aborted by the caller");
+                    abort.store(true, Ordering::Relaxed);
+                    error.get_or_insert_with(|| "Download is aborted by the caller".to_string());
+                }
+            }
+        }
+    }
+    for thread in threads {
+        let _ = thread.join();
+    }
+    progress_bar.finish_and_clear();
+
+    // The partial download is kept, the next attempt resumes it
+    if let Some(err) = error {
+        return Err(err);
+    }
+    info!(
+        "  {}{}",
+        SPARKLE,
+        format!(
+            "Downloaded {} ({} bytes) in {:?}",
+            url,
+            archive_size,
+            Instant::now().duration_since(download_start),
+        )
+    );
+
+    std::fs::rename(temp_destination_file, destination_file)
+        .map_err(|err| format!("Unable to rename: {:?}", err))?;
+
+    Ok(())
+}
+
+/// Remove the partial downloads of snapshot archives other than those for `snapshot_hash`.
+/// Interrupted downloads keep them so that they can resume, which is pointless once a newer
+/// snapshot is being downloaded
+fn purge_stale_partial_downloads(snapshot_archives_dir: &Path, snapshot_hash: (Slot, Hash)) {
+    let entries = match fs::read_dir(snapshot_archives_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        let archive_filename = match path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix(TEMP_DESTINATION_FILE_PREFIX))
+        {
+            Some(archive_filename) => archive_filename,
+            None => continue,
+        };
+        let archive_filename = archive_filename
+            .strip_suffix(DOWNLOAD_STATE_FILE_SUFFIX)
+            .unwrap_or(archive_filename);
+        let archive_hash = snapshot_utils::parse_full_snapshot_archive_filename(archive_filename)
+            .map(|(slot, hash, _)| (slot, hash))
+            .or_else(|_| {
+                snapshot_utils::parse_incremental_snapshot_archive_filename(archive_filename)
+                    .map(|(_, slot, hash, _)| (slot, hash))
+            });
+        if matches!(archive_hash, Ok(archive_hash) if archive_hash != snapshot_hash)
+            && path.is_file()
+        {
+            info!("Removing stale partial download {:?}", path);
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
 pub fn download_genesis_if_missing(
     rpc_addr: &SocketAddr,
     genesis_package: &Path,
@@ -267,8 +1272,10 @@ pub fn download_snapshot_archive<'a, 'b>(
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
     );
+    purge_stale_partial_downloads(snapshot_archives_dir, desired_snapshot_hash);
 
     for archive_format in [
+        ArchiveFormat::TarZstdChunked,
         ArchiveFormat::TarZstd,
         ArchiveFormat::TarGzip,
         ArchiveFormat::TarBzip2,
@@ -296,16 +1303,28 @@ pub fn download_snapshot_archive<'a, 'b>(
             return Ok(());
         }
 
-        match download_file(
-            &format!(
-                "http://{}/{}",
-                rpc_addr,
-                destination_path.file_name().unwrap().to_str().unwrap()
-            ),
-            &destination_path,
-            use_progress_bar,
-            progress_notify_callback,
-        ) {
+        let url = format!(
+            "http://{}/{}",
+            rpc_addr,
+            destination_path.file_name().unwrap().to_str().unwrap()
+        );
+        let result = if archive_format == ArchiveFormat::TarZstdChunked {
+            download_chunked_archive(
+                &url,
+                &destination_path,
+                DEFAULT_CHUNK_DOWNLOAD_PARALLELISM,
+                use_progress_bar,
+                progress_notify_callback,
+            )
+        } else {
+            download_file(
+                &url,
+                &destination_path,
+                use_progress_bar,
+                progress_notify_callback,
+            )
+        };
+        match result {
             Ok(()) => return Ok(()),
             Err(err) => info!("{}", err),
         }
@@ -315,3 +1334,98 @@ pub fn download_snapshot_archive<'a, 'b>(
         desired_snapshot_hash.0, rpc_addr
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::net::TcpListener, tempfile::TempDir};
+
+    /// Serve `connections` connections with one response each, built from the request
+    fn serve_http(
+        connections: usize,
+        respond: fn(&str) -> &'static str,
+    ) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/file", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = vec![];
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.unwrap();
+                let mut request = vec![];
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8(request).unwrap().to_ascii_lowercase();
+                stream.write_all(respond(&request).as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_download_empty_file() {
+        let (url, server) = serve_http(2, |request| {
+            if request.contains("\r\nrange:") {
+                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */0\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n"
+            } else {
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            }
+        });
+        let download_dir = TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("empty");
+        download_file(&url, &destination_file, false, &mut None).unwrap();
+        assert_eq!(fs::metadata(&destination_file).unwrap().len(), 0);
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains("\r\nrange: bytes=0-0\r\n"));
+        assert!(!requests[1].contains("\r\nrange:"));
+    }
+
+    #[test]
+    fn test_download_state_segments() {
+        let state = DownloadState::new("\"etag\"".to_string(), 10, 4);
+        assert_eq!(state.segments, vec![Segment::new(0, 10)]);
+
+        let total_size = 3 * MIN_DOWNLOAD_SEGMENT_SIZE + 1;
+        let state = DownloadState::new("\"etag\"".to_string(), total_size, 8);
+        assert_eq!(state.segments.len(), 4);
+        assert_eq!(state.segments.first().unwrap().start, 0);
+        assert_eq!(state.segments.last().unwrap().end, total_size);
+        assert!(state
+            .segments
+            .windows(2)
+            .all(|segments| segments[0].end == segments[1].start));
+
+        let state = DownloadState::new("\"etag\"".to_string(), total_size, 2);
+        assert_eq!(state.segments.len(), 2);
+
+        let state = DownloadState::new("\"etag\"".to_string(), 0, 4);
+        assert_eq!(state.segments, vec![Segment::new(0, 0)]);
+        assert!(state.segments[0].is_complete());
+    }
+
+    #[test]
+    fn test_download_state_serialization() {
+        let mut state = DownloadState::new("\"1f-2a\"".to_string(), 100, 1);
+        state.segments[0].written = 42;
+        state.segments[0].hash = Hash::new_unique();
+        assert_eq!(DownloadState::deserialize(&state.serialize()), Some(state));
+
+        // Segments must cover the whole file
+        let state = "\"1f-2a\"\n100\n0 50 0 11111111111111111111111111111111\n";
+        assert_eq!(DownloadState::deserialize(state), None);
+        let state = "\"1f-2a\"\n100\n0 50 0 11111111111111111111111111111111\n\
+                     60 100 0 11111111111111111111111111111111\n";
+        assert_eq!(DownloadState::deserialize(state), None);
+        // More written than the segment holds
+        let state = "\"1f-2a\"\n100\n0 100 101 11111111111111111111111111111111\n";
+        assert_eq!(DownloadState::deserialize(state), None);
+        assert_eq!(DownloadState::deserialize(""), None);
+    }
+}
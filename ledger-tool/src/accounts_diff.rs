@@ -0,0 +1,341 @@
+use {
+    serde::{Serialize, Serializer},
+    paychains_cli_output::{QuietDisplay, VerboseDisplay},
+    paychains_runtime::bank::Bank,
+    paychains_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::{Epoch, Slot},
+        hash::{hash, Hash},
+        native_token::lamports_to_pay,
+        pubkey::Pubkey,
+        sysvar,
+    },
+    std::{
+        collections::{BTreeMap, HashSet},
+        fmt,
+    },
+};
+
+fn serialize_display<T: fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// The parts of an account's state that are compared
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+    pub lamports: u64,
+    #[serde(serialize_with = "serialize_display")]
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: Epoch,
+    pub data_len: usize,
+    #[serde(serialize_with = "serialize_display")]
+    pub data_hash: Hash,
+}
+
+impl From<&AccountSharedData> for AccountState {
+    fn from(account: &AccountSharedData) -> Self {
+        Self {
+            lamports: account.lamports(),
+            owner: *account.owner(),
+            executable: account.executable(),
+            rent_epoch: account.rent_epoch(),
+            data_len: account.data().len(),
+            data_hash: hash(account.data()),
+        }
+    }
+}
+
+impl fmt::Display for AccountState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} PAY, owner: {}, executable: {}, rent_epoch: {}, data_len: {}, data_hash: {}",
+            lamports_to_pay(self.lamports),
+            self.owner,
+            self.executable,
+            self.rent_epoch,
+            self.data_len,
+            self.data_hash
+        )
+    }
+}
+
+/// The states of the accounts of a bank, see `AccountsSnapshot::from_bank()`
+pub struct AccountsSnapshot {
+    pub slot: Slot,
+    pub bank_hash: Hash,
+    pub accounts: BTreeMap<Pubkey, AccountState>,
+}
+
+impl AccountsSnapshot {
+    /// Collect the account states of `bank`, sysvars only if `include_sysvars` is set
+    pub fn from_bank(bank: &Bank, include_sysvars: bool) -> Self {
+        let accounts = bank
+            .get_all_accounts_with_modified_slots()
+            .unwrap()
+            .into_iter()
+            .filter(|(pubkey, _account, _slot)| include_sysvars || !sysvar::is_sysvar_id(pubkey))
+            .map(|(pubkey, account, _slot)| (pubkey, AccountState::from(&account)))
+            .collect();
+        Self {
+            slot: bank.slot(),
+            bank_hash: bank.hash(),
+            accounts,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountEntry {
+    #[serde(serialize_with = "serialize_display")]
+    pub pubkey: Pubkey,
+    #[serde(flatten)]
+    pub state: AccountState,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedAccount {
+    #[serde(serialize_with = "serialize_display")]
+    pub pubkey: Pubkey,
+    /// Names of the fields that differ
+    pub changes: Vec<&'static str>,
+    pub base: AccountState,
+    pub target: AccountState,
+}
+
+impl ChangedAccount {
+    fn new(pubkey: Pubkey, base: AccountState, target: AccountState) -> Option<Self> {
+        let changes: Vec<_> = [
+            ("lamports", base.lamports != target.lamports),
+            ("owner", base.owner != target.owner),
+            ("executable", base.executable != target.executable),
+            ("rentEpoch", base.rent_epoch != target.rent_epoch),
+            ("data", base.data_hash != target.data_hash),
+        ]
+        .iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| *field)
+        .collect();
+        (!changes.is_empty()).then(|| Self {
+            pubkey,
+            changes,
+            base,
+            target,
+        })
+    }
+}
+
+/// The difference between the accounts of two banks
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsDiff {
+    pub base_slot: Slot,
+    #[serde(serialize_with = "serialize_display")]
+    pub base_bank_hash: Hash,
+    pub target_slot: Slot,
+    #[serde(serialize_with = "serialize_display")]
+    pub target_bank_hash: Hash,
+    pub added: Vec<AccountEntry>,
+    pub removed: Vec<AccountEntry>,
+    pub changed: Vec<ChangedAccount>,
+}
+
+impl AccountsDiff {
+    /// Diff the accounts of `base` and `target`.  If `owners` is not empty only accounts owned
+    /// by one of them, in either bank, are compared
+    pub fn new(base: AccountsSnapshot, target: AccountsSnapshot, owners: &HashSet<Pubkey>) -> Self {
+        let is_selected = |state: &AccountState| owners.is_empty() || owners.contains(&state.owner);
+        let mut added = vec![];
+        let mut removed = vec![];
+        let mut changed = vec![];
+        let mut target_accounts = target.accounts;
+        for (pubkey, base_state) in base.accounts {
+            match target_accounts.remove(&pubkey) {
+                None => {
+                    if is_selected(&base_state) {
+                        removed.push(AccountEntry {
+                            pubkey,
+                            state: base_state,
+                        });
+                    }
+                }
+                Some(target_state) => {
+                    if is_selected(&base_state) || is_selected(&target_state) {
+                        changed.extend(ChangedAccount::new(pubkey, base_state, target_state));
+                    }
+                }
+            }
+        }
+        added.extend(
+            target_accounts
+                .into_iter()
+                .filter(|(_, state)| is_selected(state))
+                .map(|(pubkey, state)| AccountEntry { pubkey, state }),
+        );
+        Self {
+            base_slot: base.slot,
+            base_bank_hash: base.bank_hash,
+            target_slot: target.slot,
+            target_bank_hash: target.bank_hash,
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+impl QuietDisplay for AccountsDiff {}
+impl VerboseDisplay for AccountsDiff {}
+
+impl fmt::Display for AccountsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Base:   slot {}, bank hash {}",
+            self.base_slot, self.base_bank_hash
+        )?;
+        writeln!(
+            f,
+            "Target: slot {}, bank hash {}",
+            self.target_slot, self.target_bank_hash
+        )?;
+        for entry in &self.added {
+            writeln!(f, "+ {}: {}", entry.pubkey, entry.state)?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {}: {}", entry.pubkey, entry.state)?;
+        }
+        for account in &self.changed {
+            writeln!(
+                f,
+                "~ {}: {} changed",
+                account.pubkey,
+                account.changes.join(", ")
+            )?;
+            writeln!(f, "    base:   {}", account.base)?;
+            writeln!(f, "    target: {}", account.target)?;
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} changed accounts",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_state(lamports: u64, owner: &Pubkey, data: &[u8]) -> AccountState {
+        AccountState {
+            lamports,
+            owner: *owner,
+            executable: false,
+            rent_epoch: 0,
+            data_len: data.len(),
+            data_hash: hash(data),
+        }
+    }
+
+    fn snapshot(slot: Slot, accounts: &[(Pubkey, AccountState)]) -> AccountsSnapshot {
+        AccountsSnapshot {
+            slot,
+            bank_hash: Hash::new_unique(),
+            accounts: accounts.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn test_accounts_diff() {
+        let program_a = Pubkey::new_unique();
+        let program_b = Pubkey::new_unique();
+        let unchanged = Pubkey::new_unique();
+        let changed = Pubkey::new_unique();
+        let reassigned = Pubkey::new_unique();
+        let removed = Pubkey::new_unique();
+        let added = Pubkey::new_unique();
+        let base_accounts = [
+            (unchanged, account_state(1, &program_a, &[1])),
+            (changed, account_state(1, &program_a, &[1])),
+            (reassigned, account_state(1, &program_a, &[1])),
+            (removed, account_state(1, &program_b, &[])),
+        ];
+        let target_accounts = [
+            (unchanged, account_state(1, &program_a, &[1])),
+            (changed, account_state(2, &program_a, &[2])),
+            (reassigned, account_state(1, &program_b, &[1])),
+            (added, account_state(1, &program_b, &[])),
+        ];
+
+        let diff = AccountsDiff::new(
+            snapshot(1, &base_accounts),
+            snapshot(2, &target_accounts),
+            &HashSet::new(),
+        );
+        assert_eq!(diff.base_slot, 1);
+        assert_eq!(diff.target_slot, 2);
+        assert_eq!(
+            diff.added,
+            vec![AccountEntry {
+                pubkey: added,
+                state: account_state(1, &program_b, &[]),
+            }]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![AccountEntry {
+                pubkey: removed,
+                state: account_state(1, &program_b, &[]),
+            }]
+        );
+        let mut changes: Vec<_> = diff
+            .changed
+            .iter()
+            .map(|account| (account.pubkey, account.changes.clone()))
+            .collect();
+        changes.sort();
+        let mut expected_changes = vec![
+            (changed, vec!["lamports", "data"]),
+            (reassigned, vec!["owner"]),
+        ];
+        expected_changes.sort();
+        assert_eq!(changes, expected_changes);
+
+        // Only accounts owned by program A in either bank
+        let diff = AccountsDiff::new(
+            snapshot(1, &base_accounts),
+            snapshot(2, &target_accounts),
+            &[program_a].into_iter().collect(),
+        );
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 2);
+    }
+
+    #[test]
+    fn test_accounts_diff_json() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let diff = AccountsDiff::new(
+            snapshot(1, &[]),
+            snapshot(2, &[(pubkey, account_state(5, &owner, &[]))]),
+            &HashSet::new(),
+        );
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["baseSlot"], 1);
+        assert_eq!(json["added"][0]["pubkey"], pubkey.to_string());
+        assert_eq!(json["added"][0]["owner"], owner.to_string());
+        assert_eq!(json["added"][0]["lamports"], 5);
+        assert_eq!(json["added"][0]["dataHash"], hash(&[]).to_string());
+    }
+}
@@ -17,6 +17,7 @@ use {
             is_parsable, is_pow2, is_pubkey, is_pubkey_or_keypair, is_slot, is_valid_percentage,
         },
     },
+    paychains_cli_output::OutputFormat,
     paychains_core::system_monitor_service::SystemMonitorService,
     paychains_entry::entry::Entry,
     paychains_ledger::{
@@ -29,14 +30,16 @@ use {
     },
     paychains_measure::measure::Measure,
     paychains_runtime::{
-        accounts_db::AccountsDbConfig,
-        accounts_index::{AccountsIndexConfig, ScanConfig},
+        accounts_db::{AccountShrinkThreshold, AccountsDbConfig},
+        accounts_index::{AccountSecondaryIndexes, AccountsIndexConfig, ScanConfig},
         bank::{Bank, RewardCalculationEvent},
         bank_forks::BankForks,
         cost_model::CostModel,
         cost_tracker::CostTracker,
         hardened_unpack::{open_genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
-        snapshot_archive_info::SnapshotArchiveInfoGetter,
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+        },
         snapshot_config::SnapshotConfig,
         snapshot_utils::{
             self, ArchiveFormat, SnapshotVersion, DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
@@ -66,7 +69,7 @@ use {
     std::{
         collections::{BTreeMap, BTreeSet, HashMap, HashSet},
         ffi::OsStr,
-        fs::File,
+        fs::{self, File},
         io::{self, stdout, BufRead, BufReader, Write},
         path::{Path, PathBuf},
         process::{exit, Command, Stdio},
@@ -78,6 +81,8 @@ use {
     },
 };
 
+mod accounts_diff;
+use accounts_diff::*;
 mod bigtable;
 use bigtable::*;
 mod ledger_path;
@@ -695,6 +700,38 @@ fn hardforks_of(matches: &ArgMatches<'_>, name: &str) -> Option<Vec<Slot>> {
     }
 }
 
+fn accounts_index_config_of(
+    arg_matches: &ArgMatches<'_>,
+    ledger_path: &Path,
+) -> AccountsIndexConfig {
+    let mut accounts_index_config = AccountsIndexConfig::default();
+    if let Some(bins) = value_t!(arg_matches, "accounts_index_bins", usize).ok() {
+        accounts_index_config.bins = Some(bins);
+    }
+
+    if let Some(limit) = value_t!(arg_matches, "accounts_index_memory_limit_mb", usize).ok() {
+        accounts_index_config.index_limit_mb = Some(limit);
+    }
+
+    if let Some(limit) = value_t!(arg_matches, "accounts_index_memory_limit_bytes", usize).ok() {
+        accounts_index_config.index_limit_bytes = Some(limit);
+    }
+
+    let mut accounts_index_paths: Vec<PathBuf> = if arg_matches.is_present("accounts_index_path") {
+        values_t_or_exit!(arg_matches, "accounts_index_path", String)
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+    } else {
+        vec![]
+    };
+    if accounts_index_paths.is_empty() {
+        accounts_index_paths = vec![ledger_path.join("accounts_index")];
+    }
+    accounts_index_config.drives = Some(accounts_index_paths);
+    accounts_index_config
+}
+
 fn load_bank_forks(
     arg_matches: &ArgMatches,
     genesis_config: &GenesisConfig,
@@ -883,6 +920,13 @@ fn main() {
         .validator(is_parsable::<usize>)
         .takes_value(true)
         .help("How much memory the accounts index can consume. If this is exceeded, some account index entries will be stored on disk. If missing, the entire index is stored in memory.");
+    let accounts_index_limit_bytes = Arg::with_name("accounts_index_memory_limit_bytes")
+        .long("accounts-index-memory-limit-bytes")
+        .value_name("BYTES")
+        .validator(is_parsable::<usize>)
+        .takes_value(true)
+        .conflicts_with("accounts_index_memory_limit_mb")
+        .help("Same as --accounts-index-memory-limit-mb, in bytes");
     let accountsdb_skip_shrink = Arg::with_name("accounts_db_skip_shrink")
         .long("accounts-db-skip-shrink")
         .help(
@@ -1044,7 +1088,8 @@ fn main() {
                 .takes_value(true)
                 .possible_values(&["json", "json-compact"])
                 .help("Return information in specified output format, \
-                       currently only available for bigtable subcommands"),
+                       currently only available for bigtable, diff-accounts and \
+                       accounts-index-stats subcommands"),
         )
         .arg(
             Arg::with_name("verbose")
@@ -1223,6 +1268,19 @@ fn main() {
             .arg(&starting_slot_arg)
             .arg(&allow_dead_slots_arg)
         )
+        .subcommand(
+            SubCommand::with_name("accounts-index-stats")
+            .about("Print the occupancy and hit rates of the accounts index after loading the bank")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&accounts_index_path_arg)
+            .arg(&halt_at_slot_arg)
+            .arg(&accounts_index_bins)
+            .arg(&accounts_index_limit)
+            .arg(&accounts_index_limit_bytes)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+        )
         .subcommand(
             SubCommand::with_name("verify")
             .about("Verify the ledger")
@@ -1233,6 +1291,7 @@ fn main() {
             .arg(&limit_load_slot_count_from_snapshot_arg)
             .arg(&accounts_index_bins)
             .arg(&accounts_index_limit)
+            .arg(&accounts_index_limit_bytes)
             .arg(&accountsdb_skip_shrink)
             .arg(&accounts_filler_count)
             .arg(&verify_index_arg)
@@ -1448,6 +1507,79 @@ fn main() {
                     .help("Do not print account data when printing account contents."),
             )
             .arg(&max_genesis_archive_unpacked_size_arg)
+        ).subcommand(
+            SubCommand::with_name("diff-accounts")
+            .about("Compare the accounts of two banks, each loaded from snapshot archives or \
+                    by processing the ledger up to a slot")
+            .arg(&no_snapshot_arg)
+            .arg(&account_paths_arg)
+            .arg(&hard_forks_arg)
+            .arg(&max_genesis_archive_unpacked_size_arg)
+            .arg(
+                Arg::with_name("base_snapshot_archive")
+                    .long("base-snapshot-archive")
+                    .value_name("ARCHIVE")
+                    .takes_value(true)
+                    .required_unless("base_slot")
+                    .conflicts_with("base_slot")
+                    .help("Load the base bank from this full snapshot archive"),
+            )
+            .arg(
+                Arg::with_name("base_incremental_snapshot_archive")
+                    .long("base-incremental-snapshot-archive")
+                    .value_name("ARCHIVE")
+                    .takes_value(true)
+                    .requires("base_snapshot_archive")
+                    .help("Apply this incremental snapshot archive to the base bank"),
+            )
+            .arg(
+                Arg::with_name("base_slot")
+                    .long("base-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .help("Load the base bank by processing the ledger up to SLOT"),
+            )
+            .arg(
+                Arg::with_name("target_snapshot_archive")
+                    .long("target-snapshot-archive")
+                    .value_name("ARCHIVE")
+                    .takes_value(true)
+                    .required_unless("target_slot")
+                    .conflicts_with("target_slot")
+                    .help("Load the target bank from this full snapshot archive"),
+            )
+            .arg(
+                Arg::with_name("target_incremental_snapshot_archive")
+                    .long("target-incremental-snapshot-archive")
+                    .value_name("ARCHIVE")
+                    .takes_value(true)
+                    .requires("target_snapshot_archive")
+                    .help("Apply this incremental snapshot archive to the target bank"),
+            )
+            .arg(
+                Arg::with_name("target_slot")
+                    .long("target-slot")
+                    .value_name("SLOT")
+                    .validator(is_slot)
+                    .takes_value(true)
+                    .help("Load the target bank by processing the ledger up to SLOT"),
+            )
+            .arg(
+                Arg::with_name("program")
+                    .long("program")
+                    .value_name("PUBKEY")
+                    .takes_value(true)
+                    .multiple(true)
+                    .validator(is_pubkey)
+                    .help("Only compare accounts owned by this program in either bank"),
+            )
+            .arg(
+                Arg::with_name("include_sysvars")
+                    .long("include-sysvars")
+                    .takes_value(false)
+                    .help("Include sysvars too"),
+            )
         ).subcommand(
             SubCommand::with_name("capitalization")
             .about("Print capitalization (aka, total supply) while checksumming it")
@@ -1971,38 +2103,62 @@ fn main() {
                     );
                 }
             }
-            ("verify", Some(arg_matches)) => {
-                let mut accounts_index_config = AccountsIndexConfig::default();
-                if let Some(bins) = value_t!(arg_matches, "accounts_index_bins", usize).ok() {
-                    accounts_index_config.bins = Some(bins);
+            ("accounts-index-stats", Some(arg_matches)) => {
+                let accounts_db_config = Some(AccountsDbConfig {
+                    index: Some(accounts_index_config_of(arg_matches, &ledger_path)),
+                    accounts_hash_cache_path: Some(ledger_path.clone()),
+                    ..AccountsDbConfig::default()
+                });
+                let process_options = ProcessOptions {
+                    dev_halt_at_slot: value_t!(arg_matches, "halt_at_slot", Slot).ok(),
+                    new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                    poh_verify: false,
+                    accounts_db_config,
+                    ..ProcessOptions::default()
+                };
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let blockstore = open_blockstore(
+                    &ledger_path,
+                    AccessType::TryPrimaryThenSecondary,
+                    wal_recovery_mode,
+                );
+                match load_bank_forks(
+                    arg_matches,
+                    &genesis_config,
+                    &blockstore,
+                    process_options,
+                    snapshot_archive_path,
+                ) {
+                    Ok((bank_forks, ..)) => {
+                        let occupancy = bank_forks
+                            .working_bank()
+                            .accounts()
+                            .accounts_db
+                            .accounts_index
+                            .occupancy();
+                        match arg_matches.value_of("output_format") {
+                            Some("json") => {
+                                println!("{}", serde_json::to_string_pretty(&occupancy).unwrap())
+                            }
+                            Some("json-compact") => {
+                                println!("{}", serde_json::to_string(&occupancy).unwrap())
+                            }
+                            _ => print!("{}", occupancy),
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("Failed to load ledger: {:?}", err);
+                        exit(1);
+                    }
                 }
+            }
+            ("verify", Some(arg_matches)) => {
+                let accounts_index_config = accounts_index_config_of(arg_matches, &ledger_path);
 
                 let exit_signal = Arc::new(AtomicBool::new(false));
                 let system_monitor_service =
                     SystemMonitorService::new(Arc::clone(&exit_signal), false);
 
-                if let Some(limit) =
-                    value_t!(arg_matches, "accounts_index_memory_limit_mb", usize).ok()
-                {
-                    accounts_index_config.index_limit_mb = Some(limit);
-                }
-
-                {
-                    let mut accounts_index_paths: Vec<PathBuf> =
-                        if arg_matches.is_present("accounts_index_path") {
-                            values_t_or_exit!(arg_matches, "accounts_index_path", String)
-                                .into_iter()
-                                .map(PathBuf::from)
-                                .collect()
-                        } else {
-                            vec![]
-                        };
-                    if accounts_index_paths.is_empty() {
-                        accounts_index_paths = vec![ledger_path.join("accounts_index")];
-                    }
-                    accounts_index_config.drives = Some(accounts_index_paths);
-                }
-
                 let filler_account_count =
                     value_t!(arg_matches, "accounts_filler_count", usize).ok();
 
@@ -2559,6 +2715,134 @@ fn main() {
 
                 println!("{:#?}", total_accounts_stats);
             }
+            ("diff-accounts", Some(arg_matches)) => {
+                let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                let include_sysvars = arg_matches.is_present("include_sysvars");
+                let owners: HashSet<_> = pubkeys_of(arg_matches, "program")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+
+                let load_accounts = |bank_name: &str| {
+                    let mut measure = Measure::start("loading bank");
+                    let snapshot_archive =
+                        arg_matches.value_of(format!("{}_snapshot_archive", bank_name));
+                    let accounts = if let Some(snapshot_archive) = snapshot_archive {
+                        let full_snapshot_archive_info =
+                            FullSnapshotArchiveInfo::new_from_path(PathBuf::from(snapshot_archive))
+                                .unwrap_or_else(|err| {
+                                    eprintln!(
+                                        "Invalid snapshot archive {}: {}",
+                                        snapshot_archive, err
+                                    );
+                                    exit(1);
+                                });
+                        let incremental_snapshot_archive_info = arg_matches
+                            .value_of(format!("{}_incremental_snapshot_archive", bank_name))
+                            .map(|snapshot_archive| {
+                                IncrementalSnapshotArchiveInfo::new_from_path(PathBuf::from(
+                                    snapshot_archive,
+                                ))
+                                .unwrap_or_else(|err| {
+                                    eprintln!(
+                                        "Invalid incremental snapshot archive {}: {}",
+                                        snapshot_archive, err
+                                    );
+                                    exit(1);
+                                })
+                            });
+                        // Removed once the accounts are loaded
+                        let scratch_dir = tempfile::Builder::new()
+                            .prefix("diff-accounts.")
+                            .tempdir_in(&ledger_path)
+                            .unwrap_or_else(|err| {
+                                eprintln!(
+                                    "Unable to create a directory in {:?}: {}",
+                                    ledger_path, err
+                                );
+                                exit(1);
+                            });
+                        let account_paths = vec![scratch_dir.path().join("accounts")];
+                        let bank_snapshots_dir = scratch_dir.path().join("snapshot");
+                        for dir in account_paths.iter().chain([&bank_snapshots_dir]) {
+                            fs::create_dir_all(dir).unwrap_or_else(|err| {
+                                eprintln!("Unable to create {:?}: {}", dir, err);
+                                exit(1);
+                            });
+                        }
+                        let (bank, _) = snapshot_utils::bank_from_snapshot_archives(
+                            &account_paths,
+                            &bank_snapshots_dir,
+                            &full_snapshot_archive_info,
+                            incremental_snapshot_archive_info.as_ref(),
+                            &genesis_config,
+                            None,
+                            None,
+                            AccountSecondaryIndexes::default(),
+                            true,
+                            None,
+                            AccountShrinkThreshold::default(),
+                            false,
+                            true,
+                            false,
+                            None,
+                            None,
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load the {} bank: {}", bank_name, err);
+                            exit(1);
+                        });
+                        AccountsSnapshot::from_bank(&bank, include_sysvars)
+                    } else {
+                        let slot =
+                            value_t_or_exit!(arg_matches, format!("{}_slot", bank_name), Slot);
+                        let process_options = ProcessOptions {
+                            dev_halt_at_slot: Some(slot),
+                            new_hard_forks: hardforks_of(arg_matches, "hard_forks"),
+                            poh_verify: false,
+                            ..ProcessOptions::default()
+                        };
+                        let blockstore = open_blockstore(
+                            &ledger_path,
+                            AccessType::TryPrimaryThenSecondary,
+                            wal_recovery_mode.clone(),
+                        );
+                        let (bank_forks, ..) = load_bank_forks(
+                            arg_matches,
+                            &genesis_config,
+                            &blockstore,
+                            process_options,
+                            snapshot_archive_path.clone(),
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load ledger: {:?}", err);
+                            exit(1);
+                        });
+                        let bank = bank_forks.working_bank();
+                        if bank.slot() != slot {
+                            eprintln!(
+                                "Unable to load the {} bank at slot {}, the ledger was loaded up \
+                                 to slot {}. A snapshot newer than the slot can be skipped with \
+                                 --no-snapshot",
+                                bank_name,
+                                slot,
+                                bank.slot()
+                            );
+                            exit(1);
+                        }
+                        AccountsSnapshot::from_bank(&bank, include_sysvars)
+                    };
+                    measure.stop();
+                    info!("{} for the {} bank", measure, bank_name);
+                    accounts
+                };
+                let base = load_accounts("base");
+                let target = load_accounts("target");
+
+                let diff = AccountsDiff::new(base, target, &owners);
+                println!("{}", output_format.formatted_string(&diff));
+            }
             ("capitalization", Some(arg_matches)) => {
                 let dev_halt_at_slot = value_t!(arg_matches, "halt_at_slot", Slot).ok();
                 let process_options = ProcessOptions {
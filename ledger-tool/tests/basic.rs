@@ -47,3 +47,36 @@ fn nominal() {
     assert!(output.status.success());
     assert_eq!(count_newlines(&output.stdout), ticks + meta_lines);
 }
+
+#[test]
+fn diff_accounts() {
+    let genesis_config = create_genesis_config(100).genesis_config;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger!(&genesis_config);
+    let ledger_path = ledger_path.to_str().unwrap();
+
+    // A bank compared to itself has no differences
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "diff-accounts",
+        "--base-slot",
+        "0",
+        "--target-slot",
+        "0",
+        "--output",
+        "json",
+    ]);
+    assert!(output.status.success());
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diff["baseSlot"], 0);
+    assert_eq!(diff["added"], serde_json::json!([]));
+    assert_eq!(diff["removed"], serde_json::json!([]));
+    assert_eq!(diff["changed"], serde_json::json!([]));
+
+    // Each bank must be loaded from somewhere
+    assert!(
+        !run_ledger_tool(&["-l", ledger_path, "diff-accounts", "--base-slot", "0"])
+            .status
+            .success()
+    );
+}
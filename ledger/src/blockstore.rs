@@ -40,7 +40,9 @@ use {
         timing::timestamp,
         transaction::VersionedTransaction,
     },
-    paychains_storage_proto::{StoredExtendedRewards, StoredTransactionStatusMeta},
+    paychains_storage_proto::{
+        convert::generated, StoredExtendedRewards, StoredTransactionStatusMeta,
+    },
     paychains_transaction_status::{
         ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
         ConfirmedTransactionWithStatusMeta, Rewards, TransactionStatusMeta,
@@ -1989,8 +1991,12 @@ impl Blockstore {
                 let rewards = self
                     .rewards_cf
                     .get_protobuf_or_bincode::<StoredExtendedRewards>(slot)?
-                    .unwrap_or_default()
-                    .into();
+                    .unwrap_or_default();
+                let num_partitions = rewards
+                    .num_partitions
+                    .as_ref()
+                    .map(|num_partitions| num_partitions.num_partitions);
+                let rewards = rewards.into();
 
                 // The Blocktime and BlockHeight column families are updated asynchronously; they
                 // may not be written by the time the complete slot entries are available. In this
@@ -2007,6 +2013,7 @@ impl Blockstore {
                     transactions: self
                         .map_transactions_to_statuses(slot, slot_transaction_iterator)?,
                     rewards,
+                    num_partitions,
                     block_time,
                     block_height,
                 };
@@ -2725,8 +2732,27 @@ impl Blockstore {
             .map(|result| result.map(|option| option.into()))
     }
 
-    pub fn write_rewards(&self, index: Slot, rewards: Rewards) -> Result<()> {
-        let rewards = rewards.into();
+    /// The number of partitions over which the stake rewards calculated in the block at `index`
+    /// are distributed, one per block following it
+    pub fn read_num_reward_partitions(&self, index: Slot) -> Result<Option<u64>> {
+        Ok(self
+            .rewards_cf
+            .get_protobuf_or_bincode::<StoredExtendedRewards>(index)?
+            .and_then(|rewards| rewards.num_partitions)
+            .map(|num_partitions| num_partitions.num_partitions))
+    }
+
+    pub fn write_rewards(
+        &self,
+        index: Slot,
+        rewards: Rewards,
+        num_partitions: Option<u64>,
+    ) -> Result<()> {
+        let rewards = generated::Rewards {
+            num_partitions: num_partitions
+                .map(|num_partitions| generated::NumPartitions { num_partitions }),
+            ..rewards.into()
+        };
         self.rewards_cf.put_protobuf(index, &rewards)
     }
 
@@ -4151,7 +4177,6 @@ pub mod tests {
             signature::Signature,
             transaction::{Transaction, TransactionError},
         },
-        paychains_storage_proto::convert::generated,
         paychains_transaction_status::{InnerInstructions, Reward, Rewards, TransactionTokenBalance},
         std::{thread::Builder, time::Duration},
     };
@@ -6341,6 +6366,7 @@ pub mod tests {
             blockhash: blockhash.to_string(),
             previous_blockhash: Hash::default().to_string(),
             rewards: vec![],
+            num_partitions: None,
             block_time: None,
             block_height: None,
         };
@@ -6355,6 +6381,7 @@ pub mod tests {
             blockhash: blockhash.to_string(),
             previous_blockhash: blockhash.to_string(),
             rewards: vec![],
+            num_partitions: None,
             block_time: None,
             block_height: None,
         };
@@ -6372,6 +6399,7 @@ pub mod tests {
             blockhash: blockhash.to_string(),
             previous_blockhash: blockhash.to_string(),
             rewards: vec![],
+            num_partitions: None,
             block_time: None,
             block_height: None,
         };
@@ -8529,6 +8557,41 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_read_num_reward_partitions() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let rewards: Rewards = vec![Reward {
+            pubkey: paychains_sdk::pubkey::new_rand().to_string(),
+            lamports: 42,
+            post_balance: 42,
+            reward_type: Some(RewardType::Voting),
+            commission: Some(10),
+        }];
+        blockstore.write_rewards(0, rewards.clone(), None).unwrap();
+        blockstore
+            .write_rewards(1, rewards.clone(), Some(3))
+            .unwrap();
+        let deprecated_rewards: StoredExtendedRewards =
+            generated::Rewards::from(rewards.clone()).into();
+        blockstore
+            .rewards_cf
+            .put_bytes(2, &serialize(&deprecated_rewards).unwrap())
+            .unwrap();
+
+        assert_eq!(blockstore.read_num_reward_partitions(0).unwrap(), None);
+        assert_eq!(blockstore.read_num_reward_partitions(1).unwrap(), Some(3));
+        assert_eq!(blockstore.read_num_reward_partitions(2).unwrap(), None);
+        assert_eq!(blockstore.read_num_reward_partitions(3).unwrap(), None);
+        for slot in 0..3 {
+            assert_eq!(
+                blockstore.read_rewards(slot).unwrap(),
+                Some(rewards.clone())
+            );
+        }
+    }
+
     #[test]
     fn test_transaction_status_protobuf_backward_compatability() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
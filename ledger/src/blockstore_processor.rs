@@ -37,6 +37,7 @@ use {
     },
     paychains_sdk::{
         clock::{Slot, MAX_PROCESSING_AGE},
+        compute_budget::ComputeBudget,
         feature_set,
         genesis_config::GenesisConfig,
         hash::Hash,
@@ -208,6 +209,10 @@ fn execute_batch(
         }
     }
 
+    // The accounts data allocated by the block only grows, so however the batches of the block
+    // interleave, the block exceeds the limit if and only if its batches together do
+    bank.check_accounts_data_block_limit()?;
+
     bank_utils::find_and_send_votes(
         batch.sanitized_transactions(),
         &tx_results,
@@ -477,6 +482,7 @@ pub struct ProcessOptions {
     pub accounts_db_config: Option<AccountsDbConfig>,
     pub verify_index: bool,
     pub shrink_ratio: AccountShrinkThreshold,
+    pub compute_budget: Option<ComputeBudget>,
 }
 
 pub fn process_blockstore(
@@ -499,7 +505,7 @@ pub fn process_blockstore(
     }
 
     // Setup bank for slot 0
-    let bank0 = Bank::new_with_paths(
+    let mut bank0 = Bank::new_with_paths(
         genesis_config,
         account_paths,
         opts.debug_keys.clone(),
@@ -511,6 +517,9 @@ pub fn process_blockstore(
         opts.accounts_db_config.clone(),
         accounts_update_notifier,
     );
+    if let Some(compute_budget) = opts.compute_budget {
+        bank0.set_compute_budget(Some(compute_budget));
+    }
     let bank0 = Arc::new(bank0);
     info!("processing ledger for slot 0...");
     let recyclers = VerifyRecyclers::default();
@@ -539,7 +548,7 @@ pub fn process_blockstore(
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn process_blockstore_from_root(
     blockstore: &Blockstore,
-    bank: Bank,
+    mut bank: Bank,
     opts: &ProcessOptions,
     recyclers: &VerifyRecyclers,
     transaction_status_sender: Option<&TransactionStatusSender>,
@@ -549,6 +558,9 @@ pub(crate) fn process_blockstore_from_root(
     timings: BankFromArchiveTimings,
     last_full_snapshot_slot: Slot,
 ) -> BlockstoreProcessorResult {
+    if let Some(compute_budget) = opts.compute_budget {
+        bank.set_compute_budget(Some(compute_budget));
+    }
     do_process_blockstore_from_root(
         blockstore,
         Arc::new(bank),
@@ -2514,6 +2526,77 @@ pub mod tests {
         assert_eq!(bank.get_balance(&keypair3.pubkey()), 2);
     }
 
+    #[test]
+    fn test_process_entries_accounts_data_block_limit() {
+        use {
+            paychains_runtime::block_cost_limits::MAX_ACCOUNT_DATA_BLOCK_LEN,
+            paychains_sdk::system_instruction::MAX_PERMITTED_DATA_LENGTH,
+        };
+
+        paychains_logger::setup();
+        // entries of one transaction each, creating an account from its own funder.  The
+        // entries don't conflict, so their batches are executed in parallel
+        let process_entries = |num_accounts: u64| {
+            // all features are active, including cap_accounts_data_size_per_block
+            let GenesisConfigInfo {
+                genesis_config,
+                mint_keypair,
+                ..
+            } = create_genesis_config(1_000_000_000_000_000);
+            let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+            let lamports =
+                bank.get_minimum_balance_for_rent_exemption(MAX_PERMITTED_DATA_LENGTH as usize);
+            let funders: Vec<_> = (0..num_accounts).map(|_| Keypair::new()).collect();
+            for funder in &funders {
+                bank.transfer(lamports * 2, &mint_keypair, &funder.pubkey())
+                    .unwrap();
+            }
+            let keypairs: Vec<_> = (0..num_accounts).map(|_| Keypair::new()).collect();
+            let transactions: Vec<_> = funders
+                .iter()
+                .zip(keypairs.iter())
+                .map(|(funder, keypair)| {
+                    system_transaction::create_account(
+                        funder,
+                        keypair,
+                        bank.last_blockhash(),
+                        lamports,
+                        MAX_PERMITTED_DATA_LENGTH,
+                        &Pubkey::new_unique(),
+                    )
+                })
+                .collect();
+            let mut hash = bank.last_blockhash();
+            let entries = transactions
+                .iter()
+                .map(|transaction| next_entry_mut(&mut hash, 1, vec![transaction.clone()]))
+                .collect();
+            let result = process_entries_for_tests(&bank, entries, false, None, None);
+
+            // no transaction fails because of the limit, whichever batch crosses it
+            for (transaction, keypair) in transactions.iter().zip(keypairs.iter()) {
+                assert_eq!(
+                    bank.get_signature_status(&transaction.signatures[0]),
+                    Some(Ok(()))
+                );
+                assert!(bank.get_account(&keypair.pubkey()).is_some());
+            }
+            assert_eq!(
+                bank.accounts_data_len_allocated(),
+                num_accounts * MAX_PERMITTED_DATA_LENGTH
+            );
+            result
+        };
+
+        // allocating up to the limit is a valid block, one account more is not
+        let num_accounts = MAX_ACCOUNT_DATA_BLOCK_LEN / MAX_PERMITTED_DATA_LENGTH;
+        assert_eq!(process_entries(num_accounts), Ok(()));
+        assert_eq!(
+            process_entries(num_accounts + 1),
+            Err(TransactionError::WouldExceedAccountDataBlockLimit)
+        );
+    }
+
     #[test]
     fn test_process_entries_2_txes_collision_and_error() {
         let GenesisConfigInfo {
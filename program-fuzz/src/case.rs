@@ -0,0 +1,263 @@
+//! Fuzz cases: the instruction data and account permutations of a single run
+
+use {
+    paychains_sdk::{
+        account::Account,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    rand::RngCore,
+    serde_derive::{Deserialize, Serialize},
+};
+
+/// Maximum number of account mutations generated for a single case
+pub const MAX_MUTATIONS: usize = 4;
+
+/// A source of fuzzing decisions, either a seeded generator or raw bytes from an external
+/// fuzzer such as cargo-fuzz
+pub trait Entropy {
+    fn next_u8(&mut self) -> u8;
+
+    /// Return a value in `0..bound`, or 0 if `bound` is 0
+    fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        let value = u16::from_le_bytes([self.next_u8(), self.next_u8()]);
+        value as usize % bound
+    }
+}
+
+/// Entropy drawn from a random number generator
+pub struct RngEntropy<R: RngCore>(pub R);
+
+impl<R: RngCore> Entropy for RngEntropy<R> {
+    fn next_u8(&mut self) -> u8 {
+        let mut byte = [0u8; 1];
+        self.0.fill_bytes(&mut byte);
+        byte[0]
+    }
+}
+
+/// Entropy drawn from a byte slice, yielding zeroes once the slice is exhausted
+pub struct ByteEntropy<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteEntropy<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+}
+
+impl<'a> Entropy for ByteEntropy<'a> {
+    fn next_u8(&mut self) -> u8 {
+        let byte = self.bytes.get(self.position).copied().unwrap_or_default();
+        self.position = self.position.saturating_add(1);
+        byte
+    }
+}
+
+/// A change to the baseline accounts of the instruction under test
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountMutation {
+    /// Toggle the signer flag of the account meta at this index
+    FlipSigner(usize),
+    /// Toggle the writable flag of the account meta at this index
+    FlipWritable(usize),
+    /// Assign a new owner to the account at this index
+    SetOwner { index: usize, owner: Pubkey },
+    /// Replace the account meta at `index` with a copy of the one at `of`
+    Duplicate { index: usize, of: usize },
+    /// Swap the positions of two account metas
+    Swap { a: usize, b: usize },
+}
+
+/// The baseline instruction accounts that each case mutates
+#[derive(Clone, Debug, Default)]
+pub struct FuzzAccounts {
+    pub metas: Vec<AccountMeta>,
+    pub accounts: Vec<Account>,
+}
+
+impl FuzzAccounts {
+    pub fn len(&self) -> usize {
+        self.metas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metas.is_empty()
+    }
+}
+
+/// A single fuzzed instruction, replayable from its serialized form
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzCase {
+    pub data: Vec<u8>,
+    pub mutations: Vec<AccountMutation>,
+}
+
+impl FuzzCase {
+    /// Generate a case for an instruction with `num_accounts` accounts, drawing new owners
+    /// from `owners`
+    pub fn generate<E: Entropy>(
+        entropy: &mut E,
+        num_accounts: usize,
+        owners: &[Pubkey],
+        max_data_len: usize,
+    ) -> Self {
+        let data_len = entropy.below(max_data_len.saturating_add(1));
+        let data = (0..data_len).map(|_| entropy.next_u8()).collect();
+
+        let mut mutations = vec![];
+        if num_accounts > 0 {
+            let num_mutations = entropy.below(MAX_MUTATIONS + 1);
+            for _ in 0..num_mutations {
+                let index = entropy.below(num_accounts);
+                let mutation = match entropy.below(5) {
+                    0 => AccountMutation::FlipSigner(index),
+                    1 => AccountMutation::FlipWritable(index),
+                    2 if !owners.is_empty() => AccountMutation::SetOwner {
+                        index,
+                        owner: owners[entropy.below(owners.len())],
+                    },
+                    3 => AccountMutation::Duplicate {
+                        index,
+                        of: entropy.below(num_accounts),
+                    },
+                    _ => AccountMutation::Swap {
+                        a: index,
+                        b: entropy.below(num_accounts),
+                    },
+                };
+                mutations.push(mutation);
+            }
+        }
+        Self { data, mutations }
+    }
+
+    /// Apply this case's mutations to the baseline accounts, returning the instruction to
+    /// run and the account states to store before running it
+    pub fn apply(
+        &self,
+        program_id: &Pubkey,
+        baseline: &FuzzAccounts,
+    ) -> (Instruction, Vec<(Pubkey, Account)>) {
+        let mut metas = baseline.metas.clone();
+        let mut accounts: Vec<_> = baseline
+            .metas
+            .iter()
+            .map(|meta| meta.pubkey)
+            .zip(baseline.accounts.iter().cloned())
+            .collect();
+
+        for mutation in &self.mutations {
+            match *mutation {
+                AccountMutation::FlipSigner(index) => {
+                    if let Some(meta) = metas.get_mut(index) {
+                        meta.is_signer = !meta.is_signer;
+                    }
+                }
+                AccountMutation::FlipWritable(index) => {
+                    if let Some(meta) = metas.get_mut(index) {
+                        meta.is_writable = !meta.is_writable;
+                    }
+                }
+                AccountMutation::SetOwner { index, owner } => {
+                    if let Some(meta) = metas.get(index) {
+                        for (address, account) in accounts.iter_mut() {
+                            if *address == meta.pubkey {
+                                account.owner = owner;
+                            }
+                        }
+                    }
+                }
+                AccountMutation::Duplicate { index, of } => {
+                    if index < metas.len() && of < metas.len() {
+                        metas[index] = metas[of].clone();
+                    }
+                }
+                AccountMutation::Swap { a, b } => {
+                    if a < metas.len() && b < metas.len() {
+                        metas.swap(a, b);
+                    }
+                }
+            }
+        }
+
+        (
+            Instruction {
+                program_id: *program_id,
+                accounts: metas,
+                data: self.data.clone(),
+            },
+            accounts,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rand::SeedableRng, rand_chacha::ChaChaRng};
+
+    fn baseline() -> FuzzAccounts {
+        let metas = vec![
+            AccountMeta::new(Pubkey::new_unique(), true),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+        let accounts = vec![Account::default(), Account::default()];
+        FuzzAccounts { metas, accounts }
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let owners = [Pubkey::new_unique()];
+        let generate = |seed| {
+            let mut entropy = RngEntropy(ChaChaRng::seed_from_u64(seed));
+            (0..32)
+                .map(|_| FuzzCase::generate(&mut entropy, 2, &owners, 16))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(generate(42), generate(42));
+        assert_ne!(generate(42), generate(43));
+    }
+
+    #[test]
+    fn test_generate_from_exhausted_bytes() {
+        let mut entropy = ByteEntropy::new(&[]);
+        assert_eq!(
+            FuzzCase::generate(&mut entropy, 2, &[], 16),
+            FuzzCase::default()
+        );
+    }
+
+    #[test]
+    fn test_apply_mutations() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let baseline = baseline();
+        let case = FuzzCase {
+            data: vec![1, 2],
+            mutations: vec![
+                AccountMutation::FlipSigner(0),
+                AccountMutation::FlipWritable(1),
+                AccountMutation::SetOwner { index: 1, owner },
+                AccountMutation::Swap { a: 0, b: 1 },
+                AccountMutation::Duplicate { index: 0, of: 1 },
+                // out of range mutations are ignored
+                AccountMutation::FlipSigner(2),
+            ],
+        };
+        let (instruction, accounts) = case.apply(&program_id, &baseline);
+        assert_eq!(instruction.program_id, program_id);
+        assert_eq!(instruction.data, vec![1, 2]);
+        let expected_meta = AccountMeta::new(baseline.metas[0].pubkey, false);
+        assert_eq!(
+            instruction.accounts,
+            vec![expected_meta.clone(), expected_meta]
+        );
+        assert_eq!(accounts[0].1.owner, Pubkey::default());
+        assert_eq!(accounts[1].1.owner, owner);
+    }
+}
@@ -0,0 +1,364 @@
+//! The paychains-program-fuzz crate drives a `ProgramTest` bank with generated instructions to
+//! find account-validation bugs in programs.
+//!
+//! Each case runs one instruction against a fixed set of baseline accounts, after applying
+//! random instruction data and account permutations: signer and writable flips, owner swaps,
+//! duplicated and reordered accounts.  A case fails if the program panics or, for a BPF
+//! program, aborts without completing, if the runtime's
+//! account checks reject what the program did to its accounts (for example creating lamports
+//! out of thin air), or if a user supplied invariant does not hold.  Failing cases are shrunk
+//! before being reported and can be replayed with [`FuzzRunner::run_case`].
+//!
+//! Cases are generated deterministically from a seed with [`FuzzRunner::run`], or from the raw
+//! bytes supplied by an external fuzzer with [`FuzzRunner::run_bytes`], e.g. in a cargo-fuzz
+//! target:
+//!
+//! ```ignore
+//! fuzz_target!(|data: &[u8]| {
+//!     RUNNER.with(|runner| runner.borrow_mut().run_bytes(data).unwrap());
+//! });
+//! ```
+#![allow(clippy::integer_arithmetic)]
+
+pub mod case;
+pub mod minimize;
+
+use {
+    crate::case::{ByteEntropy, FuzzAccounts, FuzzCase, RngEntropy},
+    log::*,
+    paychains_program_test::{ProgramTest, ProgramTestContext},
+    paychains_runtime::bank::TransactionSimulationResult,
+    paychains_sdk::{
+        account::{Account, AccountSharedData},
+        instruction::{AccountMeta, InstructionError},
+        pubkey::Pubkey,
+        signature::Signer,
+        system_program,
+        transaction::{self, Transaction, TransactionError},
+    },
+    rand::SeedableRng,
+    rand_chacha::ChaChaRng,
+    std::{
+        any::Any,
+        mem,
+        panic::{self, AssertUnwindSafe},
+    },
+    thiserror::Error,
+    tokio::runtime::Runtime,
+};
+
+/// Default upper bound on the length of generated instruction data
+pub const DEFAULT_MAX_DATA_LEN: usize = 64;
+
+type Invariant = Box<dyn Fn(&FuzzCase, &FuzzOutcome) -> Result<(), String>>;
+
+/// The result of running a case that did not fail
+#[derive(Clone, Debug)]
+pub struct FuzzOutcome {
+    pub result: transaction::Result<()>,
+    pub logs: Vec<String>,
+    pub units_consumed: u64,
+    pub post_accounts: Vec<(Pubkey, AccountSharedData)>,
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum FuzzFailure {
+    #[error("program panicked: {0}")]
+    Panic(String),
+
+    #[error("runtime account check failed: {0}")]
+    AccountCheck(InstructionError),
+
+    #[error("invariant violated: {0}")]
+    Invariant(String),
+}
+
+impl FuzzFailure {
+    fn is_same_kind(&self, other: &Self) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+}
+
+/// A failing case found by [`FuzzRunner::run`]
+#[derive(Clone, Debug, Error)]
+#[error("case {iteration} of seed {seed} failed: {failure}, minimized case: {minimized:?}")]
+pub struct FuzzFinding {
+    pub seed: u64,
+    pub iteration: usize,
+    pub case: FuzzCase,
+    pub minimized: FuzzCase,
+    pub failure: FuzzFailure,
+}
+
+/// Instruction errors raised by the runtime when a program modifies accounts it may not
+fn is_account_check_error(err: &InstructionError) -> bool {
+    matches!(
+        err,
+        InstructionError::UnbalancedInstruction
+            | InstructionError::ModifiedProgramId
+            | InstructionError::ExternalAccountLamportSpend
+            | InstructionError::ExternalAccountDataModified
+            | InstructionError::ReadonlyLamportChange
+            | InstructionError::ReadonlyDataModified
+            | InstructionError::ExecutableLamportChange
+            | InstructionError::ExecutableDataModified
+            | InstructionError::ExecutableModified
+            | InstructionError::RentEpochModified
+            | InstructionError::AccountDataSizeChanged
+            | InstructionError::ExecutableAccountNotRentExempt
+    )
+}
+
+/// Instruction errors raised by the runtime when a BPF program aborts, e.g. on a panic or an
+/// access violation.  A native `processor!` program panics by unwinding instead
+fn is_program_abort_error(err: &InstructionError) -> bool {
+    matches!(
+        err,
+        InstructionError::ProgramFailedToComplete | InstructionError::ProgramFailedToRun
+    )
+}
+
+/// Classify an instruction error returned by a case
+fn check_instruction_error(err: &InstructionError, logs: &[String]) -> Result<(), FuzzFailure> {
+    if is_program_abort_error(err) {
+        // Prefer the panic message logged by the program over the bare error
+        let message = logs
+            .iter()
+            .rev()
+            .find(|log| log.contains("panicked at"))
+            .cloned()
+            .unwrap_or_else(|| err.to_string());
+        return Err(FuzzFailure::Panic(message));
+    }
+    if is_account_check_error(err) {
+        return Err(FuzzFailure::AccountCheck(err.clone()));
+    }
+    Ok(())
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Configures the program and accounts to fuzz
+pub struct Fuzzer {
+    program_test: ProgramTest,
+    program_id: Pubkey,
+    baseline: FuzzAccounts,
+    owners: Vec<Pubkey>,
+    max_data_len: usize,
+    invariants: Vec<Invariant>,
+}
+
+impl Fuzzer {
+    /// Fuzz instructions to `program_id`, which must be added to `program_test`
+    pub fn new(program_test: ProgramTest, program_id: Pubkey) -> Self {
+        Self {
+            program_test,
+            program_id,
+            baseline: FuzzAccounts::default(),
+            owners: vec![program_id, system_program::id()],
+            max_data_len: DEFAULT_MAX_DATA_LEN,
+            invariants: vec![],
+        }
+    }
+
+    /// Add an instruction account, stored with the state of `account` before every case
+    pub fn add_account(&mut self, meta: AccountMeta, account: Account) {
+        if !self.owners.contains(&account.owner) {
+            self.owners.push(account.owner);
+        }
+        self.baseline.metas.push(meta);
+        self.baseline.accounts.push(account);
+    }
+
+    /// Add a candidate owner for owner swaps, in addition to the program, the system program
+    /// and the owners of the baseline accounts
+    pub fn add_owner(&mut self, owner: Pubkey) {
+        if !self.owners.contains(&owner) {
+            self.owners.push(owner);
+        }
+    }
+
+    pub fn set_max_data_len(&mut self, max_data_len: usize) {
+        self.max_data_len = max_data_len;
+    }
+
+    /// Add a check run against the outcome of every case that did not otherwise fail
+    pub fn add_invariant<F>(&mut self, invariant: F)
+    where
+        F: Fn(&FuzzCase, &FuzzOutcome) -> Result<(), String> + 'static,
+    {
+        self.invariants.push(Box::new(invariant));
+    }
+
+    /// Start the test bank
+    pub fn start(self) -> FuzzRunner {
+        let runtime = Runtime::new().unwrap();
+        let context = runtime.block_on(self.program_test.start_with_context());
+        FuzzRunner {
+            runtime,
+            context,
+            program_id: self.program_id,
+            baseline: self.baseline,
+            owners: self.owners,
+            max_data_len: self.max_data_len,
+            invariants: self.invariants,
+        }
+    }
+}
+
+/// Runs fuzz cases against a started test bank
+pub struct FuzzRunner {
+    runtime: Runtime,
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    baseline: FuzzAccounts,
+    owners: Vec<Pubkey>,
+    max_data_len: usize,
+    invariants: Vec<Invariant>,
+}
+
+impl FuzzRunner {
+    pub fn context(&mut self) -> &mut ProgramTestContext {
+        &mut self.context
+    }
+
+    /// Run a single case, restoring the baseline accounts first
+    pub fn run_case(&mut self, case: &FuzzCase) -> Result<FuzzOutcome, FuzzFailure> {
+        let (instruction, accounts) = case.apply(&self.program_id, &self.baseline);
+        for (address, account) in accounts {
+            self.context
+                .set_account(&address, &AccountSharedData::from(account));
+        }
+
+        let recent_blockhash = self
+            .runtime
+            .block_on(self.context.banks_client.get_latest_blockhash())
+            .expect("latest blockhash");
+        // Simulation does not verify signatures, so signer flips need no keypairs
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction], Some(&self.context.payer.pubkey()));
+        transaction.message.recent_blockhash = recent_blockhash;
+
+        let context = &self.context;
+        let simulation = panic::catch_unwind(AssertUnwindSafe(|| {
+            context.simulate_transaction_unchecked(&transaction)
+        }))
+        .map_err(|payload| FuzzFailure::Panic(panic_message(payload)))?;
+        let outcome = match simulation {
+            Err(err) => FuzzOutcome {
+                result: Err(err),
+                logs: vec![],
+                units_consumed: 0,
+                post_accounts: vec![],
+            },
+            Ok(TransactionSimulationResult {
+                result,
+                logs,
+                post_simulation_accounts,
+                units_consumed,
+            }) => FuzzOutcome {
+                result,
+                logs,
+                units_consumed,
+                post_accounts: post_simulation_accounts,
+            },
+        };
+
+        if let Err(TransactionError::InstructionError(_, err)) = &outcome.result {
+            check_instruction_error(err, &outcome.logs)?;
+        }
+        for invariant in &self.invariants {
+            invariant(case, &outcome).map_err(FuzzFailure::Invariant)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Run the case generated from the bytes supplied by an external fuzzer
+    pub fn run_bytes(&mut self, bytes: &[u8]) -> Result<FuzzOutcome, FuzzFailure> {
+        let case = FuzzCase::generate(
+            &mut ByteEntropy::new(bytes),
+            self.baseline.len(),
+            &self.owners,
+            self.max_data_len,
+        );
+        self.run_case(&case)
+    }
+
+    /// Run `iterations` cases generated from `seed`, stopping at the first failure.  The same
+    /// seed always generates the same cases
+    pub fn run(&mut self, seed: u64, iterations: usize) -> Result<(), FuzzFinding> {
+        let mut entropy = RngEntropy(ChaChaRng::seed_from_u64(seed));
+        for iteration in 0..iterations {
+            let case = FuzzCase::generate(
+                &mut entropy,
+                self.baseline.len(),
+                &self.owners,
+                self.max_data_len,
+            );
+            if let Err(failure) = self.run_case(&case) {
+                warn!("fuzz case {} failed: {}, minimizing", iteration, failure);
+                let minimized = self.minimize(&case, &failure);
+                return Err(FuzzFinding {
+                    seed,
+                    iteration,
+                    case,
+                    minimized,
+                    failure,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Shrink a failing case to a smaller one that fails the same way
+    pub fn minimize(&mut self, case: &FuzzCase, failure: &FuzzFailure) -> FuzzCase {
+        minimize::minimize(case, |candidate| match self.run_case(candidate) {
+            Err(candidate_failure) => candidate_failure.is_same_kind(failure),
+            Ok(_) => false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_instruction_error() {
+        let logs = vec![
+            "Program log: panicked at 'config account must be read-only', src/lib.rs:12:9"
+                .to_string(),
+            "Program consumption: 199000 units remaining".to_string(),
+        ];
+        // an aborted BPF program is reported as a panic, with its logged message if any
+        assert_eq!(
+            check_instruction_error(&InstructionError::ProgramFailedToComplete, &logs),
+            Err(FuzzFailure::Panic(logs[0].clone()))
+        );
+        assert_eq!(
+            check_instruction_error(&InstructionError::ProgramFailedToRun, &[]),
+            Err(FuzzFailure::Panic(
+                InstructionError::ProgramFailedToRun.to_string()
+            ))
+        );
+        assert_eq!(
+            check_instruction_error(&InstructionError::UnbalancedInstruction, &logs),
+            Err(FuzzFailure::AccountCheck(
+                InstructionError::UnbalancedInstruction
+            ))
+        );
+        // errors returned by the program itself are not failures
+        assert_eq!(
+            check_instruction_error(&InstructionError::InvalidInstructionData, &logs),
+            Ok(())
+        );
+    }
+}
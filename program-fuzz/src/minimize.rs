@@ -0,0 +1,103 @@
+//! Shrinking of failing fuzz cases
+
+use crate::case::FuzzCase;
+
+/// Shrink `case` while `still_fails` holds, by dropping account mutations, truncating and
+/// removing instruction data bytes, and zeroing the bytes that remain
+pub fn minimize<F>(case: &FuzzCase, mut still_fails: F) -> FuzzCase
+where
+    F: FnMut(&FuzzCase) -> bool,
+{
+    let mut best = case.clone();
+    loop {
+        let mut progress = false;
+
+        let mut index = 0;
+        while index < best.mutations.len() {
+            let mut candidate = best.clone();
+            candidate.mutations.remove(index);
+            if still_fails(&candidate) {
+                best = candidate;
+                progress = true;
+            } else {
+                index += 1;
+            }
+        }
+
+        while !best.data.is_empty() {
+            let mut candidate = best.clone();
+            candidate.data.truncate(best.data.len() / 2);
+            if !still_fails(&candidate) {
+                break;
+            }
+            best = candidate;
+            progress = true;
+        }
+
+        let mut index = 0;
+        while index < best.data.len() {
+            let mut candidate = best.clone();
+            candidate.data.remove(index);
+            if still_fails(&candidate) {
+                best = candidate;
+                progress = true;
+            } else {
+                index += 1;
+            }
+        }
+
+        for index in 0..best.data.len() {
+            if best.data[index] != 0 {
+                let mut candidate = best.clone();
+                candidate.data[index] = 0;
+                if still_fails(&candidate) {
+                    best = candidate;
+                    progress = true;
+                }
+            }
+        }
+
+        if !progress {
+            return best;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::case::AccountMutation};
+
+    #[test]
+    fn test_minimize() {
+        let case = FuzzCase {
+            data: vec![9, 8, 7, 6, 5, 4, 3, 2, 1],
+            mutations: vec![
+                AccountMutation::FlipSigner(0),
+                AccountMutation::FlipWritable(1),
+                AccountMutation::Swap { a: 0, b: 1 },
+            ],
+        };
+        // fails whenever the data contains a 5 and the writable flag of account 1 is flipped
+        let minimized = minimize(&case, |case| {
+            case.data.contains(&5) && case.mutations.contains(&AccountMutation::FlipWritable(1))
+        });
+        assert_eq!(
+            minimized,
+            FuzzCase {
+                data: vec![5],
+                mutations: vec![AccountMutation::FlipWritable(1)],
+            }
+        );
+    }
+
+    #[test]
+    fn test_minimize_zeroes_irrelevant_bytes() {
+        let case = FuzzCase {
+            data: vec![1, 2, 3, 4],
+            mutations: vec![],
+        };
+        // fails whenever the data is at least 3 bytes long
+        let minimized = minimize(&case, |case| case.data.len() >= 3);
+        assert_eq!(minimized.data, vec![0, 0, 0]);
+    }
+}
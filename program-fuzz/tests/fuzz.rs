@@ -0,0 +1,126 @@
+use {
+    paychains_program_fuzz::{case::FuzzCase, FuzzFailure, Fuzzer},
+    paychains_program_test::{processor, ProgramTest},
+    paychains_sdk::{
+        account::Account,
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, InstructionError},
+        pubkey::Pubkey,
+    },
+};
+
+#[allow(clippy::unnecessary_wraps)]
+fn noop_process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _input: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn lamport_minting_process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    // credits the first account without debiting any other
+    if input.first().map(|byte| byte % 4 == 3).unwrap_or(false) {
+        **accounts[0].lamports.borrow_mut() += 1;
+    }
+    Ok(())
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn panicking_process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _input: &[u8],
+) -> ProgramResult {
+    if accounts[1].is_writable {
+        panic!("config account must be read-only");
+    }
+    Ok(())
+}
+
+fn new_fuzzer(program_test: ProgramTest, program_id: Pubkey) -> Fuzzer {
+    let mut fuzzer = Fuzzer::new(program_test, program_id);
+    let account = Account::new(1_000_000, 8, &program_id);
+    fuzzer.add_account(
+        AccountMeta::new(Pubkey::new_unique(), false),
+        account.clone(),
+    );
+    fuzzer.add_account(
+        AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        account,
+    );
+    fuzzer
+}
+
+#[test]
+fn fuzz_finds_lamport_creation() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new(
+        "lamport-minting",
+        program_id,
+        processor!(lamport_minting_process_instruction),
+    );
+    let mut runner = new_fuzzer(program_test, program_id).start();
+
+    let finding = runner.run(0, 1_000).unwrap_err();
+    assert_eq!(
+        finding.failure,
+        FuzzFailure::AccountCheck(InstructionError::UnbalancedInstruction)
+    );
+    assert!(finding.minimized.mutations.is_empty());
+    assert_eq!(finding.minimized.data.len(), 1);
+    assert_eq!(finding.minimized.data[0] % 4, 3);
+
+    // the minimized case replays the same failure
+    assert_eq!(
+        runner.run_case(&finding.minimized).unwrap_err(),
+        finding.failure
+    );
+}
+
+#[test]
+fn fuzz_finds_panic_on_account_permutation() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new(
+        "panicking",
+        program_id,
+        processor!(panicking_process_instruction),
+    );
+    let mut runner = new_fuzzer(program_test, program_id).start();
+
+    let finding = runner.run(0, 1_000).unwrap_err();
+    assert_eq!(
+        finding.failure,
+        FuzzFailure::Panic("config account must be read-only".to_string())
+    );
+    assert!(finding.minimized.data.is_empty());
+    assert_eq!(finding.minimized.mutations.len(), 1);
+}
+
+#[test]
+fn fuzz_bytes_with_invariant() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new("noop", program_id, processor!(noop_process_instruction));
+    let mut fuzzer = new_fuzzer(program_test, program_id);
+    fuzzer.add_invariant(|case: &FuzzCase, _outcome| {
+        if case.data.is_empty() {
+            Ok(())
+        } else {
+            Err("unexpected instruction data".to_string())
+        }
+    });
+    let mut runner = fuzzer.start();
+
+    assert!(runner.run_bytes(&[]).unwrap().result.is_ok());
+    // a data length of 1, followed by the data byte and no account mutations
+    assert_eq!(
+        runner.run_bytes(&[1, 0, 0xaa]).unwrap_err(),
+        FuzzFailure::Invariant("unexpected instruction data".to_string())
+    );
+}
@@ -1,7 +1,7 @@
 //! The accounts data space has a maximum size it is permitted to grow to.  This module contains
 //! the constants and types for tracking and metering the accounts data space during program
 //! runtime.
-use paychains_sdk::instruction::InstructionError;
+use paychains_sdk::{instruction::InstructionError, system_instruction::MAX_PERMITTED_DATA_LENGTH};
 
 /// The maximum allowed size, in bytes, of the accounts data
 /// 128 GB was chosen because it is the RAM amount listed under Hardware Recommendations on
@@ -9,6 +9,10 @@ use paychains_sdk::instruction::InstructionError;
 /// validators often put the ledger on a RAM disk (i.e. tmpfs).
 pub const MAX_ACCOUNTS_DATA_LEN: u64 = 128_000_000_000;
 
+/// The maximum amount, in bytes, a single transaction is permitted to grow the accounts data by.
+/// Enforced once the `cap_accounts_data_allocations_per_transaction` feature is active.
+pub const MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION: u64 = MAX_PERMITTED_DATA_LENGTH * 2;
+
 /// Meter and track the amount of available accounts data space
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
 pub struct AccountsDataMeter {
@@ -17,6 +21,10 @@ pub struct AccountsDataMeter {
 
     /// The current amount of accounts data space used (in bytes)
     current: u64,
+
+    /// The amount of accounts data space allocated so far (in bytes).  Only growth counts, so
+    /// space freed again does not offset it.
+    allocated: u64,
 }
 
 impl AccountsDataMeter {
@@ -25,6 +33,7 @@ impl AccountsDataMeter {
         let accounts_data_meter = Self {
             maximum: MAX_ACCOUNTS_DATA_LEN,
             current: current_accounts_data_len,
+            allocated: 0,
         };
         debug_assert!(accounts_data_meter.current <= accounts_data_meter.maximum);
         accounts_data_meter
@@ -40,6 +49,12 @@ impl AccountsDataMeter {
         self.current
     }
 
+    /// Return the amount of accounts data space allocated so far (in bytes), not counting any
+    /// space freed since
+    pub fn allocated(&self) -> u64 {
+        self.allocated
+    }
+
     /// Get the remaining amount of accounts data space (in bytes)
     pub fn remaining(&self) -> u64 {
         self.maximum.saturating_sub(self.current)
@@ -54,19 +69,24 @@ impl AccountsDataMeter {
             return Ok(());
         }
 
-        if amount.is_positive() {
-            let amount = amount as u64;
-            if amount > self.remaining() {
-                return Err(InstructionError::AccountsDataBudgetExceeded);
-            }
-            self.current = self.current.saturating_add(amount);
-        } else {
-            let amount = amount.abs() as u64;
-            self.current = self.current.saturating_sub(amount);
+        if amount.is_positive() && amount as u64 > self.remaining() {
+            return Err(InstructionError::AccountsDataBudgetExceeded);
         }
+        self.adjust(amount);
 
         Ok(())
     }
+
+    /// Track accounts data space, in bytes, without enforcing the maximum.  Used to keep track of
+    /// the accounts data space while the cap on it is not enforced.
+    pub fn adjust(&mut self, amount: i64) {
+        if amount.is_positive() {
+            self.current = self.current.saturating_add(amount as u64);
+            self.allocated = self.allocated.saturating_add(amount as u64);
+        } else {
+            self.current = self.current.saturating_sub(amount.unsigned_abs());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -178,4 +198,51 @@ mod tests {
         let result = accounts_data_meter.consume(0);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_adjust() {
+        let current_accounts_data_len = 1234;
+        let mut accounts_data_meter = AccountsDataMeter::new(current_accounts_data_len);
+        accounts_data_meter.maximum = current_accounts_data_len;
+
+        // Test: adjusting ignores the maximum
+        accounts_data_meter.adjust(100);
+        assert_eq!(
+            accounts_data_meter.current(),
+            current_accounts_data_len + 100
+        );
+        assert_eq!(accounts_data_meter.remaining(), 0);
+
+        accounts_data_meter.adjust(-200);
+        assert_eq!(
+            accounts_data_meter.current(),
+            current_accounts_data_len - 100
+        );
+
+        // Test: adjusting saturates
+        accounts_data_meter.adjust(i64::MIN);
+        assert_eq!(accounts_data_meter.current(), 0);
+    }
+
+    #[test]
+    fn test_allocated() {
+        let current_accounts_data_len = 1234;
+        let mut accounts_data_meter = AccountsDataMeter::new(current_accounts_data_len);
+        assert_eq!(accounts_data_meter.allocated(), 0);
+
+        // Test: freeing space does not offset what was allocated
+        accounts_data_meter.consume(100).unwrap();
+        accounts_data_meter.consume(-100).unwrap();
+        assert_eq!(accounts_data_meter.current(), current_accounts_data_len);
+        assert_eq!(accounts_data_meter.allocated(), 100);
+
+        accounts_data_meter.adjust(-200);
+        accounts_data_meter.adjust(50);
+        assert_eq!(accounts_data_meter.allocated(), 150);
+
+        // Test: a failed consume allocates nothing
+        let remaining = accounts_data_meter.remaining();
+        assert!(accounts_data_meter.consume(remaining as i64 + 1).is_err());
+        assert_eq!(accounts_data_meter.allocated(), 150);
+    }
 }
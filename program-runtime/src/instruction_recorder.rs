@@ -7,12 +7,14 @@ use {
 #[derive(Clone)]
 pub struct InstructionRecorder {
     records: Vec<Vec<CompiledInstruction>>,
+    stack_heights: Vec<Vec<usize>>,
 }
 
 impl InstructionRecorder {
     pub fn new_ref(instructions_in_message: usize) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
             records: Vec::with_capacity(instructions_in_message),
+            stack_heights: Vec::with_capacity(instructions_in_message),
         }))
     }
 
@@ -20,13 +22,29 @@ impl InstructionRecorder {
         self.records
     }
 
+    /// Like `deconstruct`, but also returns the invocation stack height each instruction was
+    /// recorded at, where the message's own instructions are at height 1
+    pub fn deconstruct_with_stack_heights(
+        self,
+    ) -> (Vec<Vec<CompiledInstruction>>, Vec<Vec<usize>>) {
+        (self.records, self.stack_heights)
+    }
+
     pub fn begin_next_recording(&mut self) {
         self.records.push(Vec::new());
+        self.stack_heights.push(Vec::new());
     }
 
-    pub fn record_compiled_instruction(&mut self, instruction: CompiledInstruction) {
-        if let Some(records) = self.records.last_mut() {
+    pub fn record_compiled_instruction(
+        &mut self,
+        instruction: CompiledInstruction,
+        stack_height: usize,
+    ) {
+        if let (Some(records), Some(stack_heights)) =
+            (self.records.last_mut(), self.stack_heights.last_mut())
+        {
             records.push(instruction);
+            stack_heights.push(stack_height);
         }
     }
 }
@@ -179,6 +179,17 @@ impl<'a> StackFrame<'a> {
     }
 }
 
+/// The BPF instruction trace of a single program invocation
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InvocationTrace {
+    pub program_id: Pubkey,
+    /// Invocation stack height, where the transaction's own instructions are at height 1
+    pub stack_height: usize,
+    /// Registers followed by the program counter of each executed instruction, as logged by
+    /// the BPF tracer.  Empty for builtin programs
+    pub trace: Vec<[u64; 12]>,
+}
+
 pub struct InvokeContext<'a> {
     pub transaction_context: &'a mut TransactionContext,
     invoke_stack: Vec<StackFrame<'a>>,
@@ -197,6 +208,9 @@ pub struct InvokeContext<'a> {
     pub timings: ExecuteDetailsTimings,
     pub blockhash: Hash,
     pub lamports_per_signature: u64,
+    /// Record an `InvocationTrace` for every invocation, in invocation order
+    pub enable_instruction_tracing: bool,
+    pub invocation_traces: Vec<InvocationTrace>,
 }
 
 impl<'a> InvokeContext<'a> {
@@ -233,6 +247,8 @@ impl<'a> InvokeContext<'a> {
             timings: ExecuteDetailsTimings::default(),
             blockhash,
             lamports_per_signature,
+            enable_instruction_tracing: false,
+            invocation_traces: Vec::new(),
         }
     }
 
@@ -469,11 +485,13 @@ impl<'a> InvokeContext<'a> {
                 .checked_add(u128::from(account.lamports()))
                 .ok_or(InstructionError::UnbalancedInstruction)?;
 
+            let pre_data_len = pre_account.data().len() as i64;
+            let post_data_len = account.data().len() as i64;
+            let data_len_delta = post_data_len.saturating_sub(pre_data_len);
             if cap_accounts_data_len {
-                let pre_data_len = pre_account.data().len() as i64;
-                let post_data_len = account.data().len() as i64;
-                let data_len_delta = post_data_len.saturating_sub(pre_data_len);
                 self.accounts_data_meter.consume(data_len_delta)?;
+            } else {
+                self.accounts_data_meter.adjust(data_len_delta);
             }
 
             Ok(())
@@ -556,15 +574,18 @@ impl<'a> InvokeContext<'a> {
                         post_sum = post_sum
                             .checked_add(u128::from(account.lamports()))
                             .ok_or(InstructionError::UnbalancedInstruction)?;
-                        if is_writable && !pre_account.executable() {
-                            pre_account.update(account.clone());
-                        }
-
+                        // measured before updating the PreAccount, which would hide the change
+                        let pre_data_len = pre_account.data().len() as i64;
+                        let post_data_len = account.data().len() as i64;
+                        let data_len_delta = post_data_len.saturating_sub(pre_data_len);
                         if cap_accounts_data_len {
-                            let pre_data_len = pre_account.data().len() as i64;
-                            let post_data_len = account.data().len() as i64;
-                            let data_len_delta = post_data_len.saturating_sub(pre_data_len);
                             self.accounts_data_meter.consume(data_len_delta)?;
+                        } else {
+                            self.accounts_data_meter.adjust(data_len_delta);
+                        }
+
+                        if is_writable && !pre_account.executable() {
+                            pre_account.update(account.clone());
                         }
 
                         return Ok(());
@@ -822,12 +843,28 @@ impl<'a> InvokeContext<'a> {
                         .collect(),
                 };
 
+                // the callee runs one level above the instruction that invoked it
+                let stack_height = self
+                    .transaction_context
+                    .get_instruction_context_stack_height()
+                    .saturating_add(1);
                 instruction_recorder
                     .borrow_mut()
-                    .record_compiled_instruction(compiled_instruction);
+                    .record_compiled_instruction(compiled_instruction, stack_height);
             }
         }
 
+        if self.enable_instruction_tracing {
+            self.invocation_traces.push(InvocationTrace {
+                program_id,
+                stack_height: self
+                    .transaction_context
+                    .get_instruction_context_stack_height()
+                    .saturating_add(1),
+                trace: Vec::new(),
+            });
+        }
+
         let result = self
             .push(instruction_accounts, program_indices, instruction_data)
             .and_then(|_| {
@@ -982,6 +1019,22 @@ impl<'a> InvokeContext<'a> {
             .map(|tx_executor| tx_executor.executor.clone())
     }
 
+    /// Attach the BPF instruction trace of the currently executing program to its
+    /// `InvocationTrace`
+    pub fn record_instruction_trace(&mut self, trace: Vec<[u64; 12]>) {
+        let stack_height = self
+            .transaction_context
+            .get_instruction_context_stack_height();
+        if let Some(invocation_trace) = self
+            .invocation_traces
+            .iter_mut()
+            .rev()
+            .find(|invocation_trace| invocation_trace.stack_height == stack_height)
+        {
+            invocation_trace.trace = trace;
+        }
+    }
+
     /// Get this invocation's compute budget
     pub fn get_compute_budget(&self) -> &ComputeBudget {
         &self.current_compute_budget
@@ -1716,4 +1769,43 @@ mod tests {
             assert_eq!(invoke_context.accounts_data_meter.remaining(), 0);
         }
     }
+
+    #[test]
+    fn test_processed_sibling_instruction() {
+        fn siblings(transaction_context: &TransactionContext) -> Vec<Vec<u8>> {
+            (0..)
+                .map_while(|index| transaction_context.get_processed_sibling_instruction(index))
+                .map(|instruction_context| instruction_context.get_instruction_data().to_vec())
+                .collect()
+        }
+
+        let transaction_accounts = vec![(Pubkey::new_unique(), AccountSharedData::default())];
+        let mut transaction_context = TransactionContext::new(transaction_accounts, 3);
+
+        // A
+        // B -> C -> D
+        // B -> E
+        // B -> F
+        transaction_context.push(&[0], &[], b"A").unwrap();
+        assert!(siblings(&transaction_context).is_empty());
+        transaction_context.pop().unwrap();
+        transaction_context.push(&[0], &[], b"B").unwrap();
+        assert_eq!(siblings(&transaction_context), vec![b"A".to_vec()]);
+        transaction_context.push(&[0], &[], b"C").unwrap();
+        assert!(siblings(&transaction_context).is_empty());
+        transaction_context.push(&[0], &[], b"D").unwrap();
+        assert!(siblings(&transaction_context).is_empty());
+        transaction_context.pop().unwrap();
+        transaction_context.pop().unwrap();
+        transaction_context.push(&[0], &[], b"E").unwrap();
+        assert_eq!(siblings(&transaction_context), vec![b"C".to_vec()]);
+        transaction_context.pop().unwrap();
+        transaction_context.push(&[0], &[], b"F").unwrap();
+        assert_eq!(
+            siblings(&transaction_context),
+            vec![b"E".to_vec(), b"C".to_vec()]
+        );
+        transaction_context.pop().unwrap();
+        transaction_context.pop().unwrap();
+    }
 }
@@ -13,29 +13,33 @@ use {
         ic_msg, invoke_context::ProcessInstructionWithContext, stable_log, timings::ExecuteTimings,
     },
     paychains_runtime::{
-        bank::Bank,
+        bank::{Bank, TransactionSimulationResult},
         bank_forks::BankForks,
         builtins::Builtin,
         commitment::BlockCommitmentCache,
-        genesis_utils::{create_genesis_config_with_leader_ex, GenesisConfigInfo},
+        genesis_utils::{
+            activate_feature, create_genesis_config_with_leader_ex, GenesisConfigInfo,
+        },
     },
     paychains_sdk::{
         account::{Account, AccountSharedData, ReadableAccount, WritableAccount},
         account_info::AccountInfo,
-        clock::Slot,
+        clock::{Epoch, Slot, UnixTimestamp},
         compute_budget::ComputeBudget,
         entrypoint::{ProgramResult, SUCCESS},
         fee_calculator::{FeeCalculator, FeeRateGovernor},
         genesis_config::{ClusterType, GenesisConfig},
         hash::Hash,
-        instruction::{Instruction, InstructionError},
+        instruction::{Instruction, InstructionError, TRANSACTION_LEVEL_STACK_HEIGHT},
         native_token::pay_to_lamports,
         poh_config::PohConfig,
+        program::MAX_RETURN_DATA,
         program_error::{ProgramError, ACCOUNT_BORROW_FAILED, UNSUPPORTED_SYSVAR},
         pubkey::Pubkey,
         rent::Rent,
         signature::{Keypair, Signer},
         sysvar::{Sysvar, SysvarId},
+        transaction::{SanitizedTransaction, Transaction, TransactionError},
     },
     paychains_vote_program::vote_state::{VoteState, VoteStateVersions},
     std::{
@@ -57,7 +61,12 @@ use {
     tokio::task::JoinHandle,
 };
 // Export types so test clients can limit their paychains crate dependencies
-pub use {paychains_banks_client::BanksClient, paychains_program_runtime::invoke_context::InvokeContext};
+pub use {
+    paychains_banks_client::{
+        BanksClient, BanksTransactionResultWithMetadata, InnerInstruction, TransactionMetadata,
+    },
+    paychains_program_runtime::invoke_context::InvokeContext,
+};
 
 pub mod programs;
 
@@ -355,6 +364,130 @@ impl paychains_sdk::program_stubs::SyscallStubs for SyscallStubs {
     fn pay_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
         get_sysvar(get_invoke_context().get_sysvar_cache().get_rent(), var_addr)
     }
+
+    fn pay_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        let transaction_context = &get_invoke_context().transaction_context;
+        let instruction_context = transaction_context.get_processed_sibling_instruction(index)?;
+        Some(Instruction::new_with_bytes(
+            *instruction_context.get_program_key(transaction_context).ok()?,
+            instruction_context.get_instruction_data(),
+            instruction_context.get_instruction_account_metas(transaction_context),
+        ))
+    }
+
+    fn pay_get_stack_height(&self) -> u64 {
+        get_invoke_context().invoke_depth() as u64
+    }
+
+    fn pay_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let (program_id, data) = get_invoke_context().transaction_context.get_return_data();
+        if data.is_empty() {
+            None
+        } else {
+            Some((*program_id, data.to_vec()))
+        }
+    }
+
+    fn pay_set_return_data(&mut self, data: &[u8]) {
+        let invoke_context = get_invoke_context();
+        if data.len() > MAX_RETURN_DATA {
+            ic_msg!(
+                invoke_context,
+                "Return data too large: {} > {}",
+                data.len(),
+                MAX_RETURN_DATA
+            );
+            return;
+        }
+        let program_id = match invoke_context.transaction_context.get_program_key() {
+            Ok(program_id) => *program_id,
+            Err(err) => {
+                ic_msg!(invoke_context, "Failed to set return data: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = invoke_context
+            .transaction_context
+            .set_return_data(program_id, data.to_vec())
+        {
+            ic_msg!(invoke_context, "Failed to set return data: {}", err);
+        }
+    }
+}
+
+/// A program invocation along with the cross-program invocations it made
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvocationNode {
+    pub program_id: Pubkey,
+    pub children: Vec<InvocationNode>,
+}
+
+impl InvocationNode {
+    pub fn new(program_id: Pubkey, children: Vec<InvocationNode>) -> Self {
+        Self {
+            program_id,
+            children,
+        }
+    }
+}
+
+/// Build the invocation tree of each instruction in `transaction` from the cross-program
+/// invocations recorded in `metadata`
+pub fn invocation_tree(
+    transaction: &Transaction,
+    metadata: &TransactionMetadata,
+) -> Vec<InvocationNode> {
+    fn build_children(
+        inner_instructions: &[InnerInstruction],
+        parent_stack_height: usize,
+        next: &mut usize,
+    ) -> Vec<InvocationNode> {
+        let mut children = vec![];
+        while let Some(inner_instruction) = inner_instructions.get(*next) {
+            if inner_instruction.stack_height <= parent_stack_height {
+                break;
+            }
+            *next += 1;
+            children.push(InvocationNode::new(
+                inner_instruction.program_id,
+                build_children(inner_instructions, inner_instruction.stack_height, next),
+            ));
+        }
+        children
+    }
+
+    let message = &transaction.message;
+    message
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let inner_instructions = metadata
+                .inner_instructions
+                .get(index)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            InvocationNode::new(
+                *instruction.program_id(&message.account_keys),
+                build_children(inner_instructions, TRANSACTION_LEVEL_STACK_HEIGHT, &mut 0),
+            )
+        })
+        .collect()
+}
+
+/// Assert that the instructions in `transaction` made exactly the cross-program invocations
+/// described by `expected`, one tree per instruction
+pub fn assert_invocation_tree(
+    transaction: &Transaction,
+    metadata: &TransactionMetadata,
+    expected: &[InvocationNode],
+) {
+    let invocation_tree = invocation_tree(transaction, metadata);
+    assert_eq!(
+        invocation_tree, expected,
+        "unexpected invocation tree, logs: {:#?}",
+        metadata.log_messages
+    );
 }
 
 pub fn find_file(filename: &str) -> Option<PathBuf> {
@@ -431,6 +564,7 @@ pub struct ProgramTest {
     compute_max_units: Option<u64>,
     prefer_bpf: bool,
     use_bpf_jit: bool,
+    activate_feature_set: HashSet<Pubkey>,
 }
 
 impl Default for ProgramTest {
@@ -461,6 +595,7 @@ impl Default for ProgramTest {
             compute_max_units: None,
             prefer_bpf,
             use_bpf_jit: false,
+            activate_feature_set: HashSet::default(),
         }
     }
 }
@@ -505,6 +640,12 @@ impl ProgramTest {
         self.use_bpf_jit = use_bpf_jit;
     }
 
+    /// Activate a runtime feature left inactive by default, see
+    /// `genesis_utils::features_inactive_in_dev_tooling`
+    pub fn activate_feature(&mut self, feature_id: Pubkey) {
+        self.activate_feature_set.insert(feature_id);
+    }
+
     /// Add an account to the test environment
     pub fn add_account(&mut self, address: Pubkey, account: Account) {
         self.accounts
@@ -729,6 +870,9 @@ impl ProgramTest {
             ClusterType::Development,
             vec![],
         );
+        for feature_id in &self.activate_feature_set {
+            activate_feature(&mut genesis_config, *feature_id);
+        }
         let target_tick_duration = Duration::from_micros(100);
         genesis_config.poh_config = PohConfig::new_sleep(target_tick_duration);
         debug!("Payer address: {}", mint_keypair.pubkey());
@@ -1089,4 +1233,61 @@ impl ProgramTestContext {
         self.last_blockhash = bank.last_blockhash();
         Ok(())
     }
+
+    /// Force the working bank ahead to the first slot of a new epoch, paying out stake rewards
+    /// at the epoch boundary
+    pub fn warp_to_epoch(&mut self, warp_epoch: Epoch) -> Result<(), ProgramTestError> {
+        let warp_slot = self
+            .genesis_config
+            .epoch_schedule
+            .get_first_slot_in_epoch(warp_epoch);
+        self.warp_to_slot(warp_slot)
+    }
+
+    /// Overwrite the `unix_timestamp` of the working bank's `Clock` sysvar, leaving its slot
+    /// and epoch untouched.  The new timestamp holds until the next warp
+    pub fn set_clock_timestamp(&self, unix_timestamp: UnixTimestamp) {
+        let bank_forks = self.bank_forks.read().unwrap();
+        let bank = bank_forks.working_bank();
+        let mut clock = bank.clock();
+        clock.unix_timestamp = unix_timestamp;
+        bank.set_sysvar_for_tests(&clock);
+    }
+
+    /// Simulate a transaction against the working bank on the calling thread, without
+    /// verifying its signatures or committing any of its effects
+    pub fn simulate_transaction_unchecked(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<TransactionSimulationResult, TransactionError> {
+        let transaction = SanitizedTransaction::try_from_legacy_transaction(transaction.clone())?;
+        let bank = self.bank_forks.read().unwrap().working_bank();
+        Ok(bank.simulate_transaction_unchecked(transaction))
+    }
+
+    /// Simulate a transaction against the working bank and return the compute units it
+    /// consumed, without committing any of its effects
+    pub fn get_compute_units_consumed(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<u64, TransactionError> {
+        let TransactionSimulationResult {
+            result,
+            units_consumed,
+            ..
+        } = self.simulate_transaction_unchecked(transaction)?;
+        result.map(|()| units_consumed)
+    }
+
+    /// Assert that a transaction succeeds and consumes exactly `expected_units` compute units
+    pub fn assert_compute_units_consumed(&self, transaction: &Transaction, expected_units: u64) {
+        let units_consumed = self
+            .get_compute_units_consumed(transaction)
+            .unwrap_or_else(|err| panic!("Transaction simulation failed: {}", err));
+        assert_eq!(
+            units_consumed, expected_units,
+            "Transaction consumed {} compute units, expected {}",
+            units_consumed, expected_units
+        );
+    }
 }
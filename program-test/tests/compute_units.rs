@@ -0,0 +1,56 @@
+use {
+    paychains_program_test::ProgramTest,
+    paychains_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+    std::str::FromStr,
+};
+
+#[tokio::test]
+async fn compute_units_consumed() {
+    let memo_program_id = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap();
+    let program_test = ProgramTest::default();
+    let mut context = program_test.start_with_context().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            memo_program_id,
+            b"compute units",
+            vec![],
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let units_consumed = context.get_compute_units_consumed(&transaction).unwrap();
+    assert!(units_consumed > 0);
+    context.assert_compute_units_consumed(&transaction, units_consumed);
+
+    // simulation leaves the transaction unprocessed, so it can still be submitted
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    // a failing transaction reports its error instead of a unit count
+    let transaction = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            memo_program_id,
+            &[0xff, 0xfe],
+            vec![],
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert_eq!(
+        context
+            .get_compute_units_consumed(&transaction)
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidInstructionData)
+    );
+}
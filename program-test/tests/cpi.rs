@@ -1,5 +1,5 @@
 use {
-    paychains_program_test::{processor, ProgramTest},
+    paychains_program_test::{assert_invocation_tree, processor, InvocationNode, ProgramTest},
     paychains_sdk::{
         account_info::{next_account_info, AccountInfo},
         entrypoint::ProgramResult,
@@ -77,3 +77,53 @@ async fn cpi() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn cpi_with_metadata() {
+    let invoker_program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "invoker",
+        invoker_program_id,
+        processor!(invoker_process_instruction),
+    );
+    let invoked_program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "invoked",
+        invoked_program_id,
+        processor!(invoked_process_instruction),
+    );
+
+    let mut context = program_test.start_with_context().await;
+    let instructions = vec![Instruction::new_with_bincode(
+        invoker_program_id,
+        &[0],
+        vec![AccountMeta::new_readonly(invoked_program_id, false)],
+    )];
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction.clone())
+        .await
+        .unwrap();
+    assert_eq!(result.result, Ok(()));
+    let metadata = result.metadata.unwrap();
+    assert!(metadata
+        .log_messages
+        .iter()
+        .any(|log| log == "Program log: Processing invoked instruction"));
+    assert_invocation_tree(
+        &transaction,
+        &metadata,
+        &[InvocationNode::new(
+            invoker_program_id,
+            vec![InvocationNode::new(invoked_program_id, vec![])],
+        )],
+    );
+}
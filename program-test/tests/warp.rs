@@ -35,6 +35,7 @@ use {
 
 // Use a big number to be sure that we get the right error
 const WRONG_SLOT_ERROR: u32 = 123456;
+const WRONG_TIMESTAMP_ERROR: u32 = 654321;
 
 async fn setup_stake(
     context: &mut ProgramTestContext,
@@ -121,6 +122,20 @@ fn process_instruction(
     }
 }
 
+fn process_check_timestamp(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let clock = Clock::get()?;
+    let expected_timestamp = i64::from_le_bytes(input.try_into().unwrap());
+    if clock.unix_timestamp == expected_timestamp {
+        Ok(())
+    } else {
+        Err(ProgramError::Custom(WRONG_TIMESTAMP_ERROR))
+    }
+}
+
 #[tokio::test]
 async fn clock_sysvar_updated_from_warp() {
     let program_id = Pubkey::new_unique();
@@ -182,6 +197,107 @@ async fn clock_sysvar_updated_from_warp() {
     );
 }
 
+#[tokio::test]
+async fn clock_timestamp_set_without_warp() {
+    let program_id = Pubkey::new_unique();
+    let program_test = ProgramTest::new(
+        "program-test-timestamp",
+        program_id,
+        processor!(process_check_timestamp),
+    );
+
+    let mut context = program_test.start_with_context().await;
+    let expected_timestamp: i64 = 1_700_000_000;
+    let instruction = Instruction::new_with_bincode(program_id, &expected_timestamp, vec![]);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction.clone()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(WRONG_TIMESTAMP_ERROR))
+    );
+
+    context.set_clock_timestamp(expected_timestamp);
+    let clock_account = context
+        .banks_client
+        .get_account(clock::id())
+        .await
+        .expect("account exists")
+        .unwrap();
+    let clock: Clock = deserialize(&clock_account.data).unwrap();
+    assert_eq!(clock.unix_timestamp, expected_timestamp);
+
+    let last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stake_rewards_from_warp_to_epoch() {
+    let program_test = ProgramTest::default();
+    let mut context = program_test.start_with_context().await;
+    let vote_address = setup_vote(&mut context).await;
+
+    let user_keypair = Keypair::new();
+    let stake_lamports = 1_000_000_000_000;
+    let stake_address =
+        setup_stake(&mut context, &user_keypair, &vote_address, stake_lamports).await;
+
+    let epoch_schedule = context.genesis_config().epoch_schedule;
+    let first_normal_epoch = epoch_schedule.first_normal_epoch;
+    context.warp_to_epoch(first_normal_epoch).unwrap();
+    context.increment_vote_account_credits(&vote_address, 100);
+    context.warp_to_epoch(first_normal_epoch + 1).unwrap();
+
+    let clock_account = context
+        .banks_client
+        .get_account(clock::id())
+        .await
+        .expect("account exists")
+        .unwrap();
+    let clock: Clock = deserialize(&clock_account.data).unwrap();
+    assert_eq!(clock.epoch, first_normal_epoch + 1);
+    assert_eq!(
+        clock.slot,
+        epoch_schedule.get_first_slot_in_epoch(first_normal_epoch + 1)
+    );
+
+    let account = context
+        .banks_client
+        .get_account(stake_address)
+        .await
+        .expect("account exists")
+        .unwrap();
+    assert!(account.lamports > stake_lamports);
+
+    assert_eq!(
+        context.warp_to_epoch(first_normal_epoch).unwrap_err(),
+        ProgramTestError::InvalidWarpSlot,
+    );
+}
+
 #[tokio::test]
 async fn stake_rewards_from_warp() {
     // Initialize and start the test network
@@ -60,8 +60,10 @@ fn main() {
         let rust_programs = [
             "128bit",
             "alloc",
+            "alt_bn128",
             "call_depth",
             "caller_access",
+            "curve25519",
             "custom_heap",
             "dep_crate",
             "deprecated_loader",
@@ -91,6 +93,7 @@ fn main() {
             "sanity",
             "secp256k1_recover",
             "sha",
+            "sibling_instructions",
             "spoof1",
             "spoof1_system",
             "upgradeable",
@@ -0,0 +1,89 @@
+//! @brief alt_bn128 syscall tests
+
+extern crate paychains_program;
+use paychains_program::{
+    alt_bn128::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing},
+    custom_panic_default, msg,
+};
+
+// (1, 2)
+const G1: [u8; 64] = {
+    let mut point = [0u8; 64];
+    point[31] = 1;
+    point[63] = 2;
+    point
+};
+
+// (1, p - 2)
+const NEGATED_G1: [u8; 64] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x45,
+];
+
+// Generator of G2, imaginary parts first
+const G2: [u8; 128] = [
+    0x19, 0x8e, 0x93, 0x93, 0x92, 0x0d, 0x48, 0x3a, 0x72, 0x60, 0xbf, 0xb7, 0x31, 0xfb, 0x5d, 0x25,
+    0xf1, 0xaa, 0x49, 0x33, 0x35, 0xa9, 0xe7, 0x12, 0x97, 0xe4, 0x85, 0xb7, 0xae, 0xf3, 0x12, 0xc2,
+    0x18, 0x00, 0xde, 0xef, 0x12, 0x1f, 0x1e, 0x76, 0x42, 0x6a, 0x00, 0x66, 0x5e, 0x5c, 0x44, 0x79,
+    0x67, 0x43, 0x22, 0xd4, 0xf7, 0x5e, 0xda, 0xdd, 0x46, 0xde, 0xbd, 0x5c, 0xd9, 0x92, 0xf6, 0xed,
+    0x09, 0x06, 0x89, 0xd0, 0x58, 0x5f, 0xf0, 0x75, 0xec, 0x9e, 0x99, 0xad, 0x69, 0x0c, 0x33, 0x95,
+    0xbc, 0x4b, 0x31, 0x33, 0x70, 0xb3, 0x8e, 0xf3, 0x55, 0xac, 0xda, 0xdc, 0xd1, 0x22, 0x97, 0x5b,
+    0x12, 0xc8, 0x5e, 0xa5, 0xdb, 0x8c, 0x6d, 0xeb, 0x4a, 0xab, 0x71, 0x80, 0x8d, 0xcb, 0x40, 0x8f,
+    0xe3, 0xd1, 0xe7, 0x69, 0x0c, 0x43, 0xd3, 0x7b, 0x4c, 0xe6, 0xcc, 0x01, 0x66, 0xfa, 0x7d, 0xaa,
+];
+
+fn test_group_ops() {
+    msg!("alt_bn128_addition");
+    let doubled = alt_bn128_addition(&[G1, G1].concat()).expect("alt_bn128_addition");
+
+    msg!("alt_bn128_multiplication");
+    let mut input = G1.to_vec();
+    input.extend_from_slice(&[0u8; 32]);
+    input[95] = 2;
+    assert_eq!(
+        doubled,
+        alt_bn128_multiplication(&input).expect("alt_bn128_multiplication")
+    );
+
+    let identity = alt_bn128_addition(&[G1, NEGATED_G1].concat()).expect("alt_bn128_addition");
+    assert_eq!(identity, [0u8; 64]);
+}
+
+/// The final check of a Groth16 verifier, e(A, B) * e(-A, B) == 1, with
+/// generator points standing in for the proof and verifying key
+fn test_pairing() {
+    msg!("alt_bn128_pairing");
+    let input = [&G1[..], &G2, &NEGATED_G1, &G2].concat();
+    let output = alt_bn128_pairing(&input).expect("alt_bn128_pairing");
+    assert_eq!(output[31], 1);
+
+    let input = [&G1[..], &G2, &G1, &G2].concat();
+    let output = alt_bn128_pairing(&input).expect("alt_bn128_pairing");
+    assert_eq!(output, [0u8; 32]);
+
+    assert!(alt_bn128_pairing(&input[1..]).is_err());
+}
+
+#[no_mangle]
+pub extern "C" fn entrypoint(_input: *mut u8) -> u64 {
+    msg!("alt_bn128");
+
+    test_group_ops();
+    test_pairing();
+
+    0
+}
+
+custom_panic_default!();
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_alt_bn128() {
+        test_group_ops();
+        test_pairing();
+    }
+}
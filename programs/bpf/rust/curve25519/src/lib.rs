@@ -0,0 +1,89 @@
+//! @brief curve25519 syscall tests
+
+extern crate paychains_program;
+use paychains_program::{
+    curve25519::{edwards, ristretto, PodScalar},
+    custom_panic_default, msg,
+};
+
+fn test_edwards() {
+    // compressed edwards25519 basepoint
+    let mut basepoint = edwards::PodEdwardsPoint([0x66; 32]);
+    basepoint.0[0] = 0x58;
+    let mut two = PodScalar::default();
+    two.0[0] = 2;
+
+    msg!("validate_edwards");
+    assert!(edwards::validate_edwards(&basepoint));
+
+    msg!("add_edwards");
+    let doubled = edwards::add_edwards(&basepoint, &basepoint).expect("add_edwards");
+
+    msg!("subtract_edwards");
+    assert_eq!(
+        basepoint,
+        edwards::subtract_edwards(&doubled, &basepoint).expect("subtract_edwards")
+    );
+
+    msg!("multiply_edwards");
+    assert_eq!(
+        doubled,
+        edwards::multiply_edwards(&two, &basepoint).expect("multiply_edwards")
+    );
+    assert!(edwards::multiply_edwards(&PodScalar([0xff; 32]), &basepoint).is_none());
+}
+
+fn test_ristretto() {
+    // compressed ristretto255 basepoint
+    let basepoint = ristretto::PodRistrettoPoint([
+        0xe2, 0xf2, 0xae, 0x0a, 0x6a, 0xbc, 0x4e, 0x71, 0xa8, 0x84, 0xa9, 0x61, 0xc5, 0x00, 0x51,
+        0x5f, 0x58, 0xe3, 0x0b, 0x6a, 0xa5, 0x82, 0xdd, 0x8d, 0xb6, 0xa6, 0x59, 0x45, 0xe0, 0x8d,
+        0x2d, 0x76,
+    ]);
+    let mut two = PodScalar::default();
+    two.0[0] = 2;
+
+    msg!("validate_ristretto");
+    assert!(ristretto::validate_ristretto(&basepoint));
+    assert!(!ristretto::validate_ristretto(
+        &ristretto::PodRistrettoPoint([0xff; 32])
+    ));
+
+    msg!("add_ristretto");
+    let doubled = ristretto::add_ristretto(&basepoint, &basepoint).expect("add_ristretto");
+
+    msg!("subtract_ristretto");
+    assert_eq!(
+        basepoint,
+        ristretto::subtract_ristretto(&doubled, &basepoint).expect("subtract_ristretto")
+    );
+
+    msg!("multiply_ristretto");
+    assert_eq!(
+        doubled,
+        ristretto::multiply_ristretto(&two, &basepoint).expect("multiply_ristretto")
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn entrypoint(_input: *mut u8) -> u64 {
+    msg!("curve25519");
+
+    test_edwards();
+    test_ristretto();
+
+    0
+}
+
+custom_panic_default!();
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_curve25519() {
+        test_edwards();
+        test_ristretto();
+    }
+}
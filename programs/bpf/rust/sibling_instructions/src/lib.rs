@@ -0,0 +1,69 @@
+//! Example Rust-based BPF program that queries its stack height and the
+//! instructions processed before it
+
+extern crate paychains_program;
+use paychains_program::{
+    account_info::AccountInfo,
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{
+        get_processed_sibling_instruction, get_stack_height, AccountMeta, Instruction,
+        TRANSACTION_LEVEL_STACK_HEIGHT,
+    },
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+entrypoint!(process_instruction);
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data {
+        [0] => {
+            msg!("transaction level");
+            assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+
+            // The preceding top-level instruction
+            let sibling = get_processed_sibling_instruction(0).unwrap();
+            assert_eq!(sibling.program_id, *program_id);
+            assert_eq!(sibling.data, vec![2]);
+            assert_eq!(
+                sibling.accounts,
+                vec![AccountMeta::new_readonly(*program_id, false)]
+            );
+            assert!(get_processed_sibling_instruction(1).is_none());
+
+            invoke(
+                &Instruction::new_with_bytes(*program_id, &[1, 0], vec![]),
+                accounts,
+            )?;
+            invoke(
+                &Instruction::new_with_bytes(*program_id, &[1, 1], vec![]),
+                accounts,
+            )?;
+        }
+        [1, index] => {
+            msg!("inner {}", index);
+            assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT + 1);
+
+            if *index == 1 {
+                let sibling = get_processed_sibling_instruction(0).unwrap();
+                assert_eq!(sibling.program_id, *program_id);
+                assert_eq!(sibling.data, vec![1, 0]);
+                assert!(sibling.accounts.is_empty());
+                assert!(get_processed_sibling_instruction(1).is_none());
+            } else {
+                assert!(get_processed_sibling_instruction(0).is_none());
+            }
+        }
+        [2] => {
+            msg!("noop");
+        }
+        _ => return Err(ProgramError::InvalidInstructionData),
+    }
+    Ok(())
+}
@@ -382,6 +382,7 @@ fn execute_transactions(
                         log_messages,
                         inner_instructions,
                         durable_nonce_fee,
+                        ..
                     } = details;
 
                     let lamports_per_signature = match durable_nonce_fee {
@@ -471,6 +472,8 @@ fn test_program_bpf_sanity() {
         programs.extend_from_slice(&[
             ("paychains_bpf_rust_128bit", true),
             ("paychains_bpf_rust_alloc", true),
+            ("paychains_bpf_rust_alt_bn128", true),
+            ("paychains_bpf_rust_curve25519", true),
             ("paychains_bpf_rust_custom_heap", true),
             ("paychains_bpf_rust_dep_crate", true),
             ("paychains_bpf_rust_external_spend", false),
@@ -1502,6 +1505,50 @@ fn test_program_bpf_instruction_introspection() {
     assert!(bank.get_account(&sysvar::instructions::id()).is_none());
 }
 
+#[cfg(feature = "bpf_rust")]
+#[test]
+fn test_program_bpf_processed_sibling_instruction() {
+    paychains_logger::setup();
+
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair,
+        ..
+    } = create_genesis_config(50_000);
+    let mut bank = Bank::new_for_tests(&genesis_config);
+
+    let (name, id, entrypoint) = paychains_bpf_loader_program!();
+    bank.add_builtin(&name, &id, entrypoint);
+    let bank = Arc::new(bank);
+    let bank_client = BankClient::new_shared(&bank);
+
+    let program_id = load_bpf_program(
+        &bank_client,
+        &bpf_loader::id(),
+        &mint_keypair,
+        "paychains_bpf_rust_sibling_instructions",
+    );
+
+    let account_metas = vec![AccountMeta::new_readonly(program_id, false)];
+    let instruction0 = Instruction::new_with_bytes(program_id, &[2], account_metas.clone());
+    let instruction1 = Instruction::new_with_bytes(program_id, &[0], account_metas);
+    let message = Message::new(&[instruction0, instruction1], Some(&mint_keypair.pubkey()));
+    let result = bank_client.send_and_confirm_message(&[&mint_keypair], message);
+    assert!(result.is_ok());
+
+    // Without a preceding sibling the program fails
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &[0],
+        vec![AccountMeta::new_readonly(program_id, false)],
+    );
+    let result = bank_client.send_and_confirm_instruction(&mint_keypair, instruction);
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::ProgramFailedToComplete)
+    );
+}
+
 #[cfg(feature = "bpf_rust")]
 #[test]
 fn test_program_bpf_test_use_latest_executor() {
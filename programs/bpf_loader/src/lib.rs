@@ -126,7 +126,8 @@ pub fn create_executor(
     let config = Config {
         max_call_depth: compute_budget.max_call_depth,
         stack_frame_size: compute_budget.stack_frame_size,
-        enable_instruction_tracing: log_enabled!(Trace),
+        enable_instruction_tracing: log_enabled!(Trace)
+            || invoke_context.enable_instruction_tracing,
         reject_unresolved_syscalls: reject_deployment_of_broken_elfs
             && invoke_context
                 .feature_set
@@ -1047,6 +1048,7 @@ impl Executor for BpfExecutor {
         let log_collector = invoke_context.get_log_collector();
         let compute_meter = invoke_context.get_compute_meter();
         let invoke_depth = invoke_context.invoke_depth();
+        let enable_instruction_tracing = invoke_context.enable_instruction_tracing;
 
         let mut serialize_time = Measure::start("serialize");
         let program_id = *invoke_context.transaction_context.get_program_key()?;
@@ -1098,7 +1100,15 @@ impl Executor for BpfExecutor {
                 let trace_string = String::from_utf8(trace_buffer).unwrap();
                 trace!("BPF Program Instruction Trace:\n{}", trace_string);
             }
+            let instruction_trace = if enable_instruction_tracing {
+                Some(vm.get_tracer().log.clone())
+            } else {
+                None
+            };
             drop(vm);
+            if let Some(instruction_trace) = instruction_trace {
+                invoke_context.record_instruction_trace(instruction_trace);
+            }
             let (_returned_from_program_id, return_data) =
                 invoke_context.transaction_context.get_return_data();
             if !return_data.is_empty() {
@@ -22,13 +22,15 @@ use {
         blake3, bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
         entrypoint::{BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
         feature_set::{
-            self, blake3_syscall_enabled, disable_fees_sysvar, do_support_realloc,
-            fixed_memcpy_nonoverlapping_check, libsecp256k1_0_5_upgrade_enabled,
-            prevent_calling_precompiles_as_programs, return_data_syscall_enabled,
-            secp256k1_recover_syscall_enabled, pay_log_data_syscall_enabled,
+            self, add_get_processed_sibling_instruction_syscall, alt_bn128_syscall_enabled,
+            blake3_syscall_enabled, curve25519_syscall_enabled, disable_fees_sysvar,
+            do_support_realloc, fixed_memcpy_nonoverlapping_check,
+            libsecp256k1_0_5_upgrade_enabled, prevent_calling_precompiles_as_programs,
+            return_data_syscall_enabled, secp256k1_recover_syscall_enabled,
+            pay_log_data_syscall_enabled,
         },
         hash::{Hasher, HASH_BYTES},
-        instruction::{AccountMeta, Instruction, InstructionError},
+        instruction::{AccountMeta, Instruction, InstructionError, ProcessedSiblingInstruction},
         keccak, native_loader,
         precompiles::is_precompile,
         program::MAX_RETURN_DATA,
@@ -172,6 +174,26 @@ pub fn register_syscalls(
         )?;
     }
 
+    if invoke_context
+        .feature_set
+        .is_active(&curve25519_syscall_enabled::id())
+    {
+        syscall_registry.register_syscall_by_name(
+            b"pay_curve_validate_point",
+            SyscallCurvePointValidation::call,
+        )?;
+        syscall_registry
+            .register_syscall_by_name(b"pay_curve_group_op", SyscallCurveGroupOps::call)?;
+    }
+
+    if invoke_context
+        .feature_set
+        .is_active(&alt_bn128_syscall_enabled::id())
+    {
+        syscall_registry
+            .register_syscall_by_name(b"pay_alt_bn128_group_op", SyscallAltBn128::call)?;
+    }
+
     syscall_registry
         .register_syscall_by_name(b"pay_get_clock_sysvar", SyscallGetClockSysvar::call)?;
     syscall_registry.register_syscall_by_name(
@@ -221,6 +243,19 @@ pub fn register_syscalls(
         syscall_registry.register_syscall_by_name(b"pay_log_data", SyscallLogData::call)?;
     }
 
+    // Instruction introspection
+    if invoke_context
+        .feature_set
+        .is_active(&add_get_processed_sibling_instruction_syscall::id())
+    {
+        syscall_registry.register_syscall_by_name(
+            b"pay_get_processed_sibling_instruction",
+            SyscallGetProcessedSiblingInstruction::call,
+        )?;
+        syscall_registry
+            .register_syscall_by_name(b"pay_get_stack_height", SyscallGetStackHeight::call)?;
+    }
+
     Ok(syscall_registry)
 }
 
@@ -261,6 +296,15 @@ pub fn bind_syscall_context_objects<'a, 'b>(
     let is_zk_token_sdk_enabled = invoke_context
         .feature_set
         .is_active(&feature_set::zk_token_sdk_enabled::id());
+    let is_curve25519_syscall_active = invoke_context
+        .feature_set
+        .is_active(&curve25519_syscall_enabled::id());
+    let is_alt_bn128_syscall_active = invoke_context
+        .feature_set
+        .is_active(&alt_bn128_syscall_enabled::id());
+    let add_get_processed_sibling_instruction_syscall = invoke_context
+        .feature_set
+        .is_active(&add_get_processed_sibling_instruction_syscall::id());
 
     let loader_id = invoke_context
         .transaction_context
@@ -391,6 +435,28 @@ pub fn bind_syscall_context_objects<'a, 'b>(
         }),
     );
 
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        is_curve25519_syscall_active,
+        Box::new(SyscallCurvePointValidation {
+            invoke_context: invoke_context.clone(),
+        }),
+    );
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        is_curve25519_syscall_active,
+        Box::new(SyscallCurveGroupOps {
+            invoke_context: invoke_context.clone(),
+        }),
+    );
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        is_alt_bn128_syscall_active,
+        Box::new(SyscallAltBn128 {
+            invoke_context: invoke_context.clone(),
+        }),
+    );
+
     vm.bind_syscall_context_object(
         Box::new(SyscallGetClockSysvar {
             invoke_context: invoke_context.clone(),
@@ -443,6 +509,22 @@ pub fn bind_syscall_context_objects<'a, 'b>(
         }),
     );
 
+    // Instruction introspection
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        add_get_processed_sibling_instruction_syscall,
+        Box::new(SyscallGetProcessedSiblingInstruction {
+            invoke_context: invoke_context.clone(),
+        }),
+    );
+    bind_feature_gated_syscall_context_object!(
+        vm,
+        add_get_processed_sibling_instruction_syscall,
+        Box::new(SyscallGetStackHeight {
+            invoke_context: invoke_context.clone(),
+        }),
+    );
+
     // Cross-program invocation syscalls
     vm.bind_syscall_context_object(
         Box::new(SyscallInvokeSignedC {
@@ -1897,6 +1979,318 @@ impl<'a, 'b> SyscallObject<BpfError> for SyscallBlake3<'a, 'b> {
     }
 }
 
+// Curve25519 point validation
+pub struct SyscallCurvePointValidation<'a, 'b> {
+    invoke_context: Rc<RefCell<&'a mut InvokeContext<'b>>>,
+}
+impl<'a, 'b> SyscallObject<BpfError> for SyscallCurvePointValidation<'a, 'b> {
+    fn call(
+        &mut self,
+        curve_id: u64,
+        point_addr: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        use paychains_sdk::curve25519::{
+            edwards, ristretto, CURVE25519_EDWARDS, CURVE25519_RISTRETTO,
+        };
+
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+        let loader_id = question_mark!(
+            invoke_context
+                .transaction_context
+                .get_loader_key()
+                .map_err(SyscallError::InstructionError),
+            result
+        );
+
+        match curve_id {
+            CURVE25519_EDWARDS => {
+                let cost = invoke_context
+                    .get_compute_budget()
+                    .curve25519_edwards_validate_point_cost;
+                question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+                let point = question_mark!(
+                    translate_type::<edwards::PodEdwardsPoint>(
+                        memory_mapping,
+                        point_addr,
+                        &loader_id
+                    ),
+                    result
+                );
+                *result = Ok(!edwards::validate_edwards(point) as u64);
+            }
+            CURVE25519_RISTRETTO => {
+                let cost = invoke_context
+                    .get_compute_budget()
+                    .curve25519_ristretto_validate_point_cost;
+                question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+                let point = question_mark!(
+                    translate_type::<ristretto::PodRistrettoPoint>(
+                        memory_mapping,
+                        point_addr,
+                        &loader_id
+                    ),
+                    result
+                );
+                *result = Ok(!ristretto::validate_ristretto(point) as u64);
+            }
+            _ => {
+                *result = Ok(1);
+            }
+        }
+    }
+}
+
+// Curve25519 group operations
+pub struct SyscallCurveGroupOps<'a, 'b> {
+    invoke_context: Rc<RefCell<&'a mut InvokeContext<'b>>>,
+}
+impl<'a, 'b> SyscallObject<BpfError> for SyscallCurveGroupOps<'a, 'b> {
+    fn call(
+        &mut self,
+        curve_id: u64,
+        group_op: u64,
+        left_input_addr: u64,
+        right_input_addr: u64,
+        result_point_addr: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        use paychains_sdk::curve25519::{
+            edwards, ristretto, PodScalar, ADD, CURVE25519_EDWARDS, CURVE25519_RISTRETTO, MUL, SUB,
+        };
+
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+        let loader_id = question_mark!(
+            invoke_context
+                .transaction_context
+                .get_loader_key()
+                .map_err(SyscallError::InstructionError),
+            result
+        );
+        let compute_budget = invoke_context.get_compute_budget();
+
+        match curve_id {
+            CURVE25519_EDWARDS => {
+                let cost = match group_op {
+                    ADD => compute_budget.curve25519_edwards_add_cost,
+                    SUB => compute_budget.curve25519_edwards_subtract_cost,
+                    MUL => compute_budget.curve25519_edwards_multiply_cost,
+                    _ => {
+                        *result = Ok(1);
+                        return;
+                    }
+                };
+                question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+                let right_point = question_mark!(
+                    translate_type::<edwards::PodEdwardsPoint>(
+                        memory_mapping,
+                        right_input_addr,
+                        &loader_id
+                    ),
+                    result
+                );
+                let result_point = if group_op == MUL {
+                    let scalar = question_mark!(
+                        translate_type::<PodScalar>(memory_mapping, left_input_addr, &loader_id),
+                        result
+                    );
+                    edwards::multiply_edwards(scalar, right_point)
+                } else {
+                    let left_point = question_mark!(
+                        translate_type::<edwards::PodEdwardsPoint>(
+                            memory_mapping,
+                            left_input_addr,
+                            &loader_id
+                        ),
+                        result
+                    );
+                    if group_op == ADD {
+                        edwards::add_edwards(left_point, right_point)
+                    } else {
+                        edwards::subtract_edwards(left_point, right_point)
+                    }
+                };
+
+                if let Some(result_point) = result_point {
+                    *question_mark!(
+                        translate_type_mut::<edwards::PodEdwardsPoint>(
+                            memory_mapping,
+                            result_point_addr,
+                            &loader_id
+                        ),
+                        result
+                    ) = result_point;
+                    *result = Ok(0);
+                } else {
+                    *result = Ok(1);
+                }
+            }
+            CURVE25519_RISTRETTO => {
+                let cost = match group_op {
+                    ADD => compute_budget.curve25519_ristretto_add_cost,
+                    SUB => compute_budget.curve25519_ristretto_subtract_cost,
+                    MUL => compute_budget.curve25519_ristretto_multiply_cost,
+                    _ => {
+                        *result = Ok(1);
+                        return;
+                    }
+                };
+                question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+                let right_point = question_mark!(
+                    translate_type::<ristretto::PodRistrettoPoint>(
+                        memory_mapping,
+                        right_input_addr,
+                        &loader_id
+                    ),
+                    result
+                );
+                let result_point = if group_op == MUL {
+                    let scalar = question_mark!(
+                        translate_type::<PodScalar>(memory_mapping, left_input_addr, &loader_id),
+                        result
+                    );
+                    ristretto::multiply_ristretto(scalar, right_point)
+                } else {
+                    let left_point = question_mark!(
+                        translate_type::<ristretto::PodRistrettoPoint>(
+                            memory_mapping,
+                            left_input_addr,
+                            &loader_id
+                        ),
+                        result
+                    );
+                    if group_op == ADD {
+                        ristretto::add_ristretto(left_point, right_point)
+                    } else {
+                        ristretto::subtract_ristretto(left_point, right_point)
+                    }
+                };
+
+                if let Some(result_point) = result_point {
+                    *question_mark!(
+                        translate_type_mut::<ristretto::PodRistrettoPoint>(
+                            memory_mapping,
+                            result_point_addr,
+                            &loader_id
+                        ),
+                        result
+                    ) = result_point;
+                    *result = Ok(0);
+                } else {
+                    *result = Ok(1);
+                }
+            }
+            _ => {
+                *result = Ok(1);
+            }
+        }
+    }
+}
+
+// alt_bn128 group operations and pairing check
+pub struct SyscallAltBn128<'a, 'b> {
+    invoke_context: Rc<RefCell<&'a mut InvokeContext<'b>>>,
+}
+impl<'a, 'b> SyscallObject<BpfError> for SyscallAltBn128<'a, 'b> {
+    fn call(
+        &mut self,
+        group_op: u64,
+        input_addr: u64,
+        input_size: u64,
+        result_addr: u64,
+        _arg5: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        use paychains_sdk::alt_bn128::{
+            native, AltBn128Error, ALT_BN128_ADD, ALT_BN128_ADDITION_OUTPUT_LEN, ALT_BN128_MUL,
+            ALT_BN128_MULTIPLICATION_OUTPUT_LEN, ALT_BN128_PAIRING, ALT_BN128_PAIRING_ELEMENT_LEN,
+            ALT_BN128_PAIRING_OUTPUT_LEN,
+        };
+
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+        let loader_id = question_mark!(
+            invoke_context
+                .transaction_context
+                .get_loader_key()
+                .map_err(SyscallError::InstructionError),
+            result
+        );
+        let compute_budget = invoke_context.get_compute_budget();
+
+        // Charge before touching the input, the pairing cost only depends on its length
+        let (cost, output_len) = match group_op {
+            ALT_BN128_ADD => (
+                compute_budget.alt_bn128_addition_cost,
+                ALT_BN128_ADDITION_OUTPUT_LEN,
+            ),
+            ALT_BN128_MUL => (
+                compute_budget.alt_bn128_multiplication_cost,
+                ALT_BN128_MULTIPLICATION_OUTPUT_LEN,
+            ),
+            ALT_BN128_PAIRING => {
+                let pairs = input_size / ALT_BN128_PAIRING_ELEMENT_LEN as u64;
+                let other_pairs_cost = compute_budget
+                    .alt_bn128_pairing_one_pair_cost_other
+                    .saturating_mul(pairs.saturating_sub(1));
+                (
+                    compute_budget
+                        .alt_bn128_pairing_one_pair_cost_first
+                        .saturating_add(other_pairs_cost),
+                    ALT_BN128_PAIRING_OUTPUT_LEN,
+                )
+            }
+            _ => {
+                *result = Ok(AltBn128Error::InvalidInputData.into());
+                return;
+            }
+        };
+        question_mark!(invoke_context.get_compute_meter().consume(cost), result);
+
+        let input = question_mark!(
+            translate_slice::<u8>(memory_mapping, input_addr, input_size, &loader_id),
+            result
+        );
+        let output = question_mark!(
+            translate_slice_mut::<u8>(memory_mapping, result_addr, output_len as u64, &loader_id),
+            result
+        );
+        match native::alt_bn128_group_op(group_op, input) {
+            Ok(value) => {
+                output.copy_from_slice(&value);
+                *result = Ok(0);
+            }
+            Err(err) => {
+                *result = Ok(err.into());
+            }
+        }
+    }
+}
+
 // Cross-program invocation syscalls
 
 struct CallerAccount<'a> {
@@ -2914,6 +3308,134 @@ impl<'a, 'b> SyscallObject<BpfError> for SyscallLogData<'a, 'b> {
     }
 }
 
+pub struct SyscallGetProcessedSiblingInstruction<'a, 'b> {
+    invoke_context: Rc<RefCell<&'a mut InvokeContext<'b>>>,
+}
+impl<'a, 'b> SyscallObject<BpfError> for SyscallGetProcessedSiblingInstruction<'a, 'b> {
+    fn call(
+        &mut self,
+        index: u64,
+        meta_addr: u64,
+        program_id_addr: u64,
+        data_addr: u64,
+        accounts_addr: u64,
+        memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+        let loader_id = question_mark!(
+            invoke_context
+                .transaction_context
+                .get_loader_key()
+                .map_err(SyscallError::InstructionError),
+            result
+        );
+
+        let budget = invoke_context.get_compute_budget();
+        question_mark!(
+            invoke_context
+                .get_compute_meter()
+                .consume(budget.syscall_base_cost),
+            result
+        );
+
+        let transaction_context = &invoke_context.transaction_context;
+        if let Some(instruction_context) =
+            transaction_context.get_processed_sibling_instruction(index as usize)
+        {
+            let meta = question_mark!(
+                translate_type_mut::<ProcessedSiblingInstruction>(
+                    memory_mapping,
+                    meta_addr,
+                    &loader_id,
+                ),
+                result
+            );
+            let data = instruction_context.get_instruction_data();
+            let account_metas =
+                instruction_context.get_instruction_account_metas(transaction_context);
+
+            // Only copy the instruction out once the caller has sized its buffers
+            if meta.data_len == data.len() && meta.accounts_len == account_metas.len() {
+                let program_id = question_mark!(
+                    translate_type_mut::<Pubkey>(memory_mapping, program_id_addr, &loader_id),
+                    result
+                );
+                let data_result = question_mark!(
+                    translate_slice_mut::<u8>(
+                        memory_mapping,
+                        data_addr,
+                        meta.data_len as u64,
+                        &loader_id,
+                    ),
+                    result
+                );
+                let accounts_result = question_mark!(
+                    translate_slice_mut::<AccountMeta>(
+                        memory_mapping,
+                        accounts_addr,
+                        meta.accounts_len as u64,
+                        &loader_id,
+                    ),
+                    result
+                );
+
+                *program_id = *question_mark!(
+                    instruction_context
+                        .get_program_key(transaction_context)
+                        .map_err(SyscallError::InstructionError),
+                    result
+                );
+                data_result.clone_from_slice(data);
+                accounts_result.clone_from_slice(&account_metas);
+            }
+            meta.data_len = data.len();
+            meta.accounts_len = account_metas.len();
+            *result = Ok(true as u64);
+            return;
+        }
+        *result = Ok(false as u64);
+    }
+}
+
+pub struct SyscallGetStackHeight<'a, 'b> {
+    invoke_context: Rc<RefCell<&'a mut InvokeContext<'b>>>,
+}
+impl<'a, 'b> SyscallObject<BpfError> for SyscallGetStackHeight<'a, 'b> {
+    fn call(
+        &mut self,
+        _arg1: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        _memory_mapping: &MemoryMapping,
+        result: &mut Result<u64, EbpfError<BpfError>>,
+    ) {
+        let invoke_context = question_mark!(
+            self.invoke_context
+                .try_borrow()
+                .map_err(|_| SyscallError::InvokeContextBorrowFailed),
+            result
+        );
+
+        let budget = invoke_context.get_compute_budget();
+        question_mark!(
+            invoke_context
+                .get_compute_meter()
+                .consume(budget.syscall_base_cost),
+            result
+        );
+
+        *result = Ok(invoke_context.invoke_depth() as u64);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(deprecated)]
@@ -3784,6 +4306,242 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_syscall_curve25519_edwards() {
+        use paychains_sdk::curve25519::{
+            edwards::{self, PodEdwardsPoint},
+            PodScalar, ADD, CURVE25519_EDWARDS, MUL,
+        };
+
+        let config = Config::default();
+        let program_id = Pubkey::new_unique();
+        let mut transaction_context = TransactionContext::new(
+            vec![(program_id, AccountSharedData::new(0, 0, &bpf_loader::id()))],
+            1,
+        );
+        let mut invoke_context = InvokeContext::new_mock(&mut transaction_context, &[]);
+        invoke_context.push(&[], &[0], &[]).unwrap();
+
+        // compressed edwards25519 basepoint
+        let mut basepoint = PodEdwardsPoint([0x66; 32]);
+        basepoint.0[0] = 0x58;
+        let mut scalar = PodScalar::default();
+        scalar.0[0] = 2;
+        let result_point = PodEdwardsPoint::default();
+
+        let basepoint_va = 0x100000000;
+        let scalar_va = 0x200000000;
+        let result_point_va = 0x300000000;
+        let memory_mapping = MemoryMapping::new::<UserError>(
+            vec![
+                MemoryRegion::default(),
+                MemoryRegion {
+                    host_addr: basepoint.0.as_ptr() as *const _ as u64,
+                    vm_addr: basepoint_va,
+                    len: 32,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: scalar.0.as_ptr() as *const _ as u64,
+                    vm_addr: scalar_va,
+                    len: 32,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: result_point.0.as_ptr() as *const _ as u64,
+                    vm_addr: result_point_va,
+                    len: 32,
+                    vm_gap_shift: 63,
+                    is_writable: true,
+                },
+            ],
+            &config,
+        )
+        .unwrap();
+
+        let compute_budget = *invoke_context.get_compute_budget();
+        invoke_context
+            .get_compute_meter()
+            .borrow_mut()
+            .mock_set_remaining(
+                compute_budget.curve25519_edwards_validate_point_cost
+                    + compute_budget.curve25519_edwards_add_cost
+                    + compute_budget.curve25519_edwards_multiply_cost,
+            );
+        let invoke_context = Rc::new(RefCell::new(&mut invoke_context));
+
+        let mut syscall = SyscallCurvePointValidation {
+            invoke_context: invoke_context.clone(),
+        };
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            CURVE25519_EDWARDS,
+            basepoint_va,
+            0,
+            0,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(0, result.unwrap());
+
+        let mut syscall = SyscallCurveGroupOps { invoke_context };
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            CURVE25519_EDWARDS,
+            ADD,
+            basepoint_va,
+            basepoint_va,
+            result_point_va,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(0, result.unwrap());
+        assert_eq!(
+            Some(result_point),
+            edwards::add_edwards(&basepoint, &basepoint)
+        );
+
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            CURVE25519_EDWARDS,
+            MUL,
+            scalar_va,
+            basepoint_va,
+            result_point_va,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(0, result.unwrap());
+        assert_eq!(
+            Some(result_point),
+            edwards::multiply_edwards(&scalar, &basepoint)
+        );
+
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            CURVE25519_EDWARDS,
+            ADD,
+            basepoint_va,
+            basepoint_va,
+            result_point_va,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(
+            Err(EbpfError::UserError(BpfError::SyscallError(
+                SyscallError::InstructionError(InstructionError::ComputationalBudgetExceeded)
+            ))),
+            result
+        );
+    }
+
+    #[test]
+    fn test_syscall_alt_bn128() {
+        use paychains_sdk::alt_bn128::{
+            alt_bn128_addition, AltBn128Error, ALT_BN128_ADD, ALT_BN128_ADDITION_INPUT_LEN,
+            ALT_BN128_ADDITION_OUTPUT_LEN,
+        };
+
+        let config = Config::default();
+        let program_id = Pubkey::new_unique();
+        let mut transaction_context = TransactionContext::new(
+            vec![(program_id, AccountSharedData::new(0, 0, &bpf_loader::id()))],
+            1,
+        );
+        let mut invoke_context = InvokeContext::new_mock(&mut transaction_context, &[]);
+        invoke_context.push(&[], &[0], &[]).unwrap();
+
+        // two copies of the G1 generator (1, 2)
+        let mut input = [0u8; ALT_BN128_ADDITION_INPUT_LEN];
+        input[31] = 1;
+        input[63] = 2;
+        input[95] = 1;
+        input[127] = 2;
+        let output = [0u8; ALT_BN128_ADDITION_OUTPUT_LEN];
+
+        let input_va = 0x100000000;
+        let output_va = 0x200000000;
+        let memory_mapping = MemoryMapping::new::<UserError>(
+            vec![
+                MemoryRegion::default(),
+                MemoryRegion {
+                    host_addr: input.as_ptr() as *const _ as u64,
+                    vm_addr: input_va,
+                    len: input.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: false,
+                },
+                MemoryRegion {
+                    host_addr: output.as_ptr() as *const _ as u64,
+                    vm_addr: output_va,
+                    len: output.len() as u64,
+                    vm_gap_shift: 63,
+                    is_writable: true,
+                },
+            ],
+            &config,
+        )
+        .unwrap();
+
+        let compute_budget = *invoke_context.get_compute_budget();
+        invoke_context
+            .get_compute_meter()
+            .borrow_mut()
+            .mock_set_remaining(compute_budget.alt_bn128_addition_cost);
+        let invoke_context = Rc::new(RefCell::new(&mut invoke_context));
+        let mut syscall = SyscallAltBn128 { invoke_context };
+
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            ALT_BN128_ADD,
+            input_va,
+            input.len() as u64,
+            output_va,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(0, result.unwrap());
+        assert_eq!(Ok(output.to_vec()), alt_bn128_addition(&input));
+
+        // unknown group operations are rejected without being charged
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            42,
+            input_va,
+            input.len() as u64,
+            output_va,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(
+            AltBn128Error::InvalidInputData,
+            AltBn128Error::from(result.unwrap())
+        );
+
+        // compute units are charged before the input is translated
+        let mut result: Result<u64, EbpfError<BpfError>> = Ok(0);
+        syscall.call(
+            ALT_BN128_ADD,
+            0x300000000,
+            input.len() as u64,
+            output_va,
+            0,
+            &memory_mapping,
+            &mut result,
+        );
+        assert_eq!(
+            Err(EbpfError::UserError(BpfError::SyscallError(
+                SyscallError::InstructionError(InstructionError::ComputationalBudgetExceeded)
+            ))),
+            result
+        );
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_syscall_get_sysvar() {
@@ -1,5 +1,12 @@
+mod profile;
+mod replay;
+
 use {
-    clap::{crate_version, App, Arg},
+    crate::{
+        profile::{fold, fold_invocations, write_folded, FoldedStacks, ProgramSymbols},
+        replay::{replay, ReplayConfig},
+    },
+    clap::{crate_version, App, Arg, ArgMatches},
     serde::{Deserialize, Serialize},
     serde_json::Result,
     paychains_bpf_loader_program::{
@@ -16,12 +23,13 @@ use {
     },
     paychains_sdk::{
         account::AccountSharedData, bpf_loader, instruction::AccountMeta, pubkey::Pubkey,
-        transaction_context::TransactionContext,
+        signature::Signature, transaction_context::TransactionContext,
     },
     std::{
+        collections::HashMap,
         fs::File,
         io::{Read, Seek, SeekFrom},
-        path::Path,
+        path::{Path, PathBuf},
     },
     time::Instant,
 };
@@ -50,6 +58,55 @@ fn load_accounts(path: &Path) -> Result<Input> {
     Ok(input)
 }
 
+fn save_folded(folded: &FoldedStacks) {
+    println!("Profile is saved in profile.folded");
+    let mut file = File::create("profile.folded").unwrap();
+    write_folded(&mut file, folded).unwrap();
+}
+
+fn replay_ledger_transaction(matches: &ArgMatches) {
+    let config = ReplayConfig {
+        ledger_path: PathBuf::from(matches.value_of("ledger").unwrap()),
+        snapshot_archive_path: matches.value_of("snapshot_archive_path").map(PathBuf::from),
+        signature: matches
+            .value_of("signature")
+            .unwrap()
+            .parse::<Signature>()
+            .expect("invalid transaction signature"),
+        instruction_index: matches
+            .value_of("instruction_index")
+            .unwrap()
+            .parse::<usize>()
+            .expect("invalid instruction index"),
+        use_jit: matches.value_of("use").unwrap() == "jit",
+    };
+    let result = replay(&config).unwrap();
+    println!(
+        "Replayed instruction {} of {} in slot {}, program {}",
+        config.instruction_index, config.signature, result.slot, result.program_id
+    );
+    println!("Program logs:");
+    for log in &result.logs {
+        println!("  {}", log);
+    }
+    println!("Result: {:?}", result.result);
+    println!("Compute units consumed: {}", result.compute_units_consumed);
+    if matches.is_present("folded") {
+        let programs: HashMap<_, _> = result
+            .programs
+            .iter()
+            .filter_map(|(program_id, elf)| match ProgramSymbols::from_elf(elf) {
+                Ok(symbols) => Some((*program_id, symbols)),
+                Err(err) => {
+                    println!("Failed to load program {}: {}", program_id, err);
+                    None
+                }
+            })
+            .collect();
+        save_folded(&fold_invocations(&result.invocation_traces, &programs));
+    }
+}
+
 fn main() {
     paychains_logger::setup();
     let matches = App::new("PayChains BPF CLI")
@@ -60,7 +117,12 @@ fn main() {
 
 The tool executes eBPF programs in a mocked environment.
 Some features, such as sysvars syscall and CPI, are not
-available for the programs executed by the CLI tool.
+available for the programs executed by the CLI tool, unless
+a transaction is replayed from a ledger with --ledger, in which
+case the instruction runs against the accounts as they were
+when the transaction executed it, after the slot's earlier
+transactions and the transaction's preceding instructions,
+including any cross-program invocations it makes.
 
 The input data for a program execution have to be in JSON format
 and the following fields are required
@@ -91,7 +153,7 @@ and the following fields are required
                     "Program file to use. This is either an ELF shared-object file to be executed, \
                      or an assembly file to be assembled and executed.",
                 )
-                .required(true)
+                .required_unless_present("ledger")
                 .index(1)
         )
         .arg(
@@ -157,10 +219,60 @@ native machine code before execting it in the virtual machine.",
                 .short('v')
                 .long("verify"),
         )
+        .arg(
+            Arg::new("folded")
+                .about(
+                    "Output profile to 'profile.folded' file in folded-stack format, for use \
+with flamegraph tools",
+                )
+                .long("folded"),
+        )
+        .arg(
+            Arg::new("ledger")
+                .about(
+                    "Replay an instruction of a transaction recorded in the ledger at DIR \
+instead of running PROGRAM",
+                )
+                .long("ledger")
+                .takes_value(true)
+                .value_name("DIR")
+                .requires("signature"),
+        )
+        .arg(
+            Arg::new("signature")
+                .about("Signature of the transaction to replay")
+                .long("signature")
+                .takes_value(true)
+                .value_name("SIGNATURE")
+                .requires("ledger"),
+        )
+        .arg(
+            Arg::new("instruction_index")
+                .about("Index of the transaction's instruction to replay, the preceding instructions are executed first")
+                .long("instruction-index")
+                .takes_value(true)
+                .value_name("INDEX")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("snapshot_archive_path")
+                .about("Directory of the snapshot archives to load, defaults to the ledger")
+                .long("snapshot-archive-path")
+                .takes_value(true)
+                .value_name("DIR")
+                .requires("ledger"),
+        )
         .get_matches();
 
+    if matches.is_present("ledger") {
+        replay_ledger_transaction(&matches);
+        return;
+    }
+
     let config = Config {
-        enable_instruction_tracing: matches.is_present("trace") || matches.is_present("profile"),
+        enable_instruction_tracing: matches.is_present("trace")
+            || matches.is_present("profile")
+            || matches.is_present("folded"),
         enable_symbol_and_section_labels: true,
         ..Config::default()
     };
@@ -240,7 +352,8 @@ native machine code before execting it in the virtual machine.",
     let mut contents = Vec::new();
     file.read_to_end(&mut contents).unwrap();
     let syscall_registry = register_syscalls(&mut invoke_context).unwrap();
-    let mut executable = if magic == [0x7f, 0x45, 0x4c, 0x46] {
+    let is_elf = magic == [0x7f, 0x45, 0x4c, 0x46];
+    let mut executable = if is_elf {
         Executable::<BpfError, ThisInstructionMeter>::from_elf(
             &contents,
             None,
@@ -312,4 +425,13 @@ native machine code before execting it in the virtual machine.",
             .visualize_graphically(&mut file, Some(&dynamic_analysis))
             .unwrap();
     }
+    if matches.is_present("folded") {
+        let elf = if is_elf {
+            Some(contents.as_slice())
+        } else {
+            None
+        };
+        let symbols = ProgramSymbols::new(&executable, elf);
+        save_folded(&fold(&symbols, &vm.get_tracer().log));
+    }
 }
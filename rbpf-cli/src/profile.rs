@@ -0,0 +1,381 @@
+//! Folded-stack profiles of instruction traces, in the format consumed by flamegraph tools
+//!
+//! Every traced instruction counts as one sample.  Frames are named after the function they
+//! execute in, annotated with the source line of the call site (or of the sampled instruction
+//! for the innermost frame) when the program carries DWARF line information.
+
+use {
+    gimli::{Dwarf, EndianSlice, LittleEndian, SectionId},
+    goblin::elf::Elf,
+    paychains_bpf_loader_program::{BpfError, ThisInstructionMeter},
+    paychains_program_runtime::invoke_context::InvocationTrace,
+    paychains_rbpf::{ebpf, elf::Executable, static_analysis::Analysis},
+    paychains_sdk::pubkey::Pubkey,
+    std::{
+        collections::{BTreeMap, HashMap},
+        io::{self, Write},
+    },
+};
+
+/// Syscalls that perform a cross-program invocation
+const CPI_SYSCALLS: [&[u8]; 2] = [b"pay_invoke_signed_c", b"pay_invoke_signed_rust"];
+
+/// Folded stacks and their sample counts
+pub type FoldedStacks = BTreeMap<String, u64>;
+
+/// What a profile needs to know about a program's code
+#[derive(Debug, Default)]
+pub struct ProgramSymbols {
+    /// Function names by the index of their first instruction
+    pub functions: BTreeMap<usize, String>,
+    /// Opcode and immediate of every instruction slot
+    pub instructions: Vec<(u8, i32)>,
+    /// Source locations by the index of the first instruction they cover
+    pub lines: BTreeMap<usize, String>,
+}
+
+impl ProgramSymbols {
+    /// Collect symbols from a loaded executable, reading line information from `elf` if given
+    pub fn new(
+        executable: &Executable<BpfError, ThisInstructionMeter>,
+        elf: Option<&[u8]>,
+    ) -> Self {
+        let analysis = Analysis::from_executable(executable);
+        let functions = analysis
+            .functions
+            .iter()
+            .map(|(pc, (_, name))| (*pc, rustc_demangle::demangle(name).to_string()))
+            .collect();
+        let instructions = executable
+            .get_text_bytes()
+            .1
+            .chunks_exact(ebpf::INSN_SIZE)
+            .map(|insn| {
+                (
+                    insn[0],
+                    i32::from_le_bytes([insn[4], insn[5], insn[6], insn[7]]),
+                )
+            })
+            .collect();
+        let lines = elf
+            .and_then(|elf| match line_table(elf) {
+                Ok(lines) => Some(lines),
+                Err(err) => {
+                    println!("Failed to read line information: {}", err);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            functions,
+            instructions,
+            lines,
+        }
+    }
+
+    /// Symbols of a program stored on chain
+    pub fn from_elf(elf: &[u8]) -> Result<Self, String> {
+        let executable = Executable::<BpfError, ThisInstructionMeter>::from_elf(
+            elf,
+            None,
+            Default::default(),
+            Default::default(),
+        )
+        .map_err(|err| format!("Executable constructor failed: {:?}", err))?;
+        Ok(Self::new(&executable, Some(elf)))
+    }
+
+    fn function_name(&self, pc: usize) -> String {
+        self.functions
+            .range(..=pc)
+            .next_back()
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| format!("function_{}", pc))
+    }
+
+    fn frame(&self, function: usize, pc: usize) -> String {
+        let name = self.function_name(function);
+        match self.lines.range(..=pc).next_back() {
+            Some((_, location)) => format!("{} ({})", name, location),
+            None => name,
+        }
+    }
+
+    fn is_cpi(&self, pc: usize) -> bool {
+        match self.instructions.get(pc) {
+            Some((opc, imm)) if *opc == ebpf::CALL_IMM => CPI_SYSCALLS
+                .iter()
+                .any(|name| ebpf::hash_symbol_name(name) == *imm as u32),
+            _ => false,
+        }
+    }
+}
+
+/// Map the line program rows of the ELF's DWARF sections to instruction indices
+fn line_table(elf_bytes: &[u8]) -> Result<BTreeMap<usize, String>, String> {
+    let elf = Elf::parse(elf_bytes).map_err(|err| err.to_string())?;
+    let section = |name: &str| {
+        elf.section_headers.iter().find(|header| {
+            elf.shdr_strtab
+                .get_at(header.sh_name)
+                .map(|section_name| section_name == name)
+                .unwrap_or(false)
+        })
+    };
+    let text_address = match section(".text") {
+        Some(header) => header.sh_addr,
+        None => return Ok(BTreeMap::new()),
+    };
+    let dwarf = Dwarf::load(|id: SectionId| -> Result<_, gimli::Error> {
+        let data = section(id.name())
+            .and_then(|header| elf_bytes.get(header.file_range()?))
+            .unwrap_or(&[]);
+        Ok(EndianSlice::new(data, LittleEndian))
+    })
+    .map_err(|err| err.to_string())?;
+
+    let mut lines = BTreeMap::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().map_err(|err| err.to_string())? {
+        let unit = dwarf.unit(header).map_err(|err| err.to_string())?;
+        let program = match unit.line_program.clone() {
+            Some(program) => program,
+            None => continue,
+        };
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row().map_err(|err| err.to_string())? {
+            if row.end_sequence() || row.address() < text_address {
+                continue;
+            }
+            let file = match row.file(header) {
+                Some(file) => dwarf
+                    .attr_string(&unit, file.path_name())
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                None => continue,
+            };
+            let line = row.line().map(u64::from).unwrap_or_default();
+            let pc = (row.address() - text_address) as usize / ebpf::INSN_SIZE;
+            lines.insert(pc, format!("{}:{}", file, line));
+        }
+    }
+    Ok(lines)
+}
+
+/// Fold the trace of a single program invocation.  `on_cpi` is called with the current stack
+/// every time the program makes a cross-program invocation
+fn fold_trace<F>(
+    symbols: &ProgramSymbols,
+    trace: &[[u64; 12]],
+    prefix: &[String],
+    folded: &mut FoldedStacks,
+    mut on_cpi: F,
+) where
+    F: FnMut(&[String], &mut FoldedStacks),
+{
+    let pc_at = |index: usize| trace.get(index).map(|entry| entry[11] as usize);
+    let first_pc = match pc_at(0) {
+        Some(pc) => pc,
+        None => return,
+    };
+    // The function of every frame, and the call site of every frame but the innermost
+    let mut functions = vec![first_pc];
+    let mut call_sites = vec![];
+    for (index, entry) in trace.iter().enumerate() {
+        let pc = entry[11] as usize;
+        let mut stack = prefix.to_vec();
+        for (function, call_site) in functions.iter().zip(call_sites.iter()) {
+            stack.push(symbols.frame(*function, *call_site));
+        }
+        stack.push(symbols.frame(*functions.last().unwrap(), pc));
+        *folded.entry(stack.join(";")).or_default() += 1;
+
+        let opc = symbols.instructions.get(pc).map(|(opc, _)| *opc);
+        if opc == Some(ebpf::CALL_IMM) || opc == Some(ebpf::CALL_REG) {
+            match pc_at(index + 1) {
+                // Syscalls return to the next instruction
+                Some(next_pc) if next_pc == pc + 1 && opc == Some(ebpf::CALL_IMM) => {
+                    if symbols.is_cpi(pc) {
+                        on_cpi(&stack, folded);
+                    }
+                }
+                Some(next_pc) => {
+                    functions.push(next_pc);
+                    call_sites.push(pc);
+                }
+                None => {}
+            }
+        } else if opc == Some(ebpf::EXIT) && functions.len() > 1 {
+            functions.pop();
+            call_sites.pop();
+        }
+    }
+}
+
+/// Fold the trace of a program run outside of a transaction
+pub fn fold(symbols: &ProgramSymbols, trace: &[[u64; 12]]) -> FoldedStacks {
+    let mut folded = FoldedStacks::new();
+    fold_trace(symbols, trace, &[], &mut folded, |_, _| {});
+    folded
+}
+
+/// Fold the traces of an instruction and of the cross-program invocations it made, nesting
+/// each invocation under the stack of the caller at the time of the call
+pub fn fold_invocations(
+    traces: &[InvocationTrace],
+    programs: &HashMap<Pubkey, ProgramSymbols>,
+) -> FoldedStacks {
+    let mut folded = FoldedStacks::new();
+    let mut index = 0;
+    while index < traces.len() {
+        index = fold_invocation(traces, index, programs, &[], &mut folded);
+    }
+    folded
+}
+
+/// Fold `traces[index]` and its descendants, returning the index of the next invocation that
+/// is not a descendant
+fn fold_invocation(
+    traces: &[InvocationTrace],
+    index: usize,
+    programs: &HashMap<Pubkey, ProgramSymbols>,
+    prefix: &[String],
+    folded: &mut FoldedStacks,
+) -> usize {
+    let invocation = &traces[index];
+    let end = traces[index + 1..]
+        .iter()
+        .position(|trace| trace.stack_height <= invocation.stack_height)
+        .map(|position| index + 1 + position)
+        .unwrap_or(traces.len());
+    let mut prefix = prefix.to_vec();
+    prefix.push(invocation.program_id.to_string());
+
+    let mut next_child = index + 1;
+    if let Some(symbols) = programs.get(&invocation.program_id) {
+        fold_trace(
+            symbols,
+            &invocation.trace,
+            &prefix,
+            folded,
+            |stack, folded| {
+                if next_child < end {
+                    next_child = fold_invocation(traces, next_child, programs, stack, folded);
+                }
+            },
+        );
+    }
+    // Invocations made by programs without a trace, e.g. builtins
+    while next_child < end {
+        next_child = fold_invocation(traces, next_child, programs, &prefix, folded);
+    }
+    end
+}
+
+/// Write folded stacks, one `stack count` line each
+pub fn write_folded<W: Write>(output: &mut W, folded: &FoldedStacks) -> io::Result<()> {
+    for (stack, count) in folded {
+        writeln!(output, "{} {}", stack, count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(pcs: &[usize]) -> Vec<[u64; 12]> {
+        pcs.iter()
+            .map(|pc| {
+                let mut entry = [0u64; 12];
+                entry[11] = *pc as u64;
+                entry
+            })
+            .collect()
+    }
+
+    // entrypoint: 0 mov, 1 call helper, 2 call pay_invoke_signed_c, 3 exit
+    // helper:     4 mov, 5 exit
+    fn caller_symbols() -> ProgramSymbols {
+        let cpi_hash = ebpf::hash_symbol_name(b"pay_invoke_signed_c") as i32;
+        ProgramSymbols {
+            functions: vec![(0, "entrypoint".to_string()), (4, "helper".to_string())]
+                .into_iter()
+                .collect(),
+            instructions: vec![
+                (ebpf::MOV64_IMM, 0),
+                (ebpf::CALL_IMM, 4),
+                (ebpf::CALL_IMM, cpi_hash),
+                (ebpf::EXIT, 0),
+                (ebpf::MOV64_IMM, 0),
+                (ebpf::EXIT, 0),
+            ],
+            lines: vec![(0, "lib.rs:1".to_string()), (4, "helper.rs:7".to_string())]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn callee_symbols() -> ProgramSymbols {
+        ProgramSymbols {
+            functions: vec![(0, "callee".to_string())].into_iter().collect(),
+            instructions: vec![(ebpf::MOV64_IMM, 0), (ebpf::EXIT, 0)],
+            lines: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let folded = fold(&caller_symbols(), &trace(&[0, 1, 4, 5, 2, 3]));
+        let expected: FoldedStacks = vec![
+            ("entrypoint (lib.rs:1)".to_string(), 4),
+            ("entrypoint (lib.rs:1);helper (helper.rs:7)".to_string(), 2),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(folded, expected);
+    }
+
+    #[test]
+    fn test_fold_invocations() {
+        let caller = Pubkey::new_unique();
+        let callee = Pubkey::new_unique();
+        let builtin = Pubkey::new_unique();
+        let mut programs = HashMap::new();
+        programs.insert(caller, caller_symbols());
+        programs.insert(callee, callee_symbols());
+        let traces = vec![
+            InvocationTrace {
+                program_id: caller,
+                stack_height: 1,
+                trace: trace(&[0, 2, 3]),
+            },
+            InvocationTrace {
+                program_id: callee,
+                stack_height: 2,
+                trace: trace(&[0, 1]),
+            },
+            InvocationTrace {
+                program_id: builtin,
+                stack_height: 1,
+                trace: vec![],
+            },
+        ];
+
+        let folded = fold_invocations(&traces, &programs);
+        let expected: FoldedStacks = vec![
+            (format!("{};entrypoint (lib.rs:1)", caller), 3),
+            (
+                format!("{};entrypoint (lib.rs:1);{};callee", caller, callee),
+                2,
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(folded, expected);
+
+        let mut output = vec![];
+        write_folded(&mut output, &folded).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap().lines().count(), 2);
+    }
+}
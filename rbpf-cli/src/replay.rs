@@ -0,0 +1,541 @@
+//! Replay of a single instruction from a transaction recorded in a local ledger
+
+use {
+    crossbeam_channel::unbounded,
+    log::*,
+    paychains_ledger::{
+        bank_forks_utils,
+        blockstore::Blockstore,
+        blockstore_db::{AccessType, BlockstoreOptions},
+        blockstore_processor::ProcessOptions,
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    paychains_program_runtime::{
+        invoke_context::{InvocationTrace, InvokeContext},
+        log_collector::LogCollector,
+        sysvar_cache::SysvarCache,
+        timings::ExecuteTimings,
+    },
+    paychains_runtime::{
+        bank::Bank,
+        hardened_unpack::{open_genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
+        snapshot_config::SnapshotConfig,
+    },
+    paychains_sdk::{
+        account::{from_account, AccountSharedData, ReadableAccount, WritableAccount},
+        account_utils::StateMut,
+        bpf_loader, bpf_loader_deprecated,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Slot,
+        compute_budget::ComputeBudget,
+        instruction::{CompiledInstruction, InstructionError},
+        message::SanitizedMessage,
+        pubkey::Pubkey,
+        signature::Signature,
+        sysvar::{self, instructions::construct_instructions_data},
+        transaction_context::{InstructionAccount, TransactionAccount, TransactionContext},
+    },
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+        convert::TryFrom,
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::Arc,
+    },
+};
+
+pub struct ReplayConfig {
+    pub ledger_path: PathBuf,
+    pub snapshot_archive_path: Option<PathBuf>,
+    pub signature: Signature,
+    pub instruction_index: usize,
+    pub use_jit: bool,
+}
+
+pub struct ReplayResult {
+    pub slot: Slot,
+    pub program_id: Pubkey,
+    pub result: Result<(), InstructionError>,
+    pub compute_units_consumed: u64,
+    /// Logs of the replayed instruction, without those of the instructions preceding it
+    pub logs: Vec<String>,
+    /// Instruction traces of the replayed instruction and the cross-program invocations it
+    /// made, in invocation order
+    pub invocation_traces: Vec<InvocationTrace>,
+    /// ELF images of the BPF programs the transaction can invoke
+    pub programs: HashMap<Pubkey, Vec<u8>>,
+}
+
+/// Load the bank the transaction's slot was built on, from the newest snapshot and the
+/// ledger replayed up to the parent slot, and process the transactions of the slot's entries
+/// that precede the one containing the transaction
+fn load_bank(
+    config: &ReplayConfig,
+    blockstore: &Blockstore,
+    slot: Slot,
+    scratch_dir: &Path,
+) -> Result<Arc<Bank>, String> {
+    let parent_slot = blockstore
+        .meta(slot)
+        .map_err(|err| format!("Failed to read slot {} metadata: {:?}", slot, err))?
+        .and_then(|meta| meta.parent_slot)
+        .ok_or_else(|| format!("Parent of slot {} is unknown", slot))?;
+
+    let ledger_path = &config.ledger_path;
+    let genesis_config = open_genesis_config(ledger_path, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE);
+    let snapshot_config = SnapshotConfig {
+        full_snapshot_archive_interval_slots: Slot::MAX,
+        incremental_snapshot_archive_interval_slots: Slot::MAX,
+        snapshot_archives_dir: config
+            .snapshot_archive_path
+            .clone()
+            .unwrap_or_else(|| ledger_path.clone()),
+        bank_snapshots_dir: scratch_dir.join("snapshot"),
+        ..SnapshotConfig::default()
+    };
+    let process_options = ProcessOptions {
+        bpf_jit: config.use_jit,
+        dev_halt_at_slot: Some(parent_slot),
+        accounts_db_caching_enabled: true,
+        ..ProcessOptions::default()
+    };
+    let (accounts_package_sender, _) = unbounded();
+    let (bank_forks, ..) = bank_forks_utils::load(
+        &genesis_config,
+        blockstore,
+        vec![scratch_dir.join("accounts")],
+        None,
+        Some(&snapshot_config),
+        process_options,
+        None,
+        None,
+        accounts_package_sender,
+        None,
+    )
+    .map_err(|err| format!("Failed to load ledger: {:?}", err))?;
+    let parent_bank = bank_forks.working_bank();
+    if parent_bank.slot() != parent_slot {
+        warn!(
+            "The newest bank available is at slot {}, not at the transaction's parent slot {}. \
+             Replayed account state may not match the original execution",
+            parent_bank.slot(),
+            parent_slot
+        );
+        return Ok(parent_bank);
+    }
+
+    let collector_id = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(slot, Some(&parent_bank))
+        .unwrap_or_default();
+    let bank = Bank::new_from_parent(&parent_bank, &collector_id, slot);
+    let entries = blockstore
+        .get_slot_entries(slot, 0)
+        .map_err(|err| format!("Failed to read slot {} entries: {:?}", slot, err))?;
+    for entry in entries {
+        if entry
+            .transactions
+            .iter()
+            .any(|transaction| transaction.signatures.get(0) == Some(&config.signature))
+        {
+            break;
+        }
+        // Transactions of an entry don't conflict, so they can be processed in one batch
+        bank.try_process_entry_transactions(entry.transactions)
+            .map_err(|err| format!("Failed to process slot {} entries: {:?}", slot, err))?;
+    }
+    Ok(Arc::new(bank))
+}
+
+fn sysvar_cache(bank: &Bank) -> SysvarCache {
+    let mut sysvar_cache = SysvarCache::default();
+    sysvar_cache.set_clock(bank.clock());
+    sysvar_cache.set_epoch_schedule(bank.epoch_schedule().clone());
+    sysvar_cache.set_rent(bank.rent_collector().rent);
+    if let Some(slot_hashes) = bank
+        .get_account(&sysvar::slot_hashes::id())
+        .and_then(|account| from_account(&account))
+    {
+        sysvar_cache.set_slot_hashes(slot_hashes);
+    }
+    #[allow(deprecated)]
+    if let Some(fees) = bank
+        .get_account(&sysvar::fees::id())
+        .and_then(|account| from_account(&account))
+    {
+        sysvar_cache.set_fees(fees);
+    }
+    sysvar_cache
+}
+
+/// Return the ELF of the BPF program `program_id`, if `account` is one
+fn program_elf(bank: &Bank, program_id: &Pubkey, account: &AccountSharedData) -> Option<Vec<u8>> {
+    if !account.executable() {
+        return None;
+    }
+    if bpf_loader::check_id(account.owner()) || bpf_loader_deprecated::check_id(account.owner()) {
+        return Some(account.data().to_vec());
+    }
+    if let Ok(UpgradeableLoaderState::Program {
+        programdata_address,
+    }) = account.state()
+    {
+        let programdata = bank.get_account(&programdata_address)?;
+        let offset = UpgradeableLoaderState::programdata_data_offset().ok()?;
+        return programdata.data().get(offset..).map(<[u8]>::to_vec);
+    }
+    debug!("{} is not a BPF program", program_id);
+    None
+}
+
+/// Point the instructions sysvar, if the transaction loads it, at the instruction about to run
+fn set_current_instruction(
+    transaction_context: &TransactionContext,
+    instructions_sysvar_index: Option<usize>,
+    instruction_index: usize,
+) {
+    if let Some(index_in_transaction) = instructions_sysvar_index {
+        let mut account = transaction_context
+            .get_account_at_index(index_in_transaction)
+            .borrow_mut();
+        sysvar::instructions::store_current_index(
+            account.data_as_mut_slice(),
+            instruction_index as u16,
+        );
+    }
+}
+
+pub fn replay(config: &ReplayConfig) -> Result<ReplayResult, String> {
+    let blockstore = Blockstore::open_with_options(
+        &config.ledger_path,
+        BlockstoreOptions {
+            access_type: AccessType::TryPrimaryThenSecondary,
+            recovery_mode: None,
+            enforce_ulimit_nofile: true,
+        },
+    )
+    .map_err(|err| format!("Failed to open ledger: {:?}", err))?;
+    let confirmed_transaction = blockstore
+        .get_rooted_transaction(config.signature)
+        .map_err(|err| format!("Failed to read transaction: {:?}", err))?
+        .ok_or_else(|| format!("Transaction {} not found in ledger", config.signature))?;
+    let slot = confirmed_transaction.slot;
+    let message = confirmed_transaction.transaction.transaction.message;
+    let instruction = message
+        .instructions
+        .get(config.instruction_index)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Transaction has no instruction at index {}",
+                config.instruction_index
+            )
+        })?;
+    let sanitized_message = SanitizedMessage::try_from(message.clone())
+        .map_err(|err| format!("Invalid transaction message: {}", err))?;
+
+    let scratch_dir = tempfile::Builder::new()
+        .prefix("rbpf-cli.")
+        .tempdir_in(&config.ledger_path)
+        .map_err(|err| format!("Failed to create scratch directory: {}", err))?;
+    let bank = load_bank(config, &blockstore, slot, scratch_dir.path())?;
+
+    // Transaction accounts, followed by the accounts of every program they may invoke
+    let mut transaction_accounts: Vec<TransactionAccount> = message
+        .account_keys
+        .iter()
+        .map(|key| {
+            let account = if sysvar::instructions::check_id(key) {
+                let mut account = AccountSharedData::new(0, 0, &sysvar::id());
+                account.set_data(construct_instructions_data(&sanitized_message));
+                account
+            } else {
+                bank.get_account(key).unwrap_or_default()
+            };
+            (*key, account)
+        })
+        .collect();
+    let mut programs = HashMap::new();
+    for (key, account) in transaction_accounts.clone() {
+        if let Some(elf) = program_elf(&bank, &key, &account) {
+            programs.insert(key, elf);
+        }
+        if let Ok(UpgradeableLoaderState::Program {
+            programdata_address,
+        }) = account.state()
+        {
+            if bpf_loader_upgradeable::check_id(account.owner()) {
+                let programdata = bank.get_account(&programdata_address).unwrap_or_default();
+                transaction_accounts.push((programdata_address, programdata));
+            }
+        }
+    }
+
+    // The program accounts of the replayed instruction and of the ones preceding it
+    let program_id = *instruction.program_id(&message.account_keys);
+    let instructions = &message.instructions[..=config.instruction_index];
+    let mut program_indices = Vec::with_capacity(instructions.len());
+    for instruction in instructions {
+        let program_key = *instruction.program_id(&message.account_keys);
+        let program_account = transaction_accounts[instruction.program_id_index as usize]
+            .1
+            .clone();
+        let mut indices = vec![];
+        if let Ok(UpgradeableLoaderState::Program {
+            programdata_address,
+        }) = program_account.state()
+        {
+            indices.push(
+                transaction_accounts
+                    .iter()
+                    .rposition(|(key, _)| *key == programdata_address)
+                    .ok_or("Missing programdata account")?,
+            );
+        }
+        indices.push(transaction_accounts.len());
+        transaction_accounts.push((program_key, program_account));
+        program_indices.push(indices);
+    }
+    let (replayed_program_indices, preceding_program_indices) =
+        program_indices.split_last().expect("replayed instruction");
+
+    let instruction_accounts = |instruction: &CompiledInstruction, program_indices: &[usize]| {
+        instruction
+            .accounts
+            .iter()
+            .map(|index_in_transaction| {
+                let index_in_transaction = *index_in_transaction as usize;
+                InstructionAccount {
+                    index_in_transaction,
+                    index_in_caller: program_indices.len().saturating_add(index_in_transaction),
+                    is_signer: message.is_signer(index_in_transaction),
+                    is_writable: message.is_writable(index_in_transaction),
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let instructions_sysvar_index = message
+        .account_keys
+        .iter()
+        .position(sysvar::instructions::check_id);
+
+    let compute_budget = ComputeBudget::default();
+    let log_collector = LogCollector::new_ref();
+    let mut transaction_context = TransactionContext::new(
+        transaction_accounts,
+        compute_budget.max_invoke_depth.saturating_add(1),
+    );
+    let mut invoke_context = InvokeContext::new(
+        &mut transaction_context,
+        bank.rent_collector().rent,
+        bank.get_builtin_programs(),
+        Cow::Owned(sysvar_cache(&bank)),
+        Some(log_collector.clone()),
+        compute_budget,
+        Default::default(),
+        None,
+        bank.feature_set.clone(),
+        bank.last_blockhash(),
+        bank.get_lamports_per_signature(),
+        0,
+    );
+
+    // Execute the instructions preceding the replayed one first, so that it sees the account
+    // state they left behind, as it did when the transaction was processed
+    for (index, (instruction, program_indices)) in instructions
+        .iter()
+        .zip(preceding_program_indices)
+        .enumerate()
+    {
+        set_current_instruction(
+            invoke_context.transaction_context,
+            instructions_sysvar_index,
+            index,
+        );
+        let mut compute_units_consumed = 0;
+        invoke_context
+            .process_instruction(
+                &instruction.data,
+                &instruction_accounts(instruction, program_indices),
+                program_indices,
+                &mut compute_units_consumed,
+                &mut ExecuteTimings::default(),
+            )
+            .map_err(|err| {
+                format!(
+                    "Instruction {} failed before reaching the replayed instruction: {}",
+                    index, err
+                )
+            })?;
+    }
+    let preceding_log_count = log_collector.borrow().get_recorded_content().len();
+
+    set_current_instruction(
+        invoke_context.transaction_context,
+        instructions_sysvar_index,
+        config.instruction_index,
+    );
+    invoke_context.enable_instruction_tracing = true;
+    let mut compute_units_consumed = 0;
+    let result = invoke_context.process_instruction(
+        &instruction.data,
+        &instruction_accounts(&instruction, replayed_program_indices),
+        replayed_program_indices,
+        &mut compute_units_consumed,
+        &mut ExecuteTimings::default(),
+    );
+    let invocation_traces = std::mem::take(&mut invoke_context.invocation_traces);
+    drop(invoke_context);
+    let logs = Rc::try_unwrap(log_collector)
+        .map(|log_collector| {
+            let mut logs: Vec<String> = log_collector.into_inner().into();
+            logs.split_off(preceding_log_count)
+        })
+        .unwrap_or_default();
+
+    Ok(ReplayResult {
+        slot,
+        program_id,
+        result,
+        compute_units_consumed,
+        logs,
+        invocation_traces,
+        programs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        paychains_entry::entry::next_entry,
+        paychains_ledger::{
+            blockstore::entries_to_test_shreds, create_new_tmp_ledger_auto_delete,
+            genesis_utils::create_genesis_config,
+        },
+        paychains_sdk::{
+            signature::{Keypair, Signer},
+            system_instruction::{self, SystemError},
+            system_program, system_transaction,
+            transaction::Transaction,
+        },
+        paychains_transaction_status::TransactionStatusMeta,
+    };
+
+    #[test]
+    fn test_replay_sees_earlier_transactions_of_slot() {
+        let genesis = create_genesis_config(100_000_000);
+        let (ledger_path, blockhash) = create_new_tmp_ledger_auto_delete!(&genesis.genesis_config);
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+        // Alice is funded, then pays Bob, in the same slot
+        let fund_alice = system_transaction::transfer(
+            &genesis.mint_keypair,
+            &alice.pubkey(),
+            10_000_000,
+            blockhash,
+        );
+        let pay_bob = system_transaction::transfer(&alice, &bob.pubkey(), 5_000_000, blockhash);
+        let signature = pay_bob.signatures[0];
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            let fund_entry = next_entry(&blockhash, 1, vec![fund_alice]);
+            let pay_entry = next_entry(&fund_entry.hash, 1, vec![pay_bob.clone()]);
+            let shreds = entries_to_test_shreds(&[fund_entry, pay_entry], 1, 0, true, 0);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            blockstore
+                .write_transaction_status(
+                    1,
+                    signature,
+                    vec![&alice.pubkey(), &bob.pubkey()],
+                    vec![&system_program::id()],
+                    TransactionStatusMeta::default(),
+                )
+                .unwrap();
+            blockstore.set_roots([0, 1].iter()).unwrap();
+        }
+
+        let result = replay(&ReplayConfig {
+            ledger_path: ledger_path.path().to_path_buf(),
+            snapshot_archive_path: None,
+            signature,
+            instruction_index: 0,
+            use_jit: false,
+        })
+        .unwrap();
+        assert_eq!(result.slot, 1);
+        assert_eq!(result.program_id, system_program::id());
+        assert_eq!(result.result, Ok(()));
+        assert!(result.programs.is_empty());
+        // The scratch directory is removed once the replay is done
+        assert!(std::fs::read_dir(ledger_path.path())
+            .unwrap()
+            .all(|entry| !entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("rbpf-cli.")));
+    }
+
+    #[test]
+    fn test_replay_executes_preceding_instructions() {
+        let genesis = create_genesis_config(100_000_000);
+        let (ledger_path, blockhash) = create_new_tmp_ledger_auto_delete!(&genesis.genesis_config);
+        let alice = Keypair::new();
+        let bob = Pubkey::new_unique();
+        let carol = Pubkey::new_unique();
+        let fund_alice = system_transaction::transfer(
+            &genesis.mint_keypair,
+            &alice.pubkey(),
+            10_000_000,
+            blockhash,
+        );
+        // Alice can afford either transfer, but not both
+        let pay_both = Transaction::new_signed_with_payer(
+            &[
+                system_instruction::transfer(&alice.pubkey(), &bob, 6_000_000),
+                system_instruction::transfer(&alice.pubkey(), &carol, 6_000_000),
+            ],
+            Some(&alice.pubkey()),
+            &[&alice],
+            blockhash,
+        );
+        let signature = pay_both.signatures[0];
+        {
+            let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+            let fund_entry = next_entry(&blockhash, 1, vec![fund_alice]);
+            let pay_entry = next_entry(&fund_entry.hash, 1, vec![pay_both]);
+            let shreds = entries_to_test_shreds(&[fund_entry, pay_entry], 1, 0, true, 0);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            blockstore
+                .write_transaction_status(
+                    1,
+                    signature,
+                    vec![&alice.pubkey(), &bob, &carol],
+                    vec![&system_program::id()],
+                    TransactionStatusMeta::default(),
+                )
+                .unwrap();
+            blockstore.set_roots([0, 1].iter()).unwrap();
+        }
+
+        let replay_instruction = |instruction_index| {
+            replay(&ReplayConfig {
+                ledger_path: ledger_path.path().to_path_buf(),
+                snapshot_archive_path: None,
+                signature,
+                instruction_index,
+                use_jit: false,
+            })
+        };
+        assert_eq!(replay_instruction(0).unwrap().result, Ok(()));
+        // The second transfer sees the lamports spent by the first
+        assert_eq!(
+            replay_instruction(1).unwrap().result,
+            Err(InstructionError::Custom(
+                SystemError::ResultWithNegativeLamports as u32
+            ))
+        );
+        assert!(replay_instruction(2).is_err());
+    }
+}
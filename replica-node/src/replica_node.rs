@@ -237,14 +237,14 @@ fn start_client_rpc_services(
             genesis_config.hash(),
             &replica_config.ledger_path,
             replica_config.replica_exit.clone(),
-            None,
+            Arc::default(),
             rpc_override_health_check,
             optimistically_confirmed_bank.clone(),
-            send_transaction_service::Config {
+            Arc::new(RwLock::new(send_transaction_service::Config {
                 retry_rate_ms: 0,
                 leader_forward_count: 0,
                 ..send_transaction_service::Config::default()
-            },
+            })),
             max_slots,
             leader_schedule_cache.clone(),
             max_complete_transaction_status_slot,
@@ -322,7 +322,12 @@ impl JsonRpcRequestProcessor {
             ))),
             blockstore,
             validator_exit: create_validator_exit(&exit),
-            health: Arc::new(RpcHealth::new(cluster_info.clone(), None, 0, exit.clone())),
+            health: Arc::new(RpcHealth::new(
+                cluster_info.clone(),
+                Arc::default(),
+                0,
+                exit.clone(),
+            )),
             cluster_info,
             genesis_hash,
             transaction_sender: Arc::new(Mutex::new(sender)),
@@ -432,6 +437,24 @@ impl JsonRpcRequestProcessor {
                 .saturating_sub(1)
         });
 
+        // Stake rewards are credited over the blocks following the first block of the next epoch
+        // when partitioned; report none until all of them have been credited
+        {
+            let bank = self.bank(config.commitment);
+            if epoch_schedule.get_epoch(bank.slot()) == epoch.saturating_add(1) {
+                if let Some(rewards_complete_block_height) =
+                    bank.epoch_rewards_complete_block_height()
+                {
+                    return Err(RpcCustomError::EpochRewardsPeriodActive {
+                        slot: bank.slot(),
+                        current_block_height: bank.block_height(),
+                        rewards_complete_block_height,
+                    }
+                    .into());
+                }
+            }
+        }
+
         // Rewards for this epoch are found in the first confirmed block of the next epoch
         let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(epoch.saturating_add(1));
         if first_slot_in_epoch < first_available_block {
@@ -477,25 +500,86 @@ impl JsonRpcRequestProcessor {
             .map(|pubkey| pubkey.to_string())
             .collect();
 
-        let reward_hash: HashMap<String, Reward> = first_confirmed_block
-            .rewards
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|reward| match reward.reward_type? {
-                RewardType::Staking | RewardType::Voting => addresses
-                    .contains(&reward.pubkey)
-                    .then(|| (reward.clone().pubkey, reward)),
-                _ => None,
-            })
-            .collect();
+        // The partition count is stored with the block, in the blockstore and in bigtable alike.
+        // Blocks written without it while the feature was active cannot be told apart from
+        // blocks with no stake rewards, so refuse to guess.
+        let num_partitions = first_confirmed_block.num_reward_partitions;
+        if num_partitions.is_none() {
+            let partitioned_rewards_activated_slot = self
+                .bank(config.commitment)
+                .feature_set
+                .activated_slot(&feature_set::enable_partitioned_epoch_reward::id());
+            if matches!(
+                partitioned_rewards_activated_slot,
+                Some(activated_slot) if activated_slot <= first_confirmed_block_in_epoch
+            ) {
+                return Err(RpcCustomError::EpochRewardPartitionsNotAvailable {
+                    slot: first_confirmed_block_in_epoch,
+                }
+                .into());
+            }
+        }
+
+        let mut reward_hash: HashMap<String, (Reward, Slot)> = HashMap::new();
+        Self::filter_inflation_rewards(
+            first_confirmed_block.rewards.unwrap_or_default(),
+            first_confirmed_block_in_epoch,
+            &addresses,
+            &mut reward_hash,
+        );
+
+        // With partitioned epoch rewards, the first block only holds the vote rewards and the
+        // stake rewards are credited one partition per block in the blocks that follow it.  Any
+        // partition may be empty, so all of those blocks are read.
+        if let Some(num_partitions) = num_partitions {
+            let num_partitions = num_partitions as usize;
+            let reward_blocks = self
+                .get_blocks_with_limit(
+                    first_confirmed_block_in_epoch.saturating_add(1),
+                    num_partitions,
+                    config.commitment,
+                )
+                .await?;
+            if reward_blocks.len() < num_partitions {
+                return Err(RpcCustomError::BlockNotAvailable {
+                    slot: reward_blocks
+                        .last()
+                        .unwrap_or(&first_confirmed_block_in_epoch)
+                        .saturating_add(1),
+                }
+                .into());
+            }
+            for slot in reward_blocks {
+                if reward_hash.len() == addresses.len() {
+                    break;
+                }
+                let block = if let Ok(Some(block)) = self
+                    .get_block(
+                        slot,
+                        Some(RpcBlockConfig::rewards_with_commitment(config.commitment).into()),
+                    )
+                    .await
+                {
+                    block
+                } else {
+                    return Err(RpcCustomError::BlockNotAvailable { slot }.into());
+                };
+                Self::filter_inflation_rewards(
+                    block.rewards.unwrap_or_default(),
+                    slot,
+                    &addresses,
+                    &mut reward_hash,
+                );
+            }
+        }
 
         let rewards = addresses
             .iter()
             .map(|address| {
-                if let Some(reward) = reward_hash.get(address) {
+                if let Some((reward, effective_slot)) = reward_hash.get(address) {
                     return Some(RpcInflationReward {
                         epoch,
-                        effective_slot: first_confirmed_block_in_epoch,
+                        effective_slot: *effective_slot,
                         amount: reward.lamports.abs() as u64,
                         post_balance: reward.post_balance,
                         commission: reward.commission,
@@ -508,6 +592,24 @@ impl JsonRpcRequestProcessor {
         Ok(rewards)
     }
 
+    /// Collect the inflation rewards of `addresses` found in the block at `slot`
+    fn filter_inflation_rewards(
+        block_rewards: Vec<Reward>,
+        slot: Slot,
+        addresses: &[String],
+        reward_hash: &mut HashMap<String, (Reward, Slot)>,
+    ) {
+        for reward in block_rewards {
+            if matches!(
+                reward.reward_type,
+                Some(RewardType::Staking) | Some(RewardType::Voting)
+            ) && addresses.contains(&reward.pubkey)
+            {
+                reward_hash.insert(reward.pubkey.clone(), (reward, slot));
+            }
+        }
+    }
+
     pub fn get_inflation_governor(
         &self,
         commitment: Option<CommitmentConfig>,
@@ -715,6 +817,11 @@ impl JsonRpcRequestProcessor {
         self.bank(commitment).capitalization()
     }
 
+    fn get_accounts_data_size(&self, commitment: Option<CommitmentConfig>) -> RpcResponse<u64> {
+        let bank = self.bank(commitment);
+        new_response(&bank, bank.load_accounts_data_len())
+    }
+
     fn get_cached_largest_accounts(
         &self,
         filter: &Option<RpcLargestAccountsFilter>,
@@ -2154,7 +2261,10 @@ pub(crate) fn check_is_at_least_confirmed(commitment: CommitmentConfig) -> Resul
     Ok(())
 }
 
-fn check_slice_and_encoding(encoding: &UiAccountEncoding, data_slice_is_some: bool) -> Result<()> {
+pub(crate) fn check_slice_and_encoding(
+    encoding: &UiAccountEncoding,
+    data_slice_is_some: bool,
+) -> Result<()> {
     match encoding {
         UiAccountEncoding::JsonParsed => {
             if data_slice_is_some {
@@ -2860,6 +2970,13 @@ pub mod rpc_accounts {
             config: Option<RpcSupplyConfig>,
         ) -> Result<RpcResponse<RpcSupply>>;
 
+        #[rpc(meta, name = "getAccountsDataSize")]
+        fn get_accounts_data_size(
+            &self,
+            meta: Self::Metadata,
+            commitment: Option<CommitmentConfig>,
+        ) -> Result<RpcResponse<u64>>;
+
         #[rpc(meta, name = "getStakeActivation")]
         fn get_stake_activation(
             &self,
@@ -3017,6 +3134,15 @@ pub mod rpc_accounts {
             Ok(meta.get_supply(config)?)
         }
 
+        fn get_accounts_data_size(
+            &self,
+            meta: Self::Metadata,
+            commitment: Option<CommitmentConfig>,
+        ) -> Result<RpcResponse<u64>> {
+            debug!("get_accounts_data_size rpc request received");
+            Ok(meta.get_accounts_data_size(commitment))
+        }
+
         fn get_stake_activation(
             &self,
             meta: Self::Metadata,
@@ -4365,7 +4491,7 @@ pub mod tests {
         },
         paychains_runtime::{
             accounts_background_service::AbsRequestSender, commitment::BlockCommitment,
-            non_circulating_supply::non_circulating_accounts,
+            genesis_utils::activate_feature, non_circulating_supply::non_circulating_accounts,
         },
         paychains_sdk::{
             account::Account,
@@ -4419,7 +4545,15 @@ pub mod tests {
         pubkey: &Pubkey,
         blockstore_roots: Vec<Slot>,
     ) -> RpcHandler {
-        let (bank_forks, alice, leader_vote_keypair) = new_bank_forks();
+        start_rpc_handler_with_tx_blockstore_and_features(pubkey, blockstore_roots, &[])
+    }
+
+    fn start_rpc_handler_with_tx_blockstore_and_features(
+        pubkey: &Pubkey,
+        blockstore_roots: Vec<Slot>,
+        features: &[Pubkey],
+    ) -> RpcHandler {
+        let (bank_forks, alice, leader_vote_keypair) = new_bank_forks_with_features(features);
         let bank = bank_forks.read().unwrap().working_bank();
         let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
 
@@ -4870,6 +5004,19 @@ pub mod tests {
         assert!(supply.non_circulating_accounts.is_empty());
     }
 
+    #[test]
+    fn test_get_accounts_data_size() {
+        let bob_pubkey = paychains_sdk::pubkey::new_rand();
+        let RpcHandler { io, meta, bank, .. } = start_rpc_handler_with_tx(&bob_pubkey);
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"getAccountsDataSize"}"#;
+        let res = io.handle_request_sync(req, meta);
+        let json: Value = serde_json::from_str(&res.unwrap()).unwrap();
+        let accounts_data_size: u64 = serde_json::from_value(json["result"]["value"].clone())
+            .expect("actual response deserialization");
+        assert!(accounts_data_size > 0);
+        assert_eq!(accounts_data_size, bank.load_accounts_data_len());
+    }
+
     #[test]
     fn test_get_largest_accounts() {
         let bob_pubkey = paychains_sdk::pubkey::new_rand();
@@ -6342,6 +6489,12 @@ pub mod tests {
     }
 
     fn new_bank_forks() -> (Arc<RwLock<BankForks>>, Keypair, Arc<Keypair>) {
+        new_bank_forks_with_features(&[])
+    }
+
+    fn new_bank_forks_with_features(
+        features: &[Pubkey],
+    ) -> (Arc<RwLock<BankForks>>, Keypair, Arc<Keypair>) {
         let GenesisConfigInfo {
             mut genesis_config,
             mint_keypair,
@@ -6353,6 +6506,9 @@ pub mod tests {
         genesis_config.rent.exemption_threshold = 2.0;
         genesis_config.epoch_schedule =
             EpochSchedule::custom(TEST_SLOTS_PER_EPOCH, TEST_SLOTS_PER_EPOCH, false);
+        for feature_id in features {
+            activate_feature(&mut genesis_config, *feature_id);
+        }
 
         let bank = Bank::new_for_tests(&genesis_config);
         (
@@ -6873,6 +7029,142 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_get_inflation_reward_partitioned() {
+        let bob_pubkey = paychains_sdk::pubkey::new_rand();
+        // the rewards of epoch 0 are calculated in the first block of epoch 1 and the stake
+        // rewards credited in the blocks after it
+        let calculation_slot = TEST_SLOTS_PER_EPOCH;
+        let roots: Vec<Slot> = std::iter::once(0)
+            .chain(calculation_slot..calculation_slot + 5)
+            .collect();
+        let RpcHandler {
+            io,
+            meta,
+            block_commitment_cache,
+            ..
+        } = start_rpc_handler_with_tx_and_blockstore(&bob_pubkey, roots.clone());
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_highest_confirmed_root(*roots.last().unwrap());
+
+        let vote_pubkey = paychains_sdk::pubkey::new_rand();
+        let stake_pubkeys: Vec<Pubkey> =
+            (0..3).map(|_| paychains_sdk::pubkey::new_rand()).collect();
+        let reward = |pubkey: &Pubkey, reward_type| Reward {
+            pubkey: pubkey.to_string(),
+            lamports: 42,
+            post_balance: 1_000,
+            reward_type: Some(reward_type),
+            commission: Some(10),
+        };
+        // three partitions, the second of which is empty; the stake reward in the block after the
+        // partitions is not part of them
+        let write_rewards = |num_partitions| {
+            meta.blockstore
+                .write_rewards(
+                    calculation_slot,
+                    vec![reward(&vote_pubkey, RewardType::Voting)],
+                    num_partitions,
+                )
+                .unwrap();
+        };
+        write_rewards(Some(3));
+        for (slot, stake_pubkey) in [
+            (calculation_slot + 1, &stake_pubkeys[0]),
+            (calculation_slot + 3, &stake_pubkeys[1]),
+            (calculation_slot + 4, &stake_pubkeys[2]),
+        ] {
+            meta.blockstore
+                .write_rewards(slot, vec![reward(stake_pubkey, RewardType::Staking)], None)
+                .unwrap();
+        }
+
+        let get_inflation_reward = || {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"getInflationReward","params":[["{}","{}","{}","{}"],{{"epoch":0}}]}}"#,
+                vote_pubkey, stake_pubkeys[0], stake_pubkeys[1], stake_pubkeys[2],
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            let rewards: Vec<Option<RpcInflationReward>> =
+                serde_json::from_value(result["result"].clone()).unwrap();
+            rewards
+                .into_iter()
+                .map(|reward| reward.map(|reward| reward.effective_slot))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            get_inflation_reward(),
+            vec![
+                Some(calculation_slot),
+                Some(calculation_slot + 1),
+                Some(calculation_slot + 3),
+                None,
+            ]
+        );
+
+        // without partitions, all rewards are found in the first block
+        write_rewards(None);
+        assert_eq!(
+            get_inflation_reward(),
+            vec![Some(calculation_slot), None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_get_inflation_reward_partitions_not_available() {
+        let bob_pubkey = paychains_sdk::pubkey::new_rand();
+        let calculation_slot = TEST_SLOTS_PER_EPOCH;
+        let roots: Vec<Slot> = std::iter::once(0)
+            .chain(calculation_slot..calculation_slot + 5)
+            .collect();
+        let RpcHandler {
+            io,
+            meta,
+            block_commitment_cache,
+            ..
+        } = start_rpc_handler_with_tx_blockstore_and_features(
+            &bob_pubkey,
+            roots.clone(),
+            &[feature_set::enable_partitioned_epoch_reward::id()],
+        );
+        block_commitment_cache
+            .write()
+            .unwrap()
+            .set_highest_confirmed_root(*roots.last().unwrap());
+
+        // the block calculating the rewards of epoch 0 was stored without its partition count
+        // while the feature was active
+        let vote_pubkey = paychains_sdk::pubkey::new_rand();
+        meta.blockstore
+            .write_rewards(
+                calculation_slot,
+                vec![Reward {
+                    pubkey: vote_pubkey.to_string(),
+                    lamports: 42,
+                    post_balance: 1_000,
+                    reward_type: Some(RewardType::Voting),
+                    commission: Some(10),
+                }],
+                None,
+            )
+            .unwrap();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"getInflationReward","params":[["{}"],{{"epoch":0}}]}}"#,
+            vote_pubkey,
+        );
+        let res = io.handle_request_sync(&req, meta);
+        let expected = format!(
+            r#"{{"jsonrpc":"2.0","error":{{"code":-32016,"message":"Number of epoch reward partitions not available for slot {}"}},"id":1}}"#,
+            calculation_slot,
+        );
+        assert_eq!(res, Some(expected));
+    }
+
     #[test]
     fn test_get_blocks_with_limit() {
         let bob_pubkey = paychains_sdk::pubkey::new_rand();
@@ -5,7 +5,7 @@ use {
         collections::HashSet,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, RwLock,
         },
     },
 };
@@ -19,7 +19,7 @@ pub enum RpcHealthStatus {
 
 pub struct RpcHealth {
     cluster_info: Arc<ClusterInfo>,
-    known_validators: Option<HashSet<Pubkey>>,
+    known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
     health_check_slot_distance: u64,
     override_health_check: Arc<AtomicBool>,
     #[cfg(test)]
@@ -29,7 +29,7 @@ pub struct RpcHealth {
 impl RpcHealth {
     pub fn new(
         cluster_info: Arc<ClusterInfo>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         health_check_slot_distance: u64,
         override_health_check: Arc<AtomicBool>,
     ) -> Self {
@@ -53,7 +53,7 @@ impl RpcHealth {
 
         if self.override_health_check.load(Ordering::Relaxed) {
             RpcHealthStatus::Ok
-        } else if let Some(known_validators) = &self.known_validators {
+        } else if let Some(known_validators) = &*self.known_validators.read().unwrap() {
             match (
                 self.cluster_info
                     .get_accounts_hash_for_node(&self.cluster_info.id(), |hashes| {
@@ -131,7 +131,7 @@ impl RpcHealth {
                 Arc::new(Keypair::new()),
                 SocketAddrSpace::Unspecified,
             )),
-            None,
+            Arc::default(),
             42,
             Arc::new(AtomicBool::new(false)),
         ))
@@ -2,13 +2,13 @@
 
 use {
     crate::{
-        rpc::check_is_at_least_confirmed,
+        rpc::{check_is_at_least_confirmed, check_slice_and_encoding},
         rpc_pubsub_service::PubSubConfig,
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            SubscriptionToken, TransactionSubscriptionParams,
         },
     },
     dashmap::DashMap,
@@ -20,11 +20,11 @@ use {
         rpc_config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
             RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         rpc_response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
         },
     },
     paychains_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -213,6 +213,32 @@ pub trait RpcPayPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get full transactions with status meta that include or exclude the given accounts
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -333,6 +359,18 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get full transactions with status meta that include or exclude the given accounts
+        #[rpc(name = "transactionSubscribe")]
+        fn transaction_subscribe(
+            &self,
+            filter: RpcTransactionSubscribeFilter,
+            config: Option<RpcTransactionSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
+
+        // Unsubscribe from transaction notification subscription.
+        #[rpc(name = "transactionUnsubscribe")]
+        fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -413,11 +451,13 @@ impl RpcPayPubSubInternal for RpcPayPubSubImpl {
         config: Option<RpcAccountInfoConfig>,
     ) -> Result<SubscriptionId> {
         let config = config.unwrap_or_default();
+        let encoding = config.encoding.unwrap_or(UiAccountEncoding::Binary);
+        check_slice_and_encoding(&encoding, config.data_slice.is_some())?;
         let params = AccountSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
             commitment: config.commitment.unwrap_or_default(),
             data_slice: config.data_slice,
-            encoding: config.encoding.unwrap_or(UiAccountEncoding::Binary),
+            encoding,
         };
         self.subscribe(SubscriptionParams::Account(params))
     }
@@ -432,13 +472,15 @@ impl RpcPayPubSubInternal for RpcPayPubSubImpl {
         config: Option<RpcProgramAccountsConfig>,
     ) -> Result<SubscriptionId> {
         let config = config.unwrap_or_default();
+        let encoding = config
+            .account_config
+            .encoding
+            .unwrap_or(UiAccountEncoding::Binary);
+        check_slice_and_encoding(&encoding, config.account_config.data_slice.is_some())?;
         let params = ProgramSubscriptionParams {
             pubkey: param::<Pubkey>(&pubkey_str, "pubkey")?,
             filters: config.filters.unwrap_or_default(),
-            encoding: config
-                .account_config
-                .encoding
-                .unwrap_or(UiAccountEncoding::Binary),
+            encoding,
             data_slice: config.account_config.data_slice,
             commitment: config.account_config.commitment.unwrap_or_default(),
             with_context: config.with_context.unwrap_or_default(),
@@ -549,6 +591,43 @@ impl RpcPayPubSubInternal for RpcPayPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        let config = config.unwrap_or_default();
+        let commitment = config.commitment.unwrap_or_default();
+        check_is_at_least_confirmed(commitment)?;
+        let parse_accounts = |accounts: Option<Vec<String>>, thing| -> Result<Vec<Pubkey>> {
+            let mut accounts = accounts
+                .unwrap_or_default()
+                .iter()
+                .map(|account| param::<Pubkey>(account, thing))
+                .collect::<Result<Vec<_>>>()?;
+            accounts.sort_unstable();
+            accounts.dedup();
+            Ok(accounts)
+        };
+        let params = TransactionSubscriptionParams {
+            account_include: parse_accounts(filter.account_include, "account_include")?,
+            account_exclude: parse_accounts(filter.account_exclude, "account_exclude")?,
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Base64),
+            commitment,
+        };
+        self.subscribe(SubscriptionParams::Transaction(params))
+    }
+
+    fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -34,6 +34,7 @@ pub const DEFAULT_WORKER_THREADS: usize = 1;
 #[derive(Debug, Clone)]
 pub struct PubSubConfig {
     pub enable_block_subscription: bool,
+    pub enable_transaction_subscription: bool,
     pub enable_vote_subscription: bool,
     pub max_active_subscriptions: usize,
     pub queue_capacity_items: usize,
@@ -46,6 +47,7 @@ impl Default for PubSubConfig {
     fn default() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_QUEUE_CAPACITY_ITEMS,
@@ -60,6 +62,7 @@ impl PubSubConfig {
     pub fn default_for_tests() -> Self {
         Self {
             enable_block_subscription: false,
+            enable_transaction_subscription: false,
             enable_vote_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_TEST_QUEUE_CAPACITY_ITEMS,
@@ -148,6 +151,9 @@ fn count_final(params: &SubscriptionParams) {
         SubscriptionParams::Block(_) => {
             inc_new_counter_info!("rpc-pubsub-final-slot-txs", 1);
         }
+        SubscriptionParams::Transaction(_) => {
+            inc_new_counter_info!("rpc-pubsub-final-transactions", 1);
+        }
     }
 }
 
@@ -160,6 +166,7 @@ impl BroadcastHandler {
             count_final(entry.get().params());
 
             let time_since_created = notification.created_at.elapsed();
+            let backlog = notification.backlog.record_delivered(notification.sequence);
 
             datapoint_info!(
                 "pubsub_notifications",
@@ -168,6 +175,7 @@ impl BroadcastHandler {
                     time_since_created.as_micros() as i64,
                     i64
                 ),
+                ("subscription_backlog", backlog, i64),
             );
 
             if notification.is_final {
@@ -237,6 +245,7 @@ pub fn test_connection(
     let rpc_impl = RpcPayPubSubImpl::new(
         PubSubConfig {
             enable_block_subscription: true,
+            enable_transaction_subscription: true,
             enable_vote_subscription: true,
             queue_capacity_items: 100,
             ..PubSubConfig::default()
@@ -39,6 +39,7 @@ use {
     paychains_send_transaction_service::send_transaction_service::{self, SendTransactionService},
     std::{
         collections::HashSet,
+        io::SeekFrom,
         net::SocketAddr,
         path::{Path, PathBuf},
         sync::{
@@ -47,11 +48,77 @@ use {
         },
         thread::{self, Builder, JoinHandle},
     },
+    tokio::io::{AsyncReadExt, AsyncSeekExt},
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
 const LARGEST_ACCOUNTS_CACHE_DURATION: u64 = 60 * 60 * 2;
 
+/// The part of a file requested by the `Range` header of a file get
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// The whole file, when no range or an unsupported range was requested
+    Full,
+    /// The first and last byte of a single range
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    fn parse(range: Option<&str>, file_length: u64) -> Self {
+        let range = match range.and_then(|range| range.trim().strip_prefix("bytes=")) {
+            // Multiple ranges are not supported, respond with the whole file instead
+            Some(range) if !range.contains(',') => range,
+            _ => return Self::Full,
+        };
+        let (first, last) = match range.split_once('-') {
+            Some(bounds) => bounds,
+            None => return Self::Full,
+        };
+        let last_byte = match file_length.checked_sub(1) {
+            Some(last_byte) => last_byte,
+            None => return Self::Unsatisfiable,
+        };
+        let bounds = if first.is_empty() {
+            // A suffix range: the last `last` bytes
+            last.parse::<u64>()
+                .ok()
+                .filter(|suffix_length| *suffix_length > 0)
+                .map(|suffix_length| (file_length.saturating_sub(suffix_length), last_byte))
+        } else {
+            first.parse::<u64>().ok().and_then(|first| {
+                if last.is_empty() {
+                    Some((first, last_byte))
+                } else {
+                    last.parse::<u64>()
+                        .ok()
+                        .map(|last| (first, last.min(last_byte)))
+                }
+            })
+        };
+        match bounds {
+            Some((first, last)) if first <= last => Self::Partial(first, last),
+            Some(_) => Self::Unsatisfiable,
+            None => Self::Full,
+        }
+    }
+}
+
+/// Identifies a version of a served file, so that clients resuming a download with `If-Range`
+/// are sent the whole file again if it changed
+fn file_etag(metadata: &std::fs::Metadata) -> Option<String> {
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some(format!(
+        "\"{:x}-{:x}\"",
+        metadata.len(),
+        modified.as_nanos()
+    ))
+}
+
 pub struct JsonRpcService {
     thread_hdl: JoinHandle<()>,
 
@@ -153,7 +220,7 @@ impl RpcRequestMiddleware {
         tokio::fs::File::open(path).await
     }
 
-    fn process_file_get(&self, path: &str) -> RequestMiddlewareAction {
+    fn process_file_get(&self, path: &str, headers: &hyper::HeaderMap) -> RequestMiddlewareAction {
         let stem = path.split_at(1).1; // Drop leading '/' from path
         let filename = {
             match path {
@@ -172,29 +239,79 @@ impl RpcRequestMiddleware {
             }
         };
 
-        let file_length = std::fs::metadata(&filename)
-            .map(|m| m.len())
-            .unwrap_or(0)
-            .to_string();
-        info!("get {} -> {:?} ({} bytes)", path, filename, file_length);
+        let metadata = std::fs::metadata(&filename).ok();
+        let file_length = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        let etag = metadata.as_ref().and_then(file_etag);
+        let header = |name: hyper::header::HeaderName| {
+            headers.get(name).and_then(|value| value.to_str().ok())
+        };
+        // A range is only served if the client's copy is of the same version of the file
+        let range = match header(hyper::header::IF_RANGE) {
+            Some(if_range) if Some(if_range) != etag.as_deref() => None,
+            _ => header(hyper::header::RANGE),
+        };
+        let byte_range = ByteRange::parse(range, file_length);
+        info!(
+            "get {} -> {:?} ({} bytes, {:?})",
+            path, filename, file_length, byte_range
+        );
         RequestMiddlewareAction::Respond {
             should_validate_hosts: true,
-            response: Box::pin(async {
+            response: Box::pin(async move {
                 match Self::open_no_follow(filename).await {
                     Err(err) => Ok(if err.kind() == std::io::ErrorKind::NotFound {
                         Self::not_found()
                     } else {
                         Self::internal_server_error()
                     }),
-                    Ok(file) => {
-                        let stream =
-                            FramedRead::new(file, BytesCodec::new()).map_ok(|b| b.freeze());
-                        let body = hyper::Body::wrap_stream(stream);
-
-                        Ok(hyper::Response::builder()
-                            .header(hyper::header::CONTENT_LENGTH, file_length)
-                            .body(body)
-                            .unwrap())
+                    Ok(mut file) => {
+                        let builder = match etag {
+                            Some(etag) => {
+                                hyper::Response::builder().header(hyper::header::ETAG, etag)
+                            }
+                            None => hyper::Response::builder(),
+                        };
+                        Ok(match byte_range {
+                            ByteRange::Full => {
+                                let stream =
+                                    FramedRead::new(file, BytesCodec::new()).map_ok(|b| b.freeze());
+                                let body = hyper::Body::wrap_stream(stream);
+
+                                builder
+                                    .header(hyper::header::CONTENT_LENGTH, file_length)
+                                    .header(hyper::header::ACCEPT_RANGES, "bytes")
+                                    .body(body)
+                                    .unwrap()
+                            }
+                            ByteRange::Partial(first, last) => {
+                                if file.seek(SeekFrom::Start(first)).await.is_err() {
+                                    return Ok(Self::internal_server_error());
+                                }
+                                let length = last - first + 1;
+                                let stream = FramedRead::new(file.take(length), BytesCodec::new())
+                                    .map_ok(|b| b.freeze());
+                                let body = hyper::Body::wrap_stream(stream);
+
+                                builder
+                                    .status(hyper::StatusCode::PARTIAL_CONTENT)
+                                    .header(hyper::header::CONTENT_LENGTH, length)
+                                    .header(
+                                        hyper::header::CONTENT_RANGE,
+                                        format!("bytes {}-{}/{}", first, last, file_length),
+                                    )
+                                    .header(hyper::header::ACCEPT_RANGES, "bytes")
+                                    .body(body)
+                                    .unwrap()
+                            }
+                            ByteRange::Unsatisfiable => builder
+                                .status(hyper::StatusCode::RANGE_NOT_SATISFIABLE)
+                                .header(
+                                    hyper::header::CONTENT_RANGE,
+                                    format!("bytes */{}", file_length),
+                                )
+                                .body(hyper::Body::empty())
+                                .unwrap(),
+                        })
                     }
                 }
             }),
@@ -246,7 +363,7 @@ impl RequestMiddleware for RpcRequestMiddleware {
                 .unwrap()
                 .into()
         } else if self.is_file_get_path(request.uri().path()) {
-            self.process_file_get(request.uri().path())
+            self.process_file_get(request.uri().path(), request.headers())
         } else if request.uri().path() == "/health" {
             hyper::Response::builder()
                 .status(hyper::StatusCode::OK)
@@ -298,10 +415,10 @@ impl JsonRpcService {
         genesis_hash: Hash,
         ledger_path: &Path,
         validator_exit: Arc<RwLock<Exit>>,
-        known_validators: Option<HashSet<Pubkey>>,
+        known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
         override_health_check: Arc<AtomicBool>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
-        send_transaction_service_config: send_transaction_service::Config,
+        send_transaction_service_config: Arc<RwLock<send_transaction_service::Config>>,
         max_slots: Arc<MaxSlots>,
         leader_schedule_cache: Arc<LeaderScheduleCache>,
         current_transaction_status_slot: Arc<AtomicU64>,
@@ -402,7 +519,7 @@ impl JsonRpcService {
 
         let leader_info =
             poh_recorder.map(|recorder| ClusterTpuInfo::new(cluster_info.clone(), recorder));
-        let _send_transaction_service = Arc::new(SendTransactionService::new_with_config(
+        let _send_transaction_service = Arc::new(SendTransactionService::new_with_shared_config(
             tpu_address,
             &bank_forks,
             leader_info,
@@ -563,14 +680,14 @@ mod tests {
             Hash::default(),
             &PathBuf::from("farf"),
             validator_exit,
-            None,
+            Arc::default(),
             Arc::new(AtomicBool::new(false)),
             optimistically_confirmed_bank,
-            send_transaction_service::Config {
+            Arc::new(RwLock::new(send_transaction_service::Config {
                 retry_rate_ms: 1000,
                 leader_forward_count: 1,
                 ..send_transaction_service::Config::default()
-            },
+            })),
             Arc::new(MaxSlots::default()),
             Arc::new(LeaderScheduleCache::default()),
             Arc::new(AtomicU64::default()),
@@ -609,6 +726,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_byte_range_parse() {
+        assert_eq!(ByteRange::parse(None, 100), ByteRange::Full);
+        assert_eq!(
+            ByteRange::parse(Some("bytes=0-9"), 100),
+            ByteRange::Partial(0, 9)
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=90-"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=90-200"), 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=-16"), 100),
+            ByteRange::Partial(84, 99)
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=-200"), 100),
+            ByteRange::Partial(0, 99)
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=100-"), 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=0-"), 0),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=0-9,20-29"), 100),
+            ByteRange::Full
+        );
+        assert_eq!(ByteRange::parse(Some("lines=0-9"), 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse(Some("bytes=a-b"), 100), ByteRange::Full);
+    }
+
     #[test]
     fn test_is_file_get_path() {
         let bank_forks = create_bank_forks();
@@ -696,7 +852,7 @@ mod tests {
         );
 
         // File does not exist => request should fail.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, &hyper::HeaderMap::new());
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -711,7 +867,7 @@ mod tests {
         }
 
         // Normal file exist => request should succeed.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, &hyper::HeaderMap::new());
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -720,6 +876,23 @@ mod tests {
             panic!("Unexpected RequestMiddlewareAction variant");
         }
 
+        // A range is only served for the current version of the file
+        let etag = file_etag(&std::fs::metadata(&genesis_path).unwrap()).unwrap();
+        for (if_range, expected_status) in [(etag.as_str(), 206), ("\"stale\"", 200)] {
+            let mut headers = hyper::HeaderMap::new();
+            headers.insert(hyper::header::RANGE, "bytes=0-1".parse().unwrap());
+            headers.insert(hyper::header::IF_RANGE, if_range.parse().unwrap());
+            let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, &headers);
+            if let RequestMiddlewareAction::Respond { response, .. } = action {
+                let response = runtime.block_on(response);
+                let response = response.unwrap();
+                assert_eq!(response.status(), expected_status);
+                assert_eq!(response.headers()[hyper::header::ETAG], etag.as_str());
+            } else {
+                panic!("Unexpected RequestMiddlewareAction variant");
+            }
+        }
+
         #[cfg(unix)]
         {
             std::fs::remove_file(&genesis_path).unwrap();
@@ -730,7 +903,8 @@ mod tests {
             symlink::symlink_file("wrong", &genesis_path).unwrap();
 
             // File is a symbolic link => request should fail.
-            let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+            let action =
+                rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, &hyper::HeaderMap::new());
             if let RequestMiddlewareAction::Respond { response, .. } = action {
                 let response = runtime.block_on(response);
                 let response = response.unwrap();
@@ -769,7 +943,9 @@ mod tests {
 
         let health = Arc::new(RpcHealth::new(
             cluster_info.clone(),
-            Some(known_validators.clone().into_iter().collect()),
+            Arc::new(RwLock::new(Some(
+                known_validators.clone().into_iter().collect(),
+            ))),
             health_check_slot_distance,
             override_health_check.clone(),
         ));
@@ -49,6 +49,7 @@ pub enum SubscriptionParams {
     Logs(LogsSubscriptionParams),
     Program(ProgramSubscriptionParams),
     Signature(SignatureSubscriptionParams),
+    Transaction(TransactionSubscriptionParams),
     Slot,
     SlotsUpdates,
     Root,
@@ -62,6 +63,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(_) => "logsNotification",
             SubscriptionParams::Program(_) => "programNotification",
             SubscriptionParams::Signature(_) => "signatureNotification",
+            SubscriptionParams::Transaction(_) => "transactionNotification",
             SubscriptionParams::Slot => "slotNotification",
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
@@ -77,6 +79,7 @@ impl SubscriptionParams {
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
+            SubscriptionParams::Transaction(params) => Some(params.commitment),
             SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
@@ -91,6 +94,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -106,6 +110,7 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
+            SubscriptionParams::Transaction(params) => &params.commitment,
             SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
@@ -148,6 +153,28 @@ pub enum BlockSubscriptionKind {
     MentionsAccountOrProgram(Pubkey),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionParams {
+    /// Sorted and deduplicated; an empty list matches every transaction
+    pub account_include: Vec<Pubkey>,
+    /// Sorted and deduplicated
+    pub account_exclude: Vec<Pubkey>,
+    pub encoding: UiTransactionEncoding,
+    pub commitment: CommitmentConfig,
+}
+
+impl TransactionSubscriptionParams {
+    pub fn is_match(&self, account_keys: &[Pubkey]) -> bool {
+        (self.account_include.is_empty()
+            || account_keys
+                .iter()
+                .any(|key| self.account_include.binary_search(key).is_ok()))
+            && !account_keys
+                .iter()
+                .any(|key| self.account_exclude.binary_search(key).is_ok())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct LogsSubscriptionParams {
     pub kind: LogsSubscriptionKind,
@@ -171,6 +198,27 @@ pub struct ProgramSubscriptionParams {
     pub with_context: bool,
 }
 
+impl ProgramSubscriptionParams {
+    /// Returns the part of the params that determines the notification contents.
+    /// Subscriptions sharing a key can reuse the filtered and encoded accounts.
+    pub fn notification_key(&self) -> ProgramNotificationKey {
+        ProgramNotificationKey {
+            pubkey: self.pubkey,
+            filters: self.filters.clone(),
+            encoding: self.encoding,
+            data_slice: self.data_slice,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProgramNotificationKey {
+    pub pubkey: Pubkey,
+    pub filters: Vec<RpcFilterType>,
+    pub encoding: UiAccountEncoding,
+    pub data_slice: Option<UiDataSliceConfig>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SignatureSubscriptionParams {
     pub signature: Signature,
@@ -301,6 +349,42 @@ impl SubscriptionControl {
     }
 }
 
+/// Tracks how far connections lag behind the notifications generated for a subscription.
+#[derive(Debug, Default)]
+pub struct NotificationBacklog {
+    generated: AtomicU64,
+    max_pending: AtomicU64,
+}
+
+impl NotificationBacklog {
+    /// Registers a new notification and returns its sequence number.
+    pub fn next_sequence(&self) -> u64 {
+        self.generated
+            .fetch_add(1, Ordering::AcqRel)
+            .saturating_add(1)
+    }
+
+    /// Records that a connection handled the notification with `sequence`
+    /// and returns how many newer notifications are still queued for it.
+    pub fn record_delivered(&self, sequence: u64) -> u64 {
+        let pending = self
+            .generated
+            .load(Ordering::Acquire)
+            .saturating_sub(sequence);
+        self.max_pending.fetch_max(pending, Ordering::AcqRel);
+        pending
+    }
+
+    pub fn generated(&self) -> u64 {
+        self.generated.load(Ordering::Acquire)
+    }
+
+    /// Returns the largest backlog seen since the previous call.
+    pub fn take_max_pending(&self) -> u64 {
+        self.max_pending.swap(0, Ordering::AcqRel)
+    }
+}
+
 #[derive(Debug)]
 pub struct SubscriptionInfo {
     id: SubscriptionId,
@@ -308,6 +392,7 @@ pub struct SubscriptionInfo {
     method: &'static str,
     pub last_notified_slot: RwLock<Slot>,
     commitment: Option<CommitmentConfig>,
+    backlog: Arc<NotificationBacklog>,
 }
 
 impl SubscriptionInfo {
@@ -326,6 +411,10 @@ impl SubscriptionInfo {
     pub fn commitment(&self) -> Option<CommitmentConfig> {
         self.commitment
     }
+
+    pub fn backlog(&self) -> &Arc<NotificationBacklog> {
+        &self.backlog
+    }
 }
 
 #[derive(Debug, Error)]
@@ -439,6 +528,7 @@ impl SubscriptionsTracker {
             commitment: params.commitment(),
             method: params.method(),
             params: params.clone(),
+            backlog: Arc::default(),
         });
         match &params {
             SubscriptionParams::Logs(params) => {
@@ -518,6 +608,37 @@ impl SubscriptionsTracker {
     pub fn node_progress_watchers(&self) -> &HashMap<SubscriptionParams, Arc<SubscriptionInfo>> {
         &self.node_progress_watchers
     }
+
+    /// Reports the notification backlog observed across all subscriptions since the last call.
+    pub fn report_notification_backlog(&self) {
+        let mut num_backlogged = 0;
+        let mut max_backlog = 0;
+        let mut total_backlog = 0;
+        let mut max_backlog_method = "";
+        for info in self
+            .commitment_watchers
+            .values()
+            .chain(self.gossip_watchers.values())
+            .chain(self.node_progress_watchers.values())
+        {
+            let pending = info.backlog.take_max_pending();
+            if pending > 0 {
+                num_backlogged += 1;
+                total_backlog += pending;
+                if pending > max_backlog {
+                    max_backlog = pending;
+                    max_backlog_method = info.method;
+                }
+            }
+        }
+        datapoint_info!(
+            "rpc_subscription_backlog",
+            ("num_backlogged_subscriptions", num_backlogged, i64),
+            ("max_backlog", max_backlog, i64),
+            ("max_backlog_method", max_backlog_method.to_string(), String),
+            ("total_backlog", total_backlog, i64),
+        );
+    }
 }
 
 struct SubscriptionTokenInner {
@@ -719,6 +840,45 @@ mod tests {
         assert_eq!(*info.last_notified_slot.read().unwrap(), 42);
     }
 
+    #[test]
+    fn transaction_params_match() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut params = TransactionSubscriptionParams {
+            account_include: vec![],
+            account_exclude: vec![],
+            encoding: UiTransactionEncoding::Base64,
+            commitment: CommitmentConfig::confirmed(),
+        };
+        assert!(params.is_match(&[a, b]));
+
+        params.account_include = vec![a];
+        assert!(params.is_match(&[a, b]));
+        assert!(!params.is_match(&[b, c]));
+
+        params.account_exclude = vec![b];
+        assert!(!params.is_match(&[a, b]));
+        assert!(params.is_match(&[a, c]));
+    }
+
+    #[test]
+    fn notification_backlog() {
+        let backlog = NotificationBacklog::default();
+        let first = backlog.next_sequence();
+        let second = backlog.next_sequence();
+        let third = backlog.next_sequence();
+        assert_eq!((first, second, third), (1, 2, 3));
+        assert_eq!(backlog.generated(), 3);
+
+        assert_eq!(backlog.record_delivered(first), 2);
+        assert_eq!(backlog.record_delivered(third), 0);
+        assert_eq!(backlog.take_max_pending(), 2);
+        assert_eq!(backlog.take_max_pending(), 0);
+    }
+
     #[test]
     fn subscription_indexes() {
         fn counts(tracker: &SubscriptionsTracker) -> (usize, usize, usize, usize) {
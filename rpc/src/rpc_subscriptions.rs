@@ -6,24 +6,29 @@ use {
         rpc_pubsub_service::PubSubConfig,
         rpc_subscription_tracker::{
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
-            LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
-            SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
-            SubscriptionParams, SubscriptionsTracker,
+            LogsSubscriptionKind, LogsSubscriptionParams, NotificationBacklog,
+            ProgramNotificationKey, ProgramSubscriptionParams, SignatureSubscriptionParams,
+            SubscriptionControl, SubscriptionId, SubscriptionInfo, SubscriptionParams,
+            SubscriptionsTracker, TransactionSubscriptionParams,
         },
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
+    dashmap::DashMap,
     rayon::prelude::*,
-    serde::Serialize,
+    serde::{Serialize, Serializer},
     paychains_account_decoder::{parse_token::spl_token_id, UiAccount, UiAccountEncoding},
     paychains_client::{
         rpc_filter::RpcFilterType,
         rpc_response::{
             ProcessedSignatureResult, ReceivedSignatureResult, Response, RpcBlockUpdate,
             RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-            RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVote, SlotInfo, SlotUpdate,
         },
     },
-    paychains_ledger::{blockstore::Blockstore, get_tmp_ledger_path},
+    paychains_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        get_tmp_ledger_path,
+    },
     paychains_measure::measure::Measure,
     paychains_rayon_threadlimit::get_thread_count,
     paychains_runtime::{
@@ -39,7 +44,7 @@ use {
         timing::timestamp,
         transaction,
     },
-    paychains_transaction_status::ConfirmedBlock,
+    paychains_transaction_status::{ConfirmedBlock, Encodable},
     paychains_vote_program::vote_state::VoteTransaction,
     std::{
         cell::RefCell,
@@ -59,7 +64,7 @@ use {
 const RECEIVE_DELAY_MILLIS: u64 = 100;
 
 fn get_transaction_logs(
-    bank: &Bank,
+    bank: &Arc<Bank>,
     params: &LogsSubscriptionParams,
 ) -> Option<Vec<TransactionLogInfo>> {
     let pubkey = match &params.kind {
@@ -141,7 +146,7 @@ fn check_commitment_and_notify<P, S, B, F, X>(
 ) -> bool
 where
     S: Clone + Serialize,
-    B: Fn(&Bank, &P) -> X,
+    B: Fn(&Arc<Bank>, &P) -> X,
     F: Fn(X, &P, Slot, Arc<Bank>) -> (Box<dyn Iterator<Item = S>>, Slot),
     X: Clone + Default,
 {
@@ -174,6 +179,8 @@ pub struct RpcNotification {
     pub is_final: bool,
     pub json: Weak<String>,
     pub created_at: Instant,
+    pub sequence: u64,
+    pub backlog: Arc<NotificationBacklog>,
 }
 
 struct RecentItems {
@@ -265,6 +272,8 @@ impl RpcNotifier {
             json: Arc::downgrade(&buf_arc),
             is_final,
             created_at: Instant::now(),
+            sequence: subscription.backlog().next_sequence(),
+            backlog: Arc::clone(subscription.backlog()),
         };
         // There is an unlikely case where this can fail: if the last subscription is closed
         // just as the notifier generates a notification for it.
@@ -317,6 +326,86 @@ fn filter_block_result_txs(
     })
 }
 
+fn filter_transaction_results(
+    block: ConfirmedBlock,
+    slot: Slot,
+    params: &TransactionSubscriptionParams,
+) -> impl Iterator<Item = RpcTransactionUpdate> + '_ {
+    let block_time = block.block_time;
+    block
+        .transactions
+        .into_iter()
+        .filter(move |tx| params.is_match(&tx.transaction.message.account_keys))
+        .map(move |tx| RpcTransactionUpdate {
+            signature: tx.transaction.signatures[0].to_string(),
+            slot,
+            block_time,
+            transaction: tx.encode(params.encoding),
+        })
+}
+
+/// Hands every complete block that `subscription` has not been notified of yet to
+/// `notify_block`, from its last unnotified slot up to `slot`, restricted to
+/// ancestors of `slot`. `notify_block` returns whether the subscription should
+/// move past the block.
+fn for_each_unnotified_block<F>(
+    subscription: &SubscriptionInfo,
+    bank_forks: &RwLock<BankForks>,
+    blockstore: &Blockstore,
+    max_complete_transaction_status_slot: &AtomicU64,
+    slot: Slot,
+    mut notify_block: F,
+) where
+    F: FnMut(Slot, Result<ConfirmedBlock, BlockstoreError>) -> bool,
+{
+    let bank = match bank_forks.read().unwrap().get(slot) {
+        Some(bank) => bank.clone(),
+        None => return,
+    };
+    // We're calling it unnotified in this context
+    // because, logically, it gets set to `last_notified_slot + 1`
+    // on the final iteration of the loop down below.
+    // This is used to notify blocks for slots that were
+    // potentially missed due to upstream transient errors
+    // that led to this notification not being triggered for
+    // a slot.
+    //
+    // e.g.
+    // notify_watchers is triggered for Slot 1
+    // some time passes
+    // notify_watchers is triggered for Slot 4
+    // this will try to fetch blocks for slots 2, 3, and 4
+    // as long as they are ancestors of `slot`
+    let mut w_last_unnotified_slot = subscription.last_notified_slot.write().unwrap();
+    // would mean it's the first notification for this subscription connection
+    if *w_last_unnotified_slot == 0 {
+        *w_last_unnotified_slot = slot;
+    }
+    let mut slots_to_notify: Vec<_> = (*w_last_unnotified_slot..slot).collect();
+    let ancestors = bank.proper_ancestors_set();
+    slots_to_notify = slots_to_notify
+        .into_iter()
+        .filter(|slot| ancestors.contains(slot))
+        .collect();
+    slots_to_notify.push(slot);
+    for s in slots_to_notify {
+        // To avoid skipping a slot that fails this condition,
+        // caused by non-deterministic concurrency accesses, we
+        // break out of the loop. Besides if the current `s` is
+        // greater, then any `s + K` is also greater.
+        if s > max_complete_transaction_status_slot.load(Ordering::SeqCst) {
+            break;
+        }
+        // If `notify_block` doesn't advance `w_last_unnotified_slot`
+        // the block will be retried on the next notification trigger
+        if notify_block(s, blockstore.get_complete_block(s, false)) {
+            // the next time this subscription is notified it will
+            // try to fetch all slots between (s + 1) to `slot`, inclusively
+            *w_last_unnotified_slot = s + 1;
+        }
+    }
+}
+
 fn filter_account_result(
     result: Option<(AccountSharedData, Slot)>,
     params: &AccountSubscriptionParams,
@@ -342,7 +431,7 @@ fn filter_account_result(
                 &account,
                 params.encoding,
                 None,
-                None,
+                params.data_slice,
             )))
         }
     } else {
@@ -366,35 +455,92 @@ fn filter_signature_result(
     )
 }
 
-fn filter_program_results(
+fn encode_program_accounts(
     accounts: Vec<(Pubkey, AccountSharedData)>,
     params: &ProgramSubscriptionParams,
-    last_notified_slot: Slot,
     bank: Arc<Bank>,
-) -> (Box<dyn Iterator<Item = RpcKeyedAccount>>, Slot) {
+) -> Vec<RpcKeyedAccount> {
     let accounts_is_empty = accounts.is_empty();
     let encoding = params.encoding;
-    let filters = params.filters.clone();
-    let keyed_accounts = accounts.into_iter().filter(move |(_, account)| {
+    let data_slice = params.data_slice;
+    let filters = &params.filters;
+    let keyed_accounts = accounts.into_iter().filter(|(_, account)| {
         filters.iter().all(|filter_type| match filter_type {
             RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
             RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
         })
     });
-    let accounts: Box<dyn Iterator<Item = RpcKeyedAccount>> = if params.pubkey == spl_token_id()
+    if params.pubkey == spl_token_id()
         && params.encoding == UiAccountEncoding::JsonParsed
         && !accounts_is_empty
     {
-        Box::new(get_parsed_token_accounts(bank, keyed_accounts))
+        get_parsed_token_accounts(bank, keyed_accounts).collect()
     } else {
-        Box::new(
-            keyed_accounts.map(move |(pubkey, account)| RpcKeyedAccount {
+        keyed_accounts
+            .map(|(pubkey, account)| RpcKeyedAccount {
                 pubkey: pubkey.to_string(),
-                account: UiAccount::encode(&pubkey, &account, encoding, None, None),
-            }),
-        )
-    };
-    (accounts, last_notified_slot)
+                account: UiAccount::encode(&pubkey, &account, encoding, None, data_slice),
+            })
+            .collect()
+    }
+}
+
+/// Program accounts modified in a bank, filtered and encoded once per distinct
+/// `ProgramNotificationKey` and shared by every program subscription using that key.
+#[derive(Default)]
+struct ProgramNotificationCache {
+    entries: DashMap<(Slot, ProgramNotificationKey), Arc<Vec<RpcKeyedAccount>>>,
+}
+
+impl ProgramNotificationCache {
+    fn get_or_encode(
+        &self,
+        bank: &Arc<Bank>,
+        params: &ProgramSubscriptionParams,
+    ) -> Arc<Vec<RpcKeyedAccount>> {
+        self.entries
+            .entry((bank.slot(), params.notification_key()))
+            .or_insert_with(|| {
+                let accounts = bank.get_program_accounts_modified_since_parent(&params.pubkey);
+                Arc::new(encode_program_accounts(accounts, params, Arc::clone(bank)))
+            })
+            .clone()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// An element of results shared by several subscriptions, serialized as the element itself so
+/// that notifying does not copy the results
+#[derive(Clone)]
+struct SharedResult<T> {
+    results: Arc<Vec<T>>,
+    index: usize,
+}
+
+impl<T: Serialize> Serialize for SharedResult<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.results[self.index].serialize(serializer)
+    }
+}
+
+fn filter_program_results(
+    accounts: Arc<Vec<RpcKeyedAccount>>,
+    last_notified_slot: Slot,
+) -> (
+    Box<dyn Iterator<Item = SharedResult<RpcKeyedAccount>>>,
+    Slot,
+) {
+    let len = accounts.len();
+    (
+        Box::new((0..len).map(move |index| SharedResult {
+            results: Arc::clone(&accounts),
+            index,
+        })),
+        last_notified_slot,
+    )
 }
 
 fn filter_logs_results(
@@ -458,7 +604,7 @@ struct PubsubNotificationStats {
 }
 
 impl PubsubNotificationStats {
-    fn maybe_submit(&mut self) {
+    fn maybe_submit(&mut self, subscriptions: &SubscriptionsTracker) {
         const SUBMIT_CADENCE: Duration = Duration::from_secs(2);
         let elapsed = self.since.as_ref().map(Instant::elapsed);
         if elapsed.unwrap_or(Duration::MAX) < SUBMIT_CADENCE {
@@ -477,6 +623,7 @@ impl PubsubNotificationStats {
                 i64
             ),
         );
+        subscriptions.report_notification_backlog();
         *self = Self {
             since: Some(Instant::now()),
             ..Self::default()
@@ -853,7 +1000,7 @@ impl RpcSubscriptions {
                     break;
                 }
             }
-            stats.maybe_submit();
+            stats.maybe_submit(&subscriptions);
         }
     }
 
@@ -874,6 +1021,9 @@ impl RpcSubscriptions {
         let num_blocks_found = AtomicUsize::new(0);
         let num_blocks_notified = AtomicUsize::new(0);
 
+        let num_transactions_found = AtomicUsize::new(0);
+        let num_transactions_notified = AtomicUsize::new(0);
+
         let num_logs_found = AtomicUsize::new(0);
         let num_logs_notified = AtomicUsize::new(0);
 
@@ -883,6 +1033,8 @@ impl RpcSubscriptions {
         let num_signatures_found = AtomicUsize::new(0);
         let num_signatures_notified = AtomicUsize::new(0);
 
+        let program_notifications = ProgramNotificationCache::default();
+
         let subscriptions = subscriptions.into_par_iter();
         subscriptions.for_each(|(_id, subscription)| {
             let slot = if let Some(commitment) = subscription.commitment() {
@@ -920,81 +1072,79 @@ impl RpcSubscriptions {
                 SubscriptionParams::Block(params) => {
                     num_blocks_found.fetch_add(1, Ordering::Relaxed);
                     if let Some(slot) = slot {
-                        if let Some(bank) = bank_forks.read().unwrap().get(slot) {
-                            // We're calling it unnotified in this context
-                            // because, logically, it gets set to `last_notified_slot + 1`
-                            // on the final iteration of the loop down below.
-                            // This is used to notify blocks for slots that were
-                            // potentially missed due to upstream transient errors
-                            // that led to this notification not being triggered for
-                            // a slot.
-                            //
-                            // e.g.
-                            // notify_watchers is triggered for Slot 1
-                            // some time passes
-                            // notify_watchers is triggered for Slot 4
-                            // this will try to fetch blocks for slots 2, 3, and 4
-                            // as long as they are ancestors of `slot`
-                            let mut w_last_unnotified_slot =
-                                subscription.last_notified_slot.write().unwrap();
-                            // would mean it's the first notification for this subscription connection
-                            if *w_last_unnotified_slot == 0 {
-                                *w_last_unnotified_slot = slot;
-                            }
-                            let mut slots_to_notify: Vec<_> =
-                                (*w_last_unnotified_slot..slot).collect();
-                            let ancestors = bank.proper_ancestors_set();
-                            slots_to_notify = slots_to_notify
-                                .into_iter()
-                                .filter(|slot| ancestors.contains(slot))
-                                .collect();
-                            slots_to_notify.push(slot);
-                            for s in slots_to_notify {
-                                // To avoid skipping a slot that fails this condition,
-                                // caused by non-deterministic concurrency accesses, we
-                                // break out of the loop. Besides if the current `s` is
-                                // greater, then any `s + K` is also greater.
-                                if s > max_complete_transaction_status_slot.load(Ordering::SeqCst) {
-                                    break;
-                                }
-                                match blockstore.get_complete_block(s, false) {
-                                    Ok(block) => {
-                                        if let Some(res) = filter_block_result_txs(block, s, params)
-                                        {
-                                            notifier.notify(
-                                                Response {
-                                                    context: RpcResponseContext { slot: s },
-                                                    value: res,
-                                                },
-                                                subscription,
-                                                false,
-                                            );
-                                            num_blocks_notified.fetch_add(1, Ordering::Relaxed);
-                                            // the next time this subscription is notified it will
-                                            // try to fetch all slots between (s + 1) to `slot`, inclusively
-                                            *w_last_unnotified_slot = s + 1;
-                                        }
+                        for_each_unnotified_block(
+                            subscription,
+                            bank_forks,
+                            blockstore,
+                            &max_complete_transaction_status_slot,
+                            slot,
+                            |s, block| match block {
+                                Ok(block) => {
+                                    if let Some(res) = filter_block_result_txs(block, s, params) {
+                                        notifier.notify(
+                                            Response {
+                                                context: RpcResponseContext { slot: s },
+                                                value: res,
+                                            },
+                                            subscription,
+                                            false,
+                                        );
+                                        num_blocks_notified.fetch_add(1, Ordering::Relaxed);
+                                        true
+                                    } else {
+                                        false
                                     }
-                                    Err(e) => {
-                                        // we don't advance `w_last_unnotified_slot` so that
-                                        // it'll retry on the next notification trigger
-                                        error!("get_complete_block error: {}", e);
+                                }
+                                Err(e) => {
+                                    error!("get_complete_block error: {}", e);
+                                    notifier.notify(
+                                        Response {
+                                            context: RpcResponseContext { slot: s },
+                                            value: RpcBlockUpdate {
+                                                slot,
+                                                block: None,
+                                                err: Some(RpcBlockUpdateError::BlockStoreError),
+                                            },
+                                        },
+                                        subscription,
+                                        false,
+                                    );
+                                    false
+                                }
+                            },
+                        );
+                    }
+                }
+                SubscriptionParams::Transaction(params) => {
+                    num_transactions_found.fetch_add(1, Ordering::Relaxed);
+                    if let Some(slot) = slot {
+                        for_each_unnotified_block(
+                            subscription,
+                            bank_forks,
+                            blockstore,
+                            &max_complete_transaction_status_slot,
+                            slot,
+                            |s, block| match block {
+                                Ok(block) => {
+                                    for update in filter_transaction_results(block, s, params) {
                                         notifier.notify(
                                             Response {
                                                 context: RpcResponseContext { slot: s },
-                                                value: RpcBlockUpdate {
-                                                    slot,
-                                                    block: None,
-                                                    err: Some(RpcBlockUpdateError::BlockStoreError),
-                                                },
+                                                value: update,
                                             },
                                             subscription,
                                             false,
                                         );
+                                        num_transactions_notified.fetch_add(1, Ordering::Relaxed);
                                     }
+                                    true
                                 }
-                            }
-                        }
+                                Err(e) => {
+                                    error!("get_complete_block error: {}", e);
+                                    false
+                                }
+                            },
+                        );
                     }
                 }
                 SubscriptionParams::Logs(params) => {
@@ -1024,10 +1174,10 @@ impl RpcSubscriptions {
                             subscription,
                             bank_forks,
                             slot,
-                            |bank, params| {
-                                bank.get_program_accounts_modified_since_parent(&params.pubkey)
+                            |bank, params| program_notifications.get_or_encode(bank, params),
+                            |accounts, _params, last_notified_slot, _bank| {
+                                filter_program_results(accounts, last_notified_slot)
                             },
-                            filter_program_results,
                             notifier,
                             false,
                         );
@@ -1067,7 +1217,8 @@ impl RpcSubscriptions {
         let total_notified = num_accounts_notified.load(Ordering::Relaxed)
             + num_logs_notified.load(Ordering::Relaxed)
             + num_programs_notified.load(Ordering::Relaxed)
-            + num_signatures_notified.load(Ordering::Relaxed);
+            + num_signatures_notified.load(Ordering::Relaxed)
+            + num_transactions_notified.load(Ordering::Relaxed);
         let total_ms = total_time.as_ms();
         if total_notified > 0 || total_ms > 10 {
             debug!(
@@ -1116,6 +1267,11 @@ impl RpcSubscriptions {
                     num_programs_notified.load(Ordering::Relaxed),
                     i64
                 ),
+                (
+                    "num_program_notification_groups",
+                    program_notifications.len(),
+                    i64
+                ),
                 (
                     "num_signature_subscriptions",
                     num_signatures_found.load(Ordering::Relaxed),
@@ -1126,6 +1282,16 @@ impl RpcSubscriptions {
                     num_signatures_notified.load(Ordering::Relaxed),
                     i64
                 ),
+                (
+                    "num_transaction_subscriptions",
+                    num_transactions_found.load(Ordering::Relaxed),
+                    i64
+                ),
+                (
+                    "num_transactions_notified",
+                    num_transactions_notified.load(Ordering::Relaxed),
+                    i64
+                ),
                 ("notifications_time", total_time.as_us() as i64, i64),
             );
             inc_new_counter_info!(
@@ -1179,9 +1345,11 @@ pub(crate) mod tests {
             rpc_pubsub_service,
         },
         serial_test::serial,
+        paychains_account_decoder::UiDataSliceConfig,
         paychains_client::rpc_config::{
             RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSignatureSubscribeConfig,
-            RpcTransactionLogsFilter, {RpcBlockSubscribeConfig, RpcBlockSubscribeFilter},
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
+            {RpcBlockSubscribeConfig, RpcBlockSubscribeFilter},
         },
         paychains_runtime::{
             commitment::BlockCommitment,
@@ -1434,6 +1602,108 @@ pub(crate) mod tests {
             .assert_unsubscribed(&SubscriptionParams::Block(params));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_confirmed_transaction_subscribe() {
+        let exit = Arc::new(AtomicBool::new(false));
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let ledger_path = get_tmp_ledger_path!();
+        let blockstore = Blockstore::open(&ledger_path).unwrap();
+        let blockstore = Arc::new(blockstore);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests_with_blockstore(
+            &exit,
+            max_complete_transaction_status_slot,
+            blockstore.clone(),
+            bank_forks.clone(),
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+
+        let keypair1 = Keypair::new();
+        let keypair2 = Keypair::new();
+        let keypair3 = Keypair::new();
+        let keypair4 = Keypair::new();
+        let filter = RpcTransactionSubscribeFilter {
+            account_include: Some(vec![keypair3.pubkey().to_string()]),
+            account_exclude: Some(vec![keypair1.pubkey().to_string()]),
+        };
+        let config = RpcTransactionSubscribeConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            encoding: Some(UiTransactionEncoding::Json),
+        };
+        let params = TransactionSubscriptionParams {
+            account_include: vec![keypair3.pubkey()],
+            account_exclude: vec![keypair1.pubkey()],
+            encoding: UiTransactionEncoding::Json,
+            commitment: CommitmentConfig::confirmed(),
+        };
+        let sub_id = rpc.transaction_subscribe(filter, Some(config)).unwrap();
+        subscriptions
+            .control
+            .assert_subscribed(&SubscriptionParams::Transaction(params.clone()));
+
+        let bank = bank_forks.read().unwrap().working_bank();
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::new(blockstore.max_root()));
+        let _confirmed_block_signatures = create_test_transactions_and_populate_blockstore(
+            vec![&keypair1, &keypair2, &keypair3, &keypair4],
+            0,
+            bank,
+            blockstore.clone(),
+            max_complete_transaction_status_slot,
+        );
+
+        let slot = 0;
+        subscriptions.notify_gossip_subscribers(slot);
+        let actual_resp = receiver.recv();
+        let actual_resp = serde_json::from_str::<serde_json::Value>(&actual_resp).unwrap();
+
+        let block = blockstore.get_complete_block(slot, false).unwrap();
+        let block_time = block.block_time;
+        let tx = block
+            .transactions
+            .into_iter()
+            .find(|tx| {
+                tx.transaction
+                    .message
+                    .account_keys
+                    .contains(&keypair3.pubkey())
+            })
+            .unwrap();
+        let expected_resp = RpcTransactionUpdate {
+            signature: tx.transaction.signatures[0].to_string(),
+            slot,
+            block_time,
+            transaction: tx.encode(UiTransactionEncoding::Json),
+        };
+        let expected_resp = json!({
+           "jsonrpc": "2.0",
+           "method": "transactionNotification",
+           "params": {
+               "result": {
+                   "context": { "slot": slot },
+                   "value": expected_resp,
+               },
+               "subscription": 0,
+           }
+        });
+        assert_eq!(expected_resp, actual_resp);
+
+        // the failed transfer to keypair3 is signed by the excluded keypair1
+        let should_err = receiver.recv_timeout(Duration::from_millis(300));
+        assert!(should_err.is_err());
+
+        rpc.transaction_unsubscribe(sub_id).unwrap();
+        subscriptions
+            .control
+            .assert_unsubscribed(&SubscriptionParams::Transaction(params));
+    }
+
     #[test]
     #[serial]
     fn test_check_confirmed_block_subscribe_with_mentions() {
@@ -1729,6 +1999,188 @@ pub(crate) mod tests {
             }));
     }
 
+    #[test]
+    #[serial]
+    fn test_check_program_subscribe_data_slice() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(100);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = bank.last_blockhash();
+        let bank_forks = Arc::new(RwLock::new(BankForks::new(bank)));
+        let alice = Keypair::new();
+        let tx = system_transaction::create_account(
+            &mint_keypair,
+            &alice,
+            blockhash,
+            1,
+            16,
+            &stake::program::id(),
+        );
+        bank_forks
+            .write()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .process_transaction(&tx)
+            .unwrap();
+
+        let exit = Arc::new(AtomicBool::new(false));
+        let optimistically_confirmed_bank =
+            OptimisticallyConfirmedBank::locked_from_bank_forks_root(&bank_forks);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let subscriptions = Arc::new(RpcSubscriptions::new_for_tests(
+            &exit,
+            max_complete_transaction_status_slot,
+            bank_forks,
+            Arc::new(RwLock::new(BlockCommitmentCache::new_for_tests())),
+            optimistically_confirmed_bank,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&subscriptions);
+
+        let account_config = RpcAccountInfoConfig {
+            commitment: Some(CommitmentConfig::processed()),
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 4,
+            }),
+        };
+        // Sliced data can't be represented as parsed json
+        assert!(rpc
+            .program_subscribe(
+                stake::program::id().to_string(),
+                Some(RpcProgramAccountsConfig {
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::JsonParsed),
+                        ..account_config.clone()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                }),
+            )
+            .is_err());
+
+        // Both subscriptions share a notification key and get the same encoded account
+        for with_context in [false, true] {
+            rpc.program_subscribe(
+                stake::program::id().to_string(),
+                Some(RpcProgramAccountsConfig {
+                    account_config: account_config.clone(),
+                    with_context: Some(with_context),
+                    ..RpcProgramAccountsConfig::default()
+                }),
+            )
+            .unwrap();
+        }
+        assert_eq!(subscriptions.total(), 2);
+
+        subscriptions.notify_subscribers(CommitmentSlots::default());
+        let mut responses: Vec<_> = (0..2)
+            .map(|_| serde_json::from_str::<serde_json::Value>(&receiver.recv()).unwrap())
+            .collect();
+        responses.sort_by_key(|response| response["params"]["subscription"].as_u64());
+        for (subscription, response) in responses.into_iter().enumerate() {
+            let expected = json!({
+               "jsonrpc": "2.0",
+               "method": "programNotification",
+               "params": {
+                   "result": {
+                       "context": { "slot": 0 },
+                       "value": {
+                           "account": {
+                              "data": ["AAAAAA==", "base64"],
+                              "executable": false,
+                              "lamports": 1,
+                              "owner": "Stake11111111111111111111111111111111111111",
+                              "rentEpoch": 0,
+                           },
+                           "pubkey": alice.pubkey().to_string(),
+                        },
+                   },
+                   "subscription": subscription,
+               }
+            });
+            assert_eq!(expected, response);
+        }
+    }
+
+    #[test]
+    fn test_program_notification_cache_shares_key() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        let alice = Keypair::new();
+        let tx = system_transaction::create_account(
+            &mint_keypair,
+            &alice,
+            bank.last_blockhash(),
+            1,
+            16,
+            &stake::program::id(),
+        );
+        bank.process_transaction(&tx).unwrap();
+
+        let params = ProgramSubscriptionParams {
+            pubkey: stake::program::id(),
+            filters: vec![],
+            encoding: UiAccountEncoding::Base64,
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 4,
+            }),
+            commitment: CommitmentConfig::processed(),
+            with_context: false,
+        };
+        // Commitment and context don't affect the notification contents
+        let same_key_params = ProgramSubscriptionParams {
+            commitment: CommitmentConfig::finalized(),
+            with_context: true,
+            ..params.clone()
+        };
+        let other_key_params = ProgramSubscriptionParams {
+            data_slice: None,
+            ..params.clone()
+        };
+        assert_eq!(
+            params.notification_key(),
+            same_key_params.notification_key()
+        );
+        assert_ne!(
+            params.notification_key(),
+            other_key_params.notification_key()
+        );
+
+        let cache = ProgramNotificationCache::default();
+        let accounts = cache.get_or_encode(&bank, &params);
+        assert_eq!(accounts.len(), 1);
+        assert!(Arc::ptr_eq(
+            &accounts,
+            &cache.get_or_encode(&bank, &same_key_params)
+        ));
+        assert_eq!(cache.len(), 1);
+
+        let other_accounts = cache.get_or_encode(&bank, &other_key_params);
+        assert!(!Arc::ptr_eq(&accounts, &other_accounts));
+        assert_eq!(other_accounts.len(), 1);
+        assert_eq!(cache.len(), 2);
+
+        // notifying iterates the shared results rather than copying them
+        let (results, _slot) = filter_program_results(Arc::clone(&accounts), 0);
+        let results: Vec<_> = results.collect();
+        assert!(results
+            .iter()
+            .all(|result| Arc::ptr_eq(&result.results, &accounts)));
+        assert_eq!(
+            serde_json::to_value(&results).unwrap(),
+            serde_json::to_value(&*accounts).unwrap()
+        );
+    }
+
     #[test]
     #[serial]
     fn test_check_program_subscribe_for_missing_optimistically_confirmed_slot() {
@@ -98,6 +98,7 @@ impl TransactionStatusService {
                             log_messages,
                             inner_instructions,
                             durable_nonce_fee,
+                            ..
                         } = details;
                         let lamports_per_signature = match durable_nonce_fee {
                             Some(DurableNonceFee::Valid(lamports_per_signature)) => {
@@ -326,6 +327,7 @@ pub(crate) mod tests {
                 status: Ok(()),
                 log_messages: None,
                 inner_instructions: None,
+                inner_instruction_stack_heights: None,
                 durable_nonce_fee: Some(DurableNonceFee::from(
                     &NonceFull::from_partial(
                         rollback_partial,
@@ -335,6 +337,8 @@ pub(crate) mod tests {
                     )
                     .unwrap(),
                 )),
+                return_data: None,
+                executed_units: 0,
             });
 
         let balances = TransactionBalancesSet {
@@ -1,5 +1,4 @@
 use {
-    enum_iterator::IntoEnumIterator,
     log::*,
     paychains_sdk::{
         account::{AccountSharedData, ReadableAccount},
@@ -9,11 +8,14 @@ use {
     },
 };
 
-#[derive(Debug, PartialEq, IntoEnumIterator)]
+#[derive(Debug, PartialEq)]
 pub(crate) enum RentState {
-    Uninitialized, // account.lamports == 0
-    RentPaying,    // 0 < account.lamports < rent-exempt-minimum
-    RentExempt,    // account.lamports >= rent-exempt-minimum
+    /// account.lamports == 0
+    Uninitialized,
+    /// 0 < account.lamports < rent-exempt-minimum
+    RentPaying { lamports: u64, data_size: usize },
+    /// account.lamports >= rent-exempt-minimum
+    RentExempt,
 }
 
 impl RentState {
@@ -21,27 +23,53 @@ impl RentState {
         if account.lamports() == 0 {
             Self::Uninitialized
         } else if !rent.is_exempt(account.lamports(), account.data().len()) {
-            Self::RentPaying
+            Self::RentPaying {
+                lamports: account.lamports(),
+                data_size: account.data().len(),
+            }
         } else {
             Self::RentExempt
         }
     }
 
-    pub(crate) fn transition_allowed_from(&self, pre_rent_state: &RentState) -> bool {
-        // Only a legacy RentPaying account may end in the RentPaying state after message processing
-        !(self == &Self::RentPaying && pre_rent_state != &Self::RentPaying)
+    pub(crate) fn transition_allowed_from(
+        &self,
+        pre_rent_state: &RentState,
+        prevent_crediting_accounts_that_end_rent_paying: bool,
+    ) -> bool {
+        match self {
+            Self::Uninitialized | Self::RentExempt => true,
+            Self::RentPaying {
+                lamports: post_lamports,
+                data_size: post_data_size,
+            } => match pre_rent_state {
+                // Only a legacy RentPaying account may end in the RentPaying state after message
+                // processing
+                Self::Uninitialized | Self::RentExempt => false,
+                Self::RentPaying {
+                    lamports: pre_lamports,
+                    data_size: pre_data_size,
+                } => {
+                    // Once crediting is prevented, a legacy RentPaying account may only remain
+                    // RentPaying if it was neither resized nor credited, so that it is eventually
+                    // drained by rent collection
+                    !prevent_crediting_accounts_that_end_rent_paying
+                        || (post_data_size == pre_data_size && post_lamports <= pre_lamports)
+                }
+            },
+        }
     }
 }
 
 pub(crate) fn submit_rent_state_metrics(pre_rent_state: &RentState, post_rent_state: &RentState) {
     match (pre_rent_state, post_rent_state) {
-        (&RentState::Uninitialized, &RentState::RentPaying) => {
+        (&RentState::Uninitialized, &RentState::RentPaying { .. }) => {
             inc_new_counter_info!("rent_paying_err-new_account", 1);
         }
-        (&RentState::RentPaying, &RentState::RentPaying) => {
+        (&RentState::RentPaying { .. }, &RentState::RentPaying { .. }) => {
             inc_new_counter_info!("rent_paying_ok-legacy", 1);
         }
-        (_, &RentState::RentPaying) => {
+        (_, &RentState::RentPaying { .. }) => {
             inc_new_counter_info!("rent_paying_err-other", 1);
         }
         _ => {}
@@ -53,16 +81,27 @@ pub(crate) fn check_rent_state(
     post_rent_state: Option<&RentState>,
     transaction_context: &TransactionContext,
     index: usize,
+    include_account_index_in_err: bool,
+    prevent_crediting_accounts_that_end_rent_paying: bool,
 ) -> Result<()> {
     if let Some((pre_rent_state, post_rent_state)) = pre_rent_state.zip(post_rent_state) {
         submit_rent_state_metrics(pre_rent_state, post_rent_state);
-        if !post_rent_state.transition_allowed_from(pre_rent_state) {
+        if !post_rent_state.transition_allowed_from(
+            pre_rent_state,
+            prevent_crediting_accounts_that_end_rent_paying,
+        ) {
             debug!(
                 "Account {:?} not rent exempt, state {:?}",
                 transaction_context.get_key_of_account_at_index(index),
                 transaction_context.get_account_at_index(index).borrow(),
             );
-            return Err(TransactionError::InvalidRentPayingAccount);
+            return if include_account_index_in_err {
+                Err(TransactionError::InsufficientFundsForRent {
+                    account_index: index as u8,
+                })
+            } else {
+                Err(TransactionError::InvalidRentPayingAccount)
+            };
         }
     }
     Ok(())
@@ -110,7 +149,10 @@ mod tests {
         );
         assert_eq!(
             RentState::from_account(&rent_paying_account, &rent),
-            RentState::RentPaying
+            RentState::RentPaying {
+                lamports: rent_paying_account.lamports(),
+                data_size: rent_paying_account.data().len(),
+            }
         );
         assert_eq!(
             RentState::from_account(&rent_exempt_account, &rent),
@@ -118,18 +160,96 @@ mod tests {
         );
     }
 
+    fn check_transition(prevent_crediting_accounts_that_end_rent_paying: bool) {
+        let post_rent_state = RentState::Uninitialized;
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::Uninitialized,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentExempt,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentPaying {
+                lamports: 1,
+                data_size: 0,
+            },
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+
+        let post_rent_state = RentState::RentExempt;
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::Uninitialized,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentExempt,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentPaying {
+                lamports: 1,
+                data_size: 0,
+            },
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+
+        let post_rent_state = RentState::RentPaying {
+            lamports: 2,
+            data_size: 2,
+        };
+        assert!(!post_rent_state.transition_allowed_from(
+            &RentState::Uninitialized,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        assert!(!post_rent_state.transition_allowed_from(
+            &RentState::RentExempt,
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        // Same data size and lamports
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentPaying {
+                lamports: 2,
+                data_size: 2,
+            },
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        // Debited
+        assert!(post_rent_state.transition_allowed_from(
+            &RentState::RentPaying {
+                lamports: 3,
+                data_size: 2,
+            },
+            prevent_crediting_accounts_that_end_rent_paying
+        ));
+        // Credited
+        assert_eq!(
+            post_rent_state.transition_allowed_from(
+                &RentState::RentPaying {
+                    lamports: 1,
+                    data_size: 2,
+                },
+                prevent_crediting_accounts_that_end_rent_paying
+            ),
+            !prevent_crediting_accounts_that_end_rent_paying
+        );
+        // Resized
+        assert_eq!(
+            post_rent_state.transition_allowed_from(
+                &RentState::RentPaying {
+                    lamports: 2,
+                    data_size: 1,
+                },
+                prevent_crediting_accounts_that_end_rent_paying
+            ),
+            !prevent_crediting_accounts_that_end_rent_paying
+        );
+    }
+
     #[test]
     fn test_transition_allowed_from() {
-        for post_rent_state in RentState::into_enum_iter() {
-            for pre_rent_state in RentState::into_enum_iter() {
-                if post_rent_state == RentState::RentPaying
-                    && pre_rent_state != RentState::RentPaying
-                {
-                    assert!(!post_rent_state.transition_allowed_from(&pre_rent_state));
-                } else {
-                    assert!(post_rent_state.transition_allowed_from(&pre_rent_state));
-                }
-            }
-        }
+        check_transition(false);
+        check_transition(true);
     }
 }
@@ -1082,7 +1082,8 @@ impl Accounts {
                 | Err(TransactionError::TooManyAccountLocks)
                 | Err(TransactionError::WouldExceedMaxBlockCostLimit)
                 | Err(TransactionError::WouldExceedMaxAccountCostLimit)
-                | Err(TransactionError::WouldExceedMaxAccountDataCostLimit) => None,
+                | Err(TransactionError::WouldExceedMaxAccountDataCostLimit)
+                | Err(TransactionError::MaxAccountsDataAllocationsExceeded) => None,
                 _ => Some(tx.get_account_locks_unchecked()),
             })
             .collect();
@@ -1352,7 +1353,10 @@ mod tests {
             status,
             log_messages: None,
             inner_instructions: None,
+            inner_instruction_stack_heights: None,
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
+            return_data: None,
+            executed_units: 0,
         })
     }
 
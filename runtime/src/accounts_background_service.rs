@@ -77,9 +77,18 @@ impl SendDroppedBankCallback {
     }
 }
 
+/// The kind of snapshot an operator asked for, overriding the configured snapshot intervals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestedSnapshotKind {
+    Full,
+    Incremental,
+}
+
 pub struct SnapshotRequest {
     pub snapshot_root_bank: Arc<Bank>,
     pub status_cache_slot_deltas: Vec<BankSlotDelta>,
+    /// If set, take this kind of snapshot regardless of the configured intervals
+    pub requested_kind: Option<RequestedSnapshotKind>,
 }
 
 pub struct SnapshotRequestHandler {
@@ -106,6 +115,7 @@ impl SnapshotRequestHandler {
                 let SnapshotRequest {
                     snapshot_root_bank,
                     status_cache_slot_deltas,
+                    requested_kind,
                 } = snapshot_request;
 
                 let previous_hash = if test_hash_calculation {
@@ -175,7 +185,13 @@ impl SnapshotRequestHandler {
                 }
 
                 let block_height = snapshot_root_bank.block_height();
-                let snapshot_type = if snapshot_utils::should_take_full_snapshot(
+                let snapshot_type = if let Some(requested_kind) = requested_kind {
+                    Self::requested_snapshot_type(
+                        requested_kind,
+                        snapshot_root_bank.slot(),
+                        last_full_snapshot_slot,
+                    )
+                } else if snapshot_utils::should_take_full_snapshot(
                     block_height,
                     self.snapshot_config.full_snapshot_archive_interval_slots,
                 ) {
@@ -256,6 +272,35 @@ impl SnapshotRequestHandler {
             })
     }
 
+    /// Map an operator-requested snapshot kind onto the snapshot type to take at `slot`.
+    ///
+    /// An incremental snapshot needs a full snapshot to be based on, so the request is dropped
+    /// if there is none yet (or if the full snapshot is at `slot` itself).
+    fn requested_snapshot_type(
+        requested_kind: RequestedSnapshotKind,
+        slot: Slot,
+        last_full_snapshot_slot: &mut Option<Slot>,
+    ) -> Option<SnapshotType> {
+        match requested_kind {
+            RequestedSnapshotKind::Full => {
+                *last_full_snapshot_slot = Some(slot);
+                Some(SnapshotType::FullSnapshot)
+            }
+            RequestedSnapshotKind::Incremental => match *last_full_snapshot_slot {
+                Some(base_slot) if base_slot < slot => {
+                    Some(SnapshotType::IncrementalSnapshot(base_slot))
+                }
+                _ => {
+                    warn!(
+                        "Ignoring incremental snapshot request for slot {}: no full snapshot to base it on",
+                        slot
+                    );
+                    None
+                }
+            },
+        }
+    }
+
     /// Check if a SnapshotError should be treated as 'fatal' by SnapshotRequestHandler, and
     /// `handle_snapshot_requests()` in particular.  Fatal errors will cause the node to shutdown.
     /// Non-fatal errors are logged and then swallowed.
@@ -473,6 +518,7 @@ impl AccountsBackgroundService {
                                 bank.force_flush_accounts_cache();
                             }
                             bank.clean_accounts(true, false, last_full_snapshot_slot);
+                            bank.pack_ancient_slots(last_full_snapshot_slot);
                             last_cleaned_block_height = bank.block_height();
                         }
                     }
@@ -554,4 +600,43 @@ mod test {
 
         assert!(bank0.rc.accounts.scan_slot(0, |_| Some(())).is_empty());
     }
+
+    #[test]
+    fn test_requested_snapshot_type() {
+        let mut last_full_snapshot_slot = None;
+        assert_eq!(
+            SnapshotRequestHandler::requested_snapshot_type(
+                RequestedSnapshotKind::Incremental,
+                10,
+                &mut last_full_snapshot_slot,
+            ),
+            None
+        );
+        assert_eq!(
+            SnapshotRequestHandler::requested_snapshot_type(
+                RequestedSnapshotKind::Full,
+                10,
+                &mut last_full_snapshot_slot,
+            ),
+            Some(SnapshotType::FullSnapshot)
+        );
+        assert_eq!(last_full_snapshot_slot, Some(10));
+        assert_eq!(
+            SnapshotRequestHandler::requested_snapshot_type(
+                RequestedSnapshotKind::Incremental,
+                10,
+                &mut last_full_snapshot_slot,
+            ),
+            None
+        );
+        assert_eq!(
+            SnapshotRequestHandler::requested_snapshot_type(
+                RequestedSnapshotKind::Incremental,
+                15,
+                &mut last_full_snapshot_slot,
+            ),
+            Some(SnapshotType::IncrementalSnapshot(10))
+        );
+        assert_eq!(last_full_snapshot_slot, Some(10));
+    }
 }
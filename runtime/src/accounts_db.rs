@@ -34,9 +34,15 @@ use {
         },
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         ancestors::Ancestors,
-        append_vec::{AppendVec, StoredAccountMeta, StoredMeta, StoredMetaWriteVersion},
+        append_vec::{
+            aligned_stored_size, AppendVec, StoredAccountMeta, StoredMeta, StoredMetaWriteVersion,
+        },
         cache_hash_data::CacheHashData,
         contains::Contains,
+        incremental_accounts_hash::{self, IncrementalAccountsHash},
+        persisted_accounts_index::{
+            PersistedAccountsIndex, PersistedAccountsIndexWriter, PersistedSlot,
+        },
         pubkey_bins::PubkeyBinCalculator24,
         read_only_accounts_cache::ReadOnlyAccountsCache,
         rent_collector::RentCollector,
@@ -56,7 +62,7 @@ use {
     paychains_rayon_threadlimit::get_thread_count,
     paychains_sdk::{
         account::{AccountSharedData, ReadableAccount},
-        clock::{BankId, Epoch, Slot, SlotCount},
+        clock::{BankId, Epoch, Slot, SlotCount, DEFAULT_SLOTS_PER_EPOCH},
         epoch_schedule::EpochSchedule,
         genesis_config::{ClusterType, GenesisConfig},
         hash::Hash,
@@ -123,12 +129,23 @@ const CACHE_VIRTUAL_WRITE_VERSION: StoredMetaWriteVersion = 0;
 pub(crate) const CACHE_VIRTUAL_OFFSET: Offset = 0;
 const CACHE_VIRTUAL_STORED_SIZE: StoredSize = 0;
 
+// Storages of slots this far behind the max root are rarely read, so they are worth compressing
+// or packing into ancient storages
+const COLD_STORAGE_SLOT_AGE: Slot = DEFAULT_SLOTS_PER_EPOCH;
+// Minimum size of the storages the accounts of cold slots are packed into
+const ANCIENT_APPEND_VEC_SIZE: u64 = 128 * 1024 * 1024;
+// Maximum number of slots packed by each call to `pack_ancient_slots()`
+const MAX_ANCIENT_SLOTS_PER_PACK: usize = 10_000;
+
 pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     index: Some(ACCOUNTS_INDEX_CONFIG_FOR_TESTING),
     accounts_hash_cache_path: None,
     filler_account_count: None,
     hash_calc_num_passes: None,
     write_cache_limit_bytes: None,
+    incremental_accounts_hash: false,
+    compress_cold_storages: false,
+    pack_ancient_storages: false,
 };
 pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig {
     index: Some(ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS),
@@ -136,6 +153,9 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     filler_account_count: None,
     hash_calc_num_passes: None,
     write_cache_limit_bytes: None,
+    incremental_accounts_hash: false,
+    compress_cold_storages: false,
+    pack_ancient_storages: false,
 };
 
 pub type BinnedHashData = Vec<Vec<CalculateHashIntermediate>>;
@@ -153,6 +173,13 @@ pub struct AccountsDbConfig {
     pub filler_account_count: Option<usize>,
     pub hash_calc_num_passes: Option<usize>,
     pub write_cache_limit_bytes: Option<u64>,
+    /// Maintain the accounts hash from the accounts written in newly rooted slots instead of
+    /// rescanning all storages each time it is calculated
+    pub incremental_accounts_hash: bool,
+    /// Store account data compressed when shrinking slots older than `COLD_STORAGE_SLOT_AGE`
+    pub compress_cold_storages: bool,
+    /// Pack the accounts of cold slots into a few large storages, see `pack_ancient_slots()`
+    pub pack_ancient_storages: bool,
 }
 
 struct FoundStoredAccount<'a> {
@@ -214,6 +241,8 @@ pub struct ErrorCounters {
     pub not_allowed_during_cluster_maintenance: usize,
     pub invalid_writable_account: usize,
     pub invalid_rent_paying_account: usize,
+    pub max_accounts_data_allocations_exceeded: usize,
+    pub program_execution_temporarily_restricted: usize,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -640,6 +669,11 @@ pub struct AccountStorageEntry {
     approx_store_count: AtomicUsize,
 
     alive_bytes: AtomicUsize,
+
+    /// For an ancient storage, the slots its accounts were written in and hashed with, as the
+    /// offset of the first account written in each slot and that slot. Empty for every other
+    /// storage. See `AccountsDb::pack_ancient_slots()`.
+    written_slots: RwLock<Vec<(usize, Slot)>>,
 }
 
 impl AccountStorageEntry {
@@ -655,9 +689,18 @@ impl AccountStorageEntry {
             count_and_status: RwLock::new((0, AccountStorageStatus::Available)),
             approx_store_count: AtomicUsize::new(0),
             alive_bytes: AtomicUsize::new(0),
+            written_slots: RwLock::default(),
         }
     }
 
+    /// A storage entry whose account data is compressed, see `AppendVec::new_compressed()`
+    pub fn new_compressed(path: &Path, slot: Slot, id: AppendVecId, file_size: u64) -> Self {
+        let tail = AppendVec::file_name(slot, id);
+        let path = Path::new(path).join(tail);
+        let accounts = AppendVec::new_compressed(&path, file_size as usize);
+        Self::new_existing(slot, id, accounts, 0)
+    }
+
     pub(crate) fn new_existing(
         slot: Slot,
         id: AppendVecId,
@@ -671,6 +714,7 @@ impl AccountStorageEntry {
             count_and_status: RwLock::new((0, AccountStorageStatus::Available)),
             approx_store_count: AtomicUsize::new(num_accounts),
             alive_bytes: AtomicUsize::new(0),
+            written_slots: RwLock::default(),
         }
     }
 
@@ -729,6 +773,45 @@ impl AccountStorageEntry {
         self.accounts.capacity()
     }
 
+    /// True if the accounts of older slots were packed into this storage, see
+    /// `AccountsDb::pack_ancient_slots()`
+    pub fn is_ancient(&self) -> bool {
+        !self.written_slots.read().unwrap().is_empty()
+    }
+
+    /// Record that the accounts appended from now on were written in `slot`, which makes this an
+    /// ancient storage
+    fn add_written_slot(&self, slot: Slot) {
+        let offset = self.accounts.len();
+        let mut written_slots = self.written_slots.write().unwrap();
+        if Self::find_written_slot(&written_slots, offset) != Some(slot) {
+            written_slots.push((offset, slot));
+        }
+    }
+
+    /// The slot the account at `offset` was written in, which its hash is calculated with
+    pub fn written_slot(&self, offset: usize) -> Slot {
+        Self::find_written_slot(&self.written_slots.read().unwrap(), offset)
+            .unwrap_or_else(|| self.slot())
+    }
+
+    fn find_written_slot(written_slots: &[(usize, Slot)], offset: usize) -> Option<Slot> {
+        // appending starts over at offset 0 once the storage was reset, so later entries win
+        written_slots
+            .iter()
+            .rev()
+            .find(|(start, _slot)| *start <= offset)
+            .map(|(_start, slot)| *slot)
+    }
+
+    pub(crate) fn written_slots(&self) -> Vec<(usize, Slot)> {
+        self.written_slots.read().unwrap().clone()
+    }
+
+    pub(crate) fn set_written_slots(&self, written_slots: Vec<(usize, Slot)>) {
+        *self.written_slots.write().unwrap() = written_slots;
+    }
+
     pub fn has_accounts(&self) -> bool {
         self.count() > 0
     }
@@ -882,6 +965,10 @@ pub const EXPIRATION_TTL_SECONDS: u64 = 1800;
 
 impl RecycleStores {
     fn add_entry(&mut self, new_entry: Arc<AccountStorageEntry>) {
+        // ancient storages are far larger than any other slot needs, so they are dropped instead
+        if new_entry.is_ancient() {
+            return;
+        }
         self.total_bytes += new_entry.total_bytes();
         self.entries.push((Instant::now(), new_entry))
     }
@@ -891,6 +978,10 @@ impl RecycleStores {
     }
 
     fn add_entries(&mut self, new_entries: Vec<Arc<AccountStorageEntry>>) {
+        let new_entries: Vec<_> = new_entries
+            .into_iter()
+            .filter(|new_entry| !new_entry.is_ancient())
+            .collect();
         self.total_bytes += new_entries.iter().map(|e| e.total_bytes()).sum::<u64>();
         let now = Instant::now();
         for new_entry in new_entries {
@@ -1064,6 +1155,19 @@ pub struct AccountsDb {
     // lower passes = faster total time, higher dynamic memory usage
     // passes=2 cuts dynamic memory usage in approximately half.
     pub num_hash_scan_passes: Option<usize>,
+
+    /// Hashes and lamports of all rooted accounts, if the accounts hash is calculated
+    /// incrementally. See `calculate_incremental_accounts_hash()`.
+    incremental_accounts_hash: Option<Mutex<IncrementalAccountsHash>>,
+
+    /// Shrink rewrites cold slots into compressed storages, see `AppendVec::new_compressed()`
+    compress_cold_storages: bool,
+
+    /// The accounts of cold slots are packed into ancient storages, see `pack_ancient_slots()`
+    pack_ancient_storages: bool,
+
+    /// Directory the index generated at startup is saved in, see `generate_index()`
+    persisted_index_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Default)]
@@ -1337,6 +1441,7 @@ struct ShrinkStats {
     dead_accounts: AtomicU64,
     alive_accounts: AtomicU64,
     accounts_loaded: AtomicU64,
+    compressed_stores: AtomicU64,
 }
 
 impl ShrinkStats {
@@ -1440,6 +1545,11 @@ impl ShrinkStats {
                     self.accounts_loaded.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "compressed_stores",
+                    self.compressed_stores.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
             );
         }
     }
@@ -1485,7 +1595,7 @@ impl<'a> ReadableAccount for StoredAccountMeta<'a> {
         self.account_meta.lamports
     }
     fn data(&self) -> &[u8] {
-        self.data
+        &self.data
     }
     fn owner(&self) -> &Pubkey {
         &self.account_meta.owner
@@ -1599,6 +1709,10 @@ impl AccountsDb {
             filler_account_count: 0,
             filler_account_suffix: None,
             num_hash_scan_passes,
+            incremental_accounts_hash: None,
+            compress_cold_storages: false,
+            pack_ancient_storages: false,
+            persisted_index_path: None,
         }
     }
 
@@ -1635,6 +1749,10 @@ impl AccountsDb {
         accounts_db_config: Option<AccountsDbConfig>,
         accounts_update_notifier: Option<AccountsUpdateNotifier>,
     ) -> Self {
+        let persisted_index_path = accounts_db_config
+            .as_ref()
+            .and_then(|x| x.index.as_ref())
+            .and_then(|x| x.persisted_index_path.clone());
         let accounts_index =
             AccountsIndex::new(accounts_db_config.as_ref().and_then(|x| x.index.clone()));
         let accounts_hash_cache_path = accounts_db_config
@@ -1662,6 +1780,25 @@ impl AccountsDb {
             write_cache_limit_bytes: accounts_db_config
                 .as_ref()
                 .and_then(|x| x.write_cache_limit_bytes),
+            incremental_accounts_hash: accounts_db_config
+                .as_ref()
+                .map(|x| x.incremental_accounts_hash)
+                .unwrap_or_default()
+                .then(|| {
+                    Mutex::new(IncrementalAccountsHash::new(
+                        PUBKEY_BINS_FOR_CALCULATING_HASHES,
+                        incremental_accounts_hash::DEFAULT_MAX_ACCOUNTS,
+                    ))
+                }),
+            compress_cold_storages: accounts_db_config
+                .as_ref()
+                .map(|x| x.compress_cold_storages)
+                .unwrap_or_default(),
+            pack_ancient_storages: accounts_db_config
+                .as_ref()
+                .map(|x| x.pack_ancient_storages)
+                .unwrap_or_default(),
+            persisted_index_path,
             ..Self::default_with_accounts_index(
                 accounts_index,
                 accounts_hash_cache_path,
@@ -2284,6 +2421,14 @@ impl AccountsDb {
             })
             .collect();
 
+        if let Some(incremental_accounts_hash) = &self.incremental_accounts_hash {
+            // these accounts will not be found in any storage anymore
+            incremental_accounts_hash
+                .lock()
+                .unwrap()
+                .purge_zero_lamport_accounts(pubkey_to_slot_set.iter().map(|(pubkey, _)| pubkey));
+        }
+
         let reclaims = self.purge_keys_exact(pubkey_to_slot_set.iter());
 
         // Don't reset from clean, since the pubkeys in those stores may need to be unref'ed
@@ -2751,7 +2896,8 @@ impl AccountsDb {
             start.stop();
             find_alive_elapsed = start.as_us();
 
-            let (shrunken_store, time) = self.get_store_for_shrink(slot, aligned_total);
+            let (shrunken_store, time) =
+                self.get_store_for_shrink(slot, aligned_total, accounts.len());
             create_and_insert_store_elapsed = time;
 
             // here, we're writing back alive_accounts. That should be an atomic operation
@@ -2861,14 +3007,39 @@ impl AccountsDb {
         total_accounts_after_shrink
     }
 
-    /// return a store that can contain 'aligned_total' bytes and the time it took to execute
+    /// Cold slots are rarely read, so their storages can be compressed when shrinking
+    fn is_cold_slot(&self, slot: Slot) -> bool {
+        slot.saturating_add(COLD_STORAGE_SLOT_AGE) <= self.accounts_index.max_root()
+    }
+
+    /// return a store that can contain 'aligned_total' bytes of 'num_accounts' accounts and the
+    /// time it took to execute
     fn get_store_for_shrink(
         &self,
         slot: Slot,
         aligned_total: u64,
+        num_accounts: usize,
     ) -> (Arc<AccountStorageEntry>, u64) {
         let mut start = Measure::start("create_and_insert_store_elapsed");
-        let shrunken_store = if let Some(new_store) =
+        let shrunken_store = if self.compress_cold_storages && self.is_cold_slot(slot) {
+            // compressed data is never larger than the original, but every account also stores
+            // the length of its data
+            let size = aligned_total + num_accounts as u64 * std::mem::size_of::<u64>() as u64;
+            let maybe_shrink_paths = self.shrink_paths.read().unwrap();
+            let paths = maybe_shrink_paths.as_ref().unwrap_or(&self.paths);
+            let path_index = thread_rng().gen_range(0, paths.len());
+            let store = Arc::new(AccountStorageEntry::new_compressed(
+                Path::new(&paths[path_index]),
+                slot,
+                self.next_id(),
+                Self::page_align(size),
+            ));
+            self.insert_store(slot, store.clone());
+            self.shrink_stats
+                .compressed_stores
+                .fetch_add(1, Ordering::Relaxed);
+            store
+        } else if let Some(new_store) =
             self.try_recycle_and_insert_store(slot, aligned_total, aligned_total + 1024)
         {
             new_store
@@ -2897,7 +3068,9 @@ impl AccountsDb {
         if let Some(stores_lock) = self.storage.get_slot_stores(slot) {
             let stores: Vec<Arc<AccountStorageEntry>> =
                 stores_lock.read().unwrap().values().cloned().collect();
-            if !Self::is_shrinking_productive(slot, &stores) {
+            if stores.iter().any(|store| store.is_ancient())
+                || !Self::is_shrinking_productive(slot, &stores)
+            {
                 return 0;
             }
             self.do_shrink_slot_stores(slot, stores.iter())
@@ -3085,6 +3258,248 @@ impl AccountsDb {
         }
     }
 
+    /// Pack the alive accounts of cold rooted slots into a few large ancient storages and remove
+    /// the slots they came from.
+    ///
+    /// Slots are packed in increasing order into the newest ancient storage, which belongs to an
+    /// older slot. Every slot in between has already been packed, so the latest version of each
+    /// account is still found in the highest slot, as hashing and clean expect. Slots newer than
+    /// `last_full_snapshot_slot` are not packed, because incremental snapshots based on it must
+    /// contain their accounts.
+    pub fn pack_ancient_slots(&self, last_full_snapshot_slot: Option<Slot>) {
+        if !self.pack_ancient_storages {
+            return;
+        }
+        let mut total_time = Measure::start("pack_ancient_slots");
+        let max_slot = last_full_snapshot_slot.unwrap_or(Slot::MAX);
+        let mut slots: Vec<_> = self
+            .all_slots_in_storage()
+            .into_iter()
+            .filter(|slot| {
+                *slot <= max_slot && self.is_cold_slot(*slot) && self.accounts_index.is_root(*slot)
+            })
+            .collect();
+        slots.sort_unstable();
+
+        let mut ancient_store = None;
+        let mut packed_slots = 0;
+        let mut packed_accounts = 0;
+        let mut created_ancient_stores = 0;
+        for slot in slots {
+            if packed_slots >= MAX_ANCIENT_SLOTS_PER_PACK {
+                break;
+            }
+            let stores = match self.storage.get_slot_storage_entries(slot) {
+                Some(stores) => stores,
+                None => continue,
+            };
+            // a full ancient storage is followed by a newer one of the same slot
+            if let Some(store) = stores
+                .iter()
+                .filter(|store| store.is_ancient())
+                .max_by_key(|store| store.append_vec_id())
+            {
+                ancient_store = Some(store.clone());
+                continue;
+            }
+            let previous_ancient_slot = ancient_store.as_ref().map(|store| store.slot());
+            match self.pack_ancient_slot(slot, &stores, &mut ancient_store) {
+                Some(num_accounts) => {
+                    packed_slots += 1;
+                    packed_accounts += num_accounts;
+                    if ancient_store.as_ref().map(|store| store.slot()) != previous_ancient_slot {
+                        created_ancient_stores += 1;
+                    }
+                }
+                // later slots can only be packed once this one is
+                None => break,
+            }
+        }
+        total_time.stop();
+
+        if packed_slots > 0 {
+            datapoint_info!(
+                "pack_ancient_slots",
+                ("packed_slots", packed_slots as i64, i64),
+                ("packed_accounts", packed_accounts as i64, i64),
+                ("created_ancient_stores", created_ancient_stores as i64, i64),
+                ("total_us", total_time.as_us() as i64, i64),
+            );
+        }
+    }
+
+    /// Move the alive accounts of `slot` into `ancient_store`, or into a new ancient storage of
+    /// `slot` if they do not fit. Return the number of accounts moved, or None if the storages of
+    /// `slot` hold accounts the index does not know about yet, which clean has to handle first.
+    fn pack_ancient_slot(
+        &self,
+        slot: Slot,
+        stores: &[Arc<AccountStorageEntry>],
+        ancient_store: &mut Option<Arc<AccountStorageEntry>>,
+    ) -> Option<usize> {
+        let (stored_accounts, _num_stores, _original_bytes) =
+            self.get_unique_accounts_from_storages(stores.iter());
+        let mut stored_accounts = stored_accounts.into_iter().collect::<Vec<_>>();
+        stored_accounts.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let alive_accounts: Vec<_> = stored_accounts
+            .iter()
+            .filter(|(pubkey, stored_account)| {
+                self.accounts_index
+                    .get_account_read_entry(pubkey)
+                    .map(|locked_entry| {
+                        locked_entry
+                            .slot_list()
+                            .iter()
+                            .any(|(_slot, account_info)| {
+                                account_info.matches_storage_location(
+                                    stored_account.store_id,
+                                    stored_account.account.offset,
+                                )
+                            })
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        // Removing `slot` must not leave any of its accounts behind in storage: an outdated
+        // version in a slot newer than the ancient storage would win over the packed version.
+        let alive_count: usize = stores.iter().map(|store| store.count()).sum();
+        if alive_accounts.is_empty() || alive_accounts.len() != alive_count {
+            return None;
+        }
+
+        let mut accounts = Vec::with_capacity(alive_accounts.len());
+        let mut hashes = Vec::with_capacity(alive_accounts.len());
+        let mut write_versions = Vec::with_capacity(alive_accounts.len());
+        let mut size = 0;
+        for (pubkey, alive_account) in &alive_accounts {
+            accounts.push((pubkey, &alive_account.account));
+            hashes.push(alive_account.account.hash);
+            write_versions.push(alive_account.account.meta.write_version);
+            size += aligned_stored_size(alive_account.account.data.len()) as u64;
+        }
+
+        let store = ancient_store
+            .as_ref()
+            .filter(|store| {
+                store.status() == AccountStorageStatus::Available
+                    && store.accounts.remaining_bytes() >= size
+            })
+            .cloned();
+        if let Some(store) = store {
+            // Storing adds a ref for the accounts not in the ancient slot yet, and removing `slot`
+            // below unrefs every account it held, so the ref counts stay correct.
+            let ancient_slot = store.slot();
+            store.add_written_slot(slot);
+            let current_store = Mutex::new(store);
+            self.store_accounts_frozen(
+                ancient_slot,
+                &accounts,
+                Some(&hashes),
+                Some(Box::new(|ancient_slot, size| {
+                    self.find_ancient_store(&current_store, ancient_slot, size, slot)
+                })),
+                Some(Box::new(write_versions.into_iter())),
+            );
+            self.remove_empty_stores(ancient_slot);
+            *ancient_store = Some(current_store.into_inner().unwrap());
+
+            let mut reclaims = Vec::with_capacity(accounts.len());
+            for (pubkey, _) in &accounts {
+                self.read_only_accounts_cache.remove(**pubkey, ancient_slot);
+                self.accounts_index
+                    .purge_exact(pubkey, &slot, &mut reclaims);
+            }
+            // every account of `slot` is now dead, so the slot is removed
+            let reset_accounts = false;
+            let mut reclaim_result = ReclaimResult::default();
+            self.handle_reclaims(
+                &reclaims,
+                Some(slot),
+                Some(&self.clean_accounts_stats.purge_stats),
+                Some(&mut reclaim_result),
+                reset_accounts,
+            );
+        } else {
+            let current_store = Mutex::new(self.create_ancient_store(slot, size, slot));
+            self.store_accounts_frozen(
+                slot,
+                &accounts,
+                Some(&hashes),
+                Some(Box::new(|_, size| {
+                    self.find_ancient_store(&current_store, slot, size, slot)
+                })),
+                Some(Box::new(write_versions.into_iter())),
+            );
+
+            // like shrink, drop the storages which were emptied by rewriting their accounts
+            self.shrink_candidate_slots.lock().unwrap().remove(&slot);
+            self.remove_empty_stores(slot);
+            *ancient_store = Some(current_store.into_inner().unwrap());
+        }
+        Some(accounts.len())
+    }
+
+    /// Create and insert an ancient storage of `slot` with room for at least `size` bytes of
+    /// accounts written in `written_slot`
+    fn create_ancient_store(
+        &self,
+        slot: Slot,
+        size: u64,
+        written_slot: Slot,
+    ) -> Arc<AccountStorageEntry> {
+        let store = self.create_store(
+            slot,
+            std::cmp::max(ANCIENT_APPEND_VEC_SIZE, size),
+            "ancient",
+            &self.paths,
+        );
+        // marked before anyone else can see it
+        store.add_written_slot(written_slot);
+        self.insert_store(slot, store.clone());
+        store
+    }
+
+    /// The storage finder used to pack accounts written in `written_slot` into the ancient
+    /// storage in `current_store`. Once that is full, a new ancient storage of the same slot
+    /// replaces it.
+    fn find_ancient_store(
+        &self,
+        current_store: &Mutex<Arc<AccountStorageEntry>>,
+        slot: Slot,
+        size: usize,
+        written_slot: Slot,
+    ) -> Arc<AccountStorageEntry> {
+        let mut current_store = current_store.lock().unwrap();
+        if current_store.status() == AccountStorageStatus::Available {
+            // starts a new entry if the storage was reset after its accounts died
+            current_store.add_written_slot(written_slot);
+        } else {
+            *current_store = self.create_ancient_store(slot, size as u64, written_slot);
+        }
+        current_store.clone()
+    }
+
+    /// Drop the storages of `slot` without any alive accounts, like shrink does once it rewrote
+    /// their accounts. An account which did not fit into an ancient storage also makes
+    /// `write_accounts_to_storage()` add a storage to the slot which is never used.
+    fn remove_empty_stores(&self, slot: Slot) {
+        let mut dead_storages = vec![];
+        if let Some(slot_stores) = self.storage.get_slot_stores(slot) {
+            slot_stores.write().unwrap().retain(|_key, store| {
+                if store.count() == 0 && !store.is_ancient() {
+                    self.dirty_stores
+                        .insert((slot, store.append_vec_id()), store.clone());
+                    dead_storages.push(store.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        drop(dead_storages);
+    }
+
     pub fn scan_accounts<F, A>(
         &self,
         ancestors: &Ancestors,
@@ -3753,7 +4168,8 @@ impl AccountsDb {
         let mut avail = 0;
         let mut recycle_stores = self.recycle_stores.write().unwrap();
         for (i, (_recycled_time, store)) in recycle_stores.iter().enumerate() {
-            if Arc::strong_count(store) == 1 {
+            // compressed stores are only meant for cold slots
+            if Arc::strong_count(store) == 1 && !store.accounts.is_compressed() {
                 max = std::cmp::max(store.accounts.capacity(), max);
                 min = std::cmp::min(store.accounts.capacity(), min);
                 avail += 1;
@@ -4357,7 +4773,7 @@ impl AccountsDb {
             &account.account_meta.owner,
             account.account_meta.executable,
             account.account_meta.rent_epoch,
-            account.data,
+            &account.data,
             &account.meta.pubkey,
         )
     }
@@ -5141,8 +5557,12 @@ impl AccountsDb {
                                             let loaded_hash = loaded_account.loaded_hash();
                                             let balance = loaded_account.lamports();
                                             if check_hash && !self.is_filler_account(pubkey) {
-                                                let computed_hash =
-                                                    loaded_account.compute_hash(*slot, pubkey);
+                                                let written_slot = self.written_slot(
+                                                    *slot,
+                                                    &account_info.storage_location(),
+                                                );
+                                                let computed_hash = loaded_account
+                                                    .compute_hash(written_slot, pubkey);
                                                 if computed_hash != loaded_hash {
                                                     info!("hash mismatch found: computed: {}, loaded: {}, pubkey: {}", computed_hash, loaded_hash, pubkey);
                                                     mismatch_found
@@ -5217,6 +5637,21 @@ impl AccountsDb {
         )
     }
 
+    /// The slot the account at `storage_location` of `slot` was written in, which its hash is
+    /// calculated with. Only differs from `slot` for accounts packed into ancient storages.
+    fn written_slot(&self, slot: Slot, storage_location: &StorageLocation) -> Slot {
+        match storage_location {
+            StorageLocation::AppendVec(store_id, offset) => self
+                .storage
+                .get_account_storage_entry(slot, *store_id)
+                .map(|store| store.written_slot(*offset))
+                .unwrap_or(slot),
+            StorageLocation::Cached => slot,
+        }
+    }
+
+    /// Call `scan_func` with each account of `storages` and the slot it was written in, which is
+    /// `slot` unless it was packed into an ancient storage
     fn scan_multiple_account_storages_one_slot<F, B>(
         storages: &[Arc<AccountStorageEntry>],
         scan_func: &F,
@@ -5230,6 +5665,7 @@ impl AccountsDb {
         let mut len = storages.len();
         let mut progress = Vec::with_capacity(len);
         let mut current = Vec::with_capacity(len);
+        let mut written_slots = Vec::with_capacity(len);
         for storage in storages {
             let accounts = storage.accounts.accounts(0);
             let mut iterator: std::vec::IntoIter<StoredAccountMeta<'_>> = accounts.into_iter();
@@ -5239,6 +5675,7 @@ impl AccountsDb {
             {
                 current.push(item);
                 progress.push(iterator);
+                written_slots.push(storage.written_slots());
             }
         }
         while !progress.is_empty() {
@@ -5252,7 +5689,11 @@ impl AccountsDb {
             }
             let mut account = (0, None);
             std::mem::swap(&mut account, &mut current[min_index]);
-            scan_func(LoadedAccount::Stored(account.1.unwrap()), retval, slot);
+            let account = account.1.unwrap();
+            let written_slot =
+                AccountStorageEntry::find_written_slot(&written_slots[min_index], account.offset)
+                    .unwrap_or(slot);
+            scan_func(LoadedAccount::Stored(account), retval, written_slot);
             let next = progress[min_index]
                 .next()
                 .map(|stored_account| (stored_account.meta.write_version, Some(stored_account)));
@@ -5263,6 +5704,7 @@ impl AccountsDb {
                 None => {
                     current.remove(min_index);
                     progress.remove(min_index);
+                    written_slots.remove(min_index);
                     len -= 1;
                 }
             }
@@ -5337,6 +5779,9 @@ impl AccountsDb {
                             let storage_file = sub_storages.first().unwrap().accounts.get_path();
                             slot.hash(&mut hasher);
                             storage_file.hash(&mut hasher);
+                            // ancient storages keep growing after their hash data was cached
+                            let storage_len = sub_storages.first().unwrap().accounts.len();
+                            storage_len.hash(&mut hasher);
                             // check alive_bytes, etc. here?
                             let amod = std::fs::metadata(storage_file);
                             if amod.is_err() {
@@ -5462,56 +5907,226 @@ impl AccountsDb {
         slots_per_epoch: Option<Slot>,
         is_startup: bool,
     ) -> Result<(Hash, u64), BankHashVerificationError> {
-        if !use_index {
-            let accounts_cache_and_ancestors = if can_cached_slot_be_unflushed {
-                Some((&self.accounts_cache, ancestors, &self.accounts_index))
+        if use_index {
+            self.calculate_accounts_hash(slot, ancestors, check_hash)
+        } else if let Some(incremental_accounts_hash) = &self.incremental_accounts_hash {
+            self.calculate_incremental_accounts_hash(
+                incremental_accounts_hash,
+                slot,
+                ancestors,
+                check_hash,
+                can_cached_slot_be_unflushed,
+                slots_per_epoch,
+                is_startup,
+            )
+        } else {
+            self.calculate_accounts_hash_from_storages(
+                slot,
+                ancestors,
+                check_hash,
+                can_cached_slot_be_unflushed,
+                slots_per_epoch,
+                is_startup,
+            )
+        }
+    }
+
+    fn calculate_accounts_hash_from_storages(
+        &self,
+        slot: Slot,
+        ancestors: &Ancestors,
+        check_hash: bool,
+        can_cached_slot_be_unflushed: bool,
+        slots_per_epoch: Option<Slot>,
+        is_startup: bool,
+    ) -> Result<(Hash, u64), BankHashVerificationError> {
+        let accounts_cache_and_ancestors = if can_cached_slot_be_unflushed {
+            Some((&self.accounts_cache, ancestors, &self.accounts_index))
+        } else {
+            None
+        };
+
+        let mut collect_time = Measure::start("collect");
+        let (combined_maps, slots) = self.get_snapshot_storages(slot, None, Some(ancestors));
+        collect_time.stop();
+
+        let mut sort_time = Measure::start("sort_storages");
+        let min_root = self.accounts_index.min_root();
+        let storages = SortedStorages::new_with_slots(
+            combined_maps.iter().zip(slots.iter()),
+            min_root,
+            Some(slot),
+        );
+
+        self.mark_old_slots_as_dirty(&storages, slots_per_epoch);
+        sort_time.stop();
+
+        let timings = HashStats {
+            collect_snapshots_us: collect_time.as_us(),
+            storage_sort_us: sort_time.as_us(),
+            ..HashStats::default()
+        };
+
+        let thread_pool = if is_startup {
+            None
+        } else {
+            Some(&self.thread_pool_clean)
+        };
+        Self::calculate_accounts_hash_without_index(
+            &self.accounts_hash_cache_path,
+            &storages,
+            thread_pool,
+            timings,
+            check_hash,
+            accounts_cache_and_ancestors,
+            if self.filler_account_count > 0 {
+                self.filler_account_suffix.as_ref()
             } else {
                 None
-            };
-
-            let mut collect_time = Measure::start("collect");
-            let (combined_maps, slots) = self.get_snapshot_storages(slot, None, Some(ancestors));
-            collect_time.stop();
+            },
+            self.num_hash_scan_passes,
+        )
+    }
 
-            let mut sort_time = Measure::start("sort_storages");
-            let min_root = self.accounts_index.min_root();
-            let storages = SortedStorages::new_with_slots(
-                combined_maps.iter().zip(slots.iter()),
-                min_root,
-                Some(slot),
+    /// Apply the accounts written in the roots since the last call to the incremental accounts
+    /// hash, then calculate the hash from it. The first call scans all storages. Falls back to
+    /// `calculate_accounts_hash_from_storages()` if `slot` is not a root, is older than the
+    /// roots which were already applied, or the incremental accounts hash grew too large.
+    #[allow(clippy::too_many_arguments)]
+    fn calculate_incremental_accounts_hash(
+        &self,
+        incremental_accounts_hash: &Mutex<IncrementalAccountsHash>,
+        slot: Slot,
+        ancestors: &Ancestors,
+        check_hash: bool,
+        can_cached_slot_be_unflushed: bool,
+        slots_per_epoch: Option<Slot>,
+        is_startup: bool,
+    ) -> Result<(Hash, u64), BankHashVerificationError> {
+        let mut incremental_accounts_hash = incremental_accounts_hash.lock().unwrap();
+        let last_slot = incremental_accounts_hash.slot();
+        if !self.accounts_index.is_root(slot)
+            || last_slot.map_or(false, |last_slot| slot < last_slot)
+            || incremental_accounts_hash.exceeded_max_accounts()
+        {
+            drop(incremental_accounts_hash);
+            return self.calculate_accounts_hash_from_storages(
+                slot,
+                ancestors,
+                check_hash,
+                can_cached_slot_be_unflushed,
+                slots_per_epoch,
+                is_startup,
             );
+        }
+
+        let accounts_cache_and_ancestors = if can_cached_slot_be_unflushed {
+            Some((&self.accounts_cache, ancestors, &self.accounts_index))
+        } else {
+            None
+        };
 
-            self.mark_old_slots_as_dirty(&storages, slots_per_epoch);
-            sort_time.stop();
+        let mut collect_time = Measure::start("collect");
+        let (combined_maps, slots) = self.get_snapshot_storages(slot, None, Some(ancestors));
+        collect_time.stop();
 
-            let timings = HashStats {
-                collect_snapshots_us: collect_time.as_us(),
-                storage_sort_us: sort_time.as_us(),
-                ..HashStats::default()
-            };
+        let mut sort_time = Measure::start("sort_storages");
+        let min_root = self.accounts_index.min_root();
+        let storages = SortedStorages::new_with_slots(
+            combined_maps.iter().zip(slots.iter()),
+            min_root,
+            Some(slot),
+        );
+        self.mark_old_slots_as_dirty(&storages, slots_per_epoch);
 
-            let thread_pool = if is_startup {
-                None
+        // only the roots which have not been applied yet have to be scanned
+        let new_storages = SortedStorages::new_with_slots(
+            combined_maps
+                .iter()
+                .zip(slots.iter())
+                .filter(|(_, storage_slot)| {
+                    last_slot.map_or(true, |last_slot| **storage_slot > last_slot)
+                }),
+            last_slot.map(|last_slot| last_slot + 1).or(min_root),
+            Some(slot),
+        );
+        sort_time.stop();
+
+        let mut stats = HashStats {
+            collect_snapshots_us: collect_time.as_us(),
+            storage_sort_us: sort_time.as_us(),
+            ..HashStats::default()
+        };
+        let filler_account_suffix = if self.filler_account_count > 0 {
+            self.filler_account_suffix.as_ref()
+        } else {
+            None
+        };
+        let incremental_accounts_hash: &mut IncrementalAccountsHash =
+            &mut incremental_accounts_hash;
+        let mut update_and_hash = || -> Result<_, BankHashVerificationError> {
+            let scanned = if last_slot == Some(slot) {
+                vec![]
             } else {
-                Some(&self.thread_pool_clean)
+                Self::scan_snapshot_stores_with_cache(
+                    &CacheHashData::new(&self.accounts_hash_cache_path),
+                    &new_storages,
+                    &mut stats,
+                    PUBKEY_BINS_FOR_CALCULATING_HASHES,
+                    &Range {
+                        start: 0,
+                        end: PUBKEY_BINS_FOR_CALCULATING_HASHES,
+                    },
+                    check_hash,
+                    accounts_cache_and_ancestors,
+                    filler_account_suffix,
+                )?
             };
-            Self::calculate_accounts_hash_without_index(
-                &self.accounts_hash_cache_path,
-                &storages,
-                thread_pool,
-                timings,
-                check_hash,
-                accounts_cache_and_ancestors,
-                if self.filler_account_count > 0 {
-                    self.filler_account_suffix.as_ref()
-                } else {
-                    None
-                },
-                self.num_hash_scan_passes,
-            )
+            let mut update_time = Measure::start("update");
+            incremental_accounts_hash.update(slot, scanned, filler_account_suffix);
+            update_time.stop();
+            let mut hash_time = Measure::start("hash");
+            let result = incremental_accounts_hash.calculate();
+            hash_time.stop();
+            Ok((result, update_time.as_us(), hash_time.as_us()))
+        };
+        let (result, update_us, hash_us) = if is_startup {
+            update_and_hash()
         } else {
-            self.calculate_accounts_hash(slot, ancestors, check_hash)
+            self.thread_pool_clean.install(update_and_hash)
+        }?;
+        if incremental_accounts_hash.exceeded_max_accounts() {
+            return self.calculate_accounts_hash_from_storages(
+                slot,
+                ancestors,
+                check_hash,
+                can_cached_slot_be_unflushed,
+                slots_per_epoch,
+                is_startup,
+            );
         }
+
+        datapoint_info!(
+            "calculate_incremental_accounts_hash",
+            ("slot", slot as i64, i64),
+            ("num_slots", stats.num_slots as i64, i64),
+            (
+                "num_snapshot_storage",
+                stats.num_snapshot_storage as i64,
+                i64
+            ),
+            ("collect_snapshots_us", stats.collect_snapshots_us, i64),
+            ("storage_sort_us", stats.storage_sort_us, i64),
+            ("accounts_scan", stats.scan_time_total_us, i64),
+            ("update_us", update_us, i64),
+            ("hash_us", hash_us, i64),
+            (
+                "accounts",
+                incremental_accounts_hash.account_count() as i64,
+                i64
+            ),
+        );
+        Ok(result)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -5538,15 +6153,28 @@ impl AccountsDb {
         )?;
         if debug_verify {
             // calculate the other way (store or non-store) and verify results match.
-            let (hash_other, total_lamports_other) = self.calculate_accounts_hash_helper(
-                !use_index,
-                slot,
-                ancestors,
-                check_hash,
-                can_cached_slot_be_unflushed,
-                None,
-                is_startup,
-            )?;
+            // The incremental accounts hash is verified against a full scan of the storages.
+            let (hash_other, total_lamports_other) =
+                if !use_index && self.incremental_accounts_hash.is_some() {
+                    self.calculate_accounts_hash_from_storages(
+                        slot,
+                        ancestors,
+                        check_hash,
+                        can_cached_slot_be_unflushed,
+                        None,
+                        is_startup,
+                    )
+                } else {
+                    self.calculate_accounts_hash_helper(
+                        !use_index,
+                        slot,
+                        ancestors,
+                        check_hash,
+                        can_cached_slot_be_unflushed,
+                        None,
+                        is_startup,
+                    )
+                }?;
 
             let success = hash == hash_other
                 && total_lamports == total_lamports_other
@@ -5635,6 +6263,7 @@ impl AccountsDb {
                 let source_item =
                     CalculateHashIntermediate::new(loaded_account.loaded_hash(), balance, *pubkey);
 
+                // `slot` is the slot the account was written in
                 if check_hash && !Self::is_filler_account_helper(pubkey, filler_account_suffix) {
                     let computed_hash = loaded_account.compute_hash(slot, pubkey);
                     if computed_hash != source_item.hash {
@@ -5987,6 +6616,11 @@ impl AccountsDb {
     }
 
     fn is_candidate_for_shrink(&self, store: &Arc<AccountStorageEntry>) -> bool {
+        // the accounts in ancient storages were written in other slots, shrinking would have to
+        // pack them again
+        if store.is_ancient() {
+            return false;
+        }
         match self.shrink_ratio {
             AccountShrinkThreshold::TotalSpace { shrink_ratio: _ } => {
                 Self::page_align(store.alive_bytes() as u64) < store.total_bytes()
@@ -6698,7 +7332,7 @@ impl AccountsDb {
                     self.accounts_index.update_secondary_indexes(
                         &pubkey,
                         &stored_account.account_meta.owner,
-                        stored_account.data,
+                        &stored_account.data,
                         &self.account_indexes,
                     );
                 }
@@ -6866,6 +7500,142 @@ impl AccountsDb {
         info!("added {} filler accounts", added.load(Ordering::Relaxed));
     }
 
+    /// Identifies the storages the index is generated from, so that a persisted index is only
+    /// loaded for the storages it was generated from. None if the index cannot be persisted,
+    /// which is the case if a slot has more than one storage: the ids a snapshot's storages
+    /// are remapped to differ between restarts, so only a slot identifies its storage.
+    fn persisted_index_fingerprint(&self, slots: &[Slot]) -> Option<Hash> {
+        let mut hasher = paychains_sdk::hash::Hasher::default();
+        for slot in slots {
+            let stores = self.storage.get_slot_storage_entries(*slot)?;
+            if stores.len() != 1 {
+                return None;
+            }
+            hasher.hash(&slot.to_le_bytes());
+            hasher.hash(&(stores[0].accounts.len() as u64).to_le_bytes());
+        }
+        let bank_hashes = self.bank_hashes.read().unwrap();
+        let mut bank_hashes = bank_hashes.iter().collect::<Vec<_>>();
+        bank_hashes.sort_unstable_by_key(|(slot, _)| **slot);
+        for (slot, bank_hash_info) in bank_hashes {
+            hasher.hash(&slot.to_le_bytes());
+            hasher.hash(bank_hash_info.hash.as_ref());
+        }
+        Some(hasher.result())
+    }
+
+    /// Check that `persisted` holds the index entries of exactly the storages of `slots`
+    fn verify_persisted_index(
+        &self,
+        persisted: &PersistedAccountsIndex,
+        slots: &[Slot],
+    ) -> Result<(), String> {
+        if persisted.slot_count() != slots.len() as u64 {
+            return Err(format!(
+                "{} slots persisted, {} expected",
+                persisted.slot_count(),
+                slots.len()
+            ));
+        }
+        let mut remaining_slots = slots.iter().copied().collect::<HashSet<_>>();
+        for persisted_slot in persisted.slots().map_err(|err| err.to_string())? {
+            let persisted_slot = persisted_slot.map_err(|err| err.to_string())?;
+            let slot = persisted_slot.slot;
+            if !remaining_slots.remove(&slot) {
+                return Err(format!("unexpected slot {}", slot));
+            }
+            // the fingerprint matched, so the slot has exactly one storage
+            let len = self.storage.get_slot_storage_entries(slot).unwrap()[0]
+                .accounts
+                .len();
+            let valid = persisted_slot.count == persisted_slot.accounts.len()
+                && persisted_slot.accounts.iter().all(
+                    |(_pubkey, offset, stored_size, _lamports)| {
+                        offset.saturating_add(*stored_size as usize) <= len
+                    },
+                );
+            if !valid {
+                return Err(format!("invalid entries for slot {}", slot));
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate the index from an index persisted by an earlier `generate_index()`. None if the
+    /// persisted index turns out not to hold the entries of the storages of `slots`.
+    fn generate_index_from_persisted(
+        &self,
+        persisted: &PersistedAccountsIndex,
+        slots: &[Slot],
+    ) -> Option<IndexGenerationInfo> {
+        let mut total_time = Measure::start("generate_index_from_persisted");
+        // entries cannot be removed from the index again, so all of them are checked first
+        let persisted_slots = self
+            .verify_persisted_index(persisted, slots)
+            .and_then(|_| persisted.slots().map_err(|err| err.to_string()));
+        let persisted_slots = match persisted_slots {
+            Ok(persisted_slots) => persisted_slots,
+            Err(err) => {
+                warn!(
+                    "Ignoring accounts index persisted in {:?}: {}",
+                    persisted.path(),
+                    err
+                );
+                return None;
+            }
+        };
+        self.accounts_index.set_startup(true);
+        persisted_slots.par_bridge().for_each(|persisted_slot| {
+            // every slot was read successfully above
+            let persisted_slot = persisted_slot.expect("read persisted accounts index");
+            let slot = persisted_slot.slot;
+            // the fingerprint matched, so the slot has exactly one storage
+            let store = self.storage.get_slot_storage_entries(slot).unwrap()[0].clone();
+            let store_id = store.append_vec_id();
+            let accounts = &persisted_slot.accounts;
+            let items = accounts
+                .iter()
+                .map(|(pubkey, offset, stored_size, lamports)| {
+                    (
+                        *pubkey,
+                        AccountInfo::new(
+                            StorageLocation::AppendVec(store_id, *offset),
+                            *stored_size,
+                            *lamports,
+                        ),
+                    )
+                });
+            let (dirty_pubkeys, _insert_time_us) = self
+                .accounts_index
+                .insert_new_if_missing_into_primary_index(slot, accounts.len(), items);
+            if !dirty_pubkeys.is_empty() {
+                self.uncleaned_pubkeys.insert(slot, dirty_pubkeys);
+            }
+            store.count_and_status.write().unwrap().0 = persisted_slot.count;
+            store
+                .alive_bytes
+                .store(persisted_slot.stored_size, Ordering::SeqCst);
+        });
+        self.accounts_index.set_startup(false);
+        // Need to add these last, otherwise older updates will be cleaned
+        for slot in slots {
+            self.accounts_index.add_root(*slot, false);
+        }
+        total_time.stop();
+
+        datapoint_info!(
+            "generate_index_from_persisted",
+            ("slots", slots.len() as i64, i64),
+            ("total_us", total_time.as_us() as i64, i64),
+        );
+        Some(IndexGenerationInfo {
+            accounts_data_len: persisted.accounts_data_len(),
+        })
+    }
+
+    /// Generate the index from the storages. If `persisted_index_path` is set, the generated
+    /// index is saved there, and loaded instead of generated again on the next startup from the
+    /// same storages.
     #[allow(clippy::needless_collect)]
     pub fn generate_index(
         &self,
@@ -6879,6 +7649,32 @@ impl AccountsDb {
         if let Some(limit) = limit_load_slot_count_from_snapshot {
             slots.truncate(limit); // get rid of the newer slots and keep just the older
         }
+
+        // the persisted index does not include the secondary indexes
+        let persisted_index = self
+            .persisted_index_path
+            .as_ref()
+            .filter(|_| {
+                limit_load_slot_count_from_snapshot.is_none()
+                    && !verify
+                    && self.account_indexes.is_empty()
+            })
+            .and_then(|path| Some((path, self.persisted_index_fingerprint(&slots)?)));
+        if let Some((path, fingerprint)) = persisted_index {
+            if let Some(persisted) = PersistedAccountsIndex::open(path, &fingerprint) {
+                info!("loading accounts index persisted in {:?}", path);
+                if let Some(info) = self.generate_index_from_persisted(&persisted, &slots) {
+                    return info;
+                }
+            }
+        }
+        // the entries of each slot are saved as soon as they are generated
+        let persisted_index_writer = persisted_index.and_then(|(path, fingerprint)| {
+            PersistedAccountsIndexWriter::new(path, fingerprint)
+                .map_err(|err| warn!("Unable to persist accounts index in {:?}: {}", path, err))
+                .ok()
+        });
+
         let max_slot = slots.last().cloned().unwrap_or_default();
         let schedule = genesis_config.epoch_schedule;
         let rent_collector = RentCollector::new(
@@ -6937,6 +7733,9 @@ impl AccountsDb {
                             &accounts_map,
                             &storage_info_timings,
                         );
+                        if let Some(writer) = &persisted_index_writer {
+                            writer.write_slot(&Self::persisted_slot(*slot, &accounts_map));
+                        }
 
                         let insert_us = if pass == 0 {
                             // generate index
@@ -7065,8 +7864,37 @@ impl AccountsDb {
             timings.report();
         }
 
-        IndexGenerationInfo {
-            accounts_data_len: accounts_data_len.load(Ordering::Relaxed),
+        let accounts_data_len = accounts_data_len.load(Ordering::Relaxed);
+        if let Some(writer) = persisted_index_writer {
+            if let Err(err) = writer.finish(accounts_data_len) {
+                warn!("Unable to persist accounts index: {}", err);
+            }
+        }
+
+        IndexGenerationInfo { accounts_data_len }
+    }
+
+    /// The index entries generated from the storage of `slot`, to be persisted
+    fn persisted_slot(slot: Slot, accounts_map: &GenerateIndexAccountsMap<'_>) -> PersistedSlot {
+        let accounts = accounts_map
+            .iter()
+            .map(|(pubkey, entry)| {
+                (
+                    *pubkey,
+                    entry.stored_account.offset,
+                    entry.stored_account.stored_size as StoredSize,
+                    entry.stored_account.account_meta.lamports,
+                )
+            })
+            .collect();
+        PersistedSlot {
+            slot,
+            count: accounts_map.len(),
+            stored_size: accounts_map
+                .values()
+                .map(|entry| entry.stored_account.stored_size)
+                .sum(),
+            accounts,
         }
     }
 
@@ -7880,6 +8708,85 @@ pub mod tests {
         assert_eq!(result, (expected_hash, sum));
     }
 
+    #[test]
+    fn test_incremental_accounts_hash() {
+        paychains_logger::setup();
+
+        let db = AccountsDb::new_with_config(
+            Vec::new(),
+            &ClusterType::Development,
+            AccountSecondaryIndexes::default(),
+            true,
+            AccountShrinkThreshold::default(),
+            Some(AccountsDbConfig {
+                incremental_accounts_hash: true,
+                ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+            }),
+            None,
+        );
+        let ancestors = Ancestors::default();
+        let owner = Pubkey::default();
+        let keys: Vec<_> = (0..10).map(|_| paychains_sdk::pubkey::new_rand()).collect();
+        let incremental_slot = || {
+            db.incremental_accounts_hash
+                .as_ref()
+                .unwrap()
+                .lock()
+                .unwrap()
+                .slot()
+        };
+        // debug_verify compares the incremental hash to a full scan of the storages
+        let update_and_verify = |slot| {
+            let result = db.update_accounts_hash_with_index_option(
+                false, true, slot, &ancestors, None, true, None, false,
+            );
+            assert_eq!(
+                result,
+                db.calculate_accounts_hash(slot, &ancestors, false).unwrap()
+            );
+            result
+        };
+
+        let mut slot = 1;
+        for (i, key) in keys.iter().enumerate() {
+            let account = AccountSharedData::new(i as u64 + 1, 0, &owner);
+            db.store_cached(slot, &[(key, &account)]);
+        }
+        db.add_root(slot);
+        db.flush_accounts_cache(true, None);
+        assert_eq!(incremental_slot(), None);
+        assert_eq!(update_and_verify(slot).1, 55);
+        assert_eq!(incremental_slot(), Some(slot));
+
+        // modify and remove accounts in a flushed root
+        slot += 1;
+        db.store_cached(slot, &[(&keys[0], &AccountSharedData::new(100, 0, &owner))]);
+        db.store_cached(slot, &[(&keys[1], &AccountSharedData::new(0, 0, &owner))]);
+        db.add_root(slot);
+        db.flush_accounts_cache(true, None);
+        db.clean_accounts(None, false, None);
+        assert_eq!(update_and_verify(slot).1, 55 + 99 - 2);
+
+        // add an account in a root which is still in the write cache
+        slot += 1;
+        let new_key = paychains_sdk::pubkey::new_rand();
+        db.store_cached(
+            slot,
+            &[(&new_key, &AccountSharedData::new(1_000, 0, &owner))],
+        );
+        db.add_root(slot);
+        assert_eq!(update_and_verify(slot).1, 55 + 99 - 2 + 1_000);
+        assert_eq!(incremental_slot(), Some(slot));
+
+        // nothing new to apply
+        let (hash, lamports) = update_and_verify(slot);
+        assert_eq!(lamports, 55 + 99 - 2 + 1_000);
+
+        // older slots are calculated with a full scan and leave the incremental hash alone
+        assert_ne!(update_and_verify(slot - 1).0, hash);
+        assert_eq!(incremental_slot(), Some(slot));
+    }
+
     fn sample_storage() -> (SnapshotStorages, usize, Slot) {
         let (_temp_dirs, paths) = get_temp_accounts_paths(1).unwrap();
         let slot_expected: Slot = 0;
@@ -9771,7 +10678,7 @@ pub mod tests {
         let stored_account = StoredAccountMeta {
             meta: &meta,
             account_meta: &account_meta,
-            data: &data,
+            data: Cow::Borrowed(&data),
             offset,
             stored_size,
             hash: &hash,
@@ -9811,7 +10718,7 @@ pub mod tests {
         let stored_account = StoredAccountMeta {
             meta: &meta,
             account_meta: &account_meta,
-            data: &data,
+            data: Cow::Borrowed(&data),
             offset,
             stored_size: CACHE_VIRTUAL_STORED_SIZE as usize,
             hash: &hash,
@@ -10670,6 +11577,231 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_shrink_cold_slot_compressed() {
+        paychains_logger::setup();
+
+        let mut accounts = AccountsDb::new_single_for_tests();
+        accounts.compress_cold_storages = true;
+
+        let pubkeys: Vec<_> = (0..20).map(|_| paychains_sdk::pubkey::new_rand()).collect();
+        let owner = Pubkey::default();
+        let mut account = AccountSharedData::new(223, 0, &owner);
+        account.set_data(vec![7u8; 4096]);
+
+        let shrink_slot = 1;
+        for pubkey in &pubkeys {
+            accounts.store_uncached(shrink_slot, &[(pubkey, &account)]);
+        }
+        accounts.get_accounts_delta_hash(shrink_slot);
+        accounts.add_root(shrink_slot);
+
+        // not cold yet: shrinking creates an uncompressed store
+        let mut current_slot = shrink_slot + 1;
+        accounts.store_uncached(current_slot, &[(&pubkeys[0], &account)]);
+        accounts.get_accounts_delta_hash(current_slot);
+        accounts.add_root(current_slot);
+        accounts.clean_accounts(None, false, None);
+        accounts.shrink_slot_forced(shrink_slot);
+        let is_compressed = |accounts: &AccountsDb| {
+            let stores = accounts
+                .storage
+                .get_slot_storage_entries(shrink_slot)
+                .unwrap();
+            assert_eq!(stores.len(), 1);
+            stores[0].accounts.is_compressed()
+        };
+        assert!(!is_compressed(&accounts));
+
+        current_slot = shrink_slot + COLD_STORAGE_SLOT_AGE;
+        for pubkey in &pubkeys[1..10] {
+            accounts.store_uncached(current_slot, &[(pubkey, &account)]);
+        }
+        accounts.get_accounts_delta_hash(current_slot);
+        accounts.add_root(current_slot);
+        accounts.clean_accounts(None, false, None);
+        accounts.shrink_slot_forced(shrink_slot);
+        assert!(is_compressed(&accounts));
+        assert_eq!(
+            pubkeys.len() - 10,
+            accounts.all_account_count_in_append_vec(shrink_slot)
+        );
+
+        let ancestors = Ancestors::default();
+        for pubkey in &pubkeys {
+            assert_eq!(
+                accounts
+                    .load_without_fixed_root(&ancestors, pubkey)
+                    .unwrap()
+                    .0,
+                account
+            );
+        }
+        let total_lamports = pubkeys.len() as u64 * 223;
+        accounts.update_accounts_hash(current_slot, &ancestors);
+        accounts
+            .verify_bank_hash_and_lamports(current_slot, &ancestors, total_lamports, true)
+            .unwrap();
+
+        let accounts = reconstruct_accounts_db_via_serialization(&accounts, current_slot);
+        assert!(is_compressed(&accounts));
+        accounts
+            .verify_bank_hash_and_lamports(current_slot, &ancestors, total_lamports, true)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_pack_ancient_slots() {
+        paychains_logger::setup();
+
+        let mut accounts = AccountsDb::new_single_for_tests();
+        accounts.pack_ancient_storages = true;
+
+        let owner = Pubkey::default();
+        let shared_pubkey = paychains_sdk::pubkey::new_rand();
+        let pubkeys: Vec<_> = (0..3).map(|_| paychains_sdk::pubkey::new_rand()).collect();
+        let packed_slots = [1, 2, 3];
+        for (pubkey, slot) in pubkeys.iter().zip(packed_slots) {
+            let account = AccountSharedData::new(slot * 10, 0, &owner);
+            let shared_account = AccountSharedData::new(slot * 100, 0, &owner);
+            accounts.store_uncached(
+                slot,
+                &[(pubkey, &account), (&shared_pubkey, &shared_account)],
+            );
+            accounts.get_accounts_delta_hash(slot);
+            accounts.add_root(slot);
+        }
+        let current_slot = 3 + COLD_STORAGE_SLOT_AGE;
+        let current_pubkey = paychains_sdk::pubkey::new_rand();
+        let account = AccountSharedData::new(1, 0, &owner);
+        accounts.store_uncached(current_slot, &[(&current_pubkey, &account)]);
+        accounts.get_accounts_delta_hash(current_slot);
+        accounts.add_root(current_slot);
+
+        let ancestors = Ancestors::default();
+        let check = |accounts: &AccountsDb| {
+            for (pubkey, slot) in pubkeys.iter().zip(packed_slots) {
+                assert_eq!(
+                    accounts
+                        .load_without_fixed_root(&ancestors, pubkey)
+                        .unwrap()
+                        .0
+                        .lamports(),
+                    slot * 10
+                );
+            }
+            assert_eq!(
+                accounts
+                    .load_without_fixed_root(&ancestors, &shared_pubkey)
+                    .unwrap()
+                    .0
+                    .lamports(),
+                300
+            );
+            let total_lamports = 10 + 20 + 30 + 300 + 1;
+            accounts.update_accounts_hash(current_slot, &ancestors);
+            accounts
+                .verify_bank_hash_and_lamports(current_slot, &ancestors, total_lamports, true)
+                .unwrap();
+        };
+
+        // slots newer than the last full snapshot are not packed
+        accounts.pack_ancient_slots(Some(2));
+        let ancient_stores = accounts.storage.get_slot_storage_entries(1).unwrap();
+        assert_eq!(ancient_stores.len(), 1);
+        assert!(ancient_stores[0].is_ancient());
+        assert!(accounts.storage.get_slot_stores(2).is_none());
+        assert!(!accounts.accounts_index.is_root(2));
+        assert!(accounts.storage.get_slot_stores(3).is_some());
+        check(&accounts);
+
+        accounts.pack_ancient_slots(None);
+        assert!(accounts.storage.get_slot_stores(3).is_none());
+        assert_eq!(accounts.all_slots_in_storage().len(), 2);
+        assert_eq!(accounts.ref_count_for_pubkey(&shared_pubkey), 1);
+        // the outdated versions of the shared account remain in the ancient storage
+        assert_eq!(accounts.all_account_count_in_append_vec(1), 6);
+        let ancient_stores = accounts.storage.get_slot_storage_entries(1).unwrap();
+        assert_eq!(ancient_stores[0].count(), 4);
+        let written_slots = ancient_stores[0].written_slots();
+        assert_eq!(
+            written_slots
+                .iter()
+                .map(|(_, slot)| *slot)
+                .collect::<Vec<_>>(),
+            packed_slots
+        );
+        check(&accounts);
+
+        // packing again finds nothing left to pack
+        accounts.pack_ancient_slots(None);
+        assert_eq!(accounts.all_slots_in_storage().len(), 2);
+
+        accounts.clean_accounts(None, false, None);
+        check(&accounts);
+
+        let accounts = reconstruct_accounts_db_via_serialization(&accounts, current_slot);
+        let ancient_stores = accounts.storage.get_slot_storage_entries(1).unwrap();
+        assert_eq!(ancient_stores[0].written_slots(), written_slots);
+        check(&accounts);
+    }
+
+    #[test]
+    fn test_find_ancient_store() {
+        let accounts = AccountsDb::new_single_for_tests();
+        let ancient_slot = 1;
+        let store = accounts.create_ancient_store(ancient_slot, 0, ancient_slot);
+        assert!(store.is_ancient());
+        assert_eq!(store.written_slot(0), ancient_slot);
+        let current_store = Mutex::new(store.clone());
+
+        // accounts written in another slot start a new entry at the end of the storage
+        let account = AccountSharedData::new(1, 0, &Pubkey::default());
+        store
+            .accounts
+            .append_account(
+                StoredMeta {
+                    write_version: 0,
+                    pubkey: Pubkey::new_unique(),
+                    data_len: 0,
+                },
+                &account,
+                Hash::default(),
+            )
+            .unwrap();
+        let found = accounts.find_ancient_store(&current_store, ancient_slot, 0, 2);
+        assert_eq!(found.append_vec_id(), store.append_vec_id());
+        assert_eq!(store.written_slot(0), ancient_slot);
+        assert_eq!(store.written_slot(store.accounts.len()), 2);
+
+        // a full storage is replaced by a new ancient storage of the same slot
+        store.add_account(0);
+        store.set_status(AccountStorageStatus::Full);
+        let found = accounts.find_ancient_store(&current_store, ancient_slot, 0, 3);
+        assert_ne!(found.append_vec_id(), store.append_vec_id());
+        assert_eq!(found.slot(), ancient_slot);
+        assert_eq!(found.written_slot(0), 3);
+        assert_eq!(
+            current_store.lock().unwrap().append_vec_id(),
+            found.append_vec_id()
+        );
+        assert_eq!(
+            accounts
+                .storage
+                .get_slot_storage_entries(ancient_slot)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // ancient storages are never recycled
+        let mut recycle_stores = RecycleStores::default();
+        recycle_stores.add_entry(found);
+        recycle_stores.add_entries(vec![store]);
+        assert_eq!(recycle_stores.entry_count(), 0);
+        assert_eq!(recycle_stores.total_bytes(), 0);
+    }
+
     #[test]
     fn test_shrink_candidate_slots() {
         paychains_logger::setup();
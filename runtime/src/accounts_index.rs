@@ -3,6 +3,7 @@ use {
         accounts_index_storage::AccountsIndexStorage,
         ancestors::Ancestors,
         bucket_map_holder::{Age, BucketMapHolder},
+        bucket_map_holder_stats::AccountsIndexOccupancy,
         contains::Contains,
         in_mem_accounts_index::{InMemAccountsIndex, InsertNewEntryResults},
         inline_spl_token::{self, SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
@@ -49,16 +50,20 @@ pub const ACCOUNTS_INDEX_CONFIG_FOR_TESTING: AccountsIndexConfig = AccountsIndex
     flush_threads: Some(FLUSH_THREADS_TESTING),
     drives: None,
     index_limit_mb: None,
+    index_limit_bytes: None,
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
+    persisted_index_path: None,
 };
 pub const ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS: AccountsIndexConfig = AccountsIndexConfig {
     bins: Some(BINS_FOR_BENCHMARKS),
     flush_threads: Some(FLUSH_THREADS_TESTING),
     drives: None,
     index_limit_mb: None,
+    index_limit_bytes: None,
     ages_to_stay_in_cache: None,
     scan_results_limit_bytes: None,
+    persisted_index_path: None,
 };
 pub type ScanResult<T> = Result<T, ScanError>;
 pub type SlotList<T> = Vec<(Slot, T)>;
@@ -159,8 +164,13 @@ pub struct AccountsIndexConfig {
     pub flush_threads: Option<usize>,
     pub drives: Option<Vec<PathBuf>>,
     pub index_limit_mb: Option<usize>,
+    /// like `index_limit_mb`, in bytes. Takes precedence over `index_limit_mb`.
+    pub index_limit_bytes: Option<usize>,
     pub ages_to_stay_in_cache: Option<Age>,
     pub scan_results_limit_bytes: Option<usize>,
+    /// directory to save the index generated at startup in, so that restarting from the same
+    /// snapshot does not have to generate it again
+    pub persisted_index_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -920,6 +930,12 @@ impl<T: IndexValue> AccountsIndex<T> {
         self.storage.storage.is_disk_index_enabled()
     }
 
+    /// how the index is spread between memory and disk, and its hit rates since startup
+    pub fn occupancy(&self) -> AccountsIndexOccupancy {
+        let storage = &self.storage.storage;
+        storage.stats.occupancy(storage)
+    }
+
     fn do_checked_scan_accounts<F, R>(
         &self,
         metric_name: &'static str,
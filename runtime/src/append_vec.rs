@@ -13,9 +13,10 @@ use {
         clock::{Epoch, Slot},
         hash::Hash,
         pubkey::Pubkey,
+        system_instruction::MAX_PERMITTED_DATA_LENGTH,
     },
     std::{
-        borrow::Borrow,
+        borrow::{Borrow, Cow},
         convert::TryFrom,
         fs::{remove_file, OpenOptions},
         io::{self, Seek, SeekFrom, Write},
@@ -39,8 +40,23 @@ macro_rules! u64_align {
 
 pub const MAXIMUM_APPEND_VEC_FILE_SIZE: u64 = 16 * 1024 * 1024 * 1024; // 16 GiB
 
+const ACCOUNT_DATA_COMPRESSION_LEVEL: i32 = 3;
+// Smaller account data is not worth compressing
+const MIN_COMPRESSED_ACCOUNT_DATA_LEN: usize = 128;
+
 pub type StoredMetaWriteVersion = u64;
 
+/// The number of bytes an account with `data_len` bytes of data takes in an uncompressed
+/// AppendVec, including the padding up to the next account
+pub fn aligned_stored_size(data_len: usize) -> usize {
+    u64_align!(
+        mem::size_of::<StoredMeta>()
+            + mem::size_of::<AccountMeta>()
+            + mem::size_of::<Hash>()
+            + data_len
+    )
+}
+
 /// Meta contains enough context to recover the index from storage itself
 /// This struct will be backed by mmaped and snapshotted data files.
 /// So the data layout must be stable and consistent across the entire cluster!
@@ -94,7 +110,8 @@ pub struct StoredAccountMeta<'a> {
     pub meta: &'a StoredMeta,
     /// account data
     pub account_meta: &'a AccountMeta,
-    pub data: &'a [u8],
+    /// borrowed from the storage unless it had to be decompressed
+    pub data: Cow<'a, [u8]>,
     pub offset: usize,
     pub stored_size: usize,
     pub hash: &'a Hash,
@@ -159,6 +176,9 @@ pub struct AppendVec {
 
     /// True if the file should automatically be deleted when this AppendVec is dropped.
     remove_on_drop: bool,
+
+    /// True if account data is stored compressed, see `new_compressed()`.
+    compressed: bool,
 }
 
 impl Drop for AppendVec {
@@ -227,9 +247,30 @@ impl AppendVec {
             current_len: AtomicUsize::new(initial_len),
             file_size: size as u64,
             remove_on_drop: true,
+            compressed: false,
         }
     }
 
+    /// Create an AppendVec which stores account data compressed with zstd. Meant for cold
+    /// storages, which are rarely read: loading an account decompresses its data.
+    ///
+    /// Layout: for each account `StoredMeta`, `AccountMeta`, `Hash`, the length of the stored
+    /// data and the stored data. The stored data is compressed unless its length is the account
+    /// data length.
+    ///
+    /// Nothing in the file records the format, so compressed AppendVecs never leave this node:
+    /// snapshots archive them uncompressed, see `write_uncompressed()`, and `new_from_file()`
+    /// always opens uncompressed AppendVecs.
+    pub fn new_compressed(file: &Path, size: usize) -> Self {
+        let mut append_vec = Self::new(file, true, size);
+        append_vec.compressed = true;
+        append_vec
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
     pub fn set_no_remove_on_drop(&mut self) {
         self.remove_on_drop = false;
     }
@@ -251,6 +292,7 @@ impl AppendVec {
             current_len: AtomicUsize::new(current_len),
             file_size: 0, // will be filled by set_file()
             remove_on_drop: true,
+            compressed: false,
         }
     }
 
@@ -301,6 +343,12 @@ impl AppendVec {
         self.file_size
     }
 
+    /// The number of bytes left for appending accounts
+    pub fn remaining_bytes(&self) -> u64 {
+        self.capacity()
+            .saturating_sub(u64_align!(self.len()) as u64)
+    }
+
     pub fn file_name(slot: Slot, id: impl std::fmt::Display) -> String {
         format!("{}.{}", slot, id)
     }
@@ -331,6 +379,7 @@ impl AppendVec {
             current_len: AtomicUsize::new(current_len),
             file_size,
             remove_on_drop: true,
+            compressed: false,
         };
 
         let (sanitized, num_accounts) = new.sanitize_layout_and_length();
@@ -441,7 +490,17 @@ impl AppendVec {
         let (meta, next): (&'a StoredMeta, _) = self.get_type(offset)?;
         let (account_meta, next): (&'a AccountMeta, _) = self.get_type(next)?;
         let (hash, next): (&'a Hash, _) = self.get_type(next)?;
-        let (data, next) = self.get_slice(next, meta.data_len as usize)?;
+        let (data, next) = if self.compressed {
+            let (stored_data_len, next): (&'a u64, _) = self.get_type(next)?;
+            let (stored_data, next) = self.get_slice(next, *stored_data_len as usize)?;
+            (
+                Self::decompress_account_data(stored_data, meta.data_len)?,
+                next,
+            )
+        } else {
+            let (data, next) = self.get_slice(next, meta.data_len as usize)?;
+            (Cow::Borrowed(data), next)
+        };
         let stored_size = next - offset;
         Some((
             StoredAccountMeta {
@@ -484,10 +543,24 @@ impl AppendVec {
         accounts: &[(StoredMeta, Option<&impl ReadableAccount>)],
         hashes: &[impl Borrow<Hash>],
     ) -> Vec<usize> {
+        // compress before taking the lock
+        let stored_data: Vec<_> = if self.compressed {
+            accounts
+                .iter()
+                .map(|(_, account)| {
+                    Self::compress_account_data(
+                        account.map(|account| account.data()).unwrap_or_default(),
+                    )
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
         let _lock = self.append_lock.lock().unwrap();
         let mut offset = self.len();
         let mut rv = Vec::with_capacity(accounts.len());
-        for ((stored_meta, account), hash) in accounts.iter().zip(hashes) {
+        for (i, ((stored_meta, account), hash)) in accounts.iter().zip(hashes).enumerate() {
             let meta_ptr = stored_meta as *const StoredMeta;
             let account_meta = AccountMeta::from(*account);
             let account_meta_ptr = &account_meta as *const AccountMeta;
@@ -497,13 +570,28 @@ impl AppendVec {
                 .unwrap_or_default()
                 .as_ptr();
             let hash_ptr = hash.borrow().as_ref().as_ptr();
-            let ptrs = [
-                (meta_ptr as *const u8, mem::size_of::<StoredMeta>()),
-                (account_meta_ptr as *const u8, mem::size_of::<AccountMeta>()),
-                (hash_ptr as *const u8, mem::size_of::<Hash>()),
-                (data_ptr, data_len),
-            ];
-            if let Some(res) = self.append_ptrs_locked(&mut offset, &ptrs) {
+            let meta = (meta_ptr as *const u8, mem::size_of::<StoredMeta>());
+            let account_meta = (account_meta_ptr as *const u8, mem::size_of::<AccountMeta>());
+            let hash = (hash_ptr as *const u8, mem::size_of::<Hash>());
+            let res = if self.compressed {
+                let stored_data = &stored_data[i];
+                let stored_data_len = stored_data.len() as u64;
+                let ptrs = [
+                    meta,
+                    account_meta,
+                    hash,
+                    (
+                        &stored_data_len as *const u64 as *const u8,
+                        mem::size_of::<u64>(),
+                    ),
+                    (stored_data.as_ptr(), stored_data.len()),
+                ];
+                self.append_ptrs_locked(&mut offset, &ptrs)
+            } else {
+                let ptrs = [meta, account_meta, hash, (data_ptr, data_len)];
+                self.append_ptrs_locked(&mut offset, &ptrs)
+            };
+            if let Some(res) = res {
                 rv.push(res)
             } else {
                 break;
@@ -533,6 +621,87 @@ impl AppendVec {
             res.first().cloned()
         }
     }
+
+    /// The length of this AppendVec with its account data stored uncompressed, which is the
+    /// length of the copy made by `write_uncompressed()`
+    pub fn uncompressed_len(&self) -> usize {
+        if !self.compressed {
+            return self.len();
+        }
+        let mut offset = 0;
+        let mut uncompressed_len = 0;
+        while let Some((data_len, next)) = self.get_compressed_account_data_len(offset) {
+            // same layout as `append_accounts()` writes into an uncompressed AppendVec
+            for len in [
+                mem::size_of::<StoredMeta>(),
+                mem::size_of::<AccountMeta>(),
+                mem::size_of::<Hash>(),
+                data_len as usize,
+            ] {
+                uncompressed_len = u64_align!(uncompressed_len) + len;
+            }
+            offset = next;
+        }
+        uncompressed_len
+    }
+
+    /// The data length of the account at `offset` of a compressed AppendVec and the offset of
+    /// the next account, without decompressing the data
+    fn get_compressed_account_data_len(&self, offset: usize) -> Option<(u64, usize)> {
+        let (meta, next): (&StoredMeta, _) = self.get_type(offset)?;
+        let (_, next): (&AccountMeta, _) = self.get_type(next)?;
+        let (_, next): (&Hash, _) = self.get_type(next)?;
+        let (stored_data_len, next): (&u64, _) = self.get_type(next)?;
+        let (_, next) = self.get_slice(next, *stored_data_len as usize)?;
+        Some((meta.data_len, next))
+    }
+
+    /// Copy all accounts into a new uncompressed AppendVec at `path`, which is kept when this
+    /// returns. Its length is `uncompressed_len()`.
+    pub fn write_uncompressed(&self, path: &Path) -> io::Result<()> {
+        let mut uncompressed =
+            AppendVec::new(path, true, std::cmp::max(self.uncompressed_len(), 1));
+        uncompressed.set_no_remove_on_drop();
+        let mut offset = 0;
+        while let Some((account, next)) = self.get_account(offset) {
+            let offsets = uncompressed
+                .append_accounts(&[(account.meta.clone(), Some(&account))], &[account.hash]);
+            if offsets.len() != 2 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("accounts of {:?} do not fit into {:?}", self.path, path),
+                ));
+            }
+            offset = next;
+        }
+        uncompressed.flush()
+    }
+
+    /// The data to store for `data` in a compressed AppendVec: compressed if that makes it
+    /// smaller, as is otherwise
+    fn compress_account_data(data: &[u8]) -> Cow<[u8]> {
+        if data.len() >= MIN_COMPRESSED_ACCOUNT_DATA_LEN {
+            if let Ok(compressed) = zstd::bulk::compress(data, ACCOUNT_DATA_COMPRESSION_LEVEL) {
+                if compressed.len() < data.len() {
+                    return Cow::Owned(compressed);
+                }
+            }
+        }
+        Cow::Borrowed(data)
+    }
+
+    /// The account data for `stored_data` read from a compressed AppendVec, None if it is not
+    /// valid for an account with `data_len` bytes of data
+    fn decompress_account_data(stored_data: &[u8], data_len: u64) -> Option<Cow<[u8]>> {
+        if stored_data.len() as u64 == data_len {
+            return Some(Cow::Borrowed(stored_data));
+        }
+        if stored_data.len() as u64 > data_len || data_len > MAX_PERMITTED_DATA_LENGTH {
+            return None;
+        }
+        let data = zstd::bulk::decompress(stored_data, data_len as usize).ok()?;
+        (data.len() as u64 == data_len).then(|| Cow::Owned(data))
+    }
 }
 
 pub mod test_utils {
@@ -921,4 +1090,127 @@ pub mod tests {
         let result = AppendVec::new_from_file(path, accounts_len);
         assert_matches!(result, Err(ref message) if message.to_string() == *"incorrect layout/length/data");
     }
+
+    fn create_test_accounts_for_compression() -> Vec<(StoredMeta, AccountSharedData)> {
+        let mut rng = thread_rng();
+        [
+            vec![7u8; 4096],
+            (0..4096).map(|_| rng.gen()).collect(),
+            vec![7u8; MIN_COMPRESSED_ACCOUNT_DATA_LEN - 1],
+            vec![],
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, data)| {
+            let mut account = AccountSharedData::new(i as u64 + 1, 0, &Pubkey::default());
+            let stored_meta = StoredMeta {
+                write_version: i as u64,
+                pubkey: paychains_sdk::pubkey::new_rand(),
+                data_len: data.len() as u64,
+            };
+            account.set_data(data);
+            (stored_meta, account)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_append_vec_compressed() {
+        let path = get_append_vec_path("test_append_vec_compressed");
+        let av = AppendVec::new_compressed(&path.path, 1024 * 1024);
+        assert!(av.is_compressed());
+        assert!(av.is_empty());
+        assert!(av.accounts(0).is_empty());
+
+        let accounts = create_test_accounts_for_compression();
+        let indexes: Vec<_> = accounts
+            .iter()
+            .map(|account| av.append_account_test(account).unwrap())
+            .collect();
+        assert!(!av.is_empty());
+        for (account, index) in accounts.iter().zip(indexes.iter()) {
+            assert_eq!(&av.get_account_test(*index).unwrap(), account);
+        }
+
+        // only data which gets smaller is stored compressed
+        let stored = av.accounts(0);
+        assert_eq!(stored.len(), accounts.len());
+        assert_matches!(stored[0].data, Cow::Owned(_));
+        assert_matches!(stored[1].data, Cow::Borrowed(_));
+        assert_matches!(stored[2].data, Cow::Borrowed(_));
+        assert_matches!(stored[3].data, Cow::Borrowed(_));
+        assert!(stored[0].stored_size < stored[1].stored_size);
+
+        let path = get_append_vec_path("test_append_vec_compressed_uncompressed");
+        let uncompressed = AppendVec::new(&path.path, true, 1024 * 1024);
+        for account in accounts.iter() {
+            uncompressed.append_account_test(account).unwrap();
+        }
+        assert!(av.len() < uncompressed.len());
+
+        av.reset();
+        assert!(av.is_empty());
+        assert!(av.accounts(0).is_empty());
+    }
+
+    #[test]
+    fn test_write_uncompressed() {
+        let file = get_append_vec_path("test_write_uncompressed");
+        let av = AppendVec::new_compressed(&file.path, 1024 * 1024);
+        let accounts = create_test_accounts_for_compression();
+        let hashes: Vec<_> = (0..accounts.len()).map(|_| Hash::new_unique()).collect();
+        for ((meta, account), hash) in accounts.iter().zip(hashes.iter()) {
+            av.append_account(meta.clone(), account, *hash).unwrap();
+        }
+
+        let uncompressed_file = get_append_vec_path("test_write_uncompressed_copy");
+        let path = &uncompressed_file.path;
+        let uncompressed = AppendVec::new(path, true, 1024 * 1024);
+        for ((meta, account), hash) in accounts.iter().zip(hashes.iter()) {
+            uncompressed
+                .append_account(meta.clone(), account, *hash)
+                .unwrap();
+        }
+        assert_eq!(av.uncompressed_len(), uncompressed.len());
+        assert_eq!(uncompressed.uncompressed_len(), uncompressed.len());
+        drop(uncompressed);
+
+        // the copy is opened as an uncompressed AppendVec, like snapshots do
+        av.write_uncompressed(path).unwrap();
+        let (copy, num_accounts) = AppendVec::new_from_file(path, av.uncompressed_len()).unwrap();
+        assert!(!copy.is_compressed());
+        assert_eq!(num_accounts, accounts.len());
+        let stored = copy.accounts(0);
+        for ((stored, (meta, account)), hash) in
+            stored.iter().zip(accounts.iter()).zip(hashes.iter())
+        {
+            assert_matches!(stored.data, Cow::Borrowed(_));
+            assert_eq!(stored.meta, meta);
+            assert_eq!(&stored.clone_account(), account);
+            assert_eq!(stored.hash, hash);
+        }
+    }
+
+    #[test]
+    fn test_decompress_account_data() {
+        let data = vec![7u8; 4096];
+        let compressed = AppendVec::compress_account_data(&data);
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            AppendVec::decompress_account_data(&compressed, data.len() as u64).unwrap(),
+            &data[..]
+        );
+
+        // stored as is
+        assert_eq!(
+            AppendVec::decompress_account_data(&data, data.len() as u64).unwrap(),
+            &data[..]
+        );
+
+        // crafted lengths
+        assert!(AppendVec::decompress_account_data(&compressed, data.len() as u64 - 1).is_none());
+        assert!(AppendVec::decompress_account_data(&compressed, data.len() as u64 + 1).is_none());
+        assert!(AppendVec::decompress_account_data(&data, data.len() as u64 - 1).is_none());
+        assert!(AppendVec::decompress_account_data(&compressed, u64::MAX).is_none());
+    }
 }
@@ -45,12 +45,14 @@ use {
         accounts_index::{AccountSecondaryIndexes, IndexKey, ScanConfig, ScanResult},
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         ancestors::{Ancestors, AncestorsForSerialization},
+        block_cost_limits::MAX_ACCOUNT_DATA_BLOCK_LEN,
         blockhash_queue::BlockhashQueue,
         builtins::{self, ActivationType, Builtin, Builtins},
         cost_tracker::CostTracker,
         epoch_stakes::{EpochStakes, NodeVoteAccounts},
         inline_spl_token,
         message_processor::MessageProcessor,
+        partitioned_epoch_rewards::{EpochRewardStatus, PartitionedStakeReward},
         rent_collector::{CollectedInfo, RentCollector},
         stake_weighted_timestamp::{
             calculate_stake_weighted_timestamp, MaxAllowableDrift, MAX_ALLOWABLE_DRIFT_PERCENTAGE,
@@ -74,6 +76,7 @@ use {
     paychains_measure::measure::Measure,
     paychains_metrics::{inc_new_counter_debug, inc_new_counter_info},
     paychains_program_runtime::{
+        accounts_data_meter::MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION,
         instruction_recorder::InstructionRecorder,
         invoke_context::{
             BuiltinProgram, Executor, Executors, ProcessInstructionWithContext, TransactionExecutor,
@@ -131,7 +134,7 @@ use {
             AddressLookupError, Result, SanitizedTransaction, Transaction, TransactionError,
             TransactionVerificationMode, VersionedTransaction,
         },
-        transaction_context::{TransactionAccount, TransactionContext},
+        transaction_context::{TransactionAccount, TransactionContext, TransactionReturnData},
     },
     paychains_stake_program::stake_state::{
         self, InflationPointCalculationEvent, PointValue, StakeState,
@@ -161,6 +164,7 @@ use {
     },
 };
 
+pub mod partitioned_epoch_rewards;
 mod sysvar_cache;
 mod transaction_account_state_info;
 
@@ -581,7 +585,11 @@ pub struct TransactionExecutionDetails {
     pub status: Result<()>,
     pub log_messages: Option<Vec<String>>,
     pub inner_instructions: Option<Vec<Vec<CompiledInstruction>>>,
+    /// Invocation stack height of each of the `inner_instructions`
+    pub inner_instruction_stack_heights: Option<Vec<Vec<usize>>>,
     pub durable_nonce_fee: Option<DurableNonceFee>,
+    pub return_data: Option<TransactionReturnData>,
+    pub executed_units: u64,
 }
 
 /// Type safe representation of a transaction execution attempt which
@@ -880,6 +888,7 @@ pub(crate) struct BankFieldsToDeserialize {
     pub(crate) stakes: Stakes,
     pub(crate) epoch_stakes: HashMap<Epoch, EpochStakes>,
     pub(crate) is_delta: bool,
+    pub(crate) epoch_reward_status: EpochRewardStatus,
 }
 
 // Bank's common fields shared by all supported snapshot versions for serialization.
@@ -958,6 +967,7 @@ impl PartialEq for Bank {
             && *self.stakes_cache.stakes() == *other.stakes_cache.stakes()
             && self.epoch_stakes == other.epoch_stakes
             && self.is_delta.load(Relaxed) == other.is_delta.load(Relaxed)
+            && self.epoch_reward_status == other.epoch_reward_status
     }
 }
 
@@ -1193,6 +1203,15 @@ pub struct Bank {
     /// Current size of the accounts data.  Used when processing messages to enforce a limit on its
     /// maximum size.
     accounts_data_len: AtomicU64,
+
+    /// The sum of the accounts data len growth of the transactions of this bank which grew it.
+    /// Transactions shrinking the accounts data do not reduce it.  Used to enforce a limit on the
+    /// accounts data allocated per block.
+    accounts_data_len_allocated: AtomicU64,
+
+    /// Stake rewards of the previous epoch still being distributed, see
+    /// `partitioned_epoch_rewards`
+    epoch_reward_status: EpochRewardStatus,
 }
 
 impl Default for BlockhashQueue {
@@ -1335,6 +1354,8 @@ impl Bank {
             cost_tracker: RwLock::<CostTracker>::default(),
             sysvar_cache: RwLock::<SysvarCache>::default(),
             accounts_data_len: AtomicU64::default(),
+            accounts_data_len_allocated: AtomicU64::default(),
+            epoch_reward_status: EpochRewardStatus::default(),
         };
 
         let total_accounts_stats = bank.get_total_accounts_stats().unwrap();
@@ -1585,9 +1606,16 @@ impl Bank {
                     .map(|drop_callback| drop_callback.clone_box()),
             )),
             freeze_started: AtomicBool::new(false),
-            cost_tracker: RwLock::new(CostTracker::default()),
+            cost_tracker: RwLock::new(CostTracker::new_with_account_data_transaction_limit(
+                parent
+                    .feature_set
+                    .is_active(&feature_set::cap_accounts_data_allocations_per_transaction::id())
+                    .then(|| MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION),
+            )),
             sysvar_cache: RwLock::new(SysvarCache::default()),
             accounts_data_len: AtomicU64::new(parent.load_accounts_data_len()),
+            accounts_data_len_allocated: AtomicU64::default(),
+            epoch_reward_status: parent.epoch_reward_status.clone(),
         };
 
         let mut ancestors = Vec::with_capacity(1 + new.parents().len());
@@ -1602,6 +1630,9 @@ impl Bank {
         if parent_epoch < new.epoch() {
             let thread_pool = ThreadPoolBuilder::new().build().unwrap();
 
+            // Stake rewards of the previous epoch must be credited before the new epoch begins
+            new.distribute_remaining_partitioned_epoch_rewards();
+
             new.apply_feature_activations(false, false);
 
             // Add new entry to stakes.stake_history, set appropriate epoch and
@@ -1619,6 +1650,9 @@ impl Bank {
             // Save a snapshot of stakes for use in consensus and stake weighted networking
             let leader_schedule_epoch = epoch_schedule.get_leader_schedule_epoch(slot);
             new.update_epoch_stakes(leader_schedule_epoch);
+
+            // Credit the partition of stake rewards that falls on this block, if any
+            new.distribute_partitioned_epoch_rewards();
         }
 
         // Update sysvars before processing transactions
@@ -1776,6 +1810,8 @@ impl Bank {
             cost_tracker: RwLock::new(CostTracker::default()),
             sysvar_cache: RwLock::new(SysvarCache::default()),
             accounts_data_len: AtomicU64::new(accounts_data_len),
+            accounts_data_len_allocated: AtomicU64::default(),
+            epoch_reward_status: fields.epoch_reward_status,
         };
         bank.finish_init(
             genesis_config,
@@ -2257,13 +2293,15 @@ impl Bank {
 
         let old_vote_balance_and_staked = self.stakes_cache.stakes().vote_balance_and_staked();
 
-        let validator_point_value = self.pay_validator_rewards_with_thread_pool(
-            prev_epoch,
-            validator_rewards,
-            reward_calc_tracer,
-            self.stake_program_advance_activating_credits_observed(),
-            thread_pool,
-        );
+        let (validator_point_value, partitioned_stake_rewards) = self
+            .pay_validator_rewards_with_thread_pool(
+                prev_epoch,
+                validator_rewards,
+                reward_calc_tracer,
+                self.stake_program_advance_activating_credits_observed(),
+                self.is_partitioned_rewards_feature_enabled(),
+                thread_pool,
+            );
 
         if !self
             .feature_set
@@ -2298,17 +2336,27 @@ impl Bank {
             .unwrap()
         );
 
+        // stake rewards credited in the following blocks
+        let stake_rewards_pending: u64 = partitioned_stake_rewards
+            .iter()
+            .map(|stake_reward| stake_reward.stake_reward_lamports)
+            .sum();
+
         // verify that we didn't pay any more than we expected to
-        assert!(validator_rewards >= validator_rewards_paid);
+        assert!(validator_rewards >= validator_rewards_paid + stake_rewards_pending);
 
         info!(
-            "distributed inflation: {} (rounded from: {})",
-            validator_rewards_paid, validator_rewards
+            "distributed inflation: {} (rounded from: {}), pending: {}",
+            validator_rewards_paid, validator_rewards, stake_rewards_pending
         );
 
         self.capitalization
             .fetch_add(validator_rewards_paid, Relaxed);
 
+        if !partitioned_stake_rewards.is_empty() {
+            self.set_epoch_reward_status_active(partitioned_stake_rewards);
+        }
+
         let active_stake = if let Some(stake_history_entry) =
             self.stakes_cache.stakes().history().get(prev_epoch)
         {
@@ -2325,6 +2373,7 @@ impl Bank {
             ("foundation_rate", foundation_rate, f64),
             ("epoch_duration_in_years", epoch_duration_in_years, f64),
             ("validator_rewards", validator_rewards_paid, i64),
+            ("stake_rewards_pending", stake_rewards_pending, i64),
             ("active_stake", active_stake, i64),
             ("pre_capitalization", capitalization, i64),
             ("post_capitalization", self.capitalization(), i64)
@@ -2444,14 +2493,18 @@ impl Bank {
 
     /// iterate over all stakes, redeem vote credits for each stake we can
     ///   successfully load and parse, return the lamport value of one point
+    ///
+    /// If `defer_stake_rewards`, only vote rewards are paid and the stake rewards are returned to
+    ///   be distributed in the following blocks
     fn pay_validator_rewards_with_thread_pool(
         &mut self,
         rewarded_epoch: Epoch,
         rewards: u64,
         reward_calc_tracer: Option<impl Fn(&RewardCalculationEvent) + Send + Sync>,
         fix_activating_credits_observed: bool,
+        defer_stake_rewards: bool,
         thread_pool: &ThreadPool,
-    ) -> f64 {
+    ) -> (f64, Vec<PartitionedStakeReward>) {
         let stake_history = self.stakes_cache.stakes().history().clone();
         let vote_with_stake_delegations_map = {
             let LoadVoteAndStakeAccountsResult {
@@ -2501,7 +2554,7 @@ impl Bank {
         });
 
         if points == 0 {
-            return 0.0;
+            return (0.0, vec![]);
         }
 
         // pay according to point value
@@ -2525,7 +2578,7 @@ impl Bank {
             },
         );
 
-        let mut stake_rewards = thread_pool.install(|| {
+        let redeemed_stake_rewards: Vec<_> = thread_pool.install(|| {
             stake_delegation_iterator
                 .filter_map(
                     |(
@@ -2563,32 +2616,72 @@ impl Bank {
                                 *vote_rewards_sum = vote_rewards_sum.saturating_add(voters_reward);
                             }
 
+                            return Some((
+                                stake_pubkey,
+                                stake_account,
+                                stakers_reward,
+                                vote_state.commission,
+                            ));
+                        } else {
+                            debug!(
+                                "stake_state::redeem_rewards() failed for {}: {:?}",
+                                stake_pubkey, redeemed
+                            );
+                        }
+                        None
+                    },
+                )
+                .collect()
+        });
+
+        let (mut stake_rewards, partitioned_stake_rewards) = if defer_stake_rewards {
+            let partitioned_stake_rewards = redeemed_stake_rewards
+                .into_iter()
+                .filter_map(
+                    |(stake_pubkey, stake_account, stake_reward_lamports, commission)| {
+                        let stake = stake_account
+                            .state()
+                            .ok()
+                            .and_then(|stake_state: StakeState| stake_state.stake())?;
+                        Some(PartitionedStakeReward {
+                            stake_pubkey,
+                            stake,
+                            stake_reward_lamports,
+                            commission,
+                        })
+                    },
+                )
+                .collect();
+            (vec![], partitioned_stake_rewards)
+        } else {
+            let stake_rewards = thread_pool.install(|| {
+                redeemed_stake_rewards
+                    .into_par_iter()
+                    .filter_map(
+                        |(stake_pubkey, stake_account, stakers_reward, commission)| {
                             // store stake account even if stakers_reward is 0
                             // because credits observed has changed
                             self.store_account(&stake_pubkey, &stake_account);
 
                             if stakers_reward > 0 {
-                                return Some((
+                                Some((
                                     stake_pubkey,
                                     RewardInfo {
                                         reward_type: RewardType::Staking,
                                         lamports: stakers_reward as i64,
                                         post_balance: stake_account.lamports(),
-                                        commission: Some(vote_state.commission),
+                                        commission: Some(commission),
                                     },
-                                ));
+                                ))
+                            } else {
+                                None
                             }
-                        } else {
-                            debug!(
-                                "stake_state::redeem_rewards() failed for {}: {:?}",
-                                stake_pubkey, redeemed
-                            );
-                        }
-                        None
-                    },
-                )
-                .collect()
-        });
+                        },
+                    )
+                    .collect()
+            });
+            (stake_rewards, vec![])
+        };
 
         let mut vote_rewards = vote_account_rewards
             .into_iter()
@@ -2626,7 +2719,10 @@ impl Bank {
             rewards.append(&mut stake_rewards);
         }
 
-        point_value.rewards as f64 / point_value.points as f64
+        (
+            point_value.rewards as f64 / point_value.points as f64,
+            partitioned_stake_rewards,
+        )
     }
 
     fn update_recent_blockhashes_locked(&self, locked_blockhash_queue: &BlockhashQueue) {
@@ -2778,6 +2874,17 @@ impl Bank {
             self.freeze_started.store(true, Relaxed);
             *hash = self.hash_internal_state();
             self.rc.accounts.accounts_db.mark_slot_frozen(self.slot());
+
+            datapoint_info!(
+                "bank-accounts_data_len",
+                ("slot", self.slot(), i64),
+                ("accounts_data_len", self.load_accounts_data_len(), i64),
+                (
+                    "accounts_data_len_allocated",
+                    self.accounts_data_len_allocated(),
+                    i64
+                ),
+            );
         }
     }
 
@@ -3461,7 +3568,8 @@ impl Bank {
     ) -> Vec<TransactionCheckResult> {
         let age_results =
             self.check_age(sanitized_txs.iter(), lock_results, max_age, error_counters);
-        self.check_status_cache(sanitized_txs, age_results, error_counters)
+        let cache_results = self.check_status_cache(sanitized_txs, age_results, error_counters);
+        self.check_epoch_rewards_restrictions(sanitized_txs, cache_results, error_counters)
     }
 
     pub fn collect_balances(&self, batch: &TransactionBatch) -> TransactionBalances {
@@ -3556,6 +3664,18 @@ impl Bank {
                 error_counters.invalid_writable_account
             );
         }
+        if 0 != error_counters.max_accounts_data_allocations_exceeded {
+            inc_new_counter_info!(
+                "bank-process_transactions-error-max_accounts_data_allocations_exceeded",
+                error_counters.max_accounts_data_allocations_exceeded
+            );
+        }
+        if 0 != error_counters.program_execution_temporarily_restricted {
+            inc_new_counter_info!(
+                "bank-process_transactions-error-program_execution_temporarily_restricted",
+                error_counters.program_execution_temporarily_restricted
+            );
+        }
     }
 
     /// Get any cached executors needed by the transaction
@@ -3694,6 +3814,8 @@ impl Bank {
 
         let (blockhash, lamports_per_signature) = self.last_blockhash_and_lamports_per_signature();
 
+        let mut executed_units = 0u64;
+
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
             &self.builtin_programs.vec,
@@ -3711,6 +3833,7 @@ impl Bank {
             blockhash,
             lamports_per_signature,
             self.load_accounts_data_len(),
+            &mut executed_units,
         );
         process_message_time.stop();
         saturating_add_assign!(
@@ -3737,14 +3860,23 @@ impl Bank {
                 )
                 .map(|_| info)
             })
+            .and_then(|info| {
+                self.check_accounts_data_allocations(info.accounts_data_allocations)?;
+                Ok(info)
+            })
             .map(|info| {
-                self.store_accounts_data_len(info.accounts_data_len);
+                self.allocate_accounts_data(info.accounts_data_allocations);
+                self.update_accounts_data_len(info.accounts_data_len_delta);
             })
             .map_err(|err| {
                 match err {
-                    TransactionError::InvalidRentPayingAccount => {
+                    TransactionError::InvalidRentPayingAccount
+                    | TransactionError::InsufficientFundsForRent { .. } => {
                         error_counters.invalid_rent_paying_account += 1;
                     }
+                    TransactionError::MaxAccountsDataAllocationsExceeded => {
+                        error_counters.max_accounts_data_allocations_exceeded += 1;
+                    }
                     _ => {
                         error_counters.instruction_error += 1;
                     }
@@ -3759,9 +3891,27 @@ impl Bank {
                     .ok()
             });
 
-        let inner_instructions = instruction_recorder
+        let (inner_instructions, inner_instruction_stack_heights) = match instruction_recorder
             .and_then(|instruction_recorder| Rc::try_unwrap(instruction_recorder).ok())
-            .map(|instruction_recorder| instruction_recorder.into_inner().deconstruct());
+        {
+            Some(instruction_recorder) => {
+                let (inner_instructions, stack_heights) = instruction_recorder
+                    .into_inner()
+                    .deconstruct_with_stack_heights();
+                (Some(inner_instructions), Some(stack_heights))
+            }
+            None => (None, None),
+        };
+
+        let (return_data_program_id, return_data) = transaction_context.get_return_data();
+        let return_data = if return_data.is_empty() {
+            None
+        } else {
+            Some(TransactionReturnData {
+                program_id: *return_data_program_id,
+                data: return_data.to_vec(),
+            })
+        };
 
         loaded_transaction.accounts = transaction_context.deconstruct();
 
@@ -3769,7 +3919,10 @@ impl Bank {
             status,
             log_messages,
             inner_instructions,
+            inner_instruction_stack_heights,
             durable_nonce_fee,
+            return_data,
+            executed_units,
         })
     }
 
@@ -4001,10 +4154,56 @@ impl Bank {
     }
 
     /// Load the accounts data len
-    fn load_accounts_data_len(&self) -> u64 {
+    pub fn load_accounts_data_len(&self) -> u64 {
         self.accounts_data_len.load(Acquire)
     }
 
+    /// Return the accounts data len allocated by the transactions of this bank, in bytes
+    pub fn accounts_data_len_allocated(&self) -> u64 {
+        self.accounts_data_len_allocated.load(Relaxed)
+    }
+
+    /// Check the accounts data allocated by a transaction against the limit per transaction.
+    /// Accounts data freed by the transaction does not offset its allocations.
+    fn check_accounts_data_allocations(&self, accounts_data_allocations: u64) -> Result<()> {
+        if accounts_data_allocations > MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION
+            && self
+                .feature_set
+                .is_active(&feature_set::cap_accounts_data_allocations_per_transaction::id())
+        {
+            Err(TransactionError::MaxAccountsDataAllocationsExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add the accounts data allocated by a transaction to the accounts data allocated by the
+    /// transactions of this bank
+    fn allocate_accounts_data(&self, accounts_data_allocations: u64) {
+        if accounts_data_allocations > 0 {
+            self.accounts_data_len_allocated
+                .fetch_add(accounts_data_allocations, Relaxed);
+        }
+    }
+
+    /// Check the accounts data allocated by the transactions of this bank against the limit per
+    /// block.  The limit applies to the block as a whole: which transaction crosses it depends on
+    /// the order the transactions of parallel batches happen to execute in, so failing that
+    /// transaction would make the result of replaying the block nondeterministic.  Instead the
+    /// leader stops packing transactions at the limit, see `CostTracker`, and a block whose
+    /// transactions allocate more than the limit is invalid.
+    pub fn check_accounts_data_block_limit(&self) -> Result<()> {
+        if self.accounts_data_len_allocated() > MAX_ACCOUNT_DATA_BLOCK_LEN
+            && self
+                .feature_set
+                .is_active(&feature_set::cap_accounts_data_size_per_block::id())
+        {
+            Err(TransactionError::WouldExceedAccountDataBlockLimit)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Store a new value to the accounts data len
     fn store_accounts_data_len(&self, accounts_data_len: u64) {
         self.accounts_data_len.store(accounts_data_len, Release)
@@ -4886,6 +5085,31 @@ impl Bank {
             .map_or(Ok(()), |sig| self.get_signature_status(sig).unwrap())
     }
 
+    /// Process a Transaction and return its execution details, including logs, inner
+    /// instructions, return data and compute units consumed. This is used for tests.
+    pub fn process_transaction_with_metadata(&self, tx: Transaction) -> TransactionExecutionResult {
+        let batch = match self.prepare_entry_batch(vec![tx.into()]) {
+            Ok(batch) => batch,
+            Err(err) => return TransactionExecutionResult::NotExecuted(err),
+        };
+
+        let (
+            TransactionResults {
+                mut execution_results,
+                ..
+            },
+            _balances,
+        ) = self.load_execute_and_commit_transactions(
+            &batch,
+            MAX_PROCESSING_AGE,
+            false,
+            true,
+            true,
+            &mut ExecuteTimings::default(),
+        );
+        execution_results.remove(0)
+    }
+
     /// Process multiple transaction in a single batch. This is used for benches and unit tests.
     ///
     /// # Panics
@@ -5148,6 +5372,10 @@ impl Bank {
         self.compute_budget = compute_budget;
     }
 
+    pub fn compute_budget(&self) -> Option<ComputeBudget> {
+        self.compute_budget
+    }
+
     pub fn hard_forks(&self) -> Arc<RwLock<HardForks>> {
         self.hard_forks.clone()
     }
@@ -5837,6 +6065,11 @@ impl Bank {
         !self.is_delta.load(Relaxed)
     }
 
+    /// Instruction processors of the builtin programs, including the BPF loaders
+    pub fn get_builtin_programs(&self) -> &[BuiltinProgram] {
+        &self.builtin_programs.vec
+    }
+
     /// Add an instruction processor to intercept instructions before the dynamic loader.
     pub fn add_builtin(
         &mut self,
@@ -5959,6 +6192,13 @@ impl Bank {
         self.rc.accounts.accounts_db.shrink_candidate_slots()
     }
 
+    pub fn pack_ancient_slots(&self, last_full_snapshot_slot: Option<Slot>) {
+        self.rc
+            .accounts
+            .accounts_db
+            .pack_ancient_slots(last_full_snapshot_slot)
+    }
+
     pub fn no_overflow_rent_distribution_enabled(&self) -> bool {
         self.feature_set
             .is_active(&feature_set::no_overflow_rent_distribution::id())
@@ -6422,7 +6662,7 @@ pub(crate) mod tests {
             accounts_index::{AccountIndex, AccountSecondaryIndexes, ScanError, ITER_BATCH_SIZE},
             ancestors::Ancestors,
             genesis_utils::{
-                activate_all_features, bootstrap_validator_stake_lamports,
+                activate_all_features, activate_feature, bootstrap_validator_stake_lamports,
                 create_genesis_config_with_leader, create_genesis_config_with_vote_accounts,
                 GenesisConfigInfo, ValidatorVoteKeypairs,
             },
@@ -6487,7 +6727,10 @@ pub(crate) mod tests {
             status,
             log_messages: None,
             inner_instructions: None,
+            inner_instruction_stack_heights: None,
             durable_nonce_fee: nonce.map(DurableNonceFee::from),
+            return_data: None,
+            executed_units: 0,
         })
     }
 
@@ -8708,6 +8951,285 @@ pub(crate) mod tests {
         }
     }
 
+    #[test]
+    fn test_partitioned_epoch_rewards() {
+        paychains_logger::setup();
+
+        // create a bank that ticks really slowly...
+        let mut genesis_config = GenesisConfig {
+            accounts: (0..42)
+                .map(|_| {
+                    (
+                        paychains_sdk::pubkey::new_rand(),
+                        Account::new(1_000_000_000, 0, &Pubkey::default()),
+                    )
+                })
+                .collect(),
+            // set it up so the first epoch is a full year long
+            poh_config: PohConfig {
+                target_tick_duration: Duration::from_secs(
+                    SECONDS_PER_YEAR as u64
+                        / MINIMUM_SLOTS_PER_EPOCH as u64
+                        / DEFAULT_TICKS_PER_SLOT,
+                ),
+                hashes_per_tick: None,
+                target_tick_count: None,
+            },
+            cluster_type: ClusterType::MainnetBeta,
+
+            ..GenesisConfig::default()
+        };
+        activate_feature(
+            &mut genesis_config,
+            feature_set::enable_partitioned_epoch_reward::id(),
+        );
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank.restore_old_behavior_for_fragile_tests();
+
+        let vote_id = paychains_sdk::pubkey::new_rand();
+        let mut vote_account =
+            vote_state::create_account(&vote_id, &paychains_sdk::pubkey::new_rand(), 50, 100);
+        let (stake_id1, stake_account1) = crate::stakes::tests::create_stake_account(123, &vote_id);
+        let (stake_id2, stake_account2) = crate::stakes::tests::create_stake_account(456, &vote_id);
+        bank.store_account_and_update_capitalization(&stake_id1, &stake_account1);
+        bank.store_account_and_update_capitalization(&stake_id2, &stake_account2);
+
+        // generate some rewards
+        let mut vote_state = VoteState::from(&vote_account).unwrap();
+        for i in 0..MAX_LOCKOUT_HISTORY + 42 {
+            vote_state.process_slot_vote_unchecked(i as u64);
+        }
+        let versioned = VoteStateVersions::Current(Box::new(vote_state));
+        VoteState::to(&versioned, &mut vote_account).unwrap();
+        bank.store_account_and_update_capitalization(&vote_id, &vote_account);
+
+        // the first block of epoch 1 only pays the vote rewards
+        let bank1 = Bank::new_from_parent(
+            &bank,
+            &Pubkey::default(),
+            bank.get_slots_in_epoch(bank.epoch()) + 1,
+        );
+        assert!(bank1.is_epoch_rewards_distribution_active());
+        assert_eq!(
+            bank1.epoch_rewards_complete_block_height(),
+            Some(bank1.block_height() + 1)
+        );
+        assert_eq!(bank1.num_reward_partitions_calculated(), Some(1));
+        assert!(bank1
+            .rewards
+            .read()
+            .unwrap()
+            .iter()
+            .all(|(_address, reward_info)| reward_info.reward_type == RewardType::Voting));
+        assert!(bank1.get_balance(&vote_id) > vote_account.lamports());
+        assert_eq!(bank1.get_balance(&stake_id1), stake_account1.lamports());
+        assert_eq!(bank1.get_balance(&stake_id2), stake_account2.lamports());
+
+        // the stake program can't be used until the stake rewards are distributed
+        let authority = Keypair::new();
+        let tx = Transaction::new_signed_with_payer(
+            &[stake_instruction::deactivate_stake(
+                &stake_id1,
+                &authority.pubkey(),
+            )],
+            Some(&authority.pubkey()),
+            &[&authority],
+            bank1.last_blockhash(),
+        );
+        let account_index = tx
+            .message
+            .account_keys
+            .iter()
+            .position(|key| key == &paychains_stake_program::id())
+            .unwrap() as u8;
+        assert_eq!(
+            bank1.process_transaction(&tx),
+            Err(TransactionError::ProgramExecutionTemporarilyRestricted { account_index })
+        );
+        bank1.freeze();
+        assert!(bank1.calculate_and_verify_capitalization(true));
+
+        // the next block credits the stake rewards
+        let bank1 = Arc::new(bank1);
+        let bank2 = new_from_parent(&bank1);
+        assert!(!bank2.is_epoch_rewards_distribution_active());
+        assert_eq!(bank2.epoch_rewards_complete_block_height(), None);
+        assert_eq!(bank2.num_reward_partitions_calculated(), None);
+        assert!(bank2.get_balance(&stake_id1) > stake_account1.lamports());
+        assert!(bank2.get_balance(&stake_id2) > stake_account2.lamports());
+        assert_eq!(
+            bank2
+                .rewards
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|(_address, reward_info)| reward_info.reward_type == RewardType::Staking)
+                .count(),
+            2
+        );
+        bank2.freeze();
+        assert!(bank2.calculate_and_verify_capitalization(true));
+        assert!(bank2.capitalization() > bank1.capitalization());
+    }
+
+    #[test]
+    fn test_partitioned_epoch_rewards_multiple_partitions() {
+        paychains_logger::setup();
+        let GenesisConfigInfo {
+            mut genesis_config, ..
+        } = create_genesis_config_with_leader(
+            1_000_000_000,
+            &paychains_sdk::pubkey::new_rand(),
+            1_000_000,
+        );
+        activate_feature(
+            &mut genesis_config,
+            feature_set::enable_partitioned_epoch_reward::id(),
+        );
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+
+        // three stake accounts, an account that is not a stake account and one that is gone
+        let vote_id = paychains_sdk::pubkey::new_rand();
+        let stake_accounts: Vec<_> = (0..3)
+            .map(|_| crate::stakes::tests::create_stake_account(1_000, &vote_id))
+            .collect();
+        for (stake_id, stake_account) in &stake_accounts {
+            bank0.store_account_and_update_capitalization(stake_id, stake_account);
+        }
+        let other_id = paychains_sdk::pubkey::new_rand();
+        let other_account = AccountSharedData::new(1_000, 0, &system_program::id());
+        bank0.store_account_and_update_capitalization(&other_id, &other_account);
+        let missing_id = paychains_sdk::pubkey::new_rand();
+
+        let delegated_stake = |stake_account: &AccountSharedData| match stake_account.state() {
+            Ok(StakeState::Stake(_meta, stake)) => stake,
+            state => panic!("unexpected stake state {:?}", state),
+        };
+        // the delegation after redeeming the reward
+        let redeemed_stake = Stake {
+            credits_observed: 42,
+            ..delegated_stake(&stake_accounts[0].1)
+        };
+        let reward = |stake_pubkey: &Pubkey, stake_reward_lamports| PartitionedStakeReward {
+            stake_pubkey: *stake_pubkey,
+            stake: redeemed_stake,
+            stake_reward_lamports,
+            commission: 0,
+        };
+        let stake_rewards_by_partition = vec![
+            vec![reward(&stake_accounts[0].0, 10), reward(&missing_id, 11)],
+            vec![reward(&stake_accounts[1].0, 12), reward(&other_id, 13)],
+            vec![reward(&stake_accounts[2].0, 14)],
+        ];
+
+        // the rewards are calculated in bank1 and pending until credited
+        let mut bank1 = Bank::new_from_parent(&bank0, &Pubkey::default(), 1);
+        bank1.epoch_reward_status = EpochRewardStatus::Active {
+            calculation_block_height: bank1.block_height(),
+            stake_rewards_by_partition: Arc::new(stake_rewards_by_partition),
+        };
+        assert_eq!(
+            bank1.epoch_rewards_complete_block_height(),
+            Some(bank1.block_height() + 3)
+        );
+        bank1.freeze();
+        assert!(bank1.calculate_and_verify_capitalization(true));
+        let bank1 = Arc::new(bank1);
+
+        // bank2 only credits the first partition, including the missing account
+        let bank2 = Bank::new_from_parent(&bank1, &Pubkey::default(), 2);
+        assert!(bank2.is_epoch_rewards_distribution_active());
+        assert_eq!(bank2.get_balance(&stake_accounts[0].0), 1_000 + 10);
+        assert_eq!(
+            delegated_stake(&bank2.get_account(&stake_accounts[0].0).unwrap()),
+            redeemed_stake
+        );
+        assert_eq!(bank2.get_balance(&missing_id), 11);
+        assert_eq!(bank2.get_balance(&stake_accounts[1].0), 1_000);
+        assert_eq!(bank2.get_balance(&other_id), 1_000);
+        assert_eq!(bank2.get_balance(&stake_accounts[2].0), 1_000);
+        assert_eq!(bank2.rewards.read().unwrap().len(), 2);
+        assert_eq!(bank2.capitalization(), bank1.capitalization() + 10 + 11);
+
+        // a snapshot taken mid-distribution keeps the partitions left to credit
+        while !bank2.is_complete() {
+            bank2.register_tick(&Hash::new_unique());
+        }
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_info = crate::snapshot_utils::bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank2,
+            None,
+            snapshot_archives_dir.path(),
+            crate::snapshot_utils::ArchiveFormat::Tar,
+            crate::snapshot_utils::DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            crate::snapshot_utils::DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+        let (roundtrip_bank, _) = crate::snapshot_utils::bank_from_snapshot_archives(
+            &[accounts_dir.path().to_path_buf()],
+            bank_snapshots_dir.path(),
+            &snapshot_archive_info,
+            None,
+            &genesis_config,
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            false,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            roundtrip_bank.epoch_reward_status(),
+            bank2.epoch_reward_status()
+        );
+        assert_eq!(roundtrip_bank.capitalization(), bank2.capitalization());
+
+        // the first block of the next epoch credits the partitions not credited yet before
+        // calculating new rewards
+        let roundtrip_bank = Arc::new(roundtrip_bank);
+        let bank3 = Bank::new_from_parent(
+            &roundtrip_bank,
+            &Pubkey::default(),
+            genesis_config
+                .epoch_schedule
+                .get_first_slot_in_epoch(roundtrip_bank.epoch() + 1),
+        );
+        assert!(!bank3.is_epoch_rewards_distribution_active());
+        assert_eq!(bank3.get_balance(&stake_accounts[0].0), 1_000 + 10);
+        assert_eq!(bank3.get_balance(&missing_id), 11);
+        assert_eq!(bank3.get_balance(&stake_accounts[1].0), 1_000 + 12);
+        assert_eq!(
+            delegated_stake(&bank3.get_account(&stake_accounts[1].0).unwrap()),
+            redeemed_stake
+        );
+        let other_account_after = bank3.get_account(&other_id).unwrap();
+        assert_eq!(other_account_after.lamports(), 1_000 + 13);
+        assert_eq!(other_account_after.owner(), &system_program::id());
+        assert_eq!(bank3.get_balance(&stake_accounts[2].0), 1_000 + 14);
+        assert_eq!(
+            bank3
+                .rewards
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|(_address, reward_info)| reward_info.reward_type == RewardType::Staking)
+                .count(),
+            3
+        );
+        bank3.freeze();
+        assert!(bank3.calculate_and_verify_capitalization(true));
+        assert!(bank3.capitalization() >= roundtrip_bank.capitalization() + 12 + 13 + 14);
+    }
+
     // Test that purging 0 lamports accounts works.
     #[test]
     fn test_purge_empty_accounts() {
@@ -11629,6 +12151,67 @@ pub(crate) mod tests {
         assert_eq!(transaction_balances_set.post_balances[2], vec![8, 0, 1]);
     }
 
+    #[test]
+    fn test_process_transaction_with_metadata() {
+        let (genesis_config, mint_keypair) = create_genesis_config(500);
+        let mut bank = Bank::new_for_tests(&genesis_config);
+
+        fn mock_process_instruction(
+            _first_instruction_account: usize,
+            data: &[u8],
+            invoke_context: &mut InvokeContext,
+        ) -> result::Result<(), InstructionError> {
+            let program_id = invoke_context.transaction_context.get_program_key()?;
+            invoke_context
+                .get_compute_meter()
+                .borrow_mut()
+                .consume(42)?;
+            paychains_program_runtime::ic_msg!(invoke_context, "mock log");
+            invoke_context
+                .transaction_context
+                .set_return_data(program_id, data.to_vec())
+        }
+
+        let mock_program_id = Pubkey::new(&[2u8; 32]);
+        bank.add_builtin("mock_program", &mock_program_id, mock_process_instruction);
+
+        let instruction = Instruction::new_with_bytes(mock_program_id, &[1, 2, 3], vec![]);
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair],
+            bank.last_blockhash(),
+        );
+
+        match bank.process_transaction_with_metadata(tx.clone()) {
+            TransactionExecutionResult::Executed(details) => {
+                assert_eq!(details.status, Ok(()));
+                assert_eq!(details.executed_units, 42);
+                assert_eq!(
+                    details.return_data,
+                    Some(TransactionReturnData {
+                        program_id: mock_program_id,
+                        data: vec![1, 2, 3],
+                    })
+                );
+                assert!(details
+                    .log_messages
+                    .unwrap()
+                    .contains(&"mock log".to_string()));
+                assert_eq!(details.inner_instructions, Some(vec![vec![]]));
+                assert_eq!(details.inner_instruction_stack_heights, Some(vec![vec![]]));
+            }
+            TransactionExecutionResult::NotExecuted(err) => panic!("not executed: {}", err),
+        }
+        assert_eq!(bank.get_signature_status(&tx.signatures[0]), Some(Ok(())));
+
+        // replaying the same transaction is rejected before execution
+        assert!(matches!(
+            bank.process_transaction_with_metadata(tx),
+            TransactionExecutionResult::NotExecuted(TransactionError::AlreadyProcessed)
+        ));
+    }
+
     #[test]
     fn test_transaction_with_duplicate_accounts_in_instruction() {
         let (genesis_config, mint_keypair) = create_genesis_config(500);
@@ -15540,6 +16123,205 @@ pub(crate) mod tests {
         ));
     }
 
+    fn create_accounts_transaction(
+        bank: &Bank,
+        mint_keypair: &Keypair,
+        num_accounts: usize,
+        space: u64,
+    ) -> Transaction {
+        let keypairs: Vec<_> = (0..num_accounts).map(|_| Keypair::new()).collect();
+        let instructions: Vec<_> = keypairs
+            .iter()
+            .map(|keypair| {
+                system_instruction::create_account(
+                    &mint_keypair.pubkey(),
+                    &keypair.pubkey(),
+                    1,
+                    space,
+                    &system_program::id(),
+                )
+            })
+            .collect();
+        let mut signers: Vec<&Keypair> = vec![mint_keypair];
+        signers.extend(keypairs.iter());
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&mint_keypair.pubkey()),
+            &signers,
+            bank.last_blockhash(),
+        )
+    }
+
+    #[test]
+    fn test_accounts_data_allocations_per_transaction() {
+        use paychains_sdk::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+
+        paychains_logger::setup();
+        for cap_allocations in [false, true] {
+            let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000_000);
+            let mut bank = Bank::new_for_tests(&genesis_config);
+            if cap_allocations {
+                bank.activate_feature(
+                    &feature_set::cap_accounts_data_allocations_per_transaction::id(),
+                );
+            }
+            let accounts_data_len = bank.load_accounts_data_len();
+
+            // allocating up to the limit succeeds
+            let max_accounts =
+                MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION / MAX_PERMITTED_DATA_LENGTH;
+            let num_accounts = max_accounts as usize;
+            let txn = create_accounts_transaction(
+                &bank,
+                &mint_keypair,
+                num_accounts,
+                MAX_PERMITTED_DATA_LENGTH,
+            );
+            assert_eq!(bank.process_transaction(&txn), Ok(()));
+            let allocated = num_accounts as u64 * MAX_PERMITTED_DATA_LENGTH;
+            assert_eq!(bank.load_accounts_data_len(), accounts_data_len + allocated);
+            assert_eq!(bank.accounts_data_len_allocated(), allocated);
+
+            // allocating more fails once the cap is enforced
+            let txn = create_accounts_transaction(
+                &bank,
+                &mint_keypair,
+                num_accounts + 1,
+                MAX_PERMITTED_DATA_LENGTH,
+            );
+            let result = bank.process_transaction(&txn);
+            if cap_allocations {
+                assert_eq!(
+                    result,
+                    Err(TransactionError::MaxAccountsDataAllocationsExceeded)
+                );
+                assert_eq!(bank.load_accounts_data_len(), accounts_data_len + allocated);
+                assert_eq!(bank.accounts_data_len_allocated(), allocated);
+            } else {
+                assert_eq!(result, Ok(()));
+                let allocated = allocated + (num_accounts + 1) as u64 * MAX_PERMITTED_DATA_LENGTH;
+                assert_eq!(bank.load_accounts_data_len(), accounts_data_len + allocated);
+                assert_eq!(bank.accounts_data_len_allocated(), allocated);
+            }
+        }
+    }
+
+    #[test]
+    fn test_accounts_data_allocations_freed_within_transaction() {
+        use paychains_sdk::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+
+        fn mock_realloc(
+            _first_instruction_account: usize,
+            data: &[u8],
+            invoke_context: &mut InvokeContext,
+        ) -> result::Result<(), InstructionError> {
+            let transaction_context = &invoke_context.transaction_context;
+            let instruction_context = transaction_context.get_current_instruction_context()?;
+            let new_len: u64 =
+                bincode::deserialize(data).map_err(|_| InstructionError::InvalidInstructionData)?;
+            instruction_context
+                .try_borrow_instruction_account(transaction_context, 0)?
+                .set_data(&vec![0; new_len as usize])
+        }
+
+        paychains_logger::setup();
+        let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000_000);
+        let mut bank = Bank::new_for_tests(&genesis_config);
+        bank.activate_feature(&feature_set::do_support_realloc::id());
+        bank.activate_feature(&feature_set::cap_accounts_data_allocations_per_transaction::id());
+        let mock_program_id = Pubkey::new_unique();
+        bank.add_builtin("mock_realloc", &mock_program_id, mock_realloc);
+        let address = Pubkey::new_unique();
+        bank.store_account(
+            &address,
+            &AccountSharedData::new(1_000_000_000, 0, &mock_program_id),
+        );
+
+        let realloc = |new_len: u64| {
+            Instruction::new_with_bincode(
+                mock_program_id,
+                &new_len,
+                vec![AccountMeta::new(address, false)],
+            )
+        };
+        let grow_and_free = |times: u64| {
+            let instructions: Vec<_> = (0..times)
+                .flat_map(|_| [realloc(MAX_PERMITTED_DATA_LENGTH), realloc(0)])
+                .collect();
+            Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&mint_keypair.pubkey()),
+                &[&mint_keypair],
+                bank.last_blockhash(),
+            )
+        };
+
+        // every growth counts towards the limit, even if the transaction frees it again
+        let times = MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION / MAX_PERMITTED_DATA_LENGTH;
+        let accounts_data_len = bank.load_accounts_data_len();
+        assert_eq!(bank.process_transaction(&grow_and_free(times)), Ok(()));
+        assert_eq!(bank.load_accounts_data_len(), accounts_data_len);
+        assert_eq!(
+            bank.accounts_data_len_allocated(),
+            times * MAX_PERMITTED_DATA_LENGTH
+        );
+        assert_eq!(
+            bank.process_transaction(&grow_and_free(times + 1)),
+            Err(TransactionError::MaxAccountsDataAllocationsExceeded)
+        );
+        assert_eq!(
+            bank.accounts_data_len_allocated(),
+            times * MAX_PERMITTED_DATA_LENGTH
+        );
+    }
+
+    #[test]
+    fn test_accounts_data_allocations_per_block() {
+        use paychains_sdk::system_instruction::MAX_PERMITTED_DATA_LENGTH;
+
+        paychains_logger::setup();
+        for cap_block in [false, true] {
+            let (genesis_config, mint_keypair) = create_genesis_config(1_000_000_000_000);
+            let mut bank = Bank::new_for_tests(&genesis_config);
+            if cap_block {
+                bank.activate_feature(&feature_set::cap_accounts_data_size_per_block::id());
+            }
+
+            while bank.accounts_data_len_allocated() + MAX_PERMITTED_DATA_LENGTH
+                <= MAX_ACCOUNT_DATA_BLOCK_LEN
+            {
+                let txn =
+                    create_accounts_transaction(&bank, &mint_keypair, 1, MAX_PERMITTED_DATA_LENGTH);
+                assert_eq!(bank.process_transaction(&txn), Ok(()));
+            }
+            assert_eq!(bank.check_accounts_data_block_limit(), Ok(()));
+            let allocated = bank.accounts_data_len_allocated();
+
+            // the transaction crossing the limit succeeds, it is the block that exceeds it
+            let txn =
+                create_accounts_transaction(&bank, &mint_keypair, 1, MAX_PERMITTED_DATA_LENGTH);
+            assert_eq!(bank.process_transaction(&txn), Ok(()));
+            assert_eq!(
+                bank.accounts_data_len_allocated(),
+                allocated + MAX_PERMITTED_DATA_LENGTH
+            );
+            if cap_block {
+                assert_eq!(
+                    bank.check_accounts_data_block_limit(),
+                    Err(TransactionError::WouldExceedAccountDataBlockLimit)
+                );
+            } else {
+                assert_eq!(bank.check_accounts_data_block_limit(), Ok(()));
+            }
+
+            // the allocations of the parent do not count towards the limit of the child
+            bank.freeze();
+            let child = Bank::new_from_parent(&Arc::new(bank), &Pubkey::default(), 1);
+            assert_eq!(child.accounts_data_len_allocated(), 0);
+            assert_eq!(child.check_accounts_data_block_limit(), Ok(()));
+        }
+    }
+
     #[test]
     fn test_executor_cache_get_primer_count_upper_bound_inclusive() {
         let pubkey = Pubkey::default();
@@ -15756,7 +16538,10 @@ pub(crate) mod tests {
             &mock_program_id,
         );
         let result = bank.process_transaction(&tx);
-        assert!(result.is_err());
+        assert_eq!(
+            result,
+            Err(TransactionError::InsufficientFundsForRent { account_index: 1 })
+        );
         assert!(bank.get_account(&rent_paying_account.pubkey()).is_none());
 
         // Try to create RentExempt account
@@ -15774,6 +16559,67 @@ pub(crate) mod tests {
         assert!(check_account_is_rent_exempt(&rent_exempt_account.pubkey()));
     }
 
+    #[test]
+    fn test_credit_legacy_rent_paying_account() {
+        let GenesisConfigInfo {
+            mut genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config_with_leader(pay_to_lamports(100.), &Pubkey::new_unique(), 42);
+        genesis_config.rent = Rent::default();
+
+        let mock_program_id = Pubkey::new_unique();
+        let account_data_size = 100;
+        let rent_exempt_minimum = genesis_config.rent.minimum_balance(account_data_size);
+
+        let rent_paying_account = Keypair::new();
+        genesis_config.accounts.insert(
+            rent_paying_account.pubkey(),
+            Account::new(rent_exempt_minimum - 2, account_data_size, &mock_program_id),
+        );
+        activate_all_features(&mut genesis_config);
+
+        // A legacy RentPaying account cannot be credited if it remains RentPaying
+        let bank = Bank::new_for_tests(&genesis_config);
+        let result = bank.transfer(1, &mint_keypair, &rent_paying_account.pubkey());
+        assert_eq!(
+            result,
+            Err(TransactionError::InsufficientFundsForRent { account_index: 1 })
+        );
+        assert_eq!(
+            bank.get_balance(&rent_paying_account.pubkey()),
+            rent_exempt_minimum - 2
+        );
+
+        // ...but may still be topped up to RentExempt
+        let result = bank.transfer(2, &mint_keypair, &rent_paying_account.pubkey());
+        assert!(result.is_ok());
+        assert_eq!(
+            bank.get_balance(&rent_paying_account.pubkey()),
+            rent_exempt_minimum
+        );
+
+        // Before feature activation, crediting is allowed and the error carries no index
+        genesis_config
+            .accounts
+            .remove(&feature_set::prevent_crediting_accounts_that_end_rent_paying::id())
+            .unwrap();
+        genesis_config
+            .accounts
+            .remove(&feature_set::include_account_index_in_rent_error::id())
+            .unwrap();
+        let bank = Bank::new_for_tests(&genesis_config);
+        let result = bank.transfer(1, &mint_keypair, &rent_paying_account.pubkey());
+        assert!(result.is_ok());
+        assert_eq!(
+            bank.get_balance(&rent_paying_account.pubkey()),
+            rent_exempt_minimum - 1
+        );
+        let new_account = Keypair::new();
+        let result = bank.transfer(1, &mint_keypair, &new_account.pubkey());
+        assert_eq!(result, Err(TransactionError::InvalidRentPayingAccount));
+    }
+
     #[test]
     fn test_rent_state_changes_sysvars() {
         let GenesisConfigInfo {
@@ -0,0 +1,475 @@
+//! Partitioned epoch rewards
+//!
+//! With `enable_partitioned_epoch_reward` active, vote rewards are still paid in the first block
+//! of an epoch, but stake rewards are only calculated there.  The rewarded stake accounts are
+//! hashed into partitions, and the blocks that follow credit one partition each.  While the
+//! distribution is in progress, transactions referencing the stake program are rejected so that
+//! no stake account can be modified between the calculation and the credit of its reward.  Reads
+//! of a stake account, such as its balance or stake activation, do not include its reward until
+//! the block crediting its partition.
+
+use {
+    super::{Bank, RewardInfo, RewardType, TransactionCheckResult},
+    crate::accounts_db::ErrorCounters,
+    log::*,
+    paychains_sdk::{
+        account::{AccountSharedData, ReadableAccount, WritableAccount},
+        account_utils::StateMut,
+        feature_set,
+        hash::{hashv, Hash},
+        pubkey::Pubkey,
+        system_program,
+        transaction::{SanitizedTransaction, TransactionError},
+    },
+    paychains_stake_program::stake_state::{Stake, StakeState},
+    serde::{Deserialize, Serialize},
+    std::{
+        ops::Range,
+        sync::{atomic::Ordering::Relaxed, Arc},
+    },
+};
+
+/// Number of stake accounts credited per block while distributing partitioned epoch rewards
+pub const STAKE_ACCOUNT_STORES_PER_BLOCK: u64 = 4096;
+
+/// The distribution never spans more than 1/MAX_FACTOR_OF_REWARD_BLOCKS_IN_EPOCH of the slots of
+/// an epoch, no matter how many stake accounts there are
+pub const MAX_FACTOR_OF_REWARD_BLOCKS_IN_EPOCH: u64 = 10;
+
+/// The reward of a stake account, calculated at the epoch boundary and credited later
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, AbiExample)]
+pub struct PartitionedStakeReward {
+    pub stake_pubkey: Pubkey,
+    /// The delegation after redeeming the reward; stored even if the reward is 0, since the
+    /// credits observed have changed
+    pub stake: Stake,
+    pub stake_reward_lamports: u64,
+    pub commission: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, AbiExample)]
+pub enum EpochRewardStatus {
+    /// Stake rewards are being distributed, one partition per block starting with the block after
+    /// the one at `calculation_block_height`
+    Active {
+        calculation_block_height: u64,
+        stake_rewards_by_partition: Arc<Vec<Vec<PartitionedStakeReward>>>,
+    },
+    /// All stake rewards have been credited
+    Inactive,
+}
+
+impl Default for EpochRewardStatus {
+    fn default() -> Self {
+        Self::Inactive
+    }
+}
+
+/// How an active `EpochRewardStatus` is written to snapshots: the calculation block height and
+/// the stake rewards of each partition, including the ones that were already credited
+pub(crate) type SerdeEpochRewardStatus = Option<(u64, Vec<Vec<PartitionedStakeReward>>)>;
+
+impl From<SerdeEpochRewardStatus> for EpochRewardStatus {
+    fn from(status: SerdeEpochRewardStatus) -> Self {
+        match status {
+            Some((calculation_block_height, stake_rewards_by_partition)) => Self::Active {
+                calculation_block_height,
+                stake_rewards_by_partition: Arc::new(stake_rewards_by_partition),
+            },
+            None => Self::Inactive,
+        }
+    }
+}
+
+/// Number of blocks over which `num_stake_rewards` stake rewards are distributed
+pub fn get_reward_distribution_num_blocks(num_stake_rewards: usize, slots_per_epoch: u64) -> u64 {
+    let max_num_blocks = (slots_per_epoch / MAX_FACTOR_OF_REWARD_BLOCKS_IN_EPOCH).max(1);
+    ((num_stake_rewards as u64).saturating_add(STAKE_ACCOUNT_STORES_PER_BLOCK - 1)
+        / STAKE_ACCOUNT_STORES_PER_BLOCK)
+        .clamp(1, max_num_blocks)
+}
+
+/// The partition the reward of `address` falls into; `seed` is the blockhash of the parent of the
+/// block that calculated the rewards
+pub fn get_reward_partition(seed: &Hash, address: &Pubkey, num_partitions: u64) -> usize {
+    let hash = hashv(&[seed.as_ref(), address.as_ref()]);
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&hash.as_ref()[..8]);
+    (u64::from_le_bytes(prefix) % num_partitions) as usize
+}
+
+pub fn hash_rewards_into_partitions(
+    stake_rewards: Vec<PartitionedStakeReward>,
+    seed: &Hash,
+    num_partitions: u64,
+) -> Vec<Vec<PartitionedStakeReward>> {
+    let mut stake_rewards_by_partition = vec![vec![]; num_partitions as usize];
+    for stake_reward in stake_rewards {
+        let partition = get_reward_partition(seed, &stake_reward.stake_pubkey, num_partitions);
+        stake_rewards_by_partition[partition].push(stake_reward);
+    }
+    stake_rewards_by_partition
+}
+
+impl Bank {
+    pub(super) fn is_partitioned_rewards_feature_enabled(&self) -> bool {
+        self.feature_set
+            .is_active(&feature_set::enable_partitioned_epoch_reward::id())
+    }
+
+    pub fn epoch_reward_status(&self) -> &EpochRewardStatus {
+        &self.epoch_reward_status
+    }
+
+    /// Whether stake rewards of the previous epoch remain to be credited after this bank
+    pub fn is_epoch_rewards_distribution_active(&self) -> bool {
+        matches!(self.epoch_reward_status, EpochRewardStatus::Active { .. })
+    }
+
+    /// The height of the block that credits the last partition of the stake rewards being
+    /// distributed, if any
+    pub fn epoch_rewards_complete_block_height(&self) -> Option<u64> {
+        match &self.epoch_reward_status {
+            EpochRewardStatus::Active {
+                calculation_block_height,
+                stake_rewards_by_partition,
+            } => Some(calculation_block_height + stake_rewards_by_partition.len() as u64),
+            EpochRewardStatus::Inactive => None,
+        }
+    }
+
+    /// The number of partitions over which the stake rewards calculated in this block are
+    /// distributed, zero if there were none to distribute, None unless this block calculated
+    /// partitioned rewards
+    pub fn num_reward_partitions_calculated(&self) -> Option<u64> {
+        match &self.epoch_reward_status {
+            EpochRewardStatus::Active {
+                calculation_block_height,
+                stake_rewards_by_partition,
+            } if *calculation_block_height == self.block_height() => {
+                Some(stake_rewards_by_partition.len() as u64)
+            }
+            _ => (self.is_partitioned_rewards_feature_enabled()
+                && self.epoch_schedule.get_epoch(self.parent_slot) < self.epoch)
+                .then(|| 0),
+        }
+    }
+
+    pub(crate) fn epoch_reward_status_to_serialize(
+        &self,
+    ) -> Option<(u64, &Vec<Vec<PartitionedStakeReward>>)> {
+        match &self.epoch_reward_status {
+            EpochRewardStatus::Active {
+                calculation_block_height,
+                stake_rewards_by_partition,
+            } => Some((*calculation_block_height, stake_rewards_by_partition)),
+            EpochRewardStatus::Inactive => None,
+        }
+    }
+
+    /// Start distributing `stake_rewards` over the blocks following this one
+    pub(super) fn set_epoch_reward_status_active(
+        &mut self,
+        stake_rewards: Vec<PartitionedStakeReward>,
+    ) {
+        let num_partitions = get_reward_distribution_num_blocks(
+            stake_rewards.len(),
+            self.epoch_schedule.get_slots_in_epoch(self.epoch),
+        );
+        let stake_rewards_by_partition =
+            hash_rewards_into_partitions(stake_rewards, &self.last_blockhash(), num_partitions);
+        self.epoch_reward_status = EpochRewardStatus::Active {
+            calculation_block_height: self.block_height(),
+            stake_rewards_by_partition: Arc::new(stake_rewards_by_partition),
+        };
+        datapoint_info!(
+            "epoch-rewards-status-update",
+            ("slot", self.slot(), i64),
+            ("block_height", self.block_height(), i64),
+            ("active", 1, i64),
+            ("num_partitions", num_partitions, i64),
+        );
+    }
+
+    /// Credit the partition of stake rewards that falls on this block
+    pub(super) fn distribute_partitioned_epoch_rewards(&mut self) {
+        if let EpochRewardStatus::Active {
+            calculation_block_height,
+            ..
+        } = self.epoch_reward_status
+        {
+            if self.block_height() > calculation_block_height {
+                let partition_index = (self.block_height() - calculation_block_height - 1) as usize;
+                self.distribute_epoch_reward_partitions(partition_index..partition_index + 1);
+            }
+        }
+    }
+
+    /// Credit all partitions not credited yet; a new epoch can only begin once the stake rewards
+    /// of the previous one have been distributed
+    pub(super) fn distribute_remaining_partitioned_epoch_rewards(&mut self) {
+        let (calculation_block_height, num_partitions) = match &self.epoch_reward_status {
+            EpochRewardStatus::Active {
+                calculation_block_height,
+                stake_rewards_by_partition,
+            } => (*calculation_block_height, stake_rewards_by_partition.len()),
+            EpochRewardStatus::Inactive => return,
+        };
+        // Blocks after the calculation block and before this one credited a partition each
+        let num_distributed = self
+            .block_height()
+            .saturating_sub(calculation_block_height + 1);
+        let first_remaining_partition = num_distributed as usize;
+        if first_remaining_partition < num_partitions {
+            warn!(
+                "crediting {} remaining epoch reward partitions at the epoch boundary, slot {}",
+                num_partitions - first_remaining_partition,
+                self.slot()
+            );
+        }
+        self.distribute_epoch_reward_partitions(first_remaining_partition..num_partitions);
+    }
+
+    fn distribute_epoch_reward_partitions(&mut self, partitions: Range<usize>) {
+        let stake_rewards_by_partition = match &self.epoch_reward_status {
+            EpochRewardStatus::Active {
+                stake_rewards_by_partition,
+                ..
+            } => Arc::clone(stake_rewards_by_partition),
+            EpochRewardStatus::Inactive => return,
+        };
+        let num_partitions = stake_rewards_by_partition.len();
+        let partitions = partitions.start.min(num_partitions)..partitions.end.min(num_partitions);
+        for partition in &stake_rewards_by_partition[partitions.clone()] {
+            self.store_stake_rewards_in_partition(partition);
+        }
+
+        if partitions.end >= num_partitions {
+            self.epoch_reward_status = EpochRewardStatus::Inactive;
+            datapoint_info!(
+                "epoch-rewards-status-update",
+                ("slot", self.slot(), i64),
+                ("block_height", self.block_height(), i64),
+                ("active", 0, i64),
+                ("num_partitions", num_partitions, i64),
+            );
+        }
+    }
+
+    fn store_stake_rewards_in_partition(&self, partition: &[PartitionedStakeReward]) {
+        let mut total_stake_rewards = 0u64;
+        let mut rewards = Vec::with_capacity(partition.len());
+        for stake_reward in partition {
+            let PartitionedStakeReward {
+                stake_pubkey,
+                stake,
+                stake_reward_lamports,
+                commission,
+            } = stake_reward;
+
+            // The stake account may have been credited since the rewards were calculated, so
+            // update the account as it is now rather than storing a copy from the calculation.
+            // The reward lamports were accounted for at the calculation, so they are credited
+            // even if the account no longer holds the delegation, as a system account if it is
+            // gone.
+            let mut stake_account = match self.get_account_with_fixed_root(stake_pubkey) {
+                Some(stake_account) => stake_account,
+                None => {
+                    warn!(
+                        "stake account {} missing, epoch reward credited to a system account",
+                        stake_pubkey
+                    );
+                    AccountSharedData::new(0, 0, &system_program::id())
+                }
+            };
+            match stake_account.state() {
+                Ok(StakeState::Stake(meta, _stake)) => {
+                    if let Err(err) = stake_account.set_state(&StakeState::Stake(meta, *stake)) {
+                        warn!(
+                            "epoch reward for {} credited without its delegation: {}",
+                            stake_pubkey, err
+                        );
+                    }
+                }
+                state => warn!(
+                    "account {} has unexpected state {:?}, epoch reward credited without its \
+                     delegation",
+                    stake_pubkey, state
+                ),
+            }
+            if let Err(err) = stake_account.checked_add_lamports(*stake_reward_lamports) {
+                // Only reachable if the account holds close to u64::MAX lamports, more than the
+                // capitalization; the reward is then not minted
+                warn!("epoch reward for {} failed: {}", stake_pubkey, err);
+                continue;
+            }
+            self.store_account(stake_pubkey, &stake_account);
+
+            total_stake_rewards = total_stake_rewards.saturating_add(*stake_reward_lamports);
+            if *stake_reward_lamports > 0 {
+                rewards.push((
+                    *stake_pubkey,
+                    RewardInfo {
+                        reward_type: RewardType::Staking,
+                        lamports: *stake_reward_lamports as i64,
+                        post_balance: stake_account.lamports(),
+                        commission: Some(*commission),
+                    },
+                ));
+            }
+        }
+
+        self.capitalization.fetch_add(total_stake_rewards, Relaxed);
+        self.rewards.write().unwrap().append(&mut rewards);
+        datapoint_info!(
+            "epoch-rewards-partition",
+            ("slot", self.slot(), i64),
+            ("num_stake_accounts", partition.len(), i64),
+            ("stake_rewards", total_stake_rewards, i64),
+        );
+    }
+
+    /// Reject transactions referencing the stake program while stake rewards are being
+    /// distributed
+    pub(super) fn check_epoch_rewards_restrictions(
+        &self,
+        sanitized_txs: &[SanitizedTransaction],
+        check_results: Vec<TransactionCheckResult>,
+        error_counters: &mut ErrorCounters,
+    ) -> Vec<TransactionCheckResult> {
+        if !self.is_epoch_rewards_distribution_active() {
+            return check_results;
+        }
+        sanitized_txs
+            .iter()
+            .zip(check_results)
+            .map(|(sanitized_tx, (check_result, nonce))| {
+                if check_result.is_ok() {
+                    if let Some(account_index) = sanitized_tx
+                        .message()
+                        .account_keys_iter()
+                        .position(|key| key == &paychains_stake_program::id())
+                    {
+                        error_counters.program_execution_temporarily_restricted += 1;
+                        return (
+                            Err(TransactionError::ProgramExecutionTemporarilyRestricted {
+                                account_index: account_index as u8,
+                            }),
+                            None,
+                        );
+                    }
+                }
+                (check_result, nonce)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, paychains_stake_program::stake_state::Delegation};
+
+    fn new_stake_reward(stake_pubkey: Pubkey) -> PartitionedStakeReward {
+        PartitionedStakeReward {
+            stake_pubkey,
+            stake: Stake {
+                delegation: Delegation::default(),
+                credits_observed: 0,
+            },
+            stake_reward_lamports: 1,
+            commission: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_reward_distribution_num_blocks() {
+        let slots_per_epoch = 432_000;
+        assert_eq!(get_reward_distribution_num_blocks(0, slots_per_epoch), 1);
+        assert_eq!(get_reward_distribution_num_blocks(1, slots_per_epoch), 1);
+        assert_eq!(
+            get_reward_distribution_num_blocks(
+                STAKE_ACCOUNT_STORES_PER_BLOCK as usize,
+                slots_per_epoch
+            ),
+            1
+        );
+        assert_eq!(
+            get_reward_distribution_num_blocks(
+                STAKE_ACCOUNT_STORES_PER_BLOCK as usize + 1,
+                slots_per_epoch
+            ),
+            2
+        );
+
+        // Capped to a fraction of the epoch
+        let slots_per_epoch = 32;
+        assert_eq!(
+            get_reward_distribution_num_blocks(
+                10 * STAKE_ACCOUNT_STORES_PER_BLOCK as usize,
+                slots_per_epoch
+            ),
+            slots_per_epoch / MAX_FACTOR_OF_REWARD_BLOCKS_IN_EPOCH
+        );
+        assert_eq!(
+            get_reward_distribution_num_blocks(10 * STAKE_ACCOUNT_STORES_PER_BLOCK as usize, 1),
+            1
+        );
+    }
+
+    #[test]
+    fn test_hash_rewards_into_partitions() {
+        let seed = Hash::new_unique();
+        let num_partitions = 7;
+        let stake_rewards: Vec<_> = (0..1000)
+            .map(|_| new_stake_reward(Pubkey::new_unique()))
+            .collect();
+
+        let stake_rewards_by_partition =
+            hash_rewards_into_partitions(stake_rewards.clone(), &seed, num_partitions);
+        assert_eq!(stake_rewards_by_partition.len(), num_partitions as usize);
+        assert_eq!(
+            stake_rewards_by_partition
+                .iter()
+                .map(|partition| partition.len())
+                .sum::<usize>(),
+            stake_rewards.len()
+        );
+        for (partition_index, partition) in stake_rewards_by_partition.iter().enumerate() {
+            for stake_reward in partition {
+                assert_eq!(
+                    get_reward_partition(&seed, &stake_reward.stake_pubkey, num_partitions),
+                    partition_index
+                );
+            }
+        }
+
+        // Partitions only depend on the seed
+        assert_eq!(
+            hash_rewards_into_partitions(stake_rewards.clone(), &seed, num_partitions),
+            stake_rewards_by_partition
+        );
+        assert_ne!(
+            hash_rewards_into_partitions(stake_rewards, &Hash::new_unique(), num_partitions),
+            stake_rewards_by_partition
+        );
+    }
+
+    #[test]
+    fn test_epoch_reward_status_serde() {
+        let status = EpochRewardStatus::from(None);
+        assert_eq!(status, EpochRewardStatus::Inactive);
+
+        let stake_rewards_by_partition = vec![
+            vec![new_stake_reward(Pubkey::new_unique())],
+            vec![new_stake_reward(Pubkey::new_unique())],
+        ];
+        let status = EpochRewardStatus::from(Some((5, stake_rewards_by_partition.clone())));
+        assert_eq!(
+            status,
+            EpochRewardStatus::Active {
+                calculation_block_height: 5,
+                stake_rewards_by_partition: Arc::new(stake_rewards_by_partition),
+            }
+        );
+    }
+}
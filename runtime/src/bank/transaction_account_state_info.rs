@@ -49,6 +49,12 @@ impl Bank {
         let require_rent_exempt_accounts = self
             .feature_set
             .is_active(&feature_set::require_rent_exempt_accounts::id());
+        let include_account_index_in_err = self
+            .feature_set
+            .is_active(&feature_set::include_account_index_in_rent_error::id());
+        let prevent_crediting_accounts_that_end_rent_paying = self
+            .feature_set
+            .is_active(&feature_set::prevent_crediting_accounts_that_end_rent_paying::id());
         for (i, (pre_state_info, post_state_info)) in
             pre_state_infos.iter().zip(post_state_infos).enumerate()
         {
@@ -57,6 +63,8 @@ impl Bank {
                 post_state_info.rent_state.as_ref(),
                 transaction_context,
                 i,
+                include_account_index_in_err,
+                prevent_crediting_accounts_that_end_rent_paying,
             ) {
                 // Feature gate only wraps the actual error return so that the metrics and debug
                 // logging generated by `check_rent_state()` can be examined before feature
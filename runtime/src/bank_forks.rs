@@ -2,7 +2,7 @@
 
 use {
     crate::{
-        accounts_background_service::{AbsRequestSender, SnapshotRequest},
+        accounts_background_service::{AbsRequestSender, RequestedSnapshotKind, SnapshotRequest},
         bank::Bank,
         snapshot_config::SnapshotConfig,
     },
@@ -41,6 +41,7 @@ pub struct BankForks {
 
     pub accounts_hash_interval_slots: Slot,
     last_accounts_hash_slot: Slot,
+    requested_snapshot_kind: Option<RequestedSnapshotKind>,
 }
 
 impl Index<u64> for BankForks {
@@ -145,6 +146,7 @@ impl BankForks {
             snapshot_config: None,
             accounts_hash_interval_slots: std::u64::MAX,
             last_accounts_hash_slot: root,
+            requested_snapshot_kind: None,
         }
     }
 
@@ -236,6 +238,9 @@ impl BankForks {
         let mut total_squash_accounts_store_ms = 0;
         let mut total_squash_cache_ms = 0;
         let mut total_snapshot_ms = 0;
+        let mut snapshot_requested = false;
+        let snapshot_creation_enabled = self.snapshot_config.is_some()
+            && accounts_background_request_sender.is_snapshot_creation_enabled();
         for bank in banks.iter() {
             let bank_slot = bank.slot();
             if bank.block_height() % self.accounts_hash_interval_slots == 0
@@ -251,11 +256,10 @@ impl BankForks {
                 is_root_bank_squashed = bank_slot == root;
 
                 let mut snapshot_time = Measure::start("squash::snapshot_time");
-                if self.snapshot_config.is_some()
-                    && accounts_background_request_sender.is_snapshot_creation_enabled()
-                {
+                if snapshot_creation_enabled {
                     let snapshot_root_bank = self.root_bank();
                     let root_slot = snapshot_root_bank.slot();
+                    snapshot_requested = true;
                     if let Err(e) =
                         accounts_background_request_sender.send_snapshot_request(SnapshotRequest {
                             snapshot_root_bank,
@@ -263,6 +267,7 @@ impl BankForks {
                             // if another `set_root()` is called before the snapshots package
                             // can be generated
                             status_cache_slot_deltas: bank.src.slot_deltas(&bank.src.roots()),
+                            requested_kind: self.requested_snapshot_kind.take(),
                         })
                     {
                         warn!(
@@ -284,6 +289,29 @@ impl BankForks {
             total_squash_accounts_store_ms += squash_timing.squash_accounts_store_ms as i64;
             total_squash_cache_ms += squash_timing.squash_cache_ms as i64;
         }
+        if snapshot_creation_enabled && !snapshot_requested {
+            if let Some(requested_kind) = self.requested_snapshot_kind.take() {
+                let mut snapshot_time = Measure::start("squash::snapshot_time");
+                info!(
+                    "Sending requested {:?} snapshot request for slot {}",
+                    requested_kind, root
+                );
+                if let Err(e) =
+                    accounts_background_request_sender.send_snapshot_request(SnapshotRequest {
+                        snapshot_root_bank: self.root_bank(),
+                        status_cache_slot_deltas: root_bank.src.slot_deltas(&root_bank.src.roots()),
+                        requested_kind: Some(requested_kind),
+                    })
+                {
+                    warn!(
+                        "Error sending requested snapshot request for bank: {}, err: {:?}",
+                        root, e
+                    );
+                }
+                snapshot_time.stop();
+                total_snapshot_ms += snapshot_time.as_ms() as i64;
+            }
+        }
         let new_tx_count = root_bank.transaction_count();
         let mut prune_time = Measure::start("set_root::prune");
         let (removed_banks, prune_slots_ms, prune_remove_ms) =
@@ -487,6 +515,28 @@ impl BankForks {
         &self.snapshot_config
     }
 
+    /// Request a snapshot of the next root, regardless of the configured snapshot intervals.
+    /// Returns false if snapshots of the requested kind are disabled, in which case no snapshot
+    /// would ever be taken.
+    pub fn request_snapshot(&mut self, requested_kind: RequestedSnapshotKind) -> bool {
+        let enabled = match &self.snapshot_config {
+            None => false,
+            Some(snapshot_config) => {
+                snapshot_config.full_snapshot_archive_interval_slots != Slot::MAX
+                    && (requested_kind == RequestedSnapshotKind::Full
+                        || snapshot_config.incremental_snapshot_archive_interval_slots != Slot::MAX)
+            }
+        };
+        if enabled {
+            self.requested_snapshot_kind = Some(requested_kind);
+        }
+        enabled
+    }
+
+    pub fn requested_snapshot_kind(&self) -> Option<RequestedSnapshotKind> {
+        self.requested_snapshot_kind
+    }
+
     pub fn set_accounts_hash_interval_slots(&mut self, accounts_interval_slots: u64) {
         self.accounts_hash_interval_slots = accounts_interval_slots;
     }
@@ -502,6 +552,7 @@ mod tests {
                 create_genesis_config, create_genesis_config_with_leader, GenesisConfigInfo,
             },
         },
+        crossbeam_channel::unbounded,
         paychains_sdk::{
             clock::UnixTimestamp,
             hash::Hash,
@@ -788,4 +839,57 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_bank_forks_requested_snapshot() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let mut bank_forks = BankForks::new_from_banks(&[bank0.clone()], 0);
+        let (snapshot_request_sender, snapshot_request_receiver) = unbounded();
+        let abs_request_sender = AbsRequestSender::new(Some(snapshot_request_sender));
+
+        // Snapshots are disabled, so the request is rejected
+        assert!(!bank_forks.request_snapshot(RequestedSnapshotKind::Full));
+        assert_eq!(bank_forks.requested_snapshot_kind(), None);
+
+        // A snapshot config with the intervals disabled doesn't take snapshots either
+        bank_forks.set_snapshot_config(Some(SnapshotConfig {
+            full_snapshot_archive_interval_slots: Slot::MAX,
+            incremental_snapshot_archive_interval_slots: Slot::MAX,
+            ..SnapshotConfig::default()
+        }));
+        assert!(!bank_forks.request_snapshot(RequestedSnapshotKind::Full));
+        assert!(!bank_forks.request_snapshot(RequestedSnapshotKind::Incremental));
+        assert_eq!(bank_forks.requested_snapshot_kind(), None);
+
+        // Only incremental snapshots are disabled
+        bank_forks.set_snapshot_config(Some(SnapshotConfig {
+            incremental_snapshot_archive_interval_slots: Slot::MAX,
+            ..SnapshotConfig::default()
+        }));
+        assert!(!bank_forks.request_snapshot(RequestedSnapshotKind::Incremental));
+        assert_eq!(bank_forks.requested_snapshot_kind(), None);
+
+        bank_forks.set_snapshot_config(Some(SnapshotConfig::default()));
+        assert!(bank_forks.request_snapshot(RequestedSnapshotKind::Full));
+        assert_eq!(
+            bank_forks.requested_snapshot_kind(),
+            Some(RequestedSnapshotKind::Full)
+        );
+
+        let bank1 = bank_forks.insert(Bank::new_from_parent(&bank0, &Pubkey::default(), 1));
+        bank_forks.set_root(1, &abs_request_sender, None);
+        let snapshot_request = snapshot_request_receiver.try_recv().unwrap();
+        assert_eq!(snapshot_request.snapshot_root_bank.slot(), 1);
+        assert_eq!(
+            snapshot_request.requested_kind,
+            Some(RequestedSnapshotKind::Full)
+        );
+        assert_eq!(bank_forks.requested_snapshot_kind(), None);
+
+        // Without a pending request, nothing is sent outside the accounts hash interval
+        bank_forks.insert(Bank::new_from_parent(&bank1, &Pubkey::default(), 2));
+        bank_forks.set_root(2, &abs_request_sender, None);
+        assert!(snapshot_request_receiver.try_recv().is_err());
+    }
 }
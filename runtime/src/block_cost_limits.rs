@@ -60,5 +60,7 @@ pub const MAX_BLOCK_UNITS: u64 =
 /// reduce block's parallelism.
 pub const MAX_WRITABLE_ACCOUNT_UNITS: u64 = MAX_BLOCK_REPLAY_TIME_US * COMPUTE_UNIT_TO_US_RATIO;
 
-/// max length of account data in a slot (bytes)
-pub const MAX_ACCOUNT_DATA_LEN: u64 = 100_000_000;
+/// max length of account data allocated in a slot (bytes). Once the
+/// `cap_accounts_data_size_per_block` feature is active, a block whose transactions allocate more
+/// is invalid, see `Bank::check_accounts_data_block_limit()`.
+pub const MAX_ACCOUNT_DATA_BLOCK_LEN: u64 = 100_000_000;
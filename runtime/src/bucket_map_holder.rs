@@ -1,13 +1,13 @@
 use {
     crate::{
-        accounts_index::{AccountsIndexConfig, IndexValue},
+        accounts_index::{AccountMapEntry, AccountsIndexConfig, IndexValue},
         bucket_map_holder_stats::BucketMapHolderStats,
         in_mem_accounts_index::{InMemAccountsIndex, SlotT},
         waitable_condvar::WaitableCondvar,
     },
     paychains_bucket_map::bucket_map::{BucketMap, BucketMapConfig},
     paychains_measure::measure::Measure,
-    paychains_sdk::{clock::SLOT_MS, timing::AtomicInterval},
+    paychains_sdk::{clock::SLOT_MS, pubkey::Pubkey, timing::AtomicInterval},
     std::{
         fmt::Debug,
         sync::{
@@ -37,10 +37,13 @@ pub struct BucketMapHolder<T: IndexValue> {
 
     pub threads: usize,
 
-    // how much mb are we allowed to keep in the in-mem index?
+    // how many bytes are we allowed to keep in the in-mem index?
     // Rest goes to disk.
-    pub mem_budget_mb: Option<usize>,
-    ages_to_stay_in_cache: Age,
+    pub mem_budget_bytes: Option<usize>,
+    // configured number of ages an entry stays in the in-mem index after it was last used
+    max_ages_to_stay_in_cache: Age,
+    // lowered below `max_ages_to_stay_in_cache` while the in-mem index exceeds its budget
+    ages_to_stay_in_cache: AtomicU8,
 
     /// startup is a special time for flush to focus on moving everything to disk as fast and efficiently as possible
     /// with less thread count limitations. LRU and access patterns are not important. Freeing memory
@@ -70,11 +73,58 @@ impl<T: IndexValue> BucketMapHolder<T> {
         // That's what we want. 0..255, then back to 0.
         self.age.fetch_add(1, Ordering::Release);
         assert!(previous >= self.bins); // we should not have increased age before previous age was fully flushed
+        self.adjust_ages_to_stay_in_cache();
         self.wait_dirty_or_aged.notify_all(); // notify all because we can age scan in parallel
     }
 
     pub fn future_age_to_flush(&self) -> Age {
-        self.current_age().wrapping_add(self.ages_to_stay_in_cache)
+        self.current_age()
+            .wrapping_add(self.ages_to_stay_in_cache.load(Ordering::Relaxed))
+    }
+
+    pub fn ages_to_stay_in_cache(&self) -> Age {
+        self.ages_to_stay_in_cache.load(Ordering::Relaxed)
+    }
+
+    /// assumes 1 entry in the slot list. Ignores overhead of the HashMap and such
+    pub fn approx_size_of_one_entry() -> usize {
+        std::mem::size_of::<T>()
+            + std::mem::size_of::<Pubkey>()
+            + std::mem::size_of::<AccountMapEntry<T>>()
+    }
+
+    /// approximate number of bytes used by the entries in the in-mem index
+    pub fn approx_mem_bytes(&self) -> usize {
+        self.stats.count_in_mem.load(Ordering::Relaxed) * Self::approx_size_of_one_entry()
+    }
+
+    /// true if the in-mem index holds more than its memory budget
+    pub fn exceeds_mem_budget(&self) -> bool {
+        self.mem_budget_bytes
+            .map(|budget| self.approx_mem_bytes() >= budget)
+            .unwrap_or_default()
+    }
+
+    /// Called once per age. While the in-mem index exceeds its budget, entries are evicted a
+    /// few ages sooner after their last use. Once the index has shrunk well below its budget,
+    /// they stay in memory for the configured number of ages again.
+    fn adjust_ages_to_stay_in_cache(&self) {
+        let budget = match self.mem_budget_bytes {
+            Some(budget) => budget,
+            None => return,
+        };
+        let mem_bytes = self.approx_mem_bytes();
+        let ages = self.ages_to_stay_in_cache();
+        let new_ages = if mem_bytes >= budget {
+            let min_ages = std::cmp::min(1, self.max_ages_to_stay_in_cache);
+            std::cmp::max(ages.saturating_sub(1), min_ages)
+        } else if mem_bytes < budget / 4 * 3 {
+            std::cmp::min(ages.saturating_add(1), self.max_ages_to_stay_in_cache)
+        } else {
+            ages
+        };
+        self.ages_to_stay_in_cache
+            .store(new_ages, Ordering::Relaxed);
     }
 
     fn has_age_interval_elapsed(&self) -> bool {
@@ -153,12 +203,17 @@ impl<T: IndexValue> BucketMapHolder<T> {
 
         let mut bucket_config = BucketMapConfig::new(bins);
         bucket_config.drives = config.as_ref().and_then(|config| config.drives.clone());
-        let mem_budget_mb = config.as_ref().and_then(|config| config.index_limit_mb);
-        // only allocate if mem_budget_mb is Some
-        let disk = mem_budget_mb.map(|_| BucketMap::new(bucket_config));
+        let mem_budget_bytes = config.as_ref().and_then(|config| {
+            config
+                .index_limit_bytes
+                .or_else(|| config.index_limit_mb.map(|mb| mb * 1024 * 1024))
+        });
+        // only allocate if mem_budget_bytes is Some
+        let disk = mem_budget_bytes.map(|_| BucketMap::new(bucket_config));
         Self {
             disk,
-            ages_to_stay_in_cache,
+            max_ages_to_stay_in_cache: ages_to_stay_in_cache,
+            ages_to_stay_in_cache: AtomicU8::new(ages_to_stay_in_cache),
             count_ages_flushed: AtomicUsize::default(),
             age: AtomicU8::default(),
             stats: BucketMapHolderStats::new(bins),
@@ -167,7 +222,7 @@ impl<T: IndexValue> BucketMapHolder<T> {
             age_timer: AtomicInterval::default(),
             bins,
             startup: AtomicBool::default(),
-            mem_budget_mb,
+            mem_budget_bytes,
             threads,
         }
     }
@@ -383,6 +438,63 @@ pub mod tests {
         };
         let test = BucketMapHolder::<u64>::new(bins, &Some(config), 1);
         assert!(test.is_disk_index_enabled());
+        assert_eq!(test.mem_budget_bytes, Some(0));
+
+        let config = AccountsIndexConfig {
+            index_limit_mb: Some(1),
+            index_limit_bytes: Some(1000),
+            ..AccountsIndexConfig::default()
+        };
+        let test = BucketMapHolder::<u64>::new(bins, &Some(config), 1);
+        assert!(test.is_disk_index_enabled());
+        assert_eq!(test.mem_budget_bytes, Some(1000));
+    }
+
+    #[test]
+    fn test_adjust_ages_to_stay_in_cache() {
+        paychains_logger::setup();
+        let bins = 1;
+        let max_ages = 5;
+        let config = AccountsIndexConfig {
+            index_limit_mb: Some(1),
+            ages_to_stay_in_cache: Some(max_ages),
+            ..AccountsIndexConfig::default()
+        };
+        let test = BucketMapHolder::<u64>::new(bins, &Some(config), 1);
+        let budget = test.mem_budget_bytes.unwrap();
+        let entries_in_budget = budget / BucketMapHolder::<u64>::approx_size_of_one_entry();
+        assert_eq!(test.ages_to_stay_in_cache(), max_ages);
+
+        // over budget: entries are evicted sooner, but never less than 1 age after use
+        test.stats
+            .count_in_mem
+            .store(entries_in_budget + 1, Ordering::Relaxed);
+        assert!(test.exceeds_mem_budget());
+        for ages in (1..max_ages).rev() {
+            test.adjust_ages_to_stay_in_cache();
+            assert_eq!(test.ages_to_stay_in_cache(), ages);
+        }
+        test.adjust_ages_to_stay_in_cache();
+        assert_eq!(test.ages_to_stay_in_cache(), 1);
+
+        // slightly below budget: no change
+        test.stats
+            .count_in_mem
+            .store(entries_in_budget * 9 / 10, Ordering::Relaxed);
+        assert!(!test.exceeds_mem_budget());
+        test.adjust_ages_to_stay_in_cache();
+        assert_eq!(test.ages_to_stay_in_cache(), 1);
+
+        // well below budget: back to the configured ages
+        test.stats
+            .count_in_mem
+            .store(entries_in_budget / 2, Ordering::Relaxed);
+        for ages in 2..=max_ages {
+            test.adjust_ages_to_stay_in_cache();
+            assert_eq!(test.ages_to_stay_in_cache(), ages);
+        }
+        test.adjust_ages_to_stay_in_cache();
+        assert_eq!(test.ages_to_stay_in_cache(), max_ages);
     }
 
     #[test]
@@ -1,8 +1,12 @@
 use {
-    crate::{accounts_index::IndexValue, bucket_map_holder::BucketMapHolder},
+    crate::{
+        accounts_index::IndexValue,
+        bucket_map_holder::{Age, BucketMapHolder},
+    },
+    serde::{Deserialize, Serialize},
     paychains_sdk::timing::AtomicInterval,
     std::{
-        fmt::Debug,
+        fmt::{self, Debug, Display},
         sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
     },
 };
@@ -46,6 +50,11 @@ pub struct BucketMapHolderStats {
     pub flush_scan_update_us: AtomicU64,
     pub flush_remove_us: AtomicU64,
     pub flush_grow_us: AtomicU64,
+    // totals since startup, unlike the counters above they are not reset when reported
+    pub total_gets_from_mem: AtomicU64,
+    pub total_gets_missing: AtomicU64,
+    pub total_load_disk_found: AtomicU64,
+    pub total_load_disk_missing: AtomicU64,
     last_was_startup: AtomicBool,
     last_time: AtomicInterval,
     bins: u64,
@@ -151,6 +160,50 @@ impl BucketMapHolderStats {
         }
     }
 
+    fn disk_per_bucket_counts<T: IndexValue>(&self, storage: &BucketMapHolder<T>) -> Vec<usize> {
+        storage
+            .disk
+            .as_ref()
+            .map(|disk| {
+                (0..self.bins)
+                    .into_iter()
+                    .map(|i| disk.get_bucket_from_index(i as usize).bucket_len() as usize)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    }
+
+    /// how the accounts index is currently spread between memory and disk, and how often
+    /// lookups were served from memory since startup
+    pub fn occupancy<T: IndexValue>(&self, storage: &BucketMapHolder<T>) -> AccountsIndexOccupancy {
+        let in_mem_per_bucket_counts = self
+            .per_bucket_count
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        let (min_in_bin_mem, max_in_bin_mem, _, _) = Self::get_stats(in_mem_per_bucket_counts);
+        let (min_in_bin_disk, max_in_bin_disk, count_on_disk, _) =
+            Self::get_stats(self.disk_per_bucket_counts(storage));
+        AccountsIndexOccupancy {
+            bins: self.bins as usize,
+            disk_index_enabled: storage.is_disk_index_enabled(),
+            count: self.total_count(),
+            count_in_mem: self.count_in_mem.load(Ordering::Relaxed),
+            min_in_bin_mem,
+            max_in_bin_mem,
+            count_on_disk,
+            min_in_bin_disk,
+            max_in_bin_disk,
+            approx_mem_bytes: storage.approx_mem_bytes(),
+            mem_budget_bytes: storage.mem_budget_bytes,
+            ages_to_stay_in_cache: storage.ages_to_stay_in_cache(),
+            gets_from_mem: self.total_gets_from_mem.load(Ordering::Relaxed),
+            gets_missing: self.total_gets_missing.load(Ordering::Relaxed),
+            load_disk_found: self.total_load_disk_found.load(Ordering::Relaxed),
+            load_disk_missing: self.total_load_disk_missing.load(Ordering::Relaxed),
+        }
+    }
+
     pub fn report_stats<T: IndexValue>(&self, storage: &BucketMapHolder<T>) {
         let elapsed_ms = self.last_time.elapsed_ms();
         if elapsed_ms < STATS_INTERVAL_MS {
@@ -169,14 +222,7 @@ impl BucketMapHolderStats {
             .map(|count| count.load(Ordering::Relaxed))
             .collect::<Vec<_>>();
         let disk = storage.disk.as_ref();
-        let disk_per_bucket_counts = disk
-            .map(|disk| {
-                (0..self.bins)
-                    .into_iter()
-                    .map(|i| disk.get_bucket_from_index(i as usize).bucket_len() as usize)
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
+        let disk_per_bucket_counts = self.disk_per_bucket_counts(storage);
         let in_mem_stats = Self::get_stats(in_mem_per_bucket_counts);
         let disk_stats = Self::get_stats(disk_per_bucket_counts);
 
@@ -202,6 +248,12 @@ impl BucketMapHolderStats {
                     i64
                 ),
                 ("count", self.total_count(), i64),
+                ("approx_mem_bytes", storage.approx_mem_bytes(), i64),
+                (
+                    "ages_to_stay_in_cache",
+                    storage.ages_to_stay_in_cache(),
+                    i64
+                ),
                 (
                     "bg_waiting_percent",
                     Self::calc_percent(
@@ -440,6 +492,12 @@ impl BucketMapHolderStats {
                     i64
                 ),
                 ("count", self.total_count(), i64),
+                ("approx_mem_bytes", storage.approx_mem_bytes(), i64),
+                (
+                    "ages_to_stay_in_cache",
+                    storage.ages_to_stay_in_cache(),
+                    i64
+                ),
                 (
                     "bg_waiting_percent",
                     Self::calc_percent(
@@ -524,3 +582,113 @@ impl BucketMapHolderStats {
         }
     }
 }
+
+/// Point in time view of the accounts index, see `BucketMapHolderStats::occupancy()`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsIndexOccupancy {
+    pub bins: usize,
+    pub disk_index_enabled: bool,
+    pub count: usize,
+    pub count_in_mem: usize,
+    pub min_in_bin_mem: usize,
+    pub max_in_bin_mem: usize,
+    pub count_on_disk: usize,
+    pub min_in_bin_disk: usize,
+    pub max_in_bin_disk: usize,
+    pub approx_mem_bytes: usize,
+    pub mem_budget_bytes: Option<usize>,
+    pub ages_to_stay_in_cache: Age,
+    pub gets_from_mem: u64,
+    pub gets_missing: u64,
+    pub load_disk_found: u64,
+    pub load_disk_missing: u64,
+}
+
+impl AccountsIndexOccupancy {
+    fn rate(hits: u64, misses: u64) -> f64 {
+        let total = hits.saturating_add(misses);
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+
+    /// fraction of lookups that found their entry in the in-mem index
+    pub fn mem_hit_rate(&self) -> f64 {
+        Self::rate(self.gets_from_mem, self.gets_missing)
+    }
+
+    /// fraction of the lookups missing the in-mem index that found their entry on disk
+    pub fn disk_hit_rate(&self) -> f64 {
+        Self::rate(self.load_disk_found, self.load_disk_missing)
+    }
+}
+
+impl Display for AccountsIndexOccupancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Bins: {}", self.bins)?;
+        writeln!(f, "Accounts: {}", self.count)?;
+        writeln!(
+            f,
+            "In memory: {} (per bin: {} - {})",
+            self.count_in_mem, self.min_in_bin_mem, self.max_in_bin_mem
+        )?;
+        match self.mem_budget_bytes {
+            Some(budget) => writeln!(
+                f,
+                "Approximate memory: {} of {} bytes budget",
+                self.approx_mem_bytes, budget
+            )?,
+            None => writeln!(f, "Approximate memory: {} bytes", self.approx_mem_bytes)?,
+        }
+        if self.disk_index_enabled {
+            writeln!(
+                f,
+                "On disk: {} (per bin: {} - {})",
+                self.count_on_disk, self.min_in_bin_disk, self.max_in_bin_disk
+            )?;
+            writeln!(f, "Ages to stay in memory: {}", self.ages_to_stay_in_cache)?;
+        } else {
+            writeln!(f, "On disk: disabled")?;
+        }
+        writeln!(
+            f,
+            "Memory hit rate: {:.2}% of {} lookups",
+            self.mem_hit_rate() * 100.0,
+            self.gets_from_mem.saturating_add(self.gets_missing)
+        )?;
+        if self.disk_index_enabled {
+            writeln!(
+                f,
+                "Disk hit rate: {:.2}% of {} lookups",
+                self.disk_hit_rate() * 100.0,
+                self.load_disk_found.saturating_add(self.load_disk_missing)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occupancy_hit_rates() {
+        let occupancy = AccountsIndexOccupancy::default();
+        assert_eq!(occupancy.mem_hit_rate(), 0.0);
+        assert_eq!(occupancy.disk_hit_rate(), 0.0);
+
+        let occupancy = AccountsIndexOccupancy {
+            gets_from_mem: 3,
+            gets_missing: 1,
+            load_disk_found: 1,
+            load_disk_missing: 1,
+            ..AccountsIndexOccupancy::default()
+        };
+        assert_eq!(occupancy.mem_hit_rate(), 0.75);
+        assert_eq!(occupancy.disk_hit_rate(), 0.5);
+    }
+}
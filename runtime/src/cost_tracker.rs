@@ -20,6 +20,9 @@ pub enum CostTrackerError {
     WouldExceedAccountMaxLimit,
 
     WouldExceedAccountDataMaxLimit,
+
+    /// transaction allocates more account data than allowed per transaction
+    WouldExceedAccountDataTransactionLimit,
 }
 
 #[derive(AbiExample, Debug)]
@@ -30,6 +33,8 @@ pub struct CostTracker {
     block_cost: u64,
     transaction_count: u64,
     account_data_size: u64,
+    /// account data a transaction may allocate, None while not capped
+    account_data_transaction_limit: Option<u64>,
 }
 
 impl Default for CostTracker {
@@ -48,6 +53,16 @@ impl CostTracker {
             block_cost: 0,
             transaction_count: 0,
             account_data_size: 0,
+            account_data_transaction_limit: None,
+        }
+    }
+
+    pub fn new_with_account_data_transaction_limit(
+        account_data_transaction_limit: Option<u64>,
+    ) -> Self {
+        Self {
+            account_data_transaction_limit,
+            ..Self::default()
         }
     }
 
@@ -92,6 +107,12 @@ impl CostTracker {
         Ok(self.block_cost)
     }
 
+    /// Account for the account data actually allocated by the transactions of the block, which
+    /// can exceed what was estimated for them, e.g. because of `realloc`
+    pub fn update_account_data_size(&mut self, allocated: u64) {
+        self.account_data_size = self.account_data_size.max(allocated);
+    }
+
     pub fn report_stats(&self, bank_slot: Slot) {
         // skip reporting if block is empty
         if self.transaction_count == 0 {
@@ -145,7 +166,15 @@ impl CostTracker {
             return Err(CostTrackerError::WouldExceedAccountMaxLimit);
         }
 
-        if self.account_data_size.saturating_add(account_data_len) > MAX_ACCOUNT_DATA_LEN {
+        if self
+            .account_data_transaction_limit
+            .map(|limit| account_data_len > limit)
+            .unwrap_or_default()
+        {
+            return Err(CostTrackerError::WouldExceedAccountDataTransactionLimit);
+        }
+
+        if self.account_data_size.saturating_add(account_data_len) > MAX_ACCOUNT_DATA_BLOCK_LEN {
             return Err(CostTrackerError::WouldExceedAccountDataMaxLimit);
         }
 
@@ -187,6 +216,7 @@ mod tests {
             bank::Bank,
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
+        paychains_program_runtime::accounts_data_meter::MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION,
         paychains_sdk::{
             hash::Hash,
             signature::{Keypair, Signer},
@@ -346,14 +376,66 @@ mod tests {
         let (_tx2, keys2, cost2) = build_simple_transaction(&second_account, &start_hash);
 
         // build testee that passes
-        let testee = CostTracker::new(cmp::max(cost1, cost2), cost1 + cost2 - 1);
+        let mut testee = CostTracker::new(cmp::max(cost1, cost2), cost1 + cost2 - 1);
+        testee.account_data_size = MAX_ACCOUNT_DATA_BLOCK_LEN - 1;
+        assert!(testee.would_fit(&keys2, cost2, 1).is_ok());
+        // data is too big
+        assert!(matches!(
+            testee.would_fit(&keys2, cost2, 2),
+            Err(CostTrackerError::WouldExceedAccountDataMaxLimit)
+        ));
+    }
+
+    #[test]
+    fn test_cost_tracker_reach_data_transaction_limit() {
+        let (mint_keypair, start_hash) = test_setup();
+        let (_tx, keys, cost) = build_simple_transaction(&mint_keypair, &start_hash);
+
+        // not capped while the feature is inactive
+        let testee = CostTracker::new(cost, cost);
         assert!(testee
-            .would_fit(&keys2, cost2, MAX_ACCOUNT_DATA_LEN)
+            .would_fit(
+                &keys,
+                cost,
+                MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION + 1
+            )
             .is_ok());
-        // data is too big
+
+        let testee = CostTracker::new_with_account_data_transaction_limit(Some(
+            MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION,
+        ));
         assert!(testee
-            .would_fit(&keys2, cost2, MAX_ACCOUNT_DATA_LEN + 1)
-            .is_err());
+            .would_fit(&keys, cost, MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION)
+            .is_ok());
+        assert!(matches!(
+            testee.would_fit(
+                &keys,
+                cost,
+                MAX_ACCOUNTS_DATA_ALLOCATIONS_PER_TRANSACTION + 1
+            ),
+            Err(CostTrackerError::WouldExceedAccountDataTransactionLimit)
+        ));
+    }
+
+    #[test]
+    fn test_cost_tracker_update_account_data_size() {
+        let (mint_keypair, start_hash) = test_setup();
+        let (_tx, keys, cost) = build_simple_transaction(&mint_keypair, &start_hash);
+
+        let mut testee = CostTracker::new(cost, cost * 2);
+        testee.add_transaction(&keys, cost, 10);
+
+        // allocations below the estimates do not reduce the account data size
+        testee.update_account_data_size(5);
+        assert_eq!(testee.account_data_size, 10);
+
+        // allocations that exceed the estimates leave less room for the block
+        testee.update_account_data_size(MAX_ACCOUNT_DATA_BLOCK_LEN);
+        assert_eq!(testee.account_data_size, MAX_ACCOUNT_DATA_BLOCK_LEN);
+        assert!(matches!(
+            testee.would_fit(&keys, cost, 1),
+            Err(CostTrackerError::WouldExceedAccountDataMaxLimit)
+        ));
     }
 
     #[test]
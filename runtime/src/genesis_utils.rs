@@ -2,7 +2,7 @@ use {
     paychains_sdk::{
         account::{Account, AccountSharedData},
         feature::{self, Feature},
-        feature_set::FeatureSet,
+        feature_set::{self, FeatureSet},
         fee_calculator::FeeRateGovernor,
         genesis_config::{ClusterType, GenesisConfig},
         pubkey::Pubkey,
@@ -169,18 +169,40 @@ pub fn create_genesis_config_with_leader(
 pub fn activate_all_features(genesis_config: &mut GenesisConfig) {
     // Activate all features at genesis in development mode
     for feature_id in FeatureSet::default().inactive {
-        genesis_config.accounts.insert(
-            feature_id,
-            Account::from(feature::create_account(
-                &Feature {
-                    activated_at: Some(0),
-                },
-                std::cmp::max(genesis_config.rent.minimum_balance(Feature::size_of()), 1),
-            )),
-        );
+        activate_feature(genesis_config, feature_id);
     }
 }
 
+/// Features left inactive in the development genesis configs built for program-test,
+/// test-validator and local-cluster; tests and tools opt in with `activate_feature`.
+///
+/// Partitioned epoch rewards credit stake rewards after the first block of an epoch and reject
+/// stake program instructions until then, which the stake-using suites of those tools don't
+/// expect yet.
+pub fn features_inactive_in_dev_tooling() -> Vec<Pubkey> {
+    vec![feature_set::enable_partitioned_epoch_reward::id()]
+}
+
+/// Leave `features_to_deactivate` inactive at genesis, for tests relying on the behavior before
+/// them
+pub fn deactivate_features(genesis_config: &mut GenesisConfig, features_to_deactivate: &[Pubkey]) {
+    for feature_id in features_to_deactivate {
+        genesis_config.accounts.remove(feature_id);
+    }
+}
+
+pub fn activate_feature(genesis_config: &mut GenesisConfig, feature_id: Pubkey) {
+    genesis_config.accounts.insert(
+        feature_id,
+        Account::from(feature::create_account(
+            &Feature {
+                activated_at: Some(0),
+            },
+            std::cmp::max(genesis_config.rent.minimum_balance(Feature::size_of()), 1),
+        )),
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_genesis_config_with_leader_ex(
     mint_lamports: u64,
@@ -236,6 +258,7 @@ pub fn create_genesis_config_with_leader_ex(
     paychains_stake_program::add_genesis_accounts(&mut genesis_config);
     if genesis_config.cluster_type == ClusterType::Development {
         activate_all_features(&mut genesis_config);
+        deactivate_features(&mut genesis_config, &features_inactive_in_dev_tooling());
     }
 
     genesis_config
@@ -11,6 +11,7 @@ use {
             Component::{self, CurDir, Normal},
             Path, PathBuf,
         },
+        sync::atomic::{AtomicU64, Ordering},
         time::Instant,
     },
     tar::{
@@ -73,6 +74,24 @@ fn checked_total_count_increment(total_count: u64, limit_count: u64) -> Result<u
     Ok(total_count)
 }
 
+/// Running totals of the entries unpacked from an archive.  The unpacks of the parts of one
+/// archive share them, so the archive's limits apply to all of its parts together
+#[derive(Debug, Default)]
+pub struct UnpackTotals {
+    apparent_size: AtomicU64,
+    actual_size: AtomicU64,
+    count: AtomicU64,
+}
+
+/// Add `value` to `total`, returning the previous total
+fn add_to_total(total: &AtomicU64, value: u64) -> u64 {
+    total
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+            Some(total.saturating_add(value))
+        })
+        .unwrap()
+}
+
 fn check_unpack_result(unpack_result: bool, path: String) -> Result<()> {
     if !unpack_result {
         return Err(UnpackError::Archive(format!(
@@ -94,15 +113,12 @@ fn unpack_archive<'a, A: Read, C>(
     apparent_limit_size: u64,
     actual_limit_size: u64,
     limit_count: u64,
+    totals: &UnpackTotals,
     mut entry_checker: C,
 ) -> Result<()>
 where
     C: FnMut(&[&str], tar::EntryType) -> UnpackPath<'a>,
 {
-    let mut apparent_total_size: u64 = 0;
-    let mut actual_total_size: u64 = 0;
-    let mut total_count: u64 = 0;
-
     let mut total_entries = 0;
     let mut last_log_update = Instant::now();
     for entry in archive.entries()? {
@@ -148,17 +164,19 @@ where
             UnpackPath::Valid(unpack_dir) => unpack_dir,
         };
 
-        apparent_total_size = checked_total_size_sum(
-            apparent_total_size,
-            entry.header().size()?,
+        let apparent_size = entry.header().size()?;
+        checked_total_size_sum(
+            add_to_total(&totals.apparent_size, apparent_size),
+            apparent_size,
             apparent_limit_size,
         )?;
-        actual_total_size = checked_total_size_sum(
-            actual_total_size,
-            entry.header().entry_size()?,
+        let actual_size = entry.header().entry_size()?;
+        checked_total_size_sum(
+            add_to_total(&totals.actual_size, actual_size),
+            actual_size,
             actual_limit_size,
         )?;
-        total_count = checked_total_count_increment(total_count, limit_count)?;
+        checked_total_count_increment(add_to_total(&totals.count, 1), limit_count)?;
 
         let target = sanitize_path(&entry.path()?, unpack_dir)?; // ? handles file system errors
         if target.is_none() {
@@ -297,6 +315,23 @@ pub fn unpack_snapshot<A: Read>(
     ledger_dir: &Path,
     account_paths: &[PathBuf],
     parallel_selector: Option<ParallelSelector>,
+) -> Result<UnpackedAppendVecMap> {
+    unpack_snapshot_with_totals(
+        archive,
+        ledger_dir,
+        account_paths,
+        parallel_selector,
+        &UnpackTotals::default(),
+    )
+}
+
+/// Unpack one part of a snapshot archive, counting its entries towards the archive's `totals`
+pub fn unpack_snapshot_with_totals<A: Read>(
+    archive: &mut Archive<A>,
+    ledger_dir: &Path,
+    account_paths: &[PathBuf],
+    parallel_selector: Option<ParallelSelector>,
+    totals: &UnpackTotals,
 ) -> Result<UnpackedAppendVecMap> {
     assert!(!account_paths.is_empty());
     let mut unpacked_append_vec_map = UnpackedAppendVecMap::new();
@@ -307,6 +342,7 @@ pub fn unpack_snapshot<A: Read>(
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
+        totals,
         |parts, kind| {
             if is_valid_snapshot_archive_entry(parts, kind) {
                 i += 1;
@@ -448,6 +484,7 @@ fn unpack_genesis<A: Read>(
         max_genesis_archive_unpacked_size,
         max_genesis_archive_unpacked_size,
         MAX_GENESIS_ARCHIVE_UNPACKED_COUNT,
+        &UnpackTotals::default(),
         |p, k| {
             if is_valid_genesis_archive_entry(p, k) {
                 UnpackPath::Valid(unpack_dir)
@@ -679,6 +716,38 @@ mod tests {
         assert_matches!(result, Ok(()));
     }
 
+    #[test]
+    fn test_archive_unpack_snapshot_shared_totals() {
+        let new_archive = || {
+            let mut header = Header::new_gnu();
+            header.set_path("version").unwrap();
+            header.set_size(4);
+            header.set_cksum();
+            let mut archive = Builder::new(Vec::new());
+            archive.append(&header, &[1u8, 2, 3, 4][..]).unwrap();
+            archive
+        };
+        let totals = UnpackTotals::default();
+        add_to_total(&totals.count, MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT - 1);
+        let unpack_part = |archive| {
+            with_finalize_and_unpack(archive, |a, b| {
+                unpack_snapshot_with_totals(a, b, &[PathBuf::new()], None, &totals).map(|_| ())
+            })
+        };
+
+        // Each part is within the limits on its own, but not together
+        assert_matches!(unpack_part(new_archive()), Ok(()));
+        assert_matches!(
+            unpack_part(new_archive()),
+            Err(UnpackError::Archive(ref message))
+                if message == &format!(
+                    "too many files in snapshot: {}",
+                    MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT + 1
+                )
+        );
+        assert_eq!(totals.apparent_size.load(Ordering::Relaxed), 8);
+    }
+
     #[test]
     fn test_archive_unpack_genesis_ok() {
         let mut header = Header::new_gnu();
@@ -154,10 +154,12 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
                 Some(_) => {
                     Self::update_time_stat(&self.stats().load_disk_found_us, m);
                     Self::update_stat(&self.stats().load_disk_found_count, 1);
+                    Self::update_stat(&self.stats().total_load_disk_found, 1);
                 }
                 None => {
                     Self::update_time_stat(&self.stats().load_disk_missing_us, m);
                     Self::update_stat(&self.stats().load_disk_missing_count, 1);
+                    Self::update_stat(&self.stats().total_load_disk_missing, 1);
                 }
             }
             entry_disk
@@ -181,13 +183,22 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
         let map = self.map().read().unwrap();
         let result = map.get(pubkey);
         let stats = self.stats();
-        let (count, time) = if result.is_some() {
-            (&stats.gets_from_mem, &stats.get_mem_us)
+        let (count, total, time) = if result.is_some() {
+            (
+                &stats.gets_from_mem,
+                &stats.total_gets_from_mem,
+                &stats.get_mem_us,
+            )
         } else {
-            (&stats.gets_missing, &stats.get_missing_us)
+            (
+                &stats.gets_missing,
+                &stats.total_gets_missing,
+                &stats.get_missing_us,
+            )
         };
         Self::update_time_stat(time, m);
         Self::update_stat(count, 1);
+        Self::update_stat(total, 1);
 
         callback(if let Some(entry) = result {
             entry.set_age(self.storage.future_age_to_flush());
@@ -806,13 +817,6 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
         thread_rng().gen_range(0, N) == 0
     }
 
-    /// assumes 1 entry in the slot list. Ignores overhead of the HashMap and such
-    fn approx_size_of_one_entry() -> usize {
-        std::mem::size_of::<T>()
-            + std::mem::size_of::<Pubkey>()
-            + std::mem::size_of::<AccountMapEntry<T>>()
-    }
-
     /// return true if 'entry' should be removed from the in-mem index
     fn should_remove_from_mem(
         &self,
@@ -861,11 +865,7 @@ impl<T: IndexValue> InMemAccountsIndex<T> {
             return;
         }
 
-        let in_mem_count = self.stats().count_in_mem.load(Ordering::Relaxed);
-        let limit = self.storage.mem_budget_mb;
-        let exceeds_budget = limit
-            .map(|limit| in_mem_count * Self::approx_size_of_one_entry() >= limit * 1024 * 1024)
-            .unwrap_or_default();
+        let exceeds_budget = self.storage.exceeds_mem_budget();
 
         // may have to loop if disk has to grow and we have to restart
         loop {
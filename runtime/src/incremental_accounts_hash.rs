@@ -0,0 +1,372 @@
+//! The hash and lamports of the latest version of every rooted account, binned by pubkey.
+//!
+//! Calculating the accounts hash from scratch means scanning every storage.  Instead, the
+//! accounts written in each newly rooted slot are applied to this structure, which only has to
+//! rebuild the bins those accounts fall in before re-hashing.  The resulting hash and
+//! capitalization are the same as the ones calculated by a full scan.
+//!
+//! Cost: every account with a non-zero balance is held in memory as its pubkey, hash and
+//! lamports, 72 bytes per account, e.g. about 3.6 GB for 50 million accounts.  Once more than
+//! `max_accounts` are held, the structure is dropped and the hash is calculated by scanning the
+//! storages again.  Nothing is persisted: after a restart, the first calculation scans every
+//! storage, which reuses the per-storage scan results cached by `CacheHashData`.
+use {
+    crate::{
+        accounts_db::{AccountsDb, BinnedHashData},
+        accounts_hash::{AccountsHash, MERKLE_FANOUT, ZERO_RAW_LAMPORTS_SENTINEL},
+        pubkey_bins::PubkeyBinCalculator24,
+    },
+    log::*,
+    rayon::prelude::*,
+    paychains_sdk::{clock::Slot, hash::Hash, pubkey::Pubkey},
+    std::{
+        cmp::Ordering,
+        collections::{BTreeMap, HashSet},
+    },
+};
+
+/// Default upper bound on the number of accounts held, about 3.6 GB
+pub const DEFAULT_MAX_ACCOUNTS: usize = 50_000_000;
+
+/// The accounts of a bin with a non-zero balance, sorted by pubkey.  Kept as separate vectors so
+/// that `hashes` can be fed to the merkle tree calculation without copying it
+#[derive(Debug, Default)]
+struct HashBin {
+    pubkeys: Vec<Pubkey>,
+    hashes: Vec<Hash>,
+    lamports: Vec<u64>,
+    /// sum of `lamports`
+    total_lamports: u64,
+}
+
+impl HashBin {
+    fn len(&self) -> usize {
+        self.pubkeys.len()
+    }
+
+    /// Merge the latest writes of this bin's accounts, None removing the account
+    fn apply(&mut self, updates: BTreeMap<Pubkey, Option<(Hash, u64)>>) {
+        if updates.is_empty() {
+            return;
+        }
+        let capacity = self.len() + updates.len();
+        let mut merged = HashBin {
+            pubkeys: Vec::with_capacity(capacity),
+            hashes: Vec::with_capacity(capacity),
+            lamports: Vec::with_capacity(capacity),
+            total_lamports: 0,
+        };
+        let mut existing = self
+            .pubkeys
+            .iter()
+            .zip(self.hashes.iter())
+            .zip(self.lamports.iter())
+            .map(|((pubkey, hash), lamports)| (*pubkey, *hash, *lamports))
+            .peekable();
+        let mut updates = updates.into_iter().peekable();
+        loop {
+            let order = match (existing.peek(), updates.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((pubkey, ..)), Some((updated, _))) => pubkey.cmp(updated),
+            };
+            match order {
+                Ordering::Less => {
+                    let (pubkey, hash, lamports) = existing.next().unwrap();
+                    merged.push(pubkey, hash, lamports);
+                }
+                Ordering::Equal | Ordering::Greater => {
+                    if order == Ordering::Equal {
+                        // replaced by the update
+                        existing.next();
+                    }
+                    let (pubkey, update) = updates.next().unwrap();
+                    if let Some((hash, lamports)) = update {
+                        merged.push(pubkey, hash, lamports);
+                    }
+                }
+            }
+        }
+        merged.total_lamports = AccountsHash::checked_cast_for_capitalization(
+            merged
+                .lamports
+                .iter()
+                .map(|lamports| *lamports as u128)
+                .sum(),
+        );
+        *self = merged;
+    }
+
+    fn push(&mut self, pubkey: Pubkey, hash: Hash, lamports: u64) {
+        self.pubkeys.push(pubkey);
+        self.hashes.push(hash);
+        self.lamports.push(lamports);
+    }
+}
+
+#[derive(Debug)]
+pub struct IncrementalAccountsHash {
+    bin_calculator: PubkeyBinCalculator24,
+    bins: Vec<HashBin>,
+    /// highest root whose accounts have been applied, None until the first update
+    slot: Option<Slot>,
+    /// zero-lamport accounts purged by clean since the last update.  They are no longer found in
+    /// any storage, so they have to be removed explicitly.
+    purged_zero_lamport_accounts: HashSet<Pubkey>,
+    max_accounts: usize,
+    /// true once more than `max_accounts` were held, after which the structure stays empty
+    exceeded_max_accounts: bool,
+}
+
+impl IncrementalAccountsHash {
+    pub fn new(bins: usize, max_accounts: usize) -> Self {
+        Self {
+            bin_calculator: PubkeyBinCalculator24::new(bins),
+            bins: (0..bins).map(|_| HashBin::default()).collect(),
+            slot: None,
+            purged_zero_lamport_accounts: HashSet::default(),
+            max_accounts,
+            exceeded_max_accounts: false,
+        }
+    }
+
+    pub fn slot(&self) -> Option<Slot> {
+        self.slot
+    }
+
+    pub fn account_count(&self) -> usize {
+        self.bins.iter().map(HashBin::len).sum()
+    }
+
+    /// True if the structure was dropped for holding more than `max_accounts`, in which case
+    /// the accounts hash has to be calculated by scanning the storages
+    pub fn exceeded_max_accounts(&self) -> bool {
+        self.exceeded_max_accounts
+    }
+
+    /// Remember zero-lamport accounts that clean is about to purge from every storage
+    pub fn purge_zero_lamport_accounts<'a>(&mut self, pubkeys: impl Iterator<Item = &'a Pubkey>) {
+        if self.slot.is_some() && !self.exceeded_max_accounts {
+            self.purged_zero_lamport_accounts.extend(pubkeys);
+        }
+    }
+
+    /// Apply the accounts written in the roots up to and including `slot`.
+    ///
+    /// `scanned` is the output of scanning those roots: chunks in slot order, each split into
+    /// bins matching this structure's, with the items of a bin sorted by pubkey and, for the
+    /// same pubkey, in the order they were written.
+    pub fn update(
+        &mut self,
+        slot: Slot,
+        scanned: Vec<BinnedHashData>,
+        filler_account_suffix: Option<&Pubkey>,
+    ) {
+        if self.exceeded_max_accounts {
+            return;
+        }
+        let mut purged = vec![BTreeMap::default(); self.bins.len()];
+        for pubkey in std::mem::take(&mut self.purged_zero_lamport_accounts) {
+            let bin = self.bin_calculator.bin_from_pubkey(&pubkey);
+            purged[bin].insert(pubkey, None);
+        }
+        self.bins.par_iter_mut().zip(purged).enumerate().for_each(
+            |(bin, (hash_bin, mut updates))| {
+                for items in scanned.iter().filter_map(|chunk| chunk.get(bin)) {
+                    for item in items {
+                        if !AccountsDb::is_filler_account_helper(
+                            &item.pubkey,
+                            filler_account_suffix,
+                        ) {
+                            let update = (item.lamports != 0
+                                && item.lamports != ZERO_RAW_LAMPORTS_SENTINEL)
+                                .then(|| (item.hash, item.lamports));
+                            updates.insert(item.pubkey, update);
+                        }
+                    }
+                }
+                hash_bin.apply(updates);
+            },
+        );
+        self.slot = Some(slot);
+
+        if self.account_count() > self.max_accounts {
+            warn!(
+                "incremental accounts hash holds more than {} accounts, falling back to \
+                 scanning the storages",
+                self.max_accounts
+            );
+            self.bins = self.bins.iter().map(|_| HashBin::default()).collect();
+            self.exceeded_max_accounts = true;
+        }
+    }
+
+    /// Calculate the accounts hash and capitalization
+    pub fn calculate(&self) -> (Hash, u64) {
+        let mut total_hashes = 0;
+        let mut total_lamports = 0u128;
+        let slices: Vec<(usize, &[Hash])> = self
+            .bins
+            .iter()
+            .filter(|bin| !bin.hashes.is_empty())
+            .map(|bin| {
+                let start = total_hashes;
+                total_hashes += bin.hashes.len();
+                total_lamports += bin.total_lamports as u128;
+                (start, &bin.hashes[..])
+            })
+            .collect();
+
+        let (hash, _) = AccountsHash::compute_merkle_root_from_slices(
+            total_hashes,
+            MERKLE_FANOUT,
+            None,
+            |start| {
+                let index = slices.partition_point(|(bin_start, _)| *bin_start <= start) - 1;
+                let (bin_start, hashes) = slices[index];
+                &hashes[start - bin_start..]
+            },
+            None,
+        );
+        (
+            hash,
+            AccountsHash::checked_cast_for_capitalization(total_lamports),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::accounts_hash::CalculateHashIntermediate};
+
+    const BINS: usize = 4;
+
+    fn scanned(items: &[(Pubkey, Hash, u64)]) -> BinnedHashData {
+        let bin_calculator = PubkeyBinCalculator24::new(BINS);
+        let mut bins = vec![vec![]; BINS];
+        for (pubkey, hash, lamports) in items {
+            let lamports = if *lamports == 0 {
+                ZERO_RAW_LAMPORTS_SENTINEL
+            } else {
+                *lamports
+            };
+            bins[bin_calculator.bin_from_pubkey(pubkey)]
+                .push(CalculateHashIntermediate::new(*hash, lamports, *pubkey));
+        }
+        for bin in bins.iter_mut() {
+            bin.sort_by(AccountsHash::compare_two_hash_entries);
+        }
+        bins
+    }
+
+    fn expected(accounts: &[(Pubkey, Hash, u64)]) -> (Hash, u64) {
+        let hashes = accounts
+            .iter()
+            .map(|(pubkey, hash, _)| (*pubkey, *hash))
+            .collect();
+        (
+            AccountsHash::accumulate_account_hashes(hashes),
+            accounts.iter().map(|(_, _, lamports)| lamports).sum(),
+        )
+    }
+
+    #[test]
+    fn test_incremental_accounts_hash_empty() {
+        let mut incremental = IncrementalAccountsHash::new(BINS, DEFAULT_MAX_ACCOUNTS);
+        assert_eq!(incremental.slot(), None);
+        incremental.update(0, vec![], None);
+        assert_eq!(incremental.slot(), Some(0));
+        assert_eq!(incremental.calculate(), expected(&[]));
+    }
+
+    #[test]
+    fn test_incremental_accounts_hash_update() {
+        let accounts: Vec<_> = (0..100)
+            .map(|i| (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), i + 1))
+            .collect();
+        let mut incremental = IncrementalAccountsHash::new(BINS, DEFAULT_MAX_ACCOUNTS);
+        // split across two chunks
+        incremental.update(
+            1,
+            vec![scanned(&accounts[..50]), scanned(&accounts[50..])],
+            None,
+        );
+        assert_eq!(incremental.account_count(), 100);
+        assert_eq!(incremental.calculate(), expected(&accounts));
+
+        // modify one account, remove another, add a new one; the later write of a pubkey wins
+        let mut accounts = accounts;
+        let modified = (accounts[3].0, Hash::new_unique(), 1_000);
+        let removed = accounts.remove(7);
+        let added = (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), 5);
+        incremental.update(
+            2,
+            vec![scanned(&[
+                (modified.0, Hash::new_unique(), 999),
+                modified,
+                (removed.0, removed.1, 0),
+                added,
+            ])],
+            None,
+        );
+        accounts[3] = modified;
+        accounts.push(added);
+        assert_eq!(incremental.slot(), Some(2));
+        assert_eq!(incremental.account_count(), 100);
+        assert_eq!(incremental.calculate(), expected(&accounts));
+
+        // nothing changed
+        assert_eq!(incremental.calculate(), expected(&accounts));
+    }
+
+    #[test]
+    fn test_incremental_accounts_hash_purge_zero_lamport_accounts() {
+        let accounts: Vec<_> = (0..10)
+            .map(|i| (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), i + 1))
+            .collect();
+        let mut incremental = IncrementalAccountsHash::new(BINS, DEFAULT_MAX_ACCOUNTS);
+        // ignored until the first update
+        incremental.purge_zero_lamport_accounts([accounts[0].0].iter());
+        incremental.update(1, vec![scanned(&accounts)], None);
+        assert_eq!(incremental.calculate(), expected(&accounts));
+
+        incremental.purge_zero_lamport_accounts([accounts[0].0].iter());
+        incremental.update(2, vec![], None);
+        assert_eq!(incremental.calculate(), expected(&accounts[1..]));
+    }
+
+    #[test]
+    fn test_incremental_accounts_hash_filler_accounts() {
+        // filler accounts are recognized by their suffix, which the suffix itself has
+        let filler_account_suffix = paychains_sdk::pubkey::new_rand();
+        let filler_account = filler_account_suffix;
+        let account = (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), 1);
+        let mut incremental = IncrementalAccountsHash::new(BINS, DEFAULT_MAX_ACCOUNTS);
+        incremental.update(
+            1,
+            vec![scanned(&[account, (filler_account, Hash::new_unique(), 1)])],
+            Some(&filler_account_suffix),
+        );
+        assert_eq!(incremental.calculate(), expected(&[account]));
+    }
+
+    #[test]
+    fn test_incremental_accounts_hash_max_accounts() {
+        let accounts: Vec<_> = (0..10)
+            .map(|i| (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), i + 1))
+            .collect();
+        let mut incremental = IncrementalAccountsHash::new(BINS, 10);
+        incremental.update(1, vec![scanned(&accounts)], None);
+        assert!(!incremental.exceeded_max_accounts());
+        assert_eq!(incremental.calculate(), expected(&accounts));
+
+        // one account too many drops everything, and later updates are ignored
+        let added = (paychains_sdk::pubkey::new_rand(), Hash::new_unique(), 1);
+        incremental.update(2, vec![scanned(&[added])], None);
+        assert!(incremental.exceeded_max_accounts());
+        assert_eq!(incremental.account_count(), 0);
+        incremental.update(3, vec![scanned(&accounts)], None);
+        assert_eq!(incremental.account_count(), 0);
+    }
+}
@@ -33,11 +33,13 @@ pub mod execute_cost_table;
 pub mod genesis_utils;
 pub mod hardened_unpack;
 pub mod in_mem_accounts_index;
+pub mod incremental_accounts_hash;
 pub mod inline_spl_token;
 pub mod loader_utils;
 pub mod message_processor;
 pub mod non_circulating_supply;
 mod nonce_keyed_account;
+mod persisted_accounts_index;
 mod pubkey_bins;
 mod read_only_accounts_cache;
 pub mod rent_collector;
@@ -45,6 +47,7 @@ pub mod secondary_index;
 pub mod serde_snapshot;
 mod shared_buffer_reader;
 pub mod snapshot_archive_info;
+pub mod snapshot_chunked_archive;
 pub mod snapshot_config;
 pub mod snapshot_hash;
 pub mod snapshot_package;
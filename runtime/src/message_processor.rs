@@ -39,8 +39,10 @@ impl ::paychains_frozen_abi::abi_example::AbiExample for MessageProcessor {
 /// Resultant information gathered from calling process_message()
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct ProcessedMessageInfo {
-    /// The new accounts data len
-    pub accounts_data_len: u64,
+    /// The change in accounts data len
+    pub accounts_data_len_delta: i64,
+    /// The accounts data allocated, not offset by the accounts data freed
+    pub accounts_data_allocations: u64,
 }
 
 impl MessageProcessor {
@@ -66,6 +68,7 @@ impl MessageProcessor {
         blockhash: Hash,
         lamports_per_signature: u64,
         current_accounts_data_len: u64,
+        accumulated_consumed_units: &mut u64,
     ) -> Result<ProcessedMessageInfo, TransactionError> {
         let mut invoke_context = InvokeContext::new(
             transaction_context,
@@ -136,6 +139,7 @@ impl MessageProcessor {
                 timings,
             );
             time.stop();
+            saturating_add_assign!(*accumulated_consumed_units, compute_units_consumed);
             timings.details.accumulate_program(
                 program_id,
                 time.as_us(),
@@ -150,8 +154,11 @@ impl MessageProcessor {
             result
                 .map_err(|err| TransactionError::InstructionError(instruction_index as u8, err))?;
         }
+        let accounts_data_meter = invoke_context.get_accounts_data_meter();
         Ok(ProcessedMessageInfo {
-            accounts_data_len: invoke_context.get_accounts_data_meter().current(),
+            accounts_data_len_delta: (accounts_data_meter.current() as i64)
+                .saturating_sub(current_accounts_data_len as i64),
+            accounts_data_allocations: accounts_data_meter.allocated(),
         })
     }
 }
@@ -275,6 +282,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -316,6 +324,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert_eq!(
             result,
@@ -349,6 +358,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert_eq!(
             result,
@@ -476,6 +486,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert_eq!(
             result,
@@ -510,6 +521,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert!(result.is_ok());
 
@@ -541,6 +553,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert!(result.is_ok());
         assert_eq!(
@@ -615,6 +628,7 @@ mod tests {
             Hash::default(),
             0,
             0,
+            &mut 0,
         );
         assert_eq!(
             result,
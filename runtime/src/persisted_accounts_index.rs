@@ -0,0 +1,308 @@
+//! The accounts index generated from the storages of a snapshot, saved to disk.
+//!
+//! Generating the index at startup means scanning every account in every storage.  When the
+//! validator restarts from the same snapshot, the saved index is loaded instead.  It is only
+//! used if its fingerprint matches the storages being loaded, see
+//! `AccountsDb::persisted_index_fingerprint()`.
+//!
+//! The index entries are saved per slot rather than as the files of the disk index: those refer
+//! to the ids of the storages, which are assigned again on every restart, and hold the entries
+//! written after startup as well.  Loading inserts the saved entries into the disk index
+//! directly if it is enabled, like generating the index does.
+use {
+    crate::account_info::{Offset, StoredSize},
+    bincode::Options,
+    log::*,
+    serde::{Deserialize, Serialize},
+    paychains_sdk::{clock::Slot, hash::Hash, pubkey::Pubkey},
+    std::{
+        fs::{self, File},
+        io::{self, BufReader, BufWriter, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex,
+        },
+    },
+};
+
+const PERSISTED_INDEX_FILE_NAME: &str = "accounts_index.bin";
+const PERSISTED_INDEX_VERSION: u64 = 2;
+
+/// The index entries generated from the single storage of a slot
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct PersistedSlot {
+    pub slot: Slot,
+    /// number of accounts alive in the storage
+    pub count: usize,
+    /// stored bytes of the accounts alive in the storage
+    pub stored_size: usize,
+    /// pubkey, offset, stored size and lamports of every account alive in the storage
+    pub accounts: Vec<(Pubkey, Offset, StoredSize, u64)>,
+}
+
+/// Starts the file, followed by `slot_count` serialized `PersistedSlot`s
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+struct Header {
+    version: u64,
+    /// identifies the storages the index was generated from
+    fingerprint: Hash,
+    accounts_data_len: u64,
+    slot_count: u64,
+}
+
+/// Saves the index entries of each slot as soon as they are generated, so they are never all
+/// held in memory
+#[derive(Debug)]
+pub struct PersistedAccountsIndexWriter {
+    path: PathBuf,
+    temp_path: PathBuf,
+    fingerprint: Hash,
+    /// the first error writing a slot ends writing
+    writer: Mutex<io::Result<BufWriter<File>>>,
+    slot_count: AtomicU64,
+}
+
+impl PersistedAccountsIndexWriter {
+    /// Start saving an index generated from the storages identified by `fingerprint` to `dir`.
+    /// The index saved before is replaced by `finish()`.
+    pub fn new(dir: &Path, fingerprint: Hash) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(PERSISTED_INDEX_FILE_NAME);
+        let temp_path = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        // the number of slots and the accounts data len are only known once all slots are
+        // written, `finish()` writes the header again
+        bincode::serialize_into(&mut writer, &Header::default()).map_err(into_io_error)?;
+        Ok(Self {
+            path,
+            temp_path,
+            fingerprint,
+            writer: Mutex::new(Ok(writer)),
+            slot_count: AtomicU64::default(),
+        })
+    }
+
+    /// Save the index entries of a slot, slots are saved in any order
+    pub fn write_slot(&self, persisted_slot: &PersistedSlot) {
+        // serialized before locking, the slots are generated in parallel
+        let data = bincode::serialize(persisted_slot).map_err(into_io_error);
+        let mut writer = self.writer.lock().unwrap();
+        let result = match &mut *writer {
+            Ok(writer) => data.and_then(|data| writer.write_all(&data)),
+            Err(_) => return,
+        };
+        match result {
+            Ok(()) => {
+                self.slot_count.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(err) => *writer = Err(err),
+        }
+    }
+
+    /// Finish saving and replace the index saved before
+    pub fn finish(self, accounts_data_len: u64) -> io::Result<()> {
+        let header = Header {
+            version: PERSISTED_INDEX_VERSION,
+            fingerprint: self.fingerprint,
+            accounts_data_len,
+            slot_count: self.slot_count.load(Ordering::Relaxed),
+        };
+        let result = self.writer.into_inner().unwrap().and_then(|writer| {
+            let mut file = writer
+                .into_inner()
+                .map_err(io::IntoInnerError::into_error)?;
+            file.seek(SeekFrom::Start(0))?;
+            bincode::serialize_into(&mut file, &header).map_err(into_io_error)?;
+            file.sync_all()?;
+            // a crash while writing must not leave a truncated index behind
+            fs::rename(&self.temp_path, &self.path)
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+        result
+    }
+}
+
+/// An index saved by `PersistedAccountsIndexWriter`, its slots are read one at a time
+#[derive(Debug)]
+pub struct PersistedAccountsIndex {
+    path: PathBuf,
+    header: Header,
+    file_len: u64,
+}
+
+impl PersistedAccountsIndex {
+    /// Open the index saved in `dir` if it was generated from the storages identified by
+    /// `fingerprint`
+    pub fn open(dir: &Path, fingerprint: &Hash) -> Option<Self> {
+        let path = dir.join(PERSISTED_INDEX_FILE_NAME);
+        let file = File::open(&path).ok()?;
+        let file_len = file.metadata().ok()?.len();
+        let header: Header = deserialize_from(BufReader::new(file), file_len)
+            .map_err(|err| warn!("Unable to load accounts index from {:?}: {}", path, err))
+            .ok()?;
+        if header.version != PERSISTED_INDEX_VERSION {
+            info!(
+                "Ignoring accounts index {:?} saved in version {}",
+                path, header.version
+            );
+            return None;
+        }
+        if &header.fingerprint != fingerprint {
+            info!(
+                "Ignoring accounts index {:?} generated from other storages",
+                path
+            );
+            return None;
+        }
+        Some(Self {
+            path,
+            header,
+            file_len,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn accounts_data_len(&self) -> u64 {
+        self.header.accounts_data_len
+    }
+
+    pub fn slot_count(&self) -> u64 {
+        self.header.slot_count
+    }
+
+    /// Read the saved slots, in the order they were saved in
+    pub fn slots(&self) -> io::Result<impl Iterator<Item = io::Result<PersistedSlot>>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let file_len = self.file_len;
+        let _header: Header = deserialize_from(&mut reader, file_len).map_err(into_io_error)?;
+        Ok((0..self.header.slot_count).map(move |_| {
+            deserialize_from::<_, PersistedSlot>(&mut reader, file_len).map_err(into_io_error)
+        }))
+    }
+}
+
+/// Nothing read from the file is larger than the file, no matter what lengths it claims
+fn deserialize_from<R, T>(reader: R, file_len: u64) -> bincode::Result<T>
+where
+    R: io::Read,
+    T: serde::de::DeserializeOwned,
+{
+    bincode::options()
+        .with_limit(file_len)
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .deserialize_from(reader)
+}
+
+fn into_io_error(err: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn new_persisted_slots() -> Vec<PersistedSlot> {
+        (1..4)
+            .map(|slot| PersistedSlot {
+                slot,
+                count: 2,
+                stored_size: 400,
+                accounts: vec![
+                    (Pubkey::new_unique(), 0, 200, slot),
+                    (Pubkey::new_unique(), 200, 200, 0),
+                ],
+            })
+            .collect()
+    }
+
+    fn save(dir: &Path, fingerprint: Hash, persisted_slots: &[PersistedSlot]) {
+        let writer = PersistedAccountsIndexWriter::new(dir, fingerprint).unwrap();
+        for persisted_slot in persisted_slots {
+            writer.write_slot(persisted_slot);
+        }
+        writer.finish(1234).unwrap();
+    }
+
+    fn load(dir: &Path, fingerprint: &Hash) -> Option<Vec<PersistedSlot>> {
+        let persisted = PersistedAccountsIndex::open(dir, fingerprint)?;
+        assert_eq!(persisted.accounts_data_len(), 1234);
+        Some(persisted.slots().unwrap().map(Result::unwrap).collect())
+    }
+
+    #[test]
+    fn test_save_load() {
+        let dir = TempDir::new().unwrap();
+        let fingerprint = Hash::new_unique();
+        assert!(load(dir.path(), &fingerprint).is_none());
+
+        let persisted_slots = new_persisted_slots();
+        save(dir.path(), fingerprint, &persisted_slots);
+        assert_eq!(load(dir.path(), &fingerprint), Some(persisted_slots));
+        assert!(load(dir.path(), &Hash::new_unique()).is_none());
+
+        // saving again replaces the index
+        let other_fingerprint = Hash::new_unique();
+        let persisted_slots = new_persisted_slots();
+        save(dir.path(), other_fingerprint, &persisted_slots);
+        assert!(load(dir.path(), &fingerprint).is_none());
+        assert_eq!(load(dir.path(), &other_fingerprint), Some(persisted_slots));
+
+        // an unfinished index does not replace the saved one
+        let writer = PersistedAccountsIndexWriter::new(dir.path(), fingerprint).unwrap();
+        writer.write_slot(&PersistedSlot::default());
+        drop(writer);
+        assert!(load(dir.path(), &fingerprint).is_none());
+        assert!(load(dir.path(), &other_fingerprint).is_some());
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let dir = TempDir::new().unwrap();
+        let fingerprint = Hash::new_unique();
+        let path = dir.path().join(PERSISTED_INDEX_FILE_NAME);
+
+        fs::write(&path, [1, 2, 3]).unwrap();
+        assert!(PersistedAccountsIndex::open(dir.path(), &fingerprint).is_none());
+
+        let header = Header {
+            version: PERSISTED_INDEX_VERSION + 1,
+            fingerprint,
+            ..Header::default()
+        };
+        fs::write(&path, bincode::serialize(&header).unwrap()).unwrap();
+        assert!(PersistedAccountsIndex::open(dir.path(), &fingerprint).is_none());
+
+        // a slot claiming more accounts than fit into the file fails to read
+        let header = Header {
+            version: PERSISTED_INDEX_VERSION,
+            fingerprint,
+            accounts_data_len: 0,
+            slot_count: 1,
+        };
+        let mut data = bincode::serialize(&header).unwrap();
+        data.extend(bincode::serialize(&PersistedSlot::default()).unwrap());
+        let accounts_len = data.len() - std::mem::size_of::<u64>();
+        data[accounts_len..].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, data).unwrap();
+        let persisted = PersistedAccountsIndex::open(dir.path(), &fingerprint).unwrap();
+        let mut slots = persisted.slots().unwrap();
+        assert!(slots.next().unwrap().is_err());
+
+        // so does a truncated one
+        save(dir.path(), fingerprint, &new_persisted_slots());
+        let data = fs::read(&path).unwrap();
+        fs::write(&path, &data[..data.len() - 1]).unwrap();
+        let persisted = PersistedAccountsIndex::open(dir.path(), &fingerprint).unwrap();
+        assert_eq!(persisted.slot_count(), 3);
+        let slots = persisted.slots().unwrap().collect::<Vec<_>>();
+        assert!(slots[0].is_ok());
+        assert!(slots[2].is_err());
+    }
+}
@@ -36,7 +36,7 @@ use {
     },
     std::{
         collections::{HashMap, HashSet},
-        io::{self, BufReader, BufWriter, Read, Write},
+        io::{self, BufRead, BufReader, BufWriter, Read, Write},
         path::{Path, PathBuf},
         result::Result,
         sync::{
@@ -75,6 +75,25 @@ struct AccountsDbFields<T>(
     BankHashInfo,
 );
 
+/// The slots the accounts of ancient storages were written in, by slot and storage id, see
+/// `AccountStorageEntry::written_slots()`. Snapshots store it after the bank fields, so that older
+/// snapshots remain readable, see `bank_to_stream()`.
+type SerdeWrittenSlots = HashMap<Slot, HashMap<AppendVecIdSerialized, Vec<(usize, Slot)>>>;
+
+fn written_slots_to_serialize(snapshot_storages: &[SnapshotStorage]) -> SerdeWrittenSlots {
+    let mut written_slots = SerdeWrittenSlots::default();
+    for storage in snapshot_storages.iter().flatten() {
+        let storage_written_slots = storage.written_slots();
+        if !storage_written_slots.is_empty() {
+            written_slots.entry(storage.slot()).or_default().insert(
+                storage.append_vec_id() as AppendVecIdSerialized,
+                storage_written_slots,
+            );
+        }
+    }
+    written_slots
+}
+
 /// Helper type to wrap BufReader streams when deserializing and reconstructing from either just a
 /// full snapshot, or both a full and incremental snapshot
 pub struct SnapshotStreams<'a, R> {
@@ -88,6 +107,8 @@ pub struct SnapshotStreams<'a, R> {
 struct SnapshotAccountsDbFields<T> {
     full_snapshot_accounts_db_fields: AccountsDbFields<T>,
     incremental_snapshot_accounts_db_fields: Option<AccountsDbFields<T>>,
+    /// of the storages of both snapshots
+    written_slots: SerdeWrittenSlots,
 }
 
 impl<T> SnapshotAccountsDbFields<T> {
@@ -158,6 +179,7 @@ trait TypeContext<'a> {
         (
             BankFieldsToDeserialize,
             AccountsDbFields<Self::SerializableAccountStorageEntry>,
+            SerdeWrittenSlots,
         ),
         Error,
     >
@@ -183,6 +205,21 @@ where
         .deserialize_from::<R, T>(reader)
 }
 
+/// Snapshots taken before a trailing field was added end without it, read those as the default.
+/// Only a stream ending right where the field starts is read as the default, a field cut short is
+/// an error.
+fn deserialize_from_or_default_on_eof<R, T>(mut reader: R) -> bincode::Result<T>
+where
+    R: BufRead,
+    T: DeserializeOwned + Default,
+{
+    if reader.fill_buf()?.is_empty() {
+        Ok(T::default())
+    } else {
+        deserialize_from(reader)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn bank_from_streams<R>(
     serde_style: SerdeStyle,
@@ -205,14 +242,15 @@ where
 {
     macro_rules! INTO {
         ($x:ident) => {{
-            let (full_snapshot_bank_fields, full_snapshot_accounts_db_fields) =
+            let (full_snapshot_bank_fields, full_snapshot_accounts_db_fields, mut written_slots) =
                 $x::deserialize_bank_fields(snapshot_streams.full_snapshot_stream)?;
             let (incremental_snapshot_bank_fields, incremental_snapshot_accounts_db_fields) =
                 if let Some(ref mut incremental_snapshot_stream) =
                     snapshot_streams.incremental_snapshot_stream
                 {
-                    let (bank_fields, accounts_db_fields) =
+                    let (bank_fields, accounts_db_fields, incremental_written_slots) =
                         $x::deserialize_bank_fields(incremental_snapshot_stream)?;
+                    written_slots.extend(incremental_written_slots);
                     (Some(bank_fields), Some(accounts_db_fields))
                 } else {
                     (None, None)
@@ -221,6 +259,7 @@ where
             let snapshot_accounts_db_fields = SnapshotAccountsDbFields {
                 full_snapshot_accounts_db_fields,
                 incremental_snapshot_accounts_db_fields,
+                written_slots,
             };
             let bank = reconstruct_bank_from_fields(
                 incremental_snapshot_bank_fields.unwrap_or(full_snapshot_bank_fields),
@@ -250,6 +289,16 @@ where
     })
 }
 
+/// Serialize the bank and accounts db fields, followed by two trailing fields that were added
+/// to the snapshot format later, in this order:
+///
+/// 1. the slots the accounts of ancient storages were written in, see `SerdeWrittenSlots`
+/// 2. the status of the partitioned epoch rewards distribution, see `SerdeEpochRewardStatus`
+///
+/// Readers of an older format stop after the fields they know and ignore the trailing bytes.
+/// Snapshots of an older format end before some of the trailing fields, which are read as
+/// their defaults, see `deserialize_from_or_default_on_eof()`.  New fields are only ever
+/// appended after the existing ones.
 pub(crate) fn bank_to_stream<W>(
     serde_style: SerdeStyle,
     stream: &mut BufWriter<W>,
@@ -262,7 +311,7 @@ where
     macro_rules! INTO {
         ($x:ident) => {
             bincode::serialize_into(
-                stream,
+                &mut *stream,
                 &SerializableBankAndStorage::<$x> {
                     bank,
                     snapshot_storages,
@@ -274,6 +323,10 @@ where
     match serde_style {
         SerdeStyle::Newer => INTO!(TypeContextFuture),
     }
+    .and_then(|_| {
+        bincode::serialize_into(&mut *stream, &written_slots_to_serialize(snapshot_storages))
+    })
+    .and_then(|_| bincode::serialize_into(stream, &bank.epoch_reward_status_to_serialize()))
     .map_err(|err| {
         warn!("bankrc_to_stream error: {:?}", err);
         err
@@ -372,6 +425,7 @@ fn reconstruct_single_storage<E>(
     append_vec_path: &Path,
     storage_entry: &E,
     append_vec_id: AppendVecId,
+    written_slots: Option<&Vec<(usize, Slot)>>,
     new_slot_storage: &mut HashMap<AppendVecId, Arc<AccountStorageEntry>>,
 ) -> Result<(), Error>
 where
@@ -381,6 +435,9 @@ where
         AppendVec::new_from_file(append_vec_path, storage_entry.current_len())?;
     let u_storage_entry =
         AccountStorageEntry::new_existing(*slot, append_vec_id, accounts, num_accounts);
+    if let Some(written_slots) = written_slots {
+        u_storage_entry.set_written_slots(written_slots.clone());
+    }
 
     new_slot_storage.insert(append_vec_id, Arc::new(u_storage_entry));
     Ok(())
@@ -394,7 +451,7 @@ struct ReconstructedAccountsDbInfo {
 
 #[allow(clippy::too_many_arguments)]
 fn reconstruct_accountsdb_from_fields<E>(
-    snapshot_accounts_db_fields: SnapshotAccountsDbFields<E>,
+    mut snapshot_accounts_db_fields: SnapshotAccountsDbFields<E>,
     account_paths: &[PathBuf],
     unpacked_append_vec_map: UnpackedAppendVecMap,
     genesis_config: &GenesisConfig,
@@ -419,6 +476,7 @@ where
         accounts_update_notifier,
     );
 
+    let written_slots = std::mem::take(&mut snapshot_accounts_db_fields.written_slots);
     let AccountsDbFields(
         snapshot_storages,
         snapshot_version,
@@ -488,6 +546,9 @@ where
                     &remapped_append_vec_path,
                     storage_entry,
                     remapped_append_vec_id,
+                    written_slots
+                        .get(slot)
+                        .and_then(|written_slots| written_slots.get(&storage_entry.id())),
                     &mut new_slot_storage,
                 )?;
             }
@@ -2,7 +2,11 @@
 use paychains_frozen_abi::abi_example::IgnoreAsHelper;
 use {
     super::{common::UnusedAccounts, *},
-    crate::{ancestors::AncestorsForSerialization, stakes::StakesCache},
+    crate::{
+        ancestors::AncestorsForSerialization,
+        bank::partitioned_epoch_rewards::{EpochRewardStatus, SerdeEpochRewardStatus},
+        stakes::StakesCache,
+    },
     paychains_measure::measure::Measure,
     std::{cell::RefCell, sync::RwLock},
 };
@@ -40,7 +44,8 @@ impl From<&AccountStorageEntry> for SerializableAccountStorageEntry {
     fn from(rhs: &AccountStorageEntry) -> Self {
         Self {
             id: rhs.append_vec_id() as AppendVecIdSerialized,
-            accounts_current_len: rhs.accounts.len(),
+            // compressed storages are archived uncompressed
+            accounts_current_len: rhs.accounts.uncompressed_len(),
         }
     }
 }
@@ -119,6 +124,7 @@ impl From<DeserializableVersionedBank> for BankFieldsToDeserialize {
             stakes: dvb.stakes,
             epoch_stakes: dvb.epoch_stakes,
             is_delta: dvb.is_delta,
+            epoch_reward_status: EpochRewardStatus::default(),
         }
     }
 }
@@ -280,13 +286,19 @@ impl<'a> TypeContext<'a> for Context {
 
     fn deserialize_bank_fields<R>(
         mut stream: &mut BufReader<R>,
-    ) -> Result<(BankFieldsToDeserialize, AccountsDbFields), Error>
+    ) -> Result<(BankFieldsToDeserialize, AccountsDbFields, SerdeWrittenSlots), Error>
     where
         R: Read,
     {
-        let bank_fields = deserialize_from::<_, DeserializableVersionedBank>(&mut stream)?.into();
+        let mut bank_fields: BankFieldsToDeserialize =
+            deserialize_from::<_, DeserializableVersionedBank>(&mut stream)?.into();
         let accounts_db_fields = Self::deserialize_accounts_db_fields(stream)?;
-        Ok((bank_fields, accounts_db_fields))
+        // the trailing fields, in the order `bank_to_stream()` appends them
+        let written_slots = deserialize_from_or_default_on_eof(&mut stream)?;
+        let epoch_reward_status: SerdeEpochRewardStatus =
+            deserialize_from_or_default_on_eof(&mut stream)?;
+        bank_fields.epoch_reward_status = epoch_reward_status.into();
+        Ok((bank_fields, accounts_db_fields, written_slots))
     }
 
     fn deserialize_accounts_db_fields<R>(
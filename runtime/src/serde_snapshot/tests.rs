@@ -4,7 +4,13 @@ use {
     crate::{
         accounts::{create_test_accounts, Accounts},
         accounts_db::{get_temp_accounts_paths, AccountShrinkThreshold},
-        bank::{Bank, StatusCacheRc},
+        accounts_index::AccountsIndexConfig,
+        bank::{
+            partitioned_epoch_rewards::{
+                EpochRewardStatus, PartitionedStakeReward, SerdeEpochRewardStatus,
+            },
+            Bank, StatusCacheRc,
+        },
         hardened_unpack::UnpackedAppendVecMap,
     },
     bincode::serialize_into,
@@ -69,9 +75,11 @@ where
 {
     // read and deserialise the accounts database directly from the stream
     let accounts_db_fields = C::deserialize_accounts_db_fields(stream)?;
+    let written_slots = deserialize_from_or_default_on_eof(stream)?;
     let snapshot_accounts_db_fields = SnapshotAccountsDbFields {
         full_snapshot_accounts_db_fields: accounts_db_fields,
         incremental_snapshot_accounts_db_fields: None,
+        written_slots,
     };
     reconstruct_accountsdb_from_fields(
         snapshot_accounts_db_fields,
@@ -124,7 +132,7 @@ where
 {
     match serde_style {
         SerdeStyle::Newer => serialize_into(
-            stream,
+            &mut *stream,
             &SerializableAccountsDb::<TypeContextFuture> {
                 accounts_db,
                 slot,
@@ -133,6 +141,7 @@ where
             },
         ),
     }
+    .and_then(|_| serialize_into(stream, &written_slots_to_serialize(account_storage_entries)))
 }
 
 #[cfg(test)]
@@ -307,6 +316,180 @@ fn test_bank_serialize_newer() {
     test_bank_serialize_style(SerdeStyle::Newer)
 }
 
+#[test]
+fn test_bank_serialize_trailing_fields() {
+    let (genesis_config, _) = create_genesis_config(500);
+    let bank = Bank::new_for_tests(&genesis_config);
+    bank.freeze();
+    let mut buf = vec![];
+    crate::serde_snapshot::bank_to_stream(
+        SerdeStyle::Newer,
+        &mut std::io::BufWriter::new(&mut buf),
+        &bank,
+        &bank.get_snapshot_storages(None),
+    )
+    .unwrap();
+    // no written slots, then no epoch reward status
+    let mut trailing = vec![];
+    serialize_into(&mut trailing, &SerdeWrittenSlots::default()).unwrap();
+    serialize_into(&mut trailing, &SerdeEpochRewardStatus::None).unwrap();
+    assert!(buf.ends_with(&trailing));
+    let fields_len = buf.len() - trailing.len();
+
+    // the trailing fields are read in the order they were appended
+    let written_slots: SerdeWrittenSlots =
+        HashMap::from([(3, HashMap::from([(7, vec![(0, 1), (128, 2)])]))]);
+    let epoch_reward_status: SerdeEpochRewardStatus =
+        Some((5, vec![Vec::<PartitionedStakeReward>::new()]));
+    let mut stream = buf[..fields_len].to_vec();
+    serialize_into(&mut stream, &written_slots).unwrap();
+    serialize_into(&mut stream, &epoch_reward_status).unwrap();
+    let (bank_fields, _, deserialized_written_slots) =
+        TypeContextFuture::deserialize_bank_fields(&mut BufReader::new(&stream[..])).unwrap();
+    assert_eq!(deserialized_written_slots, written_slots);
+    assert_eq!(
+        bank_fields.epoch_reward_status,
+        EpochRewardStatus::from(epoch_reward_status)
+    );
+
+    // snapshots that end before either trailing field read them as their defaults
+    let mut stream = buf[..fields_len].to_vec();
+    serialize_into(&mut stream, &written_slots).unwrap();
+    let (bank_fields, _, deserialized_written_slots) =
+        TypeContextFuture::deserialize_bank_fields(&mut BufReader::new(&stream[..])).unwrap();
+    assert_eq!(deserialized_written_slots, written_slots);
+    assert_eq!(
+        bank_fields.epoch_reward_status,
+        EpochRewardStatus::default()
+    );
+    let (bank_fields, _, deserialized_written_slots) =
+        TypeContextFuture::deserialize_bank_fields(&mut BufReader::new(&buf[..fields_len]))
+            .unwrap();
+    assert!(deserialized_written_slots.is_empty());
+    assert_eq!(
+        bank_fields.epoch_reward_status,
+        EpochRewardStatus::default()
+    );
+}
+
+#[test]
+fn test_deserialize_from_or_default_on_eof() {
+    // snapshots taken before the epoch reward status was appended end early
+    let buf: Vec<u8> = vec![];
+    let status: SerdeEpochRewardStatus = deserialize_from_or_default_on_eof(&buf[..]).unwrap();
+    assert_eq!(status, None);
+
+    let mut buf = vec![];
+    let expected: SerdeEpochRewardStatus = Some((5, vec![Vec::<PartitionedStakeReward>::new()]));
+    serialize_into(&mut buf, &expected).unwrap();
+    let status: SerdeEpochRewardStatus = deserialize_from_or_default_on_eof(&buf[..]).unwrap();
+    assert_eq!(status, expected);
+
+    // a truncated status is not mistaken for a missing one
+    let truncated = &buf[..buf.len() - 1];
+    assert!(deserialize_from_or_default_on_eof::<_, SerdeEpochRewardStatus>(truncated).is_err());
+}
+
+#[test]
+fn test_accounts_db_persisted_index() {
+    paychains_logger::setup();
+    let (_accounts_dir, paths) = get_temp_accounts_paths(4).unwrap();
+    let accounts = Accounts::new_with_config_for_tests(
+        paths,
+        &ClusterType::Development,
+        AccountSecondaryIndexes::default(),
+        false,
+        AccountShrinkThreshold::default(),
+    );
+    let mut pubkeys: Vec<Pubkey> = vec![];
+    create_test_accounts(&accounts, &mut pubkeys, 100, 0);
+    accounts.add_root(0);
+
+    let mut writer = Cursor::new(vec![]);
+    accountsdb_to_stream(
+        SerdeStyle::Newer,
+        &mut writer,
+        &*accounts.accounts_db,
+        0,
+        &accounts.accounts_db.get_snapshot_storages(0, None, None).0,
+    )
+    .unwrap();
+    let buf = writer.into_inner();
+
+    let persisted_index_dir = TempDir::new().unwrap();
+    let accounts_db_config = AccountsDbConfig {
+        index: Some(AccountsIndexConfig {
+            persisted_index_path: Some(persisted_index_dir.path().to_path_buf()),
+            ..crate::accounts_index::ACCOUNTS_INDEX_CONFIG_FOR_TESTING
+        }),
+        ..crate::accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING
+    };
+    let load = || {
+        let copied_accounts = TempDir::new().unwrap();
+        let unpacked_append_vec_map =
+            copy_append_vecs(&accounts.accounts_db, copied_accounts.path()).unwrap();
+        let mut reader = BufReader::new(&buf[..]);
+        let accounts_db_fields =
+            TypeContextFuture::deserialize_accounts_db_fields(&mut reader).unwrap();
+        let (_accounts_dir, daccounts_paths) = get_temp_accounts_paths(2).unwrap();
+        let (accounts_db, info) = reconstruct_accountsdb_from_fields(
+            SnapshotAccountsDbFields {
+                full_snapshot_accounts_db_fields: accounts_db_fields,
+                incremental_snapshot_accounts_db_fields: None,
+                written_slots: SerdeWrittenSlots::default(),
+            },
+            &daccounts_paths,
+            unpacked_append_vec_map,
+            &GenesisConfig {
+                cluster_type: ClusterType::Development,
+                ..GenesisConfig::default()
+            },
+            AccountSecondaryIndexes::default(),
+            false,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            Some(accounts_db_config.clone()),
+            None,
+        )
+        .unwrap();
+        let store = accounts_db.storage.get_slot_storage_entries(0).unwrap()[0].clone();
+        let daccounts = Accounts::new_empty(accounts_db);
+        check_accounts(&daccounts, &pubkeys, 100);
+        assert_eq!(accounts.bank_hash_at(0), daccounts.bank_hash_at(0));
+        (
+            info.accounts_data_len,
+            store.count(),
+            store.alive_bytes(),
+            daccounts.accounts_db.accounts_index.occupancy().count,
+        )
+    };
+
+    // the first load generates the index from the storages and persists it
+    let generated = load();
+    assert!(std::fs::read_dir(persisted_index_dir.path())
+        .unwrap()
+        .next()
+        .is_some());
+
+    // the second load uses the persisted index
+    let persisted = load();
+    assert_eq!(generated, persisted);
+    assert_eq!(generated.1, 100);
+
+    // a truncated index is generated from the storages again
+    let path = std::fs::read_dir(persisted_index_dir.path())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let data = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &data[..data.len() - 1]).unwrap();
+    assert_eq!(load(), generated);
+    assert_eq!(std::fs::read(&path).unwrap(), data);
+}
+
 #[cfg(all(test, RUSTC_WITH_SPECIALIZATION))]
 mod test_bank_serialize {
     use super::*;
@@ -0,0 +1,505 @@
+//! Chunked snapshot archives
+//!
+//! A chunked archive splits the snapshot's files into independently compressed tar streams
+//! ("chunks") so they can be downloaded, verified, decompressed and unpacked in parallel.  The
+//! layout is
+//!
+//! ```text
+//! MAGIC | chunk 0 | chunk 1 | ... | chunk n | manifest | manifest size (u64 LE) | MAGIC
+//! ```
+//!
+//! Every chunk is a zstd compressed tar archive.  The first chunk holds the version file and
+//! the bank snapshot, the remaining chunks hold the account storage files.  The manifest is the
+//! bincode serialized `ChunkedArchiveManifest`, listing the location and hash of every chunk.
+//! The writer never seeks, so archives can be streamed to their destination as they are
+//! generated, and readers find the manifest from the fixed size footer at the end of the file.
+
+use {
+    crate::{
+        hardened_unpack::{unpack_snapshot_with_totals, UnpackTotals, UnpackedAppendVecMap},
+        snapshot_utils::{Result, SnapshotError},
+    },
+    bincode::config::Options,
+    log::*,
+    rayon::prelude::*,
+    serde_derive::{Deserialize, Serialize},
+    paychains_sdk::hash::{hash, Hash, Hasher},
+    std::{
+        fs::{self, File},
+        io::{Read, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+    },
+    tar::Archive,
+};
+
+/// Magic bytes at the start and at the end of every chunked archive
+pub const CHUNKED_ARCHIVE_MAGIC: &[u8; 8] = b"PAYCHNK1";
+/// Size of the footer: the manifest size followed by the magic bytes
+pub const CHUNKED_ARCHIVE_FOOTER_SIZE: u64 = 16;
+/// Chunks are cut once the files they contain add up to this many bytes
+pub const DEFAULT_CHUNK_TARGET_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_MANIFEST_SIZE: u64 = 64 * 1024 * 1024;
+/// Chunks are verified a buffer of this size at a time, instead of being read into memory
+const CHUNK_READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Location and hash of one chunk in a chunked archive
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkInfo {
+    /// Offset of the compressed chunk from the start of the archive
+    pub offset: u64,
+    /// Size of the compressed chunk
+    pub size: u64,
+    /// Total size of the files in the chunk
+    pub unpacked_size: u64,
+    /// Hash of the compressed chunk
+    pub hash: Hash,
+}
+
+/// The index of a chunked archive
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkedArchiveManifest {
+    pub chunks: Vec<ChunkInfo>,
+}
+
+impl ChunkedArchiveManifest {
+    /// Offset of the manifest from the start of the archive
+    pub fn manifest_offset(&self) -> u64 {
+        self.chunks
+            .last()
+            .map(|chunk| chunk.offset.saturating_add(chunk.size))
+            .unwrap_or(CHUNKED_ARCHIVE_MAGIC.len() as u64)
+    }
+
+    pub fn unpacked_size(&self) -> u64 {
+        self.chunks
+            .iter()
+            .fold(0, |size, chunk| size.saturating_add(chunk.unpacked_size))
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        let manifest: Self = bincode::options()
+            .with_limit(MAX_MANIFEST_SIZE)
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .deserialize(bytes)?;
+        let mut offset = CHUNKED_ARCHIVE_MAGIC.len() as u64;
+        for chunk in &manifest.chunks {
+            if chunk.offset != offset {
+                return Err(malformed(format!(
+                    "chunk at offset {} where {} was expected",
+                    chunk.offset, offset
+                )));
+            }
+            offset = offset
+                .checked_add(chunk.size)
+                .ok_or_else(|| malformed("chunk size overflow".to_string()))?;
+        }
+        Ok(manifest)
+    }
+}
+
+fn malformed(reason: String) -> SnapshotError {
+    SnapshotError::MalformedChunkedArchive(reason)
+}
+
+/// Incrementally checks a chunk's compressed bytes, passed to it in order, against the chunk's
+/// manifest entry
+pub struct ChunkVerifier<'a> {
+    index: usize,
+    chunk: &'a ChunkInfo,
+    hasher: Hasher,
+    size: u64,
+}
+
+impl<'a> ChunkVerifier<'a> {
+    pub fn new(index: usize, chunk: &'a ChunkInfo) -> Self {
+        Self {
+            index,
+            chunk,
+            hasher: Hasher::default(),
+            size: 0,
+        }
+    }
+
+    /// Add the next bytes of the chunk, failing as soon as there are more than the manifest
+    /// lists
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.size = self.size.saturating_add(data.len() as u64);
+        if self.size > self.chunk.size {
+            return Err(SnapshotError::ChunkVerificationFailed(self.index));
+        }
+        self.hasher.hash(data);
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if self.size != self.chunk.size || self.hasher.result() != self.chunk.hash {
+            return Err(SnapshotError::ChunkVerificationFailed(self.index));
+        }
+        Ok(())
+    }
+}
+
+/// Check the chunk at its offset in the archive `file` against its manifest entry, without
+/// reading the whole chunk into memory
+pub fn verify_chunk_in_file(file: &mut File, index: usize, chunk: &ChunkInfo) -> Result<()> {
+    file.seek(SeekFrom::Start(chunk.offset))?;
+    let mut reader = Read::by_ref(file).take(chunk.size);
+    let mut verifier = ChunkVerifier::new(index, chunk);
+    let mut buffer = vec![0u8; CHUNK_READ_BUFFER_SIZE];
+    loop {
+        let len = reader.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        verifier.update(&buffer[..len])?;
+    }
+    verifier.finish()
+}
+
+/// Parse the footer at the end of a chunked archive, returning the size of the manifest that
+/// precedes it
+pub fn parse_footer(footer: &[u8]) -> Result<u64> {
+    if footer.len() as u64 != CHUNKED_ARCHIVE_FOOTER_SIZE || &footer[8..] != CHUNKED_ARCHIVE_MAGIC {
+        return Err(malformed("invalid footer".to_string()));
+    }
+    let mut manifest_size = [0u8; 8];
+    manifest_size.copy_from_slice(&footer[..8]);
+    let manifest_size = u64::from_le_bytes(manifest_size);
+    if manifest_size > MAX_MANIFEST_SIZE {
+        return Err(malformed(format!("manifest too large: {}", manifest_size)));
+    }
+    Ok(manifest_size)
+}
+
+/// Read the manifest of the chunked archive at `archive_path`
+pub fn read_manifest(archive_path: &Path) -> Result<ChunkedArchiveManifest> {
+    let mut file = File::open(archive_path)?;
+    let archive_size = file.metadata()?.len();
+    let mut magic = [0u8; 8];
+    file.read_exact(&mut magic)?;
+    if &magic != CHUNKED_ARCHIVE_MAGIC {
+        return Err(malformed("invalid header".to_string()));
+    }
+    let footer_offset = archive_size
+        .checked_sub(CHUNKED_ARCHIVE_FOOTER_SIZE)
+        .ok_or_else(|| malformed("archive too small".to_string()))?;
+    file.seek(SeekFrom::Start(footer_offset))?;
+    let mut footer = [0u8; CHUNKED_ARCHIVE_FOOTER_SIZE as usize];
+    file.read_exact(&mut footer)?;
+    let manifest_size = parse_footer(&footer)?;
+    let manifest_offset = footer_offset
+        .checked_sub(manifest_size)
+        .ok_or_else(|| malformed("manifest size exceeds archive size".to_string()))?;
+    file.seek(SeekFrom::Start(manifest_offset))?;
+    let mut manifest = vec![0u8; manifest_size as usize];
+    file.read_exact(&mut manifest)?;
+    let manifest = ChunkedArchiveManifest::deserialize(&manifest)?;
+    if manifest.manifest_offset() != manifest_offset {
+        return Err(malformed("chunks do not end at the manifest".to_string()));
+    }
+    Ok(manifest)
+}
+
+/// Files to put in one chunk, as (path in the archive, path on disk)
+type ChunkFiles = Vec<(PathBuf, PathBuf)>;
+
+/// Group the files of a snapshot staging directory into chunks
+fn plan_chunks(staging_dir: &Path, target_chunk_size: u64) -> Result<Vec<(ChunkFiles, u64)>> {
+    let mut first_chunk = vec![(PathBuf::from("version"), staging_dir.join("version"))];
+    let mut first_chunk_size = fs::metadata(staging_dir.join("version"))?.len();
+    let snapshots_dir = staging_dir.join("snapshots");
+    let mut directories = vec![snapshots_dir.clone()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            // `metadata` follows the symlinks of the staging directory
+            let metadata = fs::metadata(&path)?;
+            if metadata.is_dir() {
+                directories.push(path);
+            } else {
+                let name = Path::new("snapshots").join(path.strip_prefix(&snapshots_dir).unwrap());
+                first_chunk_size = first_chunk_size.saturating_add(metadata.len());
+                first_chunk.push((name, path));
+            }
+        }
+    }
+
+    let mut account_files = vec![];
+    for entry in fs::read_dir(staging_dir.join("accounts"))? {
+        let path = entry?.path();
+        let size = fs::metadata(&path)?.len();
+        account_files.push((path, size));
+    }
+    account_files.sort_unstable();
+
+    let mut chunks = vec![(first_chunk, first_chunk_size)];
+    let mut chunk = vec![];
+    let mut chunk_size = 0u64;
+    for (path, size) in account_files {
+        if !chunk.is_empty() && chunk_size.saturating_add(size) > target_chunk_size {
+            chunks.push((std::mem::take(&mut chunk), chunk_size));
+            chunk_size = 0;
+        }
+        let name = Path::new("accounts").join(path.file_name().unwrap());
+        chunk.push((name, path));
+        chunk_size = chunk_size.saturating_add(size);
+    }
+    if !chunk.is_empty() {
+        chunks.push((chunk, chunk_size));
+    }
+    Ok(chunks)
+}
+
+fn compress_chunk(files: &[(PathBuf, PathBuf)]) -> Result<Vec<u8>> {
+    let encoder = zstd::stream::Encoder::new(Vec::new(), 0)?;
+    let mut archive = tar::Builder::new(encoder);
+    for (name, path) in files {
+        archive.append_path_with_name(path, name)?;
+    }
+    Ok(archive.into_inner()?.finish()?)
+}
+
+/// Write the snapshot staged in `staging_dir` to `writer` as a chunked archive.  Chunks are
+/// compressed in parallel and written in order
+pub fn write_chunked_archive<W: Write>(
+    writer: &mut W,
+    staging_dir: &Path,
+    target_chunk_size: u64,
+) -> Result<ChunkedArchiveManifest> {
+    let planned_chunks = plan_chunks(staging_dir, target_chunk_size)?;
+    writer.write_all(CHUNKED_ARCHIVE_MAGIC)?;
+    let mut manifest = ChunkedArchiveManifest::default();
+    // Bound memory use by compressing one chunk per thread at a time
+    for batch in planned_chunks.chunks(rayon::current_num_threads().max(1)) {
+        let compressed = batch
+            .par_iter()
+            .map(|(files, _)| compress_chunk(files))
+            .collect::<Result<Vec<_>>>()?;
+        for ((_, unpacked_size), data) in batch.iter().zip(compressed) {
+            writer.write_all(&data)?;
+            manifest.chunks.push(ChunkInfo {
+                offset: manifest.manifest_offset(),
+                size: data.len() as u64,
+                unpacked_size: *unpacked_size,
+                hash: hash(&data),
+            });
+        }
+    }
+    let serialized_manifest = manifest.serialize()?;
+    writer.write_all(&serialized_manifest)?;
+    writer.write_all(&(serialized_manifest.len() as u64).to_le_bytes())?;
+    writer.write_all(CHUNKED_ARCHIVE_MAGIC)?;
+    writer.flush()?;
+    info!(
+        "wrote chunked archive of {} chunks, {} bytes unpacked",
+        manifest.chunks.len(),
+        manifest.unpacked_size()
+    );
+    Ok(manifest)
+}
+
+/// Verify every chunk of the chunked archive at `archive_path` against its manifest
+pub fn verify_chunked_archive(archive_path: &Path) -> Result<ChunkedArchiveManifest> {
+    let manifest = read_manifest(archive_path)?;
+    manifest
+        .chunks
+        .par_iter()
+        .enumerate()
+        .try_for_each(|(index, chunk)| {
+            verify_chunk_in_file(&mut File::open(archive_path)?, index, chunk)
+        })?;
+    Ok(manifest)
+}
+
+/// Unpack the chunked archive at `archive_path`, verifying, decompressing and unpacking its
+/// chunks in parallel.  The unpacked size and file count limits apply to all the chunks together
+pub fn unpack_chunked_archive(
+    archive_path: &Path,
+    ledger_dir: &Path,
+    account_paths: &[PathBuf],
+) -> Result<UnpackedAppendVecMap> {
+    unpack_chunks(
+        archive_path,
+        ledger_dir,
+        account_paths,
+        &UnpackTotals::default(),
+    )
+}
+
+fn unpack_chunks(
+    archive_path: &Path,
+    ledger_dir: &Path,
+    account_paths: &[PathBuf],
+    totals: &UnpackTotals,
+) -> Result<UnpackedAppendVecMap> {
+    let manifest = read_manifest(archive_path)?;
+    let unpacked_append_vec_maps = manifest
+        .chunks
+        .par_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            // Verify the whole chunk before unpacking any of it, then read it again to unpack
+            let mut file = File::open(archive_path)?;
+            verify_chunk_in_file(&mut file, index, chunk)?;
+            file.seek(SeekFrom::Start(chunk.offset))?;
+            let decoder = zstd::stream::read::Decoder::new(file.take(chunk.size))?;
+            let mut archive = Archive::new(decoder);
+            Ok(unpack_snapshot_with_totals(
+                &mut archive,
+                ledger_dir,
+                account_paths,
+                None,
+                totals,
+            )?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut unpacked_append_vec_map = UnpackedAppendVecMap::new();
+    for map in unpacked_append_vec_maps {
+        unpacked_append_vec_map.extend(map);
+    }
+    Ok(unpacked_append_vec_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn create_staging_dir(num_storages: usize, storage_size: usize) -> TempDir {
+        let staging_dir = TempDir::new().unwrap();
+        fs::write(staging_dir.path().join("version"), "1.2.0").unwrap();
+        let bank_snapshot_dir = staging_dir.path().join("snapshots").join("42");
+        fs::create_dir_all(&bank_snapshot_dir).unwrap();
+        fs::write(bank_snapshot_dir.join("42"), vec![1u8; 100]).unwrap();
+        fs::write(
+            staging_dir.path().join("snapshots").join("status_cache"),
+            [2u8; 10],
+        )
+        .unwrap();
+        let accounts_dir = staging_dir.path().join("accounts");
+        fs::create_dir_all(&accounts_dir).unwrap();
+        for id in 0..num_storages {
+            fs::write(
+                accounts_dir.join(format!("42.{}", id)),
+                vec![id as u8; storage_size],
+            )
+            .unwrap();
+        }
+        staging_dir
+    }
+
+    #[test]
+    fn test_chunked_archive_roundtrip() {
+        let staging_dir = create_staging_dir(10, 1000);
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("snapshot.tar.zst.chunked");
+        let manifest = write_chunked_archive(
+            &mut File::create(&archive_path).unwrap(),
+            staging_dir.path(),
+            2500,
+        )
+        .unwrap();
+        // The version and bank snapshot, then five chunks of two storages each
+        assert_eq!(manifest.chunks.len(), 6);
+        assert_eq!(manifest.unpacked_size(), 5 + 100 + 10 + 10 * 1000);
+        assert_eq!(read_manifest(&archive_path).unwrap(), manifest);
+        assert_eq!(verify_chunked_archive(&archive_path).unwrap(), manifest);
+
+        let unpack_dir = TempDir::new().unwrap();
+        let account_paths = [unpack_dir.path().to_path_buf()];
+        let unpacked_append_vec_map =
+            unpack_chunked_archive(&archive_path, unpack_dir.path(), &account_paths).unwrap();
+        assert_eq!(unpacked_append_vec_map.len(), 10);
+        assert!(!dir_diff::is_different(
+            staging_dir.path().join("accounts"),
+            unpack_dir.path().join("accounts")
+        )
+        .unwrap());
+        assert!(!dir_diff::is_different(
+            staging_dir.path().join("snapshots"),
+            unpack_dir.path().join("snapshots")
+        )
+        .unwrap());
+        assert_eq!(
+            fs::read(unpack_dir.path().join("version")).unwrap(),
+            b"1.2.0"
+        );
+    }
+
+    #[test]
+    fn test_chunked_archive_corrupt_chunk() {
+        let staging_dir = create_staging_dir(4, 1000);
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("snapshot.tar.zst.chunked");
+        let manifest = write_chunked_archive(
+            &mut File::create(&archive_path).unwrap(),
+            staging_dir.path(),
+            1000,
+        )
+        .unwrap();
+
+        let mut bytes = fs::read(&archive_path).unwrap();
+        let corrupt_offset = manifest.chunks[2].offset as usize + 1;
+        bytes[corrupt_offset] = bytes[corrupt_offset].wrapping_add(1);
+        fs::write(&archive_path, bytes).unwrap();
+
+        assert!(matches!(
+            verify_chunked_archive(&archive_path),
+            Err(SnapshotError::ChunkVerificationFailed(2))
+        ));
+        let unpack_dir = TempDir::new().unwrap();
+        assert!(matches!(
+            unpack_chunked_archive(
+                &archive_path,
+                unpack_dir.path(),
+                &[unpack_dir.path().to_path_buf()]
+            ),
+            Err(SnapshotError::ChunkVerificationFailed(2))
+        ));
+    }
+
+    #[test]
+    fn test_chunk_verifier() {
+        let data = [7u8; 100];
+        let chunk = ChunkInfo {
+            offset: 8,
+            size: data.len() as u64,
+            unpacked_size: 1000,
+            hash: hash(&data),
+        };
+        let mut verifier = ChunkVerifier::new(3, &chunk);
+        for part in data.chunks(30) {
+            verifier.update(part).unwrap();
+        }
+        verifier.finish().unwrap();
+
+        // Extra bytes fail as soon as they arrive
+        let mut verifier = ChunkVerifier::new(3, &chunk);
+        verifier.update(&data).unwrap();
+        assert!(matches!(
+            verifier.update(&[0]),
+            Err(SnapshotError::ChunkVerificationFailed(3))
+        ));
+        // Missing bytes fail at the end
+        let mut verifier = ChunkVerifier::new(3, &chunk);
+        verifier.update(&data[1..]).unwrap();
+        assert!(matches!(
+            verifier.finish(),
+            Err(SnapshotError::ChunkVerificationFailed(3))
+        ));
+    }
+
+    #[test]
+    fn test_parse_footer() {
+        let mut footer = 100u64.to_le_bytes().to_vec();
+        footer.extend_from_slice(CHUNKED_ARCHIVE_MAGIC);
+        assert_eq!(parse_footer(&footer).unwrap(), 100);
+        assert!(parse_footer(&footer[1..]).is_err());
+        footer[15] = 0;
+        assert!(parse_footer(&footer).is_err());
+    }
+}
@@ -14,6 +14,9 @@ use {
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
         },
+        snapshot_chunked_archive::{
+            unpack_chunked_archive, write_chunked_archive, DEFAULT_CHUNK_TARGET_SIZE,
+        },
         snapshot_package::{
             AccountsPackage, AccountsPackageSendError, AccountsPackageSender, SnapshotPackage,
             SnapshotType,
@@ -56,8 +59,8 @@ pub const TMP_SNAPSHOT_ARCHIVE_PREFIX: &str = "tmp-snapshot-archive-";
 pub const MAX_BANK_SNAPSHOTS_TO_RETAIN: usize = 8; // Save some bank snapshots but not too many
 pub const DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN: usize = 2;
 pub const DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN: usize = 4;
-pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz)$";
-pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz)$";
+pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.zst\.chunked)$";
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar|tar\.bz2|tar\.zst|tar\.gz|tar\.zst\.chunked)$";
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum SnapshotVersion {
@@ -121,6 +124,9 @@ pub enum ArchiveFormat {
     TarGzip,
     TarZstd,
     Tar,
+    /// Independently compressed tar.zst chunks and an index of them, which can be downloaded
+    /// and unpacked in parallel.  See `snapshot_chunked_archive`
+    TarZstdChunked,
 }
 
 /// A slot and the path to its bank snapshot
@@ -208,6 +214,12 @@ pub enum SnapshotError {
 
     #[error("snapshot has mismatch: deserialized bank: {:?}, snapshot archive info: {:?}", .0, .1)]
     MismatchedSlotHash((Slot, Hash), (Slot, Hash)),
+
+    #[error("malformed chunked archive: {0}")]
+    MalformedChunkedArchive(String),
+
+    #[error("chunk {0} of chunked archive does not match its manifest entry")]
+    ChunkVerificationFailed(usize),
 }
 pub type Result<T> = std::result::Result<T, SnapshotError>;
 
@@ -217,6 +229,7 @@ fn get_archive_ext(archive_format: ArchiveFormat) -> &'static str {
         ArchiveFormat::TarGzip => "tar.gz",
         ArchiveFormat::TarZstd => "tar.zst",
         ArchiveFormat::Tar => "tar",
+        ArchiveFormat::TarZstdChunked => "tar.zst.chunked",
     }
 }
 
@@ -305,6 +318,15 @@ pub fn archive_snapshot_package(
             storage.append_vec_id(),
         ));
 
+        // Nothing records that a storage is compressed, so archives only contain uncompressed ones
+        if storage.accounts.is_compressed() {
+            storage
+                .accounts
+                .write_uncompressed(&output_path)
+                .map_err(|e| SnapshotError::IoWithSource(e, "write uncompressed storage"))?;
+            continue;
+        }
+
         // `storage_path` - The file path where the AppendVec itself is located
         // `output_path` - The file path where the AppendVec will be placed in the staging directory.
         let storage_path =
@@ -368,6 +390,13 @@ pub fn archive_snapshot_package(
             ArchiveFormat::Tar => {
                 do_archive_files(&mut archive_file)?;
             }
+            ArchiveFormat::TarZstdChunked => {
+                write_chunked_archive(
+                    &mut BufWriter::new(archive_file),
+                    staging_dir.path(),
+                    DEFAULT_CHUNK_TARGET_SIZE,
+                )?;
+            }
         };
     }
 
@@ -1071,6 +1100,7 @@ fn archive_format_from_str(archive_format: &str) -> Option<ArchiveFormat> {
         "tar.gz" => Some(ArchiveFormat::TarGzip),
         "tar.zst" => Some(ArchiveFormat::TarZstd),
         "tar" => Some(ArchiveFormat::Tar),
+        "tar.zst.chunked" => Some(ArchiveFormat::TarZstdChunked),
         _ => None,
     }
 }
@@ -1394,6 +1424,9 @@ fn untar_snapshot_in<P: AsRef<Path>>(
             account_paths,
             parallel_divisions,
         )?,
+        ArchiveFormat::TarZstdChunked => {
+            unpack_chunked_archive(snapshot_tar.as_ref(), unpack_dir, account_paths)?
+        }
     };
     Ok(account_paths_map)
 }
@@ -2061,6 +2094,14 @@ mod tests {
                 .unwrap(),
             (44, Hash::default(), ArchiveFormat::Tar)
         );
+        assert_eq!(
+            parse_full_snapshot_archive_filename(&format!(
+                "snapshot-45-{}.tar.zst.chunked",
+                Hash::default()
+            ))
+            .unwrap(),
+            (45, Hash::default(), ArchiveFormat::TarZstdChunked)
+        );
 
         assert!(parse_full_snapshot_archive_filename("invalid").is_err());
         assert!(
@@ -2695,6 +2736,56 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    /// Test roundtrip of bank to a full snapshot in the chunked archive format, then back again
+    #[test]
+    fn test_roundtrip_bank_to_and_from_chunked_full_snapshot() {
+        paychains_logger::setup();
+        let genesis_config = GenesisConfig::default();
+        let original_bank = Bank::new_for_tests(&genesis_config);
+
+        while !original_bank.is_complete() {
+            original_bank.register_tick(&Hash::new_unique());
+        }
+
+        let accounts_dir = tempfile::TempDir::new().unwrap();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::TarZstdChunked;
+
+        let snapshot_archive_info = bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &original_bank,
+            None,
+            snapshot_archives_dir.path(),
+            snapshot_archive_format,
+            DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+            DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
+        )
+        .unwrap();
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            &[PathBuf::from(accounts_dir.path())],
+            bank_snapshots_dir.path(),
+            &snapshot_archive_info,
+            None,
+            &genesis_config,
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            false,
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            Some(ACCOUNTS_DB_CONFIG_FOR_TESTING),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(original_bank, roundtrip_bank);
+    }
+
     /// Test roundtrip of bank to a full snapshot, then back again.  This test is more involved
     /// than the simple version above; creating multiple banks over multiple slots and doing
     /// multiple transfers.  So this full snapshot should contain more data.
@@ -0,0 +1,366 @@
+//! Group operations and pairing checks on the alt_bn128 (BN254) curve.
+//!
+//! Inputs and outputs use the big-endian encoding of the Ethereum
+//! `ECADD`, `ECMUL` and `ECPAIRING` precompiles (EIP-196 and EIP-197), so
+//! proofs and verifying keys produced by Ethereum tooling can be used as-is.
+
+use thiserror::Error;
+
+/// Group operation identifier for point addition
+pub const ALT_BN128_ADD: u64 = 0;
+/// Group operation identifier for scalar multiplication
+pub const ALT_BN128_MUL: u64 = 2;
+/// Group operation identifier for a pairing check
+pub const ALT_BN128_PAIRING: u64 = 3;
+
+/// Byte length of a field element
+pub const ALT_BN128_FIELD_SIZE: usize = 32;
+/// Byte length of an encoded G1 point
+pub const ALT_BN128_POINT_SIZE: usize = 64;
+/// Byte length of an encoded G2 point
+pub const ALT_BN128_G2_POINT_SIZE: usize = 128;
+
+pub const ALT_BN128_ADDITION_INPUT_LEN: usize = 128;
+pub const ALT_BN128_MULTIPLICATION_INPUT_LEN: usize = 96;
+pub const ALT_BN128_PAIRING_ELEMENT_LEN: usize = ALT_BN128_POINT_SIZE + ALT_BN128_G2_POINT_SIZE;
+pub const ALT_BN128_ADDITION_OUTPUT_LEN: usize = ALT_BN128_POINT_SIZE;
+pub const ALT_BN128_MULTIPLICATION_OUTPUT_LEN: usize = ALT_BN128_POINT_SIZE;
+pub const ALT_BN128_PAIRING_OUTPUT_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AltBn128Error {
+    #[error("The input data is invalid")]
+    InvalidInputData,
+    #[error("A point is not on the curve or not in the correct subgroup")]
+    GroupError,
+    #[error("Unexpected error")]
+    UnexpectedError,
+}
+
+impl From<u64> for AltBn128Error {
+    fn from(v: u64) -> AltBn128Error {
+        match v {
+            1 => AltBn128Error::InvalidInputData,
+            2 => AltBn128Error::GroupError,
+            _ => AltBn128Error::UnexpectedError,
+        }
+    }
+}
+
+impl From<AltBn128Error> for u64 {
+    fn from(v: AltBn128Error) -> u64 {
+        match v {
+            AltBn128Error::InvalidInputData => 1,
+            AltBn128Error::GroupError => 2,
+            AltBn128Error::UnexpectedError => 3,
+        }
+    }
+}
+
+/// Adds two G1 points, `input` being their concatenated encodings
+pub fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    alt_bn128_group_op(ALT_BN128_ADD, input, ALT_BN128_ADDITION_OUTPUT_LEN)
+}
+
+/// Multiplies a G1 point by a scalar, `input` being the point encoding
+/// followed by the 32-byte big-endian scalar
+pub fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    alt_bn128_group_op(ALT_BN128_MUL, input, ALT_BN128_MULTIPLICATION_OUTPUT_LEN)
+}
+
+/// Checks whether the product of pairings of the (G1, G2) pairs in `input`
+/// is the identity; the result is a 32-byte big-endian 1 or 0
+pub fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+    alt_bn128_group_op(ALT_BN128_PAIRING, input, ALT_BN128_PAIRING_OUTPUT_LEN)
+}
+
+fn alt_bn128_group_op(
+    group_op: u64,
+    input: &[u8],
+    output_len: usize,
+) -> Result<Vec<u8>, AltBn128Error> {
+    let mut result_buffer = vec![0u8; output_len];
+
+    #[cfg(target_arch = "bpf")]
+    let result = {
+        extern "C" {
+            fn pay_alt_bn128_group_op(
+                group_op: u64,
+                input: *const u8,
+                input_size: u64,
+                result: *mut u8,
+            ) -> u64;
+        }
+        unsafe {
+            pay_alt_bn128_group_op(
+                group_op,
+                input.as_ptr(),
+                input.len() as u64,
+                result_buffer.as_mut_ptr(),
+            )
+        }
+    };
+
+    #[cfg(not(target_arch = "bpf"))]
+    let result = crate::program_stubs::pay_alt_bn128_group_op(group_op, input, &mut result_buffer);
+
+    match result {
+        0 => Ok(result_buffer),
+        error => Err(AltBn128Error::from(error)),
+    }
+}
+
+/// Native implementation backing the syscall and the off-chain stubs
+#[cfg(not(target_arch = "bpf"))]
+pub mod native {
+    use {
+        super::*,
+        ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine},
+        ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve},
+        ark_ff::{BigInteger, BigInteger256, One, PrimeField, Zero},
+    };
+
+    /// Performs `group_op` on `input`, returning the encoded result
+    pub fn alt_bn128_group_op(group_op: u64, input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+        match group_op {
+            ALT_BN128_ADD => alt_bn128_addition(input),
+            ALT_BN128_MUL => alt_bn128_multiplication(input),
+            ALT_BN128_PAIRING => alt_bn128_pairing(input),
+            _ => Err(AltBn128Error::InvalidInputData),
+        }
+    }
+
+    /// Number of (G1, G2) pairs in a pairing check input
+    pub fn alt_bn128_pairing_count(input: &[u8]) -> Result<usize, AltBn128Error> {
+        if input.len() % ALT_BN128_PAIRING_ELEMENT_LEN != 0 {
+            return Err(AltBn128Error::InvalidInputData);
+        }
+        Ok(input.len() / ALT_BN128_PAIRING_ELEMENT_LEN)
+    }
+
+    pub fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+        let input = padded_input::<ALT_BN128_ADDITION_INPUT_LEN>(input)?;
+        let p = read_g1(&input[..ALT_BN128_POINT_SIZE])?;
+        let q = read_g1(&input[ALT_BN128_POINT_SIZE..])?;
+        Ok(write_g1(
+            &(p.into_projective() + q.into_projective()).into_affine(),
+        ))
+    }
+
+    pub fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+        let input = padded_input::<ALT_BN128_MULTIPLICATION_INPUT_LEN>(input)?;
+        let p = read_g1(&input[..ALT_BN128_POINT_SIZE])?;
+        // The scalar is not reduced modulo the group order, as in EIP-196
+        let scalar = read_big_integer(&input[ALT_BN128_POINT_SIZE..]);
+        Ok(write_g1(&p.mul(scalar).into_affine()))
+    }
+
+    pub fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, AltBn128Error> {
+        alt_bn128_pairing_count(input)?;
+        let pairs = input
+            .chunks(ALT_BN128_PAIRING_ELEMENT_LEN)
+            .map(|element| {
+                let g1 = read_g1(&element[..ALT_BN128_POINT_SIZE])?;
+                let g2 = read_g2(&element[ALT_BN128_POINT_SIZE..])?;
+                Ok((
+                    <Bn254 as PairingEngine>::G1Prepared::from(g1),
+                    <Bn254 as PairingEngine>::G2Prepared::from(g2),
+                ))
+            })
+            .collect::<Result<Vec<_>, AltBn128Error>>()?;
+
+        let mut output = vec![0u8; ALT_BN128_PAIRING_OUTPUT_LEN];
+        if Bn254::product_of_pairings(&pairs) == <Bn254 as PairingEngine>::Fqk::one() {
+            output[ALT_BN128_PAIRING_OUTPUT_LEN - 1] = 1;
+        }
+        Ok(output)
+    }
+
+    /// Shorter inputs are implicitly zero-padded, longer ones are rejected
+    fn padded_input<const N: usize>(input: &[u8]) -> Result<[u8; N], AltBn128Error> {
+        if input.len() > N {
+            return Err(AltBn128Error::InvalidInputData);
+        }
+        let mut padded = [0u8; N];
+        padded[..input.len()].copy_from_slice(input);
+        Ok(padded)
+    }
+
+    fn read_big_integer(bytes: &[u8]) -> BigInteger256 {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks(8)) {
+            *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        BigInteger256::new(limbs)
+    }
+
+    fn read_fq(bytes: &[u8]) -> Result<Fq, AltBn128Error> {
+        Fq::from_repr(read_big_integer(bytes)).ok_or(AltBn128Error::InvalidInputData)
+    }
+
+    fn read_g1(bytes: &[u8]) -> Result<G1Affine, AltBn128Error> {
+        let x = read_fq(&bytes[..ALT_BN128_FIELD_SIZE])?;
+        let y = read_fq(&bytes[ALT_BN128_FIELD_SIZE..])?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(G1Affine::zero());
+        }
+        let point = G1Affine::new(x, y, false);
+        if !point.is_on_curve() {
+            return Err(AltBn128Error::GroupError);
+        }
+        Ok(point)
+    }
+
+    /// Field extension elements are encoded with the imaginary part first
+    fn read_g2(bytes: &[u8]) -> Result<G2Affine, AltBn128Error> {
+        let mut coordinates = bytes
+            .chunks(ALT_BN128_FIELD_SIZE)
+            .map(read_fq)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let mut next_fq2 = || {
+            let c1 = coordinates.next().unwrap();
+            let c0 = coordinates.next().unwrap();
+            Fq2::new(c0, c1)
+        };
+        let x = next_fq2();
+        let y = next_fq2();
+        if x.is_zero() && y.is_zero() {
+            return Ok(G2Affine::zero());
+        }
+        let point = G2Affine::new(x, y, false);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(AltBn128Error::GroupError);
+        }
+        Ok(point)
+    }
+
+    fn write_g1(point: &G1Affine) -> Vec<u8> {
+        if point.is_zero() {
+            return vec![0u8; ALT_BN128_POINT_SIZE];
+        }
+        let mut output = point.x.into_repr().to_bytes_be();
+        output.extend(point.y.into_repr().to_bytes_be());
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELD_MODULUS: &str = "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
+
+    fn g1_generator() -> Vec<u8> {
+        let mut point = vec![0u8; ALT_BN128_POINT_SIZE];
+        point[31] = 1;
+        point[63] = 2;
+        point
+    }
+
+    fn g2_generator() -> Vec<u8> {
+        hex::decode(concat!(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+            "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+            "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+            "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_alt_bn128_error_codes() {
+        for err in [
+            AltBn128Error::InvalidInputData,
+            AltBn128Error::GroupError,
+            AltBn128Error::UnexpectedError,
+        ] {
+            assert_eq!(AltBn128Error::from(u64::from(err.clone())), err);
+        }
+        assert_eq!(AltBn128Error::from(0), AltBn128Error::UnexpectedError);
+        assert_eq!(AltBn128Error::from(42), AltBn128Error::UnexpectedError);
+    }
+
+    #[test]
+    fn test_alt_bn128_addition() {
+        let doubled = hex::decode(concat!(
+            "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+            "15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+        ))
+        .unwrap();
+        let input = [g1_generator(), g1_generator()].concat();
+        assert_eq!(alt_bn128_addition(&input), Ok(doubled));
+
+        // the point at infinity is the identity
+        assert_eq!(alt_bn128_addition(&g1_generator()), Ok(g1_generator()));
+        assert_eq!(
+            alt_bn128_addition(&[]),
+            Ok(vec![0u8; ALT_BN128_ADDITION_OUTPUT_LEN])
+        );
+
+        let mut not_on_curve = g1_generator();
+        not_on_curve[63] = 3;
+        assert_eq!(
+            alt_bn128_addition(&not_on_curve),
+            Err(AltBn128Error::GroupError)
+        );
+        assert_eq!(
+            alt_bn128_addition(&hex::decode(FIELD_MODULUS).unwrap()),
+            Err(AltBn128Error::InvalidInputData)
+        );
+        assert_eq!(
+            alt_bn128_addition(&[0u8; ALT_BN128_ADDITION_INPUT_LEN + 1]),
+            Err(AltBn128Error::InvalidInputData)
+        );
+    }
+
+    #[test]
+    fn test_alt_bn128_multiplication() {
+        let mut input = g1_generator();
+        input.extend([0u8; 32]);
+        input[ALT_BN128_MULTIPLICATION_INPUT_LEN - 1] = 2;
+        assert_eq!(
+            alt_bn128_multiplication(&input),
+            alt_bn128_addition(&[g1_generator(), g1_generator()].concat())
+        );
+
+        // the scalar defaults to zero
+        assert_eq!(
+            alt_bn128_multiplication(&g1_generator()),
+            Ok(vec![0u8; ALT_BN128_MULTIPLICATION_OUTPUT_LEN])
+        );
+    }
+
+    #[test]
+    fn test_alt_bn128_pairing() {
+        let mut negated_generator = g1_generator();
+        let mut y = hex::decode(FIELD_MODULUS).unwrap();
+        y[31] -= 2;
+        negated_generator[32..].copy_from_slice(&y);
+
+        let mut one = vec![0u8; ALT_BN128_PAIRING_OUTPUT_LEN];
+        one[31] = 1;
+        let zero = vec![0u8; ALT_BN128_PAIRING_OUTPUT_LEN];
+
+        // e(P, Q) * e(-P, Q) == 1
+        let input = [
+            g1_generator(),
+            g2_generator(),
+            negated_generator,
+            g2_generator(),
+        ]
+        .concat();
+        assert_eq!(alt_bn128_pairing(&input), Ok(one.clone()));
+
+        let input = [g1_generator(), g2_generator()].concat();
+        assert_eq!(alt_bn128_pairing(&input), Ok(zero));
+
+        // an empty product is the identity
+        assert_eq!(alt_bn128_pairing(&[]), Ok(one));
+
+        assert_eq!(
+            alt_bn128_pairing(&input[1..]),
+            Err(AltBn128Error::InvalidInputData)
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Group operations on the curve25519 elliptic curve.
+//!
+//! Points and scalars are passed around in their 32-byte compressed
+//! encodings. On-chain the operations are performed by the
+//! `pay_curve_validate_point` and `pay_curve_group_op` syscalls; off-chain
+//! they are computed natively with `curve25519-dalek`.
+
+use bytemuck::{Pod, Zeroable};
+
+/// Curve identifier for the edwards25519 representation
+pub const CURVE25519_EDWARDS: u64 = 0;
+/// Curve identifier for the ristretto255 representation
+pub const CURVE25519_RISTRETTO: u64 = 1;
+
+/// Group operation identifier for point addition
+pub const ADD: u64 = 0;
+/// Group operation identifier for point subtraction
+pub const SUB: u64 = 1;
+/// Group operation identifier for scalar multiplication
+pub const MUL: u64 = 2;
+
+/// Byte length of a compressed curve point or a scalar
+pub const CURVE25519_BYTES: usize = 32;
+
+/// A compressed edwards25519 point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct PodEdwardsPoint(pub [u8; CURVE25519_BYTES]);
+
+/// A compressed ristretto255 point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct PodRistrettoPoint(pub [u8; CURVE25519_BYTES]);
+
+/// A canonically encoded scalar modulo the curve25519 group order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+#[repr(transparent)]
+pub struct PodScalar(pub [u8; CURVE25519_BYTES]);
+
+#[cfg(target_arch = "bpf")]
+mod syscall {
+    extern "C" {
+        pub fn pay_curve_validate_point(curve_id: u64, point: *const u8) -> u64;
+        pub fn pay_curve_group_op(
+            curve_id: u64,
+            group_op: u64,
+            left_input: *const u8,
+            right_input: *const u8,
+            result: *mut u8,
+        ) -> u64;
+    }
+
+    pub fn validate_point(curve_id: u64, point: &[u8; 32]) -> bool {
+        unsafe { pay_curve_validate_point(curve_id, point.as_ptr()) == 0 }
+    }
+
+    pub fn group_op(
+        curve_id: u64,
+        group_op: u64,
+        left_input: &[u8; 32],
+        right_input: &[u8; 32],
+    ) -> Option<[u8; 32]> {
+        let mut result = [0u8; 32];
+        let status = unsafe {
+            pay_curve_group_op(
+                curve_id,
+                group_op,
+                left_input.as_ptr(),
+                right_input.as_ptr(),
+                result.as_mut_ptr(),
+            )
+        };
+        if status == 0 {
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(target_arch = "bpf"))]
+impl PodScalar {
+    fn to_scalar(self) -> Option<curve25519_dalek::scalar::Scalar> {
+        curve25519_dalek::scalar::Scalar::from_canonical_bytes(self.0)
+    }
+}
+
+pub mod edwards {
+    #[cfg(target_arch = "bpf")]
+    use super::{syscall, CURVE25519_EDWARDS};
+    pub use super::{PodEdwardsPoint, PodScalar};
+    #[cfg(target_arch = "bpf")]
+    use super::{ADD, MUL, SUB};
+    #[cfg(not(target_arch = "bpf"))]
+    use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+
+    #[cfg(not(target_arch = "bpf"))]
+    impl PodEdwardsPoint {
+        fn decompress(&self) -> Option<EdwardsPoint> {
+            CompressedEdwardsY(self.0).decompress()
+        }
+
+        fn compress(point: EdwardsPoint) -> Self {
+            Self(point.compress().to_bytes())
+        }
+    }
+
+    /// Returns whether `point` is a valid edwards25519 point encoding
+    pub fn validate_edwards(point: &PodEdwardsPoint) -> bool {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::validate_point(CURVE25519_EDWARDS, &point.0)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            point.decompress().is_some()
+        }
+    }
+
+    /// Returns `left_point + right_point`, or `None` if either input is invalid
+    pub fn add_edwards(
+        left_point: &PodEdwardsPoint,
+        right_point: &PodEdwardsPoint,
+    ) -> Option<PodEdwardsPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_EDWARDS, ADD, &left_point.0, &right_point.0)
+                .map(PodEdwardsPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodEdwardsPoint::compress(
+                left_point.decompress()? + right_point.decompress()?,
+            ))
+        }
+    }
+
+    /// Returns `left_point - right_point`, or `None` if either input is invalid
+    pub fn subtract_edwards(
+        left_point: &PodEdwardsPoint,
+        right_point: &PodEdwardsPoint,
+    ) -> Option<PodEdwardsPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_EDWARDS, SUB, &left_point.0, &right_point.0)
+                .map(PodEdwardsPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodEdwardsPoint::compress(
+                left_point.decompress()? - right_point.decompress()?,
+            ))
+        }
+    }
+
+    /// Returns `scalar * point`, or `None` if either input is invalid
+    pub fn multiply_edwards(
+        scalar: &PodScalar,
+        point: &PodEdwardsPoint,
+    ) -> Option<PodEdwardsPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_EDWARDS, MUL, &scalar.0, &point.0).map(PodEdwardsPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodEdwardsPoint::compress(
+                scalar.to_scalar()? * point.decompress()?,
+            ))
+        }
+    }
+}
+
+pub mod ristretto {
+    #[cfg(target_arch = "bpf")]
+    use super::{syscall, CURVE25519_RISTRETTO};
+    pub use super::{PodRistrettoPoint, PodScalar};
+    #[cfg(target_arch = "bpf")]
+    use super::{ADD, MUL, SUB};
+    #[cfg(not(target_arch = "bpf"))]
+    use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+
+    #[cfg(not(target_arch = "bpf"))]
+    impl PodRistrettoPoint {
+        fn decompress(&self) -> Option<RistrettoPoint> {
+            CompressedRistretto(self.0).decompress()
+        }
+
+        fn compress(point: RistrettoPoint) -> Self {
+            Self(point.compress().to_bytes())
+        }
+    }
+
+    /// Returns whether `point` is a valid ristretto255 point encoding
+    pub fn validate_ristretto(point: &PodRistrettoPoint) -> bool {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::validate_point(CURVE25519_RISTRETTO, &point.0)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            point.decompress().is_some()
+        }
+    }
+
+    /// Returns `left_point + right_point`, or `None` if either input is invalid
+    pub fn add_ristretto(
+        left_point: &PodRistrettoPoint,
+        right_point: &PodRistrettoPoint,
+    ) -> Option<PodRistrettoPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_RISTRETTO, ADD, &left_point.0, &right_point.0)
+                .map(PodRistrettoPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodRistrettoPoint::compress(
+                left_point.decompress()? + right_point.decompress()?,
+            ))
+        }
+    }
+
+    /// Returns `left_point - right_point`, or `None` if either input is invalid
+    pub fn subtract_ristretto(
+        left_point: &PodRistrettoPoint,
+        right_point: &PodRistrettoPoint,
+    ) -> Option<PodRistrettoPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_RISTRETTO, SUB, &left_point.0, &right_point.0)
+                .map(PodRistrettoPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodRistrettoPoint::compress(
+                left_point.decompress()? - right_point.decompress()?,
+            ))
+        }
+    }
+
+    /// Returns `scalar * point`, or `None` if either input is invalid
+    pub fn multiply_ristretto(
+        scalar: &PodScalar,
+        point: &PodRistrettoPoint,
+    ) -> Option<PodRistrettoPoint> {
+        #[cfg(target_arch = "bpf")]
+        {
+            syscall::group_op(CURVE25519_RISTRETTO, MUL, &scalar.0, &point.0).map(PodRistrettoPoint)
+        }
+        #[cfg(not(target_arch = "bpf"))]
+        {
+            Some(PodRistrettoPoint::compress(
+                scalar.to_scalar()? * point.decompress()?,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{edwards::*, ristretto::*},
+        curve25519_dalek::{
+            constants::{ED25519_BASEPOINT_POINT, RISTRETTO_BASEPOINT_POINT},
+            edwards::CompressedEdwardsY,
+        },
+    };
+
+    fn scalar(value: u8) -> PodScalar {
+        let mut bytes = [0u8; 32];
+        bytes[0] = value;
+        PodScalar(bytes)
+    }
+
+    #[test]
+    fn test_edwards_ops() {
+        let basepoint = PodEdwardsPoint(ED25519_BASEPOINT_POINT.compress().to_bytes());
+        assert!(validate_edwards(&basepoint));
+
+        let two = multiply_edwards(&scalar(2), &basepoint).unwrap();
+        let three = multiply_edwards(&scalar(3), &basepoint).unwrap();
+        assert_eq!(add_edwards(&basepoint, &two), Some(three));
+        assert_eq!(subtract_edwards(&three, &two), Some(basepoint));
+
+        let invalid = (2u8..)
+            .map(|y| {
+                let mut bytes = [0u8; 32];
+                bytes[0] = y;
+                PodEdwardsPoint(bytes)
+            })
+            .find(|point| CompressedEdwardsY(point.0).decompress().is_none())
+            .unwrap();
+        assert!(!validate_edwards(&invalid));
+        assert_eq!(add_edwards(&basepoint, &invalid), None);
+
+        // scalars must be canonically reduced
+        assert_eq!(multiply_edwards(&PodScalar([0xff; 32]), &basepoint), None);
+    }
+
+    #[test]
+    fn test_ristretto_ops() {
+        let basepoint = PodRistrettoPoint(RISTRETTO_BASEPOINT_POINT.compress().to_bytes());
+        assert!(validate_ristretto(&basepoint));
+
+        let two = multiply_ristretto(&scalar(2), &basepoint).unwrap();
+        let three = multiply_ristretto(&scalar(3), &basepoint).unwrap();
+        assert_eq!(add_ristretto(&basepoint, &two), Some(three));
+        assert_eq!(subtract_ristretto(&three, &two), Some(basepoint));
+
+        let invalid = PodRistrettoPoint([0xff; 32]);
+        assert!(!validate_ristretto(&invalid));
+        assert_eq!(subtract_ristretto(&basepoint, &invalid), None);
+        assert_eq!(multiply_ristretto(&PodScalar([0xff; 32]), &basepoint), None);
+    }
+}
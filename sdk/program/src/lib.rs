@@ -6,6 +6,7 @@
 extern crate self as paychains_program;
 
 pub mod account_info;
+pub mod alt_bn128;
 pub(crate) mod atomic_u64;
 pub mod blake3;
 pub mod borsh;
@@ -13,6 +14,7 @@ pub mod bpf_loader;
 pub mod bpf_loader_deprecated;
 pub mod bpf_loader_upgradeable;
 pub mod clock;
+pub mod curve25519;
 pub mod debug_account_data;
 pub mod decode_error;
 pub mod ed25519_program;
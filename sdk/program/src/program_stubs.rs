@@ -4,7 +4,7 @@
 
 use {
     crate::{
-        account_info::AccountInfo, entrypoint::ProgramResult, instruction::Instruction,
+        account_info::AccountInfo, alt_bn128, entrypoint::ProgramResult, instruction::Instruction,
         program_error::UNSUPPORTED_SYSVAR, pubkey::Pubkey,
     },
     itertools::Itertools,
@@ -91,6 +91,21 @@ pub trait SyscallStubs: Sync + Send {
     fn pay_log_data(&self, fields: &[&[u8]]) {
         println!("data: {}", fields.iter().map(base64::encode).join(" "));
     }
+    fn pay_get_processed_sibling_instruction(&self, _index: usize) -> Option<Instruction> {
+        None
+    }
+    fn pay_get_stack_height(&self) -> u64 {
+        0
+    }
+    fn pay_alt_bn128_group_op(&self, group_op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+        match alt_bn128::native::alt_bn128_group_op(group_op, input) {
+            Ok(output) => {
+                result.copy_from_slice(&output);
+                0
+            }
+            Err(err) => err.into(),
+        }
+    }
 }
 
 struct DefaultSyscallStubs {}
@@ -177,6 +192,24 @@ pub(crate) fn pay_log_data(data: &[&[u8]]) {
     SYSCALL_STUBS.read().unwrap().pay_log_data(data)
 }
 
+pub(crate) fn pay_get_processed_sibling_instruction(index: usize) -> Option<Instruction> {
+    SYSCALL_STUBS
+        .read()
+        .unwrap()
+        .pay_get_processed_sibling_instruction(index)
+}
+
+pub(crate) fn pay_get_stack_height() -> u64 {
+    SYSCALL_STUBS.read().unwrap().pay_get_stack_height()
+}
+
+pub(crate) fn pay_alt_bn128_group_op(group_op: u64, input: &[u8], result: &mut [u8]) -> u64 {
+    SYSCALL_STUBS
+        .read()
+        .unwrap()
+        .pay_alt_bn128_group_op(group_op, input, result)
+}
+
 /// Check that two regions do not overlap.
 ///
 /// Adapted from libcore, hidden to share with bpf_loader without being part of
@@ -90,6 +90,32 @@ pub struct ComputeBudget {
     pub syscall_base_cost: u64,
     /// Number of compute units consumed to call zktoken_crypto_op
     pub zk_token_elgamal_op_cost: u64,
+    /// Number of compute units consumed to validate a curve25519 edwards point
+    pub curve25519_edwards_validate_point_cost: u64,
+    /// Number of compute units consumed to add two curve25519 edwards points
+    pub curve25519_edwards_add_cost: u64,
+    /// Number of compute units consumed to subtract two curve25519 edwards points
+    pub curve25519_edwards_subtract_cost: u64,
+    /// Number of compute units consumed to multiply a curve25519 edwards point
+    pub curve25519_edwards_multiply_cost: u64,
+    /// Number of compute units consumed to validate a curve25519 ristretto point
+    pub curve25519_ristretto_validate_point_cost: u64,
+    /// Number of compute units consumed to add two curve25519 ristretto points
+    pub curve25519_ristretto_add_cost: u64,
+    /// Number of compute units consumed to subtract two curve25519 ristretto points
+    pub curve25519_ristretto_subtract_cost: u64,
+    /// Number of compute units consumed to multiply a curve25519 ristretto point
+    pub curve25519_ristretto_multiply_cost: u64,
+    /// Number of compute units consumed to add two alt_bn128 points
+    pub alt_bn128_addition_cost: u64,
+    /// Number of compute units consumed to multiply an alt_bn128 point
+    pub alt_bn128_multiplication_cost: u64,
+    /// Number of compute units consumed by the first pair of an alt_bn128
+    /// pairing check
+    pub alt_bn128_pairing_one_pair_cost_first: u64,
+    /// Number of compute units consumed by each additional pair of an
+    /// alt_bn128 pairing check
+    pub alt_bn128_pairing_one_pair_cost_other: u64,
     /// Optional program heap region size, if `None` then loader default
     pub heap_size: Option<usize>,
     /// Number of compute units per additional 32k heap above the default (~.5
@@ -120,6 +146,18 @@ impl ComputeBudget {
             secp256k1_recover_cost: 25_000,
             syscall_base_cost: 100,
             zk_token_elgamal_op_cost: 25_000,
+            curve25519_edwards_validate_point_cost: 159,
+            curve25519_edwards_add_cost: 473,
+            curve25519_edwards_subtract_cost: 475,
+            curve25519_edwards_multiply_cost: 2_177,
+            curve25519_ristretto_validate_point_cost: 169,
+            curve25519_ristretto_add_cost: 521,
+            curve25519_ristretto_subtract_cost: 519,
+            curve25519_ristretto_multiply_cost: 2_208,
+            alt_bn128_addition_cost: 334,
+            alt_bn128_multiplication_cost: 3_840,
+            alt_bn128_pairing_one_pair_cost_first: 36_364,
+            alt_bn128_pairing_one_pair_cost_other: 12_121,
             heap_size: None,
             heap_cost: 8,
         }
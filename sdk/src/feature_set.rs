@@ -299,6 +299,38 @@ pub mod require_rent_exempt_accounts {
     paychains_sdk::declare_id!("BkFDxiJQWZXGTZaJQxH7wVEHkAmwCgSEVkrvswFfRJPD");
 }
 
+pub mod curve25519_syscall_enabled {
+    paychains_sdk::declare_id!("7rcw5UtqgDTBBv2EcynNfYckgdAaH1MAsCjKgXMkN7Ri");
+}
+
+pub mod alt_bn128_syscall_enabled {
+    paychains_sdk::declare_id!("BHouivEZ3aCSnzAQnFGaFDL1MZBxWuGYxXqKua5AgQex");
+}
+
+pub mod add_get_processed_sibling_instruction_syscall {
+    paychains_sdk::declare_id!("BqTsyx4ThKL8QteoWentfKUFTyTL5foATk8BAKh3Sm96");
+}
+
+pub mod cap_accounts_data_allocations_per_transaction {
+    paychains_sdk::declare_id!("2Ruoj9F9E2dRjuDgCknLZrcMatSxQ8uqe9iwDTzUZNaf");
+}
+
+pub mod cap_accounts_data_size_per_block {
+    paychains_sdk::declare_id!("CCBQd1RKTMp2Pi2XtyXyickurkuFu8GpHKZaNf9ebe2R");
+}
+
+pub mod include_account_index_in_rent_error {
+    paychains_sdk::declare_id!("2yRM1PdNMrEKkYAgejSAbYbwQFQ2M9h3YWTcn8tm6bGg");
+}
+
+pub mod prevent_crediting_accounts_that_end_rent_paying {
+    paychains_sdk::declare_id!("9vPSSr4WNBKTtMCZS86yNwsksNb8LkbsMbj8MqnzcDhm");
+}
+
+pub mod enable_partitioned_epoch_reward {
+    paychains_sdk::declare_id!("8BBuTB17FmhVhUmNr3cXS8bkjoV4NCTv444Hjqs7K4Av");
+}
+
 lazy_static! {
     /// Map of feature identifiers to user-visible description
     pub static ref FEATURE_NAMES: HashMap<Pubkey, &'static str> = [
@@ -368,6 +400,14 @@ lazy_static! {
         (cap_accounts_data_len::id(), "cap the accounts data len"),
         (max_tx_account_locks::id(), "enforce max number of locked accounts per transaction"),
         (require_rent_exempt_accounts::id(), "require all new transaction accounts with data to be rent-exempt"),
+        (curve25519_syscall_enabled::id(), "enable curve25519 syscalls"),
+        (alt_bn128_syscall_enabled::id(), "enable alt_bn128 syscalls"),
+        (add_get_processed_sibling_instruction_syscall::id(), "add get_processed_sibling_instruction and get_stack_height syscalls"),
+        (cap_accounts_data_allocations_per_transaction::id(), "cap accounts data allocations per transaction"),
+        (cap_accounts_data_size_per_block::id(), "cap accounts data allocations per block"),
+        (include_account_index_in_rent_error::id(), "include account index in rent tx error"),
+        (prevent_crediting_accounts_that_end_rent_paying::id(), "prevent crediting rent paying accounts"),
+        (enable_partitioned_epoch_reward::id(), "enable partitioned rewards at epoch boundary"),
         /*************** ADD NEW FEATURES HERE ***************/
     ]
     .iter()
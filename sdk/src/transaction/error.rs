@@ -131,6 +131,25 @@ pub enum TransactionError {
         "Transaction leaves an account with data with a lower balance than rent-exempt minimum"
     )]
     InvalidRentPayingAccount,
+
+    /// Transaction allocates more accounts data than allowed per transaction
+    #[error("Transaction allocates more accounts data than allowed per transaction")]
+    MaxAccountsDataAllocationsExceeded,
+
+    /// Transaction would exceed the accounts data allocations allowed within the block
+    #[error("Transaction would exceed the accounts data allocations allowed within the block")]
+    WouldExceedAccountDataBlockLimit,
+
+    /// Transaction results in an account with insufficient funds for rent
+    #[error(
+        "Transaction results in an account ({account_index}) with insufficient funds for rent"
+    )]
+    InsufficientFundsForRent { account_index: u8 },
+
+    /// Execution of the program referenced by the account at this index is temporarily
+    /// restricted, e.g. the stake program while epoch rewards are being distributed
+    #[error("Execution of the program referenced by account at index {account_index} is temporarily restricted.")]
+    ProgramExecutionTemporarilyRestricted { account_index: u8 },
 }
 
 impl From<SanitizeError> for TransactionError {
@@ -2,7 +2,7 @@
 
 use crate::{
     account::{AccountSharedData, ReadableAccount, WritableAccount},
-    instruction::InstructionError,
+    instruction::{AccountMeta, InstructionError, TRANSACTION_LEVEL_STACK_HEIGHT},
     lamports::LamportsError,
     pubkey::Pubkey,
 };
@@ -31,6 +31,7 @@ pub struct TransactionContext {
     accounts: Pin<Box<[RefCell<AccountSharedData>]>>,
     instruction_context_capacity: usize,
     instruction_context_stack: Vec<InstructionContext>,
+    instruction_trace: Vec<Vec<(usize, InstructionContext)>>,
     return_data: (Pubkey, Vec<u8>),
 }
 
@@ -50,6 +51,7 @@ impl TransactionContext {
             accounts: Pin::new(accounts.into_boxed_slice()),
             instruction_context_capacity,
             instruction_context_stack: Vec::with_capacity(instruction_context_capacity),
+            instruction_trace: Vec::new(),
             return_data: (Pubkey::default(), Vec::new()),
         }
     }
@@ -143,11 +145,21 @@ impl TransactionContext {
         if self.instruction_context_stack.len() >= self.instruction_context_capacity {
             return Err(InstructionError::CallDepth);
         }
-        self.instruction_context_stack.push(InstructionContext {
+        let instruction_context = InstructionContext {
             program_accounts: program_accounts.to_vec(),
             instruction_accounts: instruction_accounts.to_vec(),
             instruction_data: instruction_data.to_vec(),
-        });
+        };
+        if self.instruction_context_stack.is_empty() {
+            self.instruction_trace.push(Vec::new());
+        }
+        if let Some(records) = self.instruction_trace.last_mut() {
+            records.push((
+                self.instruction_context_stack.len().saturating_add(1),
+                instruction_context.clone(),
+            ));
+        }
+        self.instruction_context_stack.push(instruction_context);
         Ok(())
     }
 
@@ -160,6 +172,44 @@ impl TransactionContext {
         Ok(())
     }
 
+    /// Every instruction pushed so far, grouped by top-level instruction and
+    /// paired with the stack height it was invoked at
+    pub fn get_instruction_trace(&self) -> &[Vec<(usize, InstructionContext)>] {
+        &self.instruction_trace
+    }
+
+    /// Returns the `index`th most recently processed instruction which shares
+    /// its caller and stack height with the current InstructionContext
+    pub fn get_processed_sibling_instruction(&self, index: usize) -> Option<&InstructionContext> {
+        let stack_height = self.get_instruction_context_stack_height();
+        if stack_height <= TRANSACTION_LEVEL_STACK_HEIGHT {
+            // The last top-level entry is the current instruction itself
+            return self
+                .instruction_trace
+                .len()
+                .checked_sub(2)?
+                .checked_sub(index)
+                .and_then(|index| self.instruction_trace.get(index))
+                .and_then(|records| records.first())
+                .map(|(_, instruction_context)| instruction_context);
+        }
+
+        // Walk back over the children of the current instruction and of its
+        // siblings until the caller is reached
+        let mut siblings = self
+            .instruction_trace
+            .last()?
+            .iter()
+            .rev()
+            .skip_while(|(height, _)| *height > stack_height)
+            .skip(1)
+            .take_while(|(height, _)| *height >= stack_height)
+            .filter(|(height, _)| *height == stack_height);
+        siblings
+            .nth(index)
+            .map(|(_, instruction_context)| instruction_context)
+    }
+
     /// Returns the key of the current InstructionContexts program account
     pub fn get_program_key(&self) -> Result<&Pubkey, InstructionError> {
         let instruction_context = self.get_current_instruction_context()?;
@@ -190,10 +240,17 @@ impl TransactionContext {
     }
 }
 
+/// Return data left behind by the last program to set it during a transaction
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionReturnData {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
 /// Loaded instruction shared between runtime and programs.
 ///
 /// This context is valid for the entire duration of a (possibly cross program) instruction being processed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InstructionContext {
     program_accounts: Vec<usize>,
     instruction_accounts: Vec<InstructionAccount>,
@@ -223,6 +280,31 @@ impl InstructionContext {
         &self.instruction_data
     }
 
+    /// Key of the program account which processes this Instruction
+    pub fn get_program_key<'a>(
+        &self,
+        transaction_context: &'a TransactionContext,
+    ) -> Result<&'a Pubkey, InstructionError> {
+        let index_in_transaction =
+            self.get_index_in_transaction(self.program_accounts.len().saturating_sub(1))?;
+        Ok(&transaction_context.account_keys[index_in_transaction])
+    }
+
+    /// Instruction accounts as they were passed to the program
+    pub fn get_instruction_account_metas(
+        &self,
+        transaction_context: &TransactionContext,
+    ) -> Vec<AccountMeta> {
+        self.instruction_accounts
+            .iter()
+            .map(|instruction_account| AccountMeta {
+                pubkey: transaction_context.account_keys[instruction_account.index_in_transaction],
+                is_signer: instruction_account.is_signer,
+                is_writable: instruction_account.is_writable,
+            })
+            .collect()
+    }
+
     /// Searches for a program account by its key
     pub fn find_index_of_program_account(
         &self,
@@ -107,6 +107,24 @@ impl SendTransactionService {
         leader_info: Option<T>,
         receiver: Receiver<TransactionInfo>,
         config: Config,
+    ) -> Self {
+        Self::new_with_shared_config(
+            tpu_address,
+            bank_forks,
+            leader_info,
+            receiver,
+            Arc::new(RwLock::new(config)),
+        )
+    }
+
+    /// Like `new_with_config`, but the retry settings are re-read on every iteration so that
+    /// they may be adjusted while the service is running
+    pub fn new_with_shared_config<T: TpuInfo + std::marker::Send + 'static>(
+        tpu_address: SocketAddr,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        leader_info: Option<T>,
+        receiver: Receiver<TransactionInfo>,
+        config: Arc<RwLock<Config>>,
     ) -> Self {
         let thread = Self::retry_thread(
             tpu_address,
@@ -123,7 +141,7 @@ impl SendTransactionService {
         receiver: Receiver<TransactionInfo>,
         bank_forks: Arc<RwLock<BankForks>>,
         mut leader_info: Option<T>,
-        config: Config,
+        shared_config: Arc<RwLock<Config>>,
     ) -> JoinHandle<()> {
         let mut last_status_check = Instant::now();
         let mut last_leader_refresh = Instant::now();
@@ -137,6 +155,7 @@ impl SendTransactionService {
         Builder::new()
             .name("send-tx-sv2".to_string())
             .spawn(move || loop {
+                let config = shared_config.read().unwrap().clone();
                 match receiver.recv_timeout(Duration::from_millis(1000.min(config.retry_rate_ms))) {
                     Err(RecvTimeoutError::Disconnected) => break,
                     Err(RecvTimeoutError::Timeout) => {}
@@ -823,6 +823,7 @@ mod tests {
             blockhash: Hash::default().to_string(),
             previous_blockhash: Hash::default().to_string(),
             rewards: vec![],
+            num_partitions: Some(3),
             block_time: Some(1_234_567_890),
             block_height: Some(1),
         };
@@ -869,6 +870,7 @@ mod tests {
                 meta.post_token_balances = None; // Legacy bincode implementation does not support token balances
                 meta.rewards = None; // Legacy bincode implementation does not support rewards
             }
+            block.num_partitions = None; // Legacy bincode implementation does not support num_partitions
             assert_eq!(block, bincode_block.into());
         } else {
             panic!("deserialization should produce CellData::Bincode");
@@ -37,6 +37,7 @@ impl From<Vec<Reward>> for generated::Rewards {
     fn from(rewards: Vec<Reward>) -> Self {
         Self {
             rewards: rewards.into_iter().map(|r| r.into()).collect(),
+            num_partitions: None,
         }
     }
 }
@@ -57,6 +58,7 @@ impl From<StoredExtendedRewards> for generated::Rewards {
                     r.into()
                 })
                 .collect(),
+            num_partitions: None,
         }
     }
 }
@@ -119,6 +121,7 @@ impl From<ConfirmedBlock> for generated::ConfirmedBlock {
             parent_slot,
             transactions,
             rewards,
+            num_partitions,
             block_time,
             block_height,
         } = confirmed_block;
@@ -131,6 +134,8 @@ impl From<ConfirmedBlock> for generated::ConfirmedBlock {
             rewards: rewards.into_iter().map(|r| r.into()).collect(),
             block_time: block_time.map(|timestamp| generated::UnixTimestamp { timestamp }),
             block_height: block_height.map(|block_height| generated::BlockHeight { block_height }),
+            num_partitions: num_partitions
+                .map(|num_partitions| generated::NumPartitions { num_partitions }),
         }
     }
 }
@@ -148,6 +153,7 @@ impl TryFrom<generated::ConfirmedBlock> for ConfirmedBlock {
             rewards,
             block_time,
             block_height,
+            num_partitions,
         } = confirmed_block;
 
         Ok(Self {
@@ -159,6 +165,8 @@ impl TryFrom<generated::ConfirmedBlock> for ConfirmedBlock {
                 .map(|tx| tx.try_into())
                 .collect::<std::result::Result<Vec<TransactionWithStatusMeta>, Self::Error>>()?,
             rewards: rewards.into_iter().map(|r| r.into()).collect(),
+            num_partitions: num_partitions
+                .map(|generated::NumPartitions { num_partitions }| num_partitions),
             block_time: block_time.map(|generated::UnixTimestamp { timestamp }| timestamp),
             block_height: block_height.map(|generated::BlockHeight { block_height }| block_height),
         })
@@ -547,6 +555,22 @@ impl TryFrom<tx_by_addr::TransactionError> for TransactionError {
             }
         }
 
+        if let Some(transaction_details) = transaction_error.transaction_details {
+            match transaction_error.transaction_error {
+                30 => {
+                    return Ok(TransactionError::InsufficientFundsForRent {
+                        account_index: transaction_details.index as u8,
+                    });
+                }
+                31 => {
+                    return Ok(TransactionError::ProgramExecutionTemporarilyRestricted {
+                        account_index: transaction_details.index as u8,
+                    });
+                }
+                _ => {}
+            }
+        }
+
         Ok(match transaction_error.transaction_error {
             0 => TransactionError::AccountInUse,
             1 => TransactionError::AccountLoadedTwice,
@@ -575,6 +599,8 @@ impl TryFrom<tx_by_addr::TransactionError> for TransactionError {
             25 => TransactionError::InvalidAddressLookupTableData,
             26 => TransactionError::InvalidAddressLookupTableIndex,
             27 => TransactionError::InvalidRentPayingAccount,
+            28 => TransactionError::MaxAccountsDataAllocationsExceeded,
+            29 => TransactionError::WouldExceedAccountDataBlockLimit,
             _ => return Err("Invalid TransactionError"),
         })
     }
@@ -667,6 +693,18 @@ impl From<TransactionError> for tx_by_addr::TransactionError {
                 TransactionError::InvalidRentPayingAccount => {
                     tx_by_addr::TransactionErrorType::InvalidRentPayingAccount
                 }
+                TransactionError::MaxAccountsDataAllocationsExceeded => {
+                    tx_by_addr::TransactionErrorType::MaxAccountsDataAllocationsExceeded
+                }
+                TransactionError::WouldExceedAccountDataBlockLimit => {
+                    tx_by_addr::TransactionErrorType::WouldExceedAccountDataBlockLimit
+                }
+                TransactionError::InsufficientFundsForRent { .. } => {
+                    tx_by_addr::TransactionErrorType::InsufficientFundsForRent
+                }
+                TransactionError::ProgramExecutionTemporarilyRestricted { .. } => {
+                    tx_by_addr::TransactionErrorType::ProgramExecutionTemporarilyRestricted
+                }
             } as i32,
             instruction_error: match transaction_error {
                 TransactionError::InstructionError(index, ref instruction_error) => {
@@ -835,6 +873,15 @@ impl From<TransactionError> for tx_by_addr::TransactionError {
                 }
                 _ => None,
             },
+            transaction_details: match transaction_error {
+                TransactionError::InsufficientFundsForRent { account_index }
+                | TransactionError::ProgramExecutionTemporarilyRestricted { account_index } => {
+                    Some(tx_by_addr::TransactionDetails {
+                        index: account_index as u32,
+                    })
+                }
+                _ => None,
+            },
         }
     }
 }
@@ -1494,6 +1541,23 @@ mod test {
             transaction_error,
             tx_by_addr_transaction_error.try_into().unwrap()
         );
+
+        let transaction_error = TransactionError::InsufficientFundsForRent { account_index: 10 };
+        let tx_by_addr_transaction_error: tx_by_addr::TransactionError =
+            transaction_error.clone().into();
+        assert_eq!(
+            transaction_error,
+            tx_by_addr_transaction_error.try_into().unwrap()
+        );
+
+        let transaction_error =
+            TransactionError::ProgramExecutionTemporarilyRestricted { account_index: 10 };
+        let tx_by_addr_transaction_error: tx_by_addr::TransactionError =
+            transaction_error.clone().into();
+        assert_eq!(
+            transaction_error,
+            tx_by_addr_transaction_error.try_into().unwrap()
+        );
     }
 
     #[test]
@@ -1501,10 +1565,26 @@ mod test {
         let ix_index = 1;
         let custom_error = 42;
         for error in tx_by_addr::TransactionErrorType::into_enum_iter() {
-            if error != tx_by_addr::TransactionErrorType::InstructionError {
+            if matches!(
+                error,
+                tx_by_addr::TransactionErrorType::InsufficientFundsForRent
+                    | tx_by_addr::TransactionErrorType::ProgramExecutionTemporarilyRestricted
+            ) {
+                let tx_by_addr_error = tx_by_addr::TransactionError {
+                    transaction_error: error as i32,
+                    instruction_error: None,
+                    transaction_details: Some(tx_by_addr::TransactionDetails { index: ix_index }),
+                };
+                let transaction_error: TransactionError = tx_by_addr_error
+                    .clone()
+                    .try_into()
+                    .unwrap_or_else(|_| panic!("{:?} conversion implemented?", error));
+                assert_eq!(tx_by_addr_error, transaction_error.into());
+            } else if error != tx_by_addr::TransactionErrorType::InstructionError {
                 let tx_by_addr_error = tx_by_addr::TransactionError {
                     transaction_error: error as i32,
                     instruction_error: None,
+                    transaction_details: None,
                 };
                 let transaction_error: TransactionError = tx_by_addr_error
                     .clone()
@@ -1521,6 +1601,7 @@ mod test {
                                 error: ix_error as i32,
                                 custom: None,
                             }),
+                            transaction_details: None,
                         };
                         let transaction_error: TransactionError = tx_by_addr_error
                             .clone()
@@ -1537,6 +1618,7 @@ mod test {
                                     custom: custom_error,
                                 }),
                             }),
+                            transaction_details: None,
                         };
                         let transaction_error: TransactionError =
                             tx_by_addr_error.clone().try_into().unwrap();
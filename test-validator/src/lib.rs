@@ -1,8 +1,9 @@
 #![allow(clippy::integer_arithmetic)]
 use {
     log::*,
+    paychains_account_decoder::{UiAccount, UiAccountEncoding},
     paychains_cli_output::CliAccount,
-    paychains_client::rpc_client::RpcClient,
+    paychains_client::{rpc_client::RpcClient, rpc_response::RpcKeyedAccount},
     paychains_core::{
         tower_storage::TowerStorage,
         validator::{Validator, ValidatorConfig, ValidatorStartProgress},
@@ -16,29 +17,38 @@ use {
     paychains_net_utils::PortRange,
     paychains_rpc::rpc::JsonRpcConfig,
     paychains_runtime::{
-        genesis_utils::create_genesis_config_with_leader_ex,
-        hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE, snapshot_config::SnapshotConfig,
+        bank_forks::BankForks,
+        genesis_utils::{activate_feature, create_genesis_config_with_leader_ex},
+        hardened_unpack::MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        snapshot_config::SnapshotConfig,
     },
     paychains_sdk::{
-        account::{Account, AccountSharedData},
+        account::{Account, AccountSharedData, ReadableAccount},
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
         clock::{Slot, DEFAULT_MS_PER_SLOT},
         commitment_config::CommitmentConfig,
+        compute_budget::ComputeBudget,
         epoch_schedule::EpochSchedule,
         exit::Exit,
+        feature,
+        feature_set::FEATURE_NAMES,
         fee_calculator::{FeeCalculator, FeeRateGovernor},
+        genesis_config::GenesisConfig,
         hash::Hash,
         instruction::{AccountMeta, Instruction},
         message::Message,
+        native_loader,
         native_token::pay_to_lamports,
         pubkey::Pubkey,
         rent::Rent,
         signature::{read_keypair_file, write_keypair_file, Keypair, Signer},
+        stake::{self, state::StakeState},
+        sysvar,
     },
     paychains_streamer::socket::SocketAddrSpace,
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         fs::{remove_dir_all, File},
-        io::Read,
         net::{IpAddr, Ipv4Addr, SocketAddr},
         path::{Path, PathBuf},
         str::FromStr,
@@ -105,6 +115,10 @@ pub struct TestValidatorGenesis {
     pub max_genesis_archive_unpacked_size: Option<u64>,
     pub accountsdb_plugin_config_files: Option<Vec<PathBuf>>,
     pub accounts_db_caching_enabled: bool,
+    deactivate_feature_set: HashSet<Pubkey>,
+    activate_feature_set: HashSet<Pubkey>,
+    ticks_per_slot: Option<u64>,
+    compute_unit_limit: Option<u64>,
 }
 
 impl TestValidatorGenesis {
@@ -163,6 +177,30 @@ impl TestValidatorGenesis {
         self
     }
 
+    /// Leave the listed features inactive in the genesis config
+    pub fn deactivate_features(&mut self, deactivate_list: &[Pubkey]) -> &mut Self {
+        self.deactivate_feature_set.extend(deactivate_list);
+        self
+    }
+
+    /// Activate the listed features in the genesis config, for those left inactive by default
+    /// (see `genesis_utils::features_inactive_in_dev_tooling`)
+    pub fn activate_features(&mut self, activate_list: &[Pubkey]) -> &mut Self {
+        self.activate_feature_set.extend(activate_list);
+        self
+    }
+
+    pub fn ticks_per_slot(&mut self, ticks_per_slot: u64) -> &mut Self {
+        self.ticks_per_slot = Some(ticks_per_slot);
+        self
+    }
+
+    /// Override the per-transaction compute unit limit
+    pub fn compute_unit_limit(&mut self, compute_unit_limit: u64) -> &mut Self {
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
     pub fn gossip_host(&mut self, gossip_host: IpAddr) -> &mut Self {
         self.node_config.gossip_addr.set_ip(gossip_host);
         self
@@ -214,6 +252,40 @@ impl TestValidatorGenesis {
         self
     }
 
+    /// Clone upgradeable programs along with their programdata accounts
+    pub fn clone_upgradeable_programs<T>(
+        &mut self,
+        addresses: T,
+        rpc_client: &RpcClient,
+    ) -> &mut Self
+    where
+        T: IntoIterator<Item = Pubkey>,
+    {
+        let addresses: Vec<Pubkey> = addresses.into_iter().collect();
+        self.clone_accounts(addresses.clone(), rpc_client);
+
+        let mut programdata_addresses = vec![];
+        for address in addresses {
+            let account = self.accounts.get(&address).unwrap();
+            if account.owner() != &bpf_loader_upgradeable::id() {
+                error!("Account {} is not an upgradeable program", address);
+                paychains_core::validator::abort();
+            }
+            match account.deserialize_data() {
+                Ok(UpgradeableLoaderState::Program {
+                    programdata_address,
+                }) => programdata_addresses.push(programdata_address),
+                _ => {
+                    error!("Failed to read upgradeable program account {}", address);
+                    paychains_core::validator::abort();
+                }
+            }
+        }
+
+        self.clone_accounts(programdata_addresses, rpc_client);
+        self
+    }
+
     pub fn add_accounts_from_json_files(&mut self, accounts: &[AccountInfo]) -> &mut Self {
         for account in accounts {
             let account_path =
@@ -221,34 +293,16 @@ impl TestValidatorGenesis {
                     error!("Unable to locate {}", account.filename);
                     paychains_core::validator::abort();
                 });
-            let mut file = File::open(&account_path).unwrap();
-            let mut account_info_raw = String::new();
-            file.read_to_string(&mut account_info_raw).unwrap();
-
-            let result: serde_json::Result<CliAccount> = serde_json::from_str(&account_info_raw);
-            let account_info = match result {
-                Err(err) => {
-                    error!(
-                        "Unable to deserialize {}: {}",
-                        account_path.to_str().unwrap(),
-                        err
-                    );
-                    paychains_core::validator::abort();
-                }
-                Ok(deserialized) => deserialized,
-            };
-            let address = Pubkey::from_str(account_info.keyed_account.pubkey.as_str()).unwrap();
-            let account = account_info
-                .keyed_account
-                .account
-                .decode::<AccountSharedData>()
-                .unwrap();
-
-            self.add_account(address, account);
+            self.add_accounts(read_cli_accounts(&account_path));
         }
         self
     }
 
+    /// Add all accounts from a state file written by `TestValidator::dump_state`
+    pub fn add_accounts_from_state_file(&mut self, path: &Path) -> &mut Self {
+        self.add_accounts(read_cli_accounts(path))
+    }
+
     /// Add an account to the test environment with the account data in the provided `filename`
     pub fn add_account_with_file_data(
         &mut self,
@@ -358,6 +412,47 @@ impl TestValidatorGenesis {
     }
 }
 
+/// Read a JSON file holding a `CliAccount` or a list of them, as written by
+/// `paychains account --output json` and `TestValidator::dump_state`
+fn read_cli_accounts(path: &Path) -> Vec<(Pubkey, AccountSharedData)> {
+    let file = File::open(path).unwrap_or_else(|err| {
+        error!("Unable to open {}: {}", path.display(), err);
+        paychains_core::validator::abort();
+    });
+    let result = serde_json::from_reader(file).and_then(|json: serde_json::Value| {
+        if json.is_array() {
+            serde_json::from_value::<Vec<CliAccount>>(json)
+        } else {
+            serde_json::from_value::<CliAccount>(json).map(|account_info| vec![account_info])
+        }
+    });
+    let accounts = result.unwrap_or_else(|err| {
+        error!("Unable to deserialize {}: {}", path.display(), err);
+        paychains_core::validator::abort();
+    });
+
+    accounts
+        .into_iter()
+        .map(|account_info| {
+            let keyed_account = account_info.keyed_account;
+            match (
+                Pubkey::from_str(&keyed_account.pubkey),
+                keyed_account.account.decode::<AccountSharedData>(),
+            ) {
+                (Ok(address), Some(account)) => (address, account),
+                _ => {
+                    error!(
+                        "Unable to decode account {} in {}",
+                        keyed_account.pubkey,
+                        path.display()
+                    );
+                    paychains_core::validator::abort();
+                }
+            }
+        })
+        .collect()
+}
+
 pub struct TestValidator {
     ledger_path: PathBuf,
     preserve_ledger: bool,
@@ -367,6 +462,7 @@ pub struct TestValidator {
     gossip: SocketAddr,
     validator: Option<Validator>,
     vote_account_address: Pubkey,
+    stake_account_address: Option<Pubkey>,
 }
 
 impl TestValidator {
@@ -464,6 +560,24 @@ impl TestValidator {
         genesis_config.epoch_schedule = config
             .epoch_schedule
             .unwrap_or_else(EpochSchedule::without_warmup);
+        if let Some(ticks_per_slot) = config.ticks_per_slot {
+            genesis_config.ticks_per_slot = ticks_per_slot;
+        }
+
+        for feature in &config.deactivate_feature_set {
+            if FEATURE_NAMES.contains_key(feature) {
+                info!("Feature for {:?} deactivated", feature);
+            } else {
+                warn!(
+                    "Feature {:?} set for deactivation is not a known feature id",
+                    feature
+                );
+            }
+            genesis_config.accounts.remove(feature);
+        }
+        for feature in &config.activate_feature_set {
+            activate_feature(&mut genesis_config, *feature);
+        }
 
         let ledger_path = match &config.ledger_path {
             None => create_new_tmp_ledger!(&genesis_config).0,
@@ -540,6 +654,8 @@ impl TestValidator {
         }
 
         let vote_account_address = validator_vote_account.pubkey();
+        let stake_account_address =
+            genesis_stake_account_address(&ledger_path, &vote_account_address)?;
         let rpc_url = format!("http://{}", node.info.rpc);
         let rpc_pubsub_url = format!("ws://{}/", node.info.rpc_pubsub);
         let tpu = node.info.tpu;
@@ -583,6 +699,10 @@ impl TestValidator {
             rocksdb_compaction_interval: Some(100), // Compact every 100 slots
             max_ledger_shreds: config.max_ledger_shreds,
             no_wait_for_vote_to_start_leader: true,
+            compute_budget: config.compute_unit_limit.map(|max_units| ComputeBudget {
+                max_units,
+                ..ComputeBudget::default()
+            }),
             ..ValidatorConfig::default()
         };
         if let Some(ref tower_storage) = config.tower_storage {
@@ -662,6 +782,7 @@ impl TestValidator {
             gossip,
             validator,
             vote_account_address,
+            stake_account_address,
         })
     }
 
@@ -690,6 +811,11 @@ impl TestValidator {
         self.vote_account_address
     }
 
+    /// Return the address of the stake account delegated to the validator at genesis
+    pub fn stake_account_address(&self) -> Option<Pubkey> {
+        self.stake_account_address
+    }
+
     /// Return an RpcClient for the validator.  As a convenience, also return a recent blockhash and
     /// associated fee calculator
     #[deprecated(since = "1.9.0", note = "Please use `get_rpc_client` instead")]
@@ -718,6 +844,98 @@ impl TestValidator {
     pub fn cluster_info(&self) -> Arc<ClusterInfo> {
         self.validator.as_ref().unwrap().cluster_info.clone()
     }
+
+    /// Return the underlying validator, if it is still running
+    pub fn validator(&self) -> Option<&Validator> {
+        self.validator.as_ref()
+    }
+
+    /// Write all non-builtin accounts at the current root to `path`, returning the number of
+    /// accounts written.  The file can be loaded into a new ledger with
+    /// `TestValidatorGenesis::add_accounts_from_state_file`
+    pub fn dump_state(&self, path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+        let validator = self.validator.as_ref().ok_or("validator is not running")?;
+        dump_state(
+            &validator.bank_forks,
+            &validator.cluster_info.id(),
+            &self.vote_account_address,
+            self.stake_account_address.as_ref(),
+            path,
+        )
+    }
+}
+
+/// Returns the stake account that genesis delegated to `vote_account_address`
+fn genesis_stake_account_address(
+    ledger_path: &Path,
+    vote_account_address: &Pubkey,
+) -> Result<Option<Pubkey>, Box<dyn std::error::Error>> {
+    let genesis_config = GenesisConfig::load(ledger_path)?;
+    Ok(genesis_config
+        .accounts
+        .into_iter()
+        .find(|(_address, account)| {
+            account.owner == stake::program::id()
+                && account
+                    .deserialize_data::<StakeState>()
+                    .ok()
+                    .and_then(|stake_state| stake_state.delegation())
+                    .map(|delegation| delegation.voter_pubkey == *vote_account_address)
+                    .unwrap_or_default()
+        })
+        .map(|(address, _account)| address))
+}
+
+/// Write all non-builtin accounts at the root of `bank_forks` to `path` as a JSON list of
+/// `CliAccount`s.  Sysvars, native programs and feature accounts are recreated by every genesis,
+/// and the validator's identity, vote account and genesis stake account belong to the
+/// validator that wrote the file, so those are all left out
+pub fn dump_state(
+    bank_forks: &RwLock<BankForks>,
+    validator_identity: &Pubkey,
+    vote_account_address: &Pubkey,
+    stake_account_address: Option<&Pubkey>,
+    path: &Path,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let bank = bank_forks.read().unwrap().root_bank();
+    let accounts: Vec<_> = bank
+        .get_all_accounts_with_modified_slots()
+        .map_err(|err| format!("Failed to load accounts at slot {}: {:?}", bank.slot(), err))?
+        .into_iter()
+        .filter(|(address, account, _slot)| {
+            let owner = account.owner();
+            owner != &native_loader::id()
+                && owner != &sysvar::id()
+                && owner != &feature::id()
+                && address != validator_identity
+                && address != vote_account_address
+                && Some(address) != stake_account_address
+        })
+        .map(|(address, account, _slot)| CliAccount {
+            keyed_account: RpcKeyedAccount {
+                pubkey: address.to_string(),
+                account: UiAccount::encode(
+                    &address,
+                    &account,
+                    UiAccountEncoding::Base64,
+                    None,
+                    None,
+                ),
+            },
+            use_lamports_unit: false,
+        })
+        .collect();
+
+    let file = File::create(path)
+        .map_err(|err| format!("Unable to create {}: {}", path.display(), err))?;
+    serde_json::to_writer(file, &accounts)?;
+    info!(
+        "Wrote {} accounts at slot {} to {}",
+        accounts.len(),
+        bank.slot(),
+        path.display()
+    );
+    Ok(accounts.len())
 }
 
 impl Drop for TestValidator {
@@ -736,3 +954,156 @@ impl Drop for TestValidator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        paychains_runtime::bank::Bank,
+        paychains_sdk::{
+            feature_set,
+            stake::state::{Delegation, Meta, Stake},
+        },
+    };
+
+    #[test]
+    fn test_dump_and_load_state() {
+        let mint_address = Pubkey::new_unique();
+        let validator_identity = Pubkey::new_unique();
+        let vote_account_address = Pubkey::new_unique();
+        let stake_account_address = Pubkey::new_unique();
+        let genesis_config = create_genesis_config_with_leader_ex(
+            1_000_000_000,
+            &mint_address,
+            &validator_identity,
+            &vote_account_address,
+            &stake_account_address,
+            1_000_000,
+            1_000_000,
+            FeeRateGovernor::default(),
+            Rent::default(),
+            paychains_sdk::genesis_config::ClusterType::Development,
+            vec![],
+        );
+        let bank = Bank::new_for_tests(&genesis_config);
+
+        // A user stake account delegated to the validator is kept
+        let user_stake_address = Pubkey::new_unique();
+        let user_stake = AccountSharedData::new_data(
+            1_000_000,
+            &StakeState::Stake(
+                Meta::default(),
+                Stake {
+                    delegation: Delegation::new(
+                        &vote_account_address,
+                        1_000,
+                        0,
+                        stake::config::DEFAULT_WARMUP_COOLDOWN_RATE,
+                    ),
+                    credits_observed: 0,
+                },
+            ),
+            &stake::program::id(),
+        )
+        .unwrap();
+        bank.store_account(&user_stake_address, &user_stake);
+        let user_address = Pubkey::new_unique();
+        let user_account = AccountSharedData::new(42, 3, &Pubkey::new_unique());
+        bank.store_account(&user_address, &user_account);
+        let bank_forks = RwLock::new(BankForks::new(bank));
+
+        let state_file = tempfile::NamedTempFile::new().unwrap();
+        let dumped = dump_state(
+            &bank_forks,
+            &validator_identity,
+            &vote_account_address,
+            Some(&stake_account_address),
+            state_file.path(),
+        )
+        .unwrap();
+
+        let mut genesis = TestValidatorGenesis::default();
+        genesis.add_accounts_from_state_file(state_file.path());
+        assert_eq!(genesis.accounts.len(), dumped);
+        assert_eq!(genesis.accounts.get(&user_address), Some(&user_account));
+        assert_eq!(genesis.accounts.get(&user_stake_address), Some(&user_stake));
+        assert!(genesis.accounts.contains_key(&mint_address));
+        for address in [
+            validator_identity,
+            vote_account_address,
+            stake_account_address,
+            sysvar::clock::id(),
+            feature_set::curve25519_syscall_enabled::id(),
+        ] {
+            assert!(!genesis.accounts.contains_key(&address));
+        }
+    }
+
+    #[test]
+    fn test_genesis_overrides() {
+        paychains_logger::setup();
+        let deactivated = feature_set::alt_bn128_syscall_enabled::id();
+        let (test_validator, _payer) = TestValidatorGenesis::default()
+            .deactivate_features(&[deactivated])
+            .ticks_per_slot(32)
+            .compute_unit_limit(100_000)
+            .start();
+        let bank = test_validator
+            .validator()
+            .unwrap()
+            .bank_forks
+            .read()
+            .unwrap()
+            .working_bank();
+
+        assert!(!bank.feature_set.is_active(&deactivated));
+        assert!(bank.get_account(&deactivated).is_none());
+        assert!(bank
+            .feature_set
+            .is_active(&feature_set::curve25519_syscall_enabled::id()));
+        assert_eq!(bank.ticks_per_slot(), 32);
+        assert_eq!(
+            bank.compute_budget()
+                .map(|compute_budget| compute_budget.max_units),
+            Some(100_000)
+        );
+    }
+
+    #[test]
+    fn test_clone_upgradeable_programs() {
+        paychains_logger::setup();
+        let program_id = Pubkey::new_unique();
+        let programdata_address = Pubkey::new_unique();
+        let upgrade_authority_address = Pubkey::new_unique();
+        let program = AccountSharedData::new_data(
+            1_000_000,
+            &UpgradeableLoaderState::Program {
+                programdata_address,
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        let programdata = AccountSharedData::new_data(
+            1_000_000,
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(upgrade_authority_address),
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        let (test_validator, _payer) = TestValidatorGenesis::default()
+            .add_account(program_id, program.clone())
+            .add_account(programdata_address, programdata.clone())
+            .start();
+
+        let mut genesis = TestValidatorGenesis::default();
+        genesis.clone_upgradeable_programs([program_id], &test_validator.get_rpc_client());
+        assert_eq!(genesis.accounts.len(), 2);
+        assert_eq!(genesis.accounts.get(&program_id), Some(&program));
+        assert_eq!(
+            genesis.accounts.get(&programdata_address),
+            Some(&programdata)
+        );
+    }
+}
@@ -395,6 +395,8 @@ pub struct ConfirmedBlock {
     pub rewards: Rewards,
     pub block_time: Option<UnixTimestamp>,
     pub block_height: Option<u64>,
+    /// Number of partitions the stake rewards calculated in this block are distributed over
+    pub num_partitions: Option<u64>,
 }
 
 impl Encodable for ConfirmedBlock {
@@ -410,6 +412,7 @@ impl Encodable for ConfirmedBlock {
                 .map(|tx| tx.encode(encoding))
                 .collect(),
             rewards: self.rewards,
+            num_partitions: self.num_partitions,
             block_time: self.block_time,
             block_height: self.block_height,
         }
@@ -455,6 +458,11 @@ impl ConfirmedBlock {
             } else {
                 None
             },
+            num_reward_partitions: if show_rewards {
+                self.num_partitions
+            } else {
+                None
+            },
             block_time: self.block_time,
             block_height: self.block_height,
         }
@@ -469,6 +477,8 @@ pub struct EncodedConfirmedBlock {
     pub parent_slot: Slot,
     pub transactions: Vec<EncodedTransactionWithStatusMeta>,
     pub rewards: Rewards,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_partitions: Option<u64>,
     pub block_time: Option<UnixTimestamp>,
     pub block_height: Option<u64>,
 }
@@ -481,6 +491,7 @@ impl From<UiConfirmedBlock> for EncodedConfirmedBlock {
             parent_slot: block.parent_slot,
             transactions: block.transactions.unwrap_or_default(),
             rewards: block.rewards.unwrap_or_default(),
+            num_partitions: block.num_reward_partitions,
             block_time: block.block_time,
             block_height: block.block_height,
         }
@@ -499,6 +510,8 @@ pub struct UiConfirmedBlock {
     pub signatures: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub rewards: Option<Rewards>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_reward_partitions: Option<u64>,
     pub block_time: Option<UnixTimestamp>,
     pub block_height: Option<u64>,
 }
@@ -512,6 +525,7 @@ impl From<EncodedConfirmedBlock> for UiConfirmedBlock {
             transactions: Some(block.transactions),
             signatures: None,
             rewards: Some(block.rewards),
+            num_reward_partitions: block.num_partitions,
             block_time: block.block_time,
             block_height: block.block_height,
         }
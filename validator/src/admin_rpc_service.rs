@@ -7,23 +7,56 @@ use {
     log::*,
     serde::{Deserialize, Serialize},
     paychains_core::{
-        consensus::Tower, tower_storage::TowerStorage, validator::ValidatorStartProgress,
+        consensus::Tower,
+        tower_storage::TowerStorage,
+        validator::{Validator, ValidatorStartProgress},
     },
     paychains_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+    paychains_runtime::{
+        accounts_background_service::RequestedSnapshotKind, bank_forks::BankForks,
+        bucket_map_holder_stats::AccountsIndexOccupancy,
+    },
     paychains_sdk::{
+        clock::{Slot, UnixTimestamp},
         exit::Exit,
+        pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
     },
+    paychains_send_transaction_service::send_transaction_service,
     std::{
+        collections::HashSet,
         fmt::{self, Display},
         net::SocketAddr,
         path::{Path, PathBuf},
+        str::FromStr,
         sync::{Arc, RwLock},
         thread::{self, Builder},
         time::{Duration, SystemTime},
     },
 };
 
+/// Validator state that only becomes available once the validator has started
+#[derive(Clone)]
+pub struct AdminRpcRequestMetadataPostInit {
+    pub cluster_info: Arc<ClusterInfo>,
+    pub bank_forks: Arc<RwLock<BankForks>>,
+    pub known_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    pub repair_validators: Arc<RwLock<Option<HashSet<Pubkey>>>>,
+    pub send_transaction_service_config: Arc<RwLock<send_transaction_service::Config>>,
+}
+
+impl From<&Validator> for AdminRpcRequestMetadataPostInit {
+    fn from(validator: &Validator) -> Self {
+        Self {
+            cluster_info: validator.cluster_info.clone(),
+            bank_forks: validator.bank_forks.clone(),
+            known_validators: validator.known_validators.clone(),
+            repair_validators: validator.repair_validators.clone(),
+            send_transaction_service_config: validator.send_transaction_service_config.clone(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AdminRpcRequestMetadata {
     pub rpc_addr: Option<SocketAddr>,
@@ -31,11 +64,26 @@ pub struct AdminRpcRequestMetadata {
     pub start_progress: Arc<RwLock<ValidatorStartProgress>>,
     pub validator_exit: Arc<RwLock<Exit>>,
     pub authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
-    pub cluster_info: Arc<RwLock<Option<Arc<ClusterInfo>>>>,
     pub tower_storage: Arc<dyn TowerStorage>,
+    pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
 }
 impl Metadata for AdminRpcRequestMetadata {}
 
+impl AdminRpcRequestMetadata {
+    fn with_post_init<F, R>(&self, func: F) -> Result<R>
+    where
+        F: FnOnce(&AdminRpcRequestMetadataPostInit) -> Result<R>,
+    {
+        if let Some(post_init) = self.post_init.read().unwrap().as_ref() {
+            func(post_init)
+        } else {
+            Err(jsonrpc_core::error::Error::invalid_params(
+                "Retry once validator start up is complete",
+            ))
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AdminRpcContactInfo {
     pub id: String,
@@ -106,6 +154,144 @@ impl Display for AdminRpcContactInfo {
     }
 }
 
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcSendTransactionServiceConfig {
+    pub retry_rate_ms: Option<u64>,
+    pub leader_forward_count: Option<u64>,
+    pub default_max_retries: Option<usize>,
+    pub service_max_retries: Option<usize>,
+}
+
+impl From<&send_transaction_service::Config> for AdminRpcSendTransactionServiceConfig {
+    fn from(config: &send_transaction_service::Config) -> Self {
+        Self {
+            retry_rate_ms: Some(config.retry_rate_ms),
+            leader_forward_count: Some(config.leader_forward_count),
+            default_max_retries: config.default_max_retries,
+            service_max_retries: Some(config.service_max_retries),
+        }
+    }
+}
+
+impl AdminRpcSendTransactionServiceConfig {
+    /// Overwrite the fields of `config` that are set in `self`, leaving the others unchanged
+    fn apply(&self, config: &mut send_transaction_service::Config) {
+        if let Some(retry_rate_ms) = self.retry_rate_ms {
+            config.retry_rate_ms = retry_rate_ms;
+        }
+        if let Some(leader_forward_count) = self.leader_forward_count {
+            config.leader_forward_count = leader_forward_count;
+        }
+        if let Some(default_max_retries) = self.default_max_retries {
+            config.default_max_retries = Some(default_max_retries);
+        }
+        if let Some(service_max_retries) = self.service_max_retries {
+            config.service_max_retries = service_max_retries;
+        }
+    }
+}
+
+impl Display for AdminRpcSendTransactionServiceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn display_option<T: Display>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| "unset".to_string())
+        }
+        writeln!(f, "Retry Rate: {} ms", display_option(&self.retry_rate_ms))?;
+        writeln!(
+            f,
+            "Leader Forward Count: {}",
+            display_option(&self.leader_forward_count)
+        )?;
+        writeln!(
+            f,
+            "Default Max Retries: {}",
+            display_option(&self.default_max_retries)
+        )?;
+        writeln!(
+            f,
+            "Service Max Retries: {}",
+            display_option(&self.service_max_retries)
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcLockout {
+    pub slot: Slot,
+    pub confirmation_count: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRpcTowerState {
+    pub node_pubkey: String,
+    pub root: Slot,
+    pub last_voted_slot: Option<Slot>,
+    pub last_vote_hash: Option<String>,
+    pub last_timestamp_slot: Slot,
+    pub last_timestamp: UnixTimestamp,
+    pub stray_restored_slot: Option<Slot>,
+    pub lockouts: Vec<AdminRpcLockout>,
+}
+
+impl From<&Tower> for AdminRpcTowerState {
+    fn from(tower: &Tower) -> Self {
+        let last_timestamp = tower.last_timestamp();
+        Self {
+            node_pubkey: tower.node_pubkey.to_string(),
+            root: tower.root(),
+            last_voted_slot: tower.last_voted_slot(),
+            last_vote_hash: tower
+                .last_voted_slot_hash()
+                .map(|(_slot, hash)| hash.to_string()),
+            last_timestamp_slot: last_timestamp.slot,
+            last_timestamp: last_timestamp.timestamp,
+            stray_restored_slot: tower.stray_restored_slot(),
+            lockouts: tower
+                .lockouts()
+                .into_iter()
+                .map(|lockout| AdminRpcLockout {
+                    slot: lockout.slot,
+                    confirmation_count: lockout.confirmation_count,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Display for AdminRpcTowerState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Identity: {}", self.node_pubkey)?;
+        writeln!(f, "Root: {}", self.root)?;
+        match (self.last_voted_slot, &self.last_vote_hash) {
+            (Some(slot), Some(hash)) => writeln!(f, "Last Vote: {} ({})", slot, hash)?,
+            _ => writeln!(f, "Last Vote: none")?,
+        }
+        writeln!(
+            f,
+            "Last Timestamp: {} (slot {})",
+            self.last_timestamp, self.last_timestamp_slot
+        )?;
+        if let Some(stray_restored_slot) = self.stray_restored_slot {
+            writeln!(f, "Stray Restored Slot: {}", stray_restored_slot)?;
+        }
+        writeln!(f, "Lockouts:")?;
+        for lockout in self.lockouts.iter().rev() {
+            writeln!(
+                f,
+                "  {:>12}  confirmation count: {}",
+                lockout.slot, lockout.confirmation_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -136,6 +322,48 @@ pub trait AdminRpc {
 
     #[rpc(meta, name = "contactInfo")]
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo>;
+
+    #[rpc(meta, name = "requestSnapshot")]
+    fn request_snapshot(&self, meta: Self::Metadata, incremental: bool) -> Result<()>;
+
+    #[rpc(meta, name = "repairValidators")]
+    fn repair_validators(&self, meta: Self::Metadata) -> Result<Option<Vec<String>>>;
+
+    #[rpc(meta, name = "setRepairValidators")]
+    fn set_repair_validators(
+        &self,
+        meta: Self::Metadata,
+        validators: Option<Vec<String>>,
+    ) -> Result<()>;
+
+    #[rpc(meta, name = "knownValidators")]
+    fn known_validators(&self, meta: Self::Metadata) -> Result<Option<Vec<String>>>;
+
+    #[rpc(meta, name = "setKnownValidators")]
+    fn set_known_validators(
+        &self,
+        meta: Self::Metadata,
+        validators: Option<Vec<String>>,
+    ) -> Result<()>;
+
+    #[rpc(meta, name = "sendTransactionServiceConfig")]
+    fn send_transaction_service_config(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<AdminRpcSendTransactionServiceConfig>;
+
+    #[rpc(meta, name = "setSendTransactionServiceConfig")]
+    fn set_send_transaction_service_config(
+        &self,
+        meta: Self::Metadata,
+        config: AdminRpcSendTransactionServiceConfig,
+    ) -> Result<AdminRpcSendTransactionServiceConfig>;
+
+    #[rpc(meta, name = "towerState")]
+    fn tower_state(&self, meta: Self::Metadata) -> Result<AdminRpcTowerState>;
+
+    #[rpc(meta, name = "accountsIndexStats")]
+    fn accounts_index_stats(&self, meta: Self::Metadata) -> Result<AccountsIndexOccupancy>;
 }
 
 pub struct AdminRpcImpl;
@@ -231,27 +459,183 @@ impl AdminRpc for AdminRpcImpl {
             ))
         })?;
 
-        if let Some(cluster_info) = meta.cluster_info.read().unwrap().as_ref() {
+        meta.with_post_init(|post_init| {
             paychains_metrics::set_host_id(identity_keypair.pubkey().to_string());
-            cluster_info.set_keypair(Arc::new(identity_keypair));
-            warn!("Identity set to {}", cluster_info.id());
+            post_init
+                .cluster_info
+                .set_keypair(Arc::new(identity_keypair));
+            warn!("Identity set to {}", post_init.cluster_info.id());
             Ok(())
-        } else {
-            Err(jsonrpc_core::error::Error::invalid_params(
-                "Retry once validator start up is complete",
-            ))
-        }
+        })
     }
 
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo> {
-        if let Some(cluster_info) = meta.cluster_info.read().unwrap().as_ref() {
-            Ok(cluster_info.my_contact_info().into())
+        meta.with_post_init(|post_init| Ok(post_init.cluster_info.my_contact_info().into()))
+    }
+
+    fn request_snapshot(&self, meta: Self::Metadata, incremental: bool) -> Result<()> {
+        debug!("request_snapshot request received");
+        let requested_kind = if incremental {
+            RequestedSnapshotKind::Incremental
         } else {
-            Err(jsonrpc_core::error::Error::invalid_params(
-                "Retry once validator start up is complete",
+            RequestedSnapshotKind::Full
+        };
+        meta.with_post_init(|post_init| {
+            if post_init
+                .bank_forks
+                .write()
+                .unwrap()
+                .request_snapshot(requested_kind)
+            {
+                warn!("{:?} snapshot requested", requested_kind);
+                Ok(())
+            } else {
+                Err(jsonrpc_core::error::Error::invalid_params(format!(
+                    "{:?} snapshots are disabled on this validator",
+                    requested_kind
+                )))
+            }
+        })
+    }
+
+    fn repair_validators(&self, meta: Self::Metadata) -> Result<Option<Vec<String>>> {
+        debug!("repair_validators request received");
+        meta.with_post_init(|post_init| {
+            Ok(validators_to_strings(
+                &post_init.repair_validators.read().unwrap(),
+            ))
+        })
+    }
+
+    fn set_repair_validators(
+        &self,
+        meta: Self::Metadata,
+        validators: Option<Vec<String>>,
+    ) -> Result<()> {
+        debug!("set_repair_validators request received");
+        meta.with_post_init(|post_init| {
+            let validators = parse_validators(&post_init.cluster_info.id(), validators)?;
+            warn!("Repair validators set to {:?}", validators);
+            *post_init.repair_validators.write().unwrap() = validators;
+            Ok(())
+        })
+    }
+
+    fn known_validators(&self, meta: Self::Metadata) -> Result<Option<Vec<String>>> {
+        debug!("known_validators request received");
+        meta.with_post_init(|post_init| {
+            Ok(validators_to_strings(
+                &post_init.known_validators.read().unwrap(),
             ))
+        })
+    }
+
+    fn set_known_validators(
+        &self,
+        meta: Self::Metadata,
+        validators: Option<Vec<String>>,
+    ) -> Result<()> {
+        debug!("set_known_validators request received");
+        meta.with_post_init(|post_init| {
+            let validators = parse_validators(&post_init.cluster_info.id(), validators)?;
+            warn!("Known validators set to {:?}", validators);
+            *post_init.known_validators.write().unwrap() = validators;
+            Ok(())
+        })
+    }
+
+    fn send_transaction_service_config(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<AdminRpcSendTransactionServiceConfig> {
+        debug!("send_transaction_service_config request received");
+        meta.with_post_init(|post_init| {
+            Ok((&*post_init.send_transaction_service_config.read().unwrap()).into())
+        })
+    }
+
+    fn set_send_transaction_service_config(
+        &self,
+        meta: Self::Metadata,
+        config: AdminRpcSendTransactionServiceConfig,
+    ) -> Result<AdminRpcSendTransactionServiceConfig> {
+        debug!("set_send_transaction_service_config request received");
+        if config.retry_rate_ms == Some(0) {
+            return Err(jsonrpc_core::error::Error::invalid_params(
+                "retryRateMs must be greater than zero",
+            ));
         }
+        meta.with_post_init(|post_init| {
+            let mut send_transaction_service_config =
+                post_init.send_transaction_service_config.write().unwrap();
+            config.apply(&mut send_transaction_service_config);
+            warn!(
+                "Send transaction service config set to {:?}",
+                *send_transaction_service_config
+            );
+            Ok((&*send_transaction_service_config).into())
+        })
     }
+
+    fn tower_state(&self, meta: Self::Metadata) -> Result<AdminRpcTowerState> {
+        debug!("tower_state request received");
+        meta.with_post_init(|post_init| {
+            let node_pubkey = post_init.cluster_info.id();
+            // ReplayStage saves the tower after every vote, so the stored tower is current
+            let tower =
+                Tower::restore(meta.tower_storage.as_ref(), &node_pubkey).map_err(|err| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "Unable to load tower for {}: {}",
+                        node_pubkey, err
+                    ))
+                })?;
+            Ok((&tower).into())
+        })
+    }
+
+    fn accounts_index_stats(&self, meta: Self::Metadata) -> Result<AccountsIndexOccupancy> {
+        debug!("accounts_index_stats request received");
+        meta.with_post_init(|post_init| {
+            let root_bank = post_init.bank_forks.read().unwrap().root_bank();
+            Ok(root_bank.accounts().accounts_db.accounts_index.occupancy())
+        })
+    }
+}
+
+fn validators_to_strings(validators: &Option<HashSet<Pubkey>>) -> Option<Vec<String>> {
+    validators.as_ref().map(|validators| {
+        let mut validators: Vec<_> = validators.iter().map(|pubkey| pubkey.to_string()).collect();
+        validators.sort();
+        validators
+    })
+}
+
+fn parse_validators(
+    identity_pubkey: &Pubkey,
+    validators: Option<Vec<String>>,
+) -> Result<Option<HashSet<Pubkey>>> {
+    validators
+        .map(|validators| {
+            validators
+                .iter()
+                .map(|validator| {
+                    let pubkey = Pubkey::from_str(validator).map_err(|err| {
+                        jsonrpc_core::error::Error::invalid_params(format!(
+                            "Invalid validator identity {}: {}",
+                            validator, err
+                        ))
+                    })?;
+                    if pubkey == *identity_pubkey {
+                        return Err(jsonrpc_core::error::Error::invalid_params(format!(
+                            "The validator's identity pubkey cannot be in the set: {}",
+                            pubkey
+                        )));
+                    }
+                    Ok(pubkey)
+                })
+                .collect()
+        })
+        .transpose()
 }
 
 // Start the Admin RPC interface
@@ -333,3 +717,180 @@ pub async fn connect(ledger_path: &Path) -> std::result::Result<gen_client::Clie
 pub fn runtime() -> jsonrpc_server_utils::tokio::runtime::Runtime {
     jsonrpc_server_utils::tokio::runtime::Runtime::new().expect("new tokio runtime")
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        paychains_core::tower_storage::NullTowerStorage,
+        paychains_runtime::{
+            bank::Bank, genesis_utils::create_genesis_config, snapshot_config::SnapshotConfig,
+        },
+        paychains_streamer::socket::SocketAddrSpace,
+        serde_json::Value,
+    };
+
+    struct RpcHandler {
+        io: MetaIoHandler<AdminRpcRequestMetadata>,
+        meta: AdminRpcRequestMetadata,
+        bank_forks: Arc<RwLock<BankForks>>,
+        identity: Pubkey,
+    }
+
+    impl RpcHandler {
+        fn start() -> Self {
+            let keypair = Arc::new(Keypair::new());
+            let identity = keypair.pubkey();
+            let cluster_info = Arc::new(ClusterInfo::new(
+                ContactInfo::new_localhost(&identity, 0),
+                keypair,
+                SocketAddrSpace::Unspecified,
+            ));
+            let genesis_config = create_genesis_config(10_000).genesis_config;
+            let bank_forks = Arc::new(RwLock::new(BankForks::new(Bank::new_for_tests(
+                &genesis_config,
+            ))));
+            let meta = AdminRpcRequestMetadata {
+                rpc_addr: None,
+                start_time: SystemTime::now(),
+                start_progress: Arc::new(RwLock::new(ValidatorStartProgress::default())),
+                validator_exit: Arc::new(RwLock::new(Exit::default())),
+                authorized_voter_keypairs: Arc::new(RwLock::new(vec![])),
+                tower_storage: Arc::new(NullTowerStorage::default()),
+                post_init: Arc::new(RwLock::new(Some(AdminRpcRequestMetadataPostInit {
+                    cluster_info,
+                    bank_forks: bank_forks.clone(),
+                    known_validators: Arc::new(RwLock::new(None)),
+                    repair_validators: Arc::new(RwLock::new(None)),
+                    send_transaction_service_config: Arc::new(RwLock::new(
+                        send_transaction_service::Config::default(),
+                    )),
+                }))),
+            };
+            let mut io = MetaIoHandler::default();
+            io.extend_with(AdminRpcImpl.to_delegate());
+            Self {
+                io,
+                meta,
+                bank_forks,
+                identity,
+            }
+        }
+
+        fn request(&self, method: &str, params: Value) -> Value {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            });
+            let response = self
+                .io
+                .handle_request_sync(&request.to_string(), self.meta.clone())
+                .expect("response");
+            serde_json::from_str(&response).expect("json")
+        }
+    }
+
+    #[test]
+    fn test_request_snapshot() {
+        let rpc = RpcHandler::start();
+
+        // No snapshot config
+        let response = rpc.request("requestSnapshot", serde_json::json!([false]));
+        assert!(response["error"].is_object());
+        assert_eq!(
+            rpc.bank_forks.read().unwrap().requested_snapshot_kind(),
+            None
+        );
+
+        // Snapshot intervals disabled
+        rpc.bank_forks
+            .write()
+            .unwrap()
+            .set_snapshot_config(Some(SnapshotConfig {
+                full_snapshot_archive_interval_slots: Slot::MAX,
+                incremental_snapshot_archive_interval_slots: Slot::MAX,
+                ..SnapshotConfig::default()
+            }));
+        let response = rpc.request("requestSnapshot", serde_json::json!([false]));
+        assert!(response["error"].is_object());
+        let response = rpc.request("requestSnapshot", serde_json::json!([true]));
+        assert!(response["error"].is_object());
+        assert_eq!(
+            rpc.bank_forks.read().unwrap().requested_snapshot_kind(),
+            None
+        );
+
+        // Snapshots enabled
+        rpc.bank_forks
+            .write()
+            .unwrap()
+            .set_snapshot_config(Some(SnapshotConfig::default()));
+        let response = rpc.request("requestSnapshot", serde_json::json!([true]));
+        assert_eq!(response["result"], Value::Null);
+        assert!(response["error"].is_null());
+        assert_eq!(
+            rpc.bank_forks.read().unwrap().requested_snapshot_kind(),
+            Some(RequestedSnapshotKind::Incremental)
+        );
+    }
+
+    #[test]
+    fn test_set_known_and_repair_validators() {
+        let rpc = RpcHandler::start();
+        let validator = Pubkey::new_unique().to_string();
+
+        for (get_method, set_method) in [
+            ("knownValidators", "setKnownValidators"),
+            ("repairValidators", "setRepairValidators"),
+        ] {
+            let response = rpc.request(get_method, serde_json::json!([]));
+            assert_eq!(response["result"], Value::Null);
+
+            let response = rpc.request(set_method, serde_json::json!([[validator]]));
+            assert!(response["error"].is_null());
+            let response = rpc.request(get_method, serde_json::json!([]));
+            assert_eq!(response["result"], serde_json::json!([validator]));
+
+            // The validator's own identity and malformed pubkeys are rejected
+            let identity = rpc.identity.to_string();
+            let response = rpc.request(set_method, serde_json::json!([[identity]]));
+            assert!(response["error"].is_object());
+            let response = rpc.request(set_method, serde_json::json!([["not a pubkey"]]));
+            assert!(response["error"].is_object());
+            let response = rpc.request(get_method, serde_json::json!([]));
+            assert_eq!(response["result"], serde_json::json!([validator]));
+
+            let response = rpc.request(set_method, serde_json::json!([null]));
+            assert!(response["error"].is_null());
+            let response = rpc.request(get_method, serde_json::json!([]));
+            assert_eq!(response["result"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn test_set_send_transaction_service_config() {
+        let rpc = RpcHandler::start();
+        let default_config = send_transaction_service::Config::default();
+
+        let response = rpc.request(
+            "setSendTransactionServiceConfig",
+            serde_json::json!([{ "retryRateMs": 0 }]),
+        );
+        assert!(response["error"].is_object());
+
+        let response = rpc.request(
+            "setSendTransactionServiceConfig",
+            serde_json::json!([{ "leaderForwardCount": 5 }]),
+        );
+        assert_eq!(response["result"]["leaderForwardCount"], 5);
+        assert_eq!(
+            response["result"]["retryRateMs"],
+            default_config.retry_rate_ms
+        );
+
+        let response = rpc.request("sendTransactionServiceConfig", serde_json::json!([]));
+        assert_eq!(response["result"]["leaderForwardCount"], 5);
+    }
+}
@@ -5,7 +5,7 @@ use {
     paychains_clap_utils::{
         input_parsers::{pubkey_of, pubkeys_of, value_of},
         input_validators::{
-            is_pubkey, is_pubkey_or_keypair, is_slot, is_url_or_moniker,
+            is_parsable, is_pubkey, is_pubkey_or_keypair, is_slot, is_url_or_moniker,
             normalize_to_url_if_moniker,
         },
     },
@@ -181,6 +181,27 @@ fn main() {
                         If the ledger already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("load_state")
+                .long("load-state")
+                .value_name("FILENAME.JSON")
+                .takes_value(true)
+                .help(
+                    "Load all accounts from a state file written by --dump-state. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("dump_state")
+                .long("dump-state")
+                .value_name("FILENAME.JSON")
+                .takes_value(true)
+                .help(
+                    "Write all non-builtin accounts at the current root to this file when \
+                     the validator is shut down with `paychains-validator exit`. \
+                     The file can be loaded into a new ledger with --load-state",
+                ),
+        )
         .arg(
             Arg::with_name("no_bpf_jit")
                 .long("no-bpf-jit")
@@ -262,6 +283,20 @@ fn main() {
                      If the ledger already exists then this parameter is silently ignored",
                 ),
         )
+        .arg(
+            Arg::with_name("clone_upgradeable_program")
+                .long("clone-upgradeable-program")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .validator(is_pubkey_or_keypair)
+                .multiple(true)
+                .requires("json_rpc_url")
+                .help(
+                    "Copy an upgradeable program and its executable data from the cluster \
+                     referenced by the --url argument into the genesis configuration. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
         .arg(
             Arg::with_name("warp_slot")
                 .required(false)
@@ -311,6 +346,37 @@ fn main() {
                 .long("no-accounts-db-caching")
                 .help("Disables accounts caching"),
         )
+        .arg(
+            Arg::with_name("deactivate_feature")
+                .long("deactivate-feature")
+                .takes_value(true)
+                .value_name("FEATURE_PUBKEY")
+                .validator(is_pubkey)
+                .multiple(true)
+                .help(
+                    "Deactivate this feature in genesis. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("ticks_per_slot")
+                .long("ticks-per-slot")
+                .value_name("TICKS")
+                .validator(is_parsable::<u64>)
+                .takes_value(true)
+                .help(
+                    "The number of ticks in a slot. \
+                     If the ledger already exists then this parameter is silently ignored",
+                ),
+        )
+        .arg(
+            Arg::with_name("compute_unit_limit")
+                .long("compute-unit-limit")
+                .value_name("COMPUTE_UNITS")
+                .validator(is_parsable::<u64>)
+                .takes_value(true)
+                .help("Override the runtime's compute unit limit per transaction"),
+        )
         .get_matches();
 
     let output = if matches.is_present("quiet") {
@@ -479,6 +545,13 @@ fn main() {
         .map(|v| v.into_iter().collect())
         .unwrap_or_default();
 
+    let upgradeable_programs_to_clone: HashSet<_> =
+        pubkeys_of(&matches, "clone_upgradeable_program")
+            .map(|v| v.into_iter().collect())
+            .unwrap_or_default();
+
+    let features_to_deactivate = pubkeys_of(&matches, "deactivate_feature").unwrap_or_default();
+
     let warp_slot = if matches.is_present("warp_slot") {
         Some(match matches.value_of("warp_slot") {
             Some(_) => value_t_or_exit!(matches, "warp_slot", Slot),
@@ -537,10 +610,14 @@ fn main() {
         for (name, long) in &[
             ("bpf_program", "--bpf-program"),
             ("clone_account", "--clone"),
+            ("clone_upgradeable_program", "--clone-upgradeable-program"),
             ("account", "--account"),
+            ("load_state", "--load-state"),
             ("mint_address", "--mint"),
             ("slots_per_epoch", "--slots-per-epoch"),
             ("faucet_pay", "--faucet-pay"),
+            ("deactivate_feature", "--deactivate-feature"),
+            ("ticks_per_slot", "--ticks-per-slot"),
         ] {
             if matches.is_present(name) {
                 println!("{} argument ignored, ledger already exists", long);
@@ -560,7 +637,7 @@ fn main() {
 
     let tower_storage = Arc::new(FileTowerStorage::new(ledger_path.clone()));
 
-    let admin_service_cluster_info = Arc::new(RwLock::new(None));
+    let admin_service_post_init = Arc::new(RwLock::new(None));
     admin_rpc_service::run(
         &ledger_path,
         admin_rpc_service::AdminRpcRequestMetadata {
@@ -572,8 +649,8 @@ fn main() {
             start_time: std::time::SystemTime::now(),
             validator_exit: genesis.validator_exit.clone(),
             authorized_voter_keypairs: genesis.authorized_voter_keypairs.clone(),
-            cluster_info: admin_service_cluster_info.clone(),
             tower_storage: tower_storage.clone(),
+            post_init: admin_service_post_init.clone(),
         },
     );
     let dashboard = if output == Output::Dashboard {
@@ -605,7 +682,12 @@ fn main() {
         .bpf_jit(!matches.is_present("no_bpf_jit"))
         .rpc_port(rpc_port)
         .add_programs_with_path(&programs_to_load)
-        .add_accounts_from_json_files(&accounts_to_load);
+        .add_accounts_from_json_files(&accounts_to_load)
+        .deactivate_features(&features_to_deactivate);
+
+    if let Some(load_state) = matches.value_of("load_state") {
+        genesis.add_accounts_from_state_file(Path::new(load_state));
+    }
 
     if !accounts_to_clone.is_empty() {
         genesis.clone_accounts(
@@ -616,6 +698,23 @@ fn main() {
         );
     }
 
+    if !upgradeable_programs_to_clone.is_empty() {
+        genesis.clone_upgradeable_programs(
+            upgradeable_programs_to_clone,
+            cluster_rpc_client
+                .as_ref()
+                .expect("bug: --url argument missing?"),
+        );
+    }
+
+    if let Ok(ticks_per_slot) = value_t!(matches, "ticks_per_slot", u64) {
+        genesis.ticks_per_slot(ticks_per_slot);
+    }
+
+    if let Ok(compute_unit_limit) = value_t!(matches, "compute_unit_limit", u64) {
+        genesis.compute_unit_limit(compute_unit_limit);
+    }
+
     if let Some(warp_slot) = warp_slot {
         genesis.warp_slot(warp_slot);
     }
@@ -657,7 +756,33 @@ fn main() {
 
     match genesis.start_with_mint_address(mint_address, socket_addr_space) {
         Ok(test_validator) => {
-            *admin_service_cluster_info.write().unwrap() = Some(test_validator.cluster_info());
+            *admin_service_post_init.write().unwrap() = test_validator
+                .validator()
+                .map(admin_rpc_service::AdminRpcRequestMetadataPostInit::from);
+            if let (Some(dump_state_path), Some(validator)) =
+                (matches.value_of("dump_state"), test_validator.validator())
+            {
+                let dump_state_path = PathBuf::from(dump_state_path);
+                let bank_forks = validator.bank_forks.clone();
+                let validator_identity = validator.cluster_info.id();
+                let vote_account_address = test_validator.vote_account_address();
+                let stake_account_address = test_validator.stake_account_address();
+                genesis
+                    .validator_exit
+                    .write()
+                    .unwrap()
+                    .register_exit(Box::new(move || {
+                        if let Err(err) = dump_state(
+                            &bank_forks,
+                            &validator_identity,
+                            &vote_account_address,
+                            stake_account_address.as_ref(),
+                            &dump_state_path,
+                        ) {
+                            eprintln!("Error: failed to dump state: {}", err);
+                        }
+                    }));
+            }
             if let Some(dashboard) = dashboard {
                 dashboard.run(Duration::from_millis(250));
             }
@@ -49,6 +49,9 @@ pub struct RpcBootstrapConfig {
     pub max_genesis_archive_unpacked_size: u64,
     pub no_check_vote_account: bool,
     pub incremental_snapshot_fetch: bool,
+    /// Abort a snapshot download whenever its throughput drops below the minimal download
+    /// speed, not only at its start, and try the next known validator instead of a slow one
+    pub known_validator_snapshot_failover: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -203,6 +206,34 @@ fn is_known_validator(id: &Pubkey, known_validators: &Option<HashSet<Pubkey>>) -
     }
 }
 
+/// Whether a snapshot download progressing as `download_progress` describes is slow enough to
+/// abort it and try a different node.  Without failover, only downloads that are slow from
+/// their start are
+fn is_snapshot_download_too_slow(
+    download_progress: &DownloadProgressRecord,
+    minimal_snapshot_download_speed: f32,
+    known_validator_snapshot_failover: bool,
+) -> bool {
+    download_progress.last_throughput < minimal_snapshot_download_speed
+        && (known_validator_snapshot_failover
+            || (download_progress.notification_count <= 1
+                && download_progress.percentage_done <= 2_f32))
+        && download_progress.estimated_remaining_time > 60_f32
+}
+
+/// Whether to exclude an RPC node that bootstrapping failed with as a future RPC candidate.
+/// Known validators never are, unless failover is enabled and a snapshot download from them
+/// was too slow
+fn should_blacklist_rpc_node(
+    id: &Pubkey,
+    known_validators: &Option<HashSet<Pubkey>>,
+    known_validator_snapshot_failover: bool,
+    slow_snapshot_download: bool,
+) -> bool {
+    !is_known_validator(id, known_validators)
+        || (known_validator_snapshot_failover && slow_snapshot_download)
+}
+
 fn start_gossip_node(
     identity_keypair: Arc<Keypair>,
     cluster_entrypoints: &[ContactInfo],
@@ -461,6 +492,7 @@ mod without_incremental_snapshots {
                 rpc_contact_info.id, rpc_contact_info.rpc
             );
             let rpc_client = RpcClient::new_socket(rpc_contact_info.rpc);
+            let download_abort_count_before = download_abort_count;
 
             let result = match rpc_client.get_version() {
             Ok(rpc_version) => {
@@ -564,10 +596,11 @@ mod without_incremental_snapshots {
                                 &mut Some(Box::new(|download_progress: &DownloadProgressRecord| {
                                     debug!("Download progress: {:?}", download_progress);
 
-                                    if download_progress.last_throughput <  minimal_snapshot_download_speed
-                                       && download_progress.notification_count <= 1
-                                       && download_progress.percentage_done <= 2_f32
-                                       && download_progress.estimated_remaining_time > 60_f32
+                                    if is_snapshot_download_too_slow(
+                                           download_progress,
+                                           minimal_snapshot_download_speed,
+                                           bootstrap_config.known_validator_snapshot_failover,
+                                       )
                                        && download_abort_count < maximum_snapshot_download_abort {
                                         if let Some(ref known_validators) = validator_config.known_validators {
                                             if known_validators.contains(&rpc_contact_info.id)
@@ -633,10 +666,16 @@ mod without_incremental_snapshots {
             }
             warn!("{}", result.unwrap_err());
 
-            if let Some(ref known_validators) = validator_config.known_validators {
-                if known_validators.contains(&rpc_contact_info.id) {
-                    continue; // Never blacklist a known node
-                }
+            // With failover, a known validator that was too slow to download a snapshot from is
+            // excluded until the blacklist times out, so that the next known validator is tried
+            let slow_snapshot_download = download_abort_count > download_abort_count_before;
+            if !should_blacklist_rpc_node(
+                &rpc_contact_info.id,
+                &validator_config.known_validators,
+                bootstrap_config.known_validator_snapshot_failover,
+                slow_snapshot_download,
+            ) {
+                continue;
             }
 
             info!(
@@ -877,6 +916,7 @@ mod with_incremental_snapshots {
                 rpc_contact_info.id, rpc_contact_info.rpc
             );
             let rpc_client = RpcClient::new_socket(rpc_contact_info.rpc);
+            let download_abort_count_before = download_abort_count;
 
             let result = match rpc_client.get_version() {
                 Ok(rpc_version) => {
@@ -973,10 +1013,16 @@ mod with_incremental_snapshots {
             }
             warn!("{}", result.unwrap_err());
 
-            if let Some(ref known_validators) = validator_config.known_validators {
-                if known_validators.contains(&rpc_contact_info.id) {
-                    continue; // Never blacklist a known node
-                }
+            // With failover, a known validator that was too slow to download a snapshot from is
+            // excluded until the blacklist times out, so that the next known validator is tried
+            let slow_snapshot_download = download_abort_count > download_abort_count_before;
+            if !should_blacklist_rpc_node(
+                &rpc_contact_info.id,
+                &validator_config.known_validators,
+                bootstrap_config.known_validator_snapshot_failover,
+                slow_snapshot_download,
+            ) {
+                continue;
             }
 
             info!(
@@ -1481,11 +1527,11 @@ mod with_incremental_snapshots {
             use_progress_bar,
             &mut Some(Box::new(|download_progress: &DownloadProgressRecord| {
                 debug!("Download progress: {:?}", download_progress);
-                if download_progress.last_throughput < minimal_snapshot_download_speed
-                    && download_progress.notification_count <= 1
-                    && download_progress.percentage_done <= 2_f32
-                    && download_progress.estimated_remaining_time > 60_f32
-                    && *download_abort_count < maximum_snapshot_download_abort
+                if is_snapshot_download_too_slow(
+                    download_progress,
+                    minimal_snapshot_download_speed,
+                    bootstrap_config.known_validator_snapshot_failover,
+                ) && *download_abort_count < maximum_snapshot_download_abort
                 {
                     if let Some(ref known_validators) = validator_config.known_validators {
                         if known_validators.contains(&rpc_contact_info.id)
@@ -1819,3 +1865,83 @@ mod with_incremental_snapshots {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn download_progress(
+        last_throughput: f32,
+        percentage_done: f32,
+        notification_count: u64,
+    ) -> DownloadProgressRecord {
+        DownloadProgressRecord {
+            elapsed_time: Duration::from_secs(notification_count * 5),
+            last_elapsed_time: Duration::from_secs(5),
+            last_throughput,
+            total_throughput: last_throughput,
+            total_bytes: 100_000_000_000,
+            current_bytes: (percentage_done * 1_000_000_000_f32) as usize,
+            percentage_done,
+            estimated_remaining_time: 1_000_f32,
+            notification_count,
+        }
+    }
+
+    #[test]
+    fn test_is_snapshot_download_too_slow() {
+        let too_slow = |download_progress: &DownloadProgressRecord, failover: bool| {
+            is_snapshot_download_too_slow(download_progress, 10_000_000_f32, failover)
+        };
+        // Slow from the start, with or without failover
+        let slow_start = download_progress(1_000_f32, 0.1, 1);
+        assert!(too_slow(&slow_start, false));
+        assert!(too_slow(&slow_start, true));
+        // Slow after a good start, only with failover
+        let slowed_down = download_progress(1_000_f32, 50_f32, 20);
+        assert!(!too_slow(&slowed_down, false));
+        assert!(too_slow(&slowed_down, true));
+        // Fast enough
+        let fast = download_progress(100_000_000_f32, 50_f32, 20);
+        assert!(!too_slow(&fast, true));
+        // Almost done
+        let mut almost_done = slowed_down;
+        almost_done.estimated_remaining_time = 10_f32;
+        assert!(!too_slow(&almost_done, true));
+    }
+
+    #[test]
+    fn test_should_blacklist_rpc_node() {
+        let known_validator = Pubkey::new_unique();
+        let other_node = Pubkey::new_unique();
+        let known_validators = Some([known_validator].iter().cloned().collect());
+
+        for failover in [false, true] {
+            for slow_snapshot_download in [false, true] {
+                assert!(should_blacklist_rpc_node(
+                    &other_node,
+                    &known_validators,
+                    failover,
+                    slow_snapshot_download
+                ));
+                assert!(should_blacklist_rpc_node(
+                    &other_node,
+                    &None,
+                    failover,
+                    slow_snapshot_download
+                ));
+                // A known validator is only skipped for being too slow with failover, so
+                // that the next known validator is tried
+                assert_eq!(
+                    should_blacklist_rpc_node(
+                        &known_validator,
+                        &known_validators,
+                        failover,
+                        slow_snapshot_download
+                    ),
+                    failover && slow_snapshot_download
+                );
+            }
+        }
+    }
+}
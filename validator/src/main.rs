@@ -366,6 +366,18 @@ fn hardforks_of(matches: &ArgMatches<'_>, name: &str) -> Option<Vec<Slot>> {
     }
 }
 
+fn print_validators_set(name: &str, validators: Option<Vec<String>>) {
+    match validators {
+        Some(validators) => {
+            println!("{}:", name);
+            for validator in validators {
+                println!("  {}", validator);
+            }
+        }
+        None => println!("{}: all", name),
+    }
+}
+
 fn validators_set(
     identity_pubkey: &Pubkey,
     matches: &ArgMatches<'_>,
@@ -936,6 +948,15 @@ pub fn main() {
                 .help("The maximum number of times to abort and retry when encountering a \
                       slow snapshot download."),
         )
+        .arg(
+            Arg::with_name("known_validator_snapshot_failover")
+                .long("known-validator-snapshot-failover")
+                .takes_value(false)
+                .help("Abort a snapshot download whenever its speed falls below \
+                      --minimal-snapshot-download-speed, not only at its start, and retry \
+                      against the next known validator instead of the slow one. Interrupted \
+                      downloads resume where they stopped when the same archive is available."),
+        )
         .arg(
             Arg::with_name("contact_debug_interval")
                 .long("contact-debug-interval")
@@ -1209,6 +1230,12 @@ pub fn main() {
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_enable_vote_subscription")
                 .long("rpc-pubsub-enable-vote-subscription")
@@ -1393,7 +1420,7 @@ pub fn main() {
             Arg::with_name("snapshot_archive_format")
                 .long("snapshot-archive-format")
                 .alias("snapshot-compression") // Legacy name used by PayChains v1.5.x and older
-                .possible_values(&["bz2", "gzip", "zstd", "tar", "none"])
+                .possible_values(&["bz2", "gzip", "zstd", "zstd-chunked", "tar", "none"])
                 .default_value("zstd")
                 .value_name("ARCHIVE_TYPE")
                 .takes_value(true)
@@ -1500,6 +1527,20 @@ pub fn main() {
                 .help("Enables faster starting of validators by skipping shrink. \
                       This option is for use during testing."),
         )
+        .arg(
+            Arg::with_name("accounts_db_compress_cold_storages")
+                .long("accounts-db-compress-cold-storages")
+                .help("Compress account data when shrinking the storages of slots more than \
+                       an epoch older than the highest root. Saves disk space at the cost of \
+                       slower loads of accounts that were not written recently."),
+        )
+        .arg(
+            Arg::with_name("accounts_db_pack_ancient_storages")
+                .long("accounts-db-pack-ancient-storages")
+                .help("Pack the accounts of slots more than an epoch older than the highest \
+                       root into a few large storages, reducing the number of account \
+                       storage files."),
+        )
         .arg(
             Arg::with_name("accounts_db_cache_limit_mb")
                 .long("accounts-db-cache-limit-mb")
@@ -1524,6 +1565,22 @@ pub fn main() {
                 .takes_value(true)
                 .help("How much memory the accounts index can consume. If this is exceeded, some account index entries will be stored on disk. If missing, the entire index is stored in memory."),
         )
+        .arg(
+            Arg::with_name("accounts_index_memory_limit_bytes")
+                .long("accounts-index-memory-limit-bytes")
+                .value_name("BYTES")
+                .validator(is_parsable::<usize>)
+                .takes_value(true)
+                .conflicts_with("accounts_index_memory_limit_mb")
+                .help("Same as --accounts-index-memory-limit-mb, in bytes"),
+        )
+        .arg(
+            Arg::with_name("accounts_index_persist")
+                .long("accounts-index-persist")
+                .help("Save the accounts index generated at startup to the ledger directory, \
+                       and load it instead of scanning the storages when restarting from the \
+                       same snapshot"),
+        )
         .arg(
             Arg::with_name("accounts_index_bins")
                 .long("accounts-index-bins")
@@ -1540,6 +1597,15 @@ pub fn main() {
                 .takes_value(true)
                 .help("Number of passes to calculate the hash of all accounts"),
         )
+        .arg(
+            Arg::with_name("accounts_hash_incremental")
+                .long("accounts-hash-incremental")
+                .help("Maintain the hash of all accounts from the accounts written in newly \
+                       rooted slots instead of rescanning all account storages each time it \
+                       is calculated. Uses about 72 bytes of memory per account, falling back \
+                       to full scans beyond 50 million accounts. Combine with \
+                       --accounts-db-test-hash-calculation to verify it against a full scan."),
+        )
         .arg(
             Arg::with_name("accounts_index_path")
                 .long("accounts-index-path")
@@ -1656,6 +1722,18 @@ pub fn main() {
                     .help("The maximum delinquent stake % permitted for an exit")
             )
         )
+        .subcommand(
+            SubCommand::with_name("accounts-index")
+            .about("Display the occupancy and hit rates of the validator's accounts index")
+            .arg(
+                Arg::with_name("output")
+                    .long("output")
+                    .takes_value(true)
+                    .value_name("MODE")
+                    .possible_values(&["json", "json-compact"])
+                    .help("Output display mode")
+            )
+        )
         .subcommand(
             SubCommand::with_name("authorized-voter")
             .about("Adjust the validator authorized voters")
@@ -1698,14 +1776,112 @@ pub fn main() {
             SubCommand::with_name("init")
             .about("Initialize the ledger directory then exit")
         )
+        .subcommand(
+            SubCommand::with_name("known-validators")
+            .about("Adjust the validator's known validators")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .setting(AppSettings::InferSubcommands)
+            .subcommand(
+                SubCommand::with_name("get")
+                .about("Display the known validators")
+            )
+            .subcommand(
+                SubCommand::with_name("set")
+                .about("Replace the known validators")
+                .arg(
+                    Arg::with_name("validators")
+                        .index(1)
+                        .value_name("VALIDATOR IDENTITY")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Identity of a known validator")
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("clear")
+                .about("Trust snapshot hashes from all validators")
+            )
+            .after_help("Note: the change only applies to the \
+                         currently running validator instance")
+        )
         .subcommand(
             SubCommand::with_name("monitor")
             .about("Monitor the validator")
         )
+        .subcommand(
+            SubCommand::with_name("repair-validators")
+            .about("Adjust the validators the validator requests repairs from")
+            .setting(AppSettings::SubcommandRequiredElseHelp)
+            .setting(AppSettings::InferSubcommands)
+            .subcommand(
+                SubCommand::with_name("get")
+                .about("Display the repair validators")
+            )
+            .subcommand(
+                SubCommand::with_name("set")
+                .about("Replace the repair validators")
+                .arg(
+                    Arg::with_name("validators")
+                        .index(1)
+                        .value_name("VALIDATOR IDENTITY")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Identity of a validator to request repairs from")
+                )
+            )
+            .subcommand(
+                SubCommand::with_name("clear")
+                .about("Request repairs from all validators")
+            )
+            .after_help("Note: the change only applies to the \
+                         currently running validator instance")
+        )
         .subcommand(
             SubCommand::with_name("run")
             .about("Run the validator")
         )
+        .subcommand(
+            SubCommand::with_name("send-transaction-service")
+            .about("Display or adjust the RPC send transaction service retry settings")
+            .arg(
+                Arg::with_name("retry_ms")
+                    .long("retry-ms")
+                    .value_name("MILLISECS")
+                    .takes_value(true)
+                    .validator(is_parsable::<u64>)
+                    .help("The rate at which transactions sent via rpc service are retried")
+            )
+            .arg(
+                Arg::with_name("leader_forward_count")
+                    .long("leader-forward-count")
+                    .value_name("NUMBER")
+                    .takes_value(true)
+                    .validator(is_parsable::<u64>)
+                    .help("The number of upcoming leaders to which to forward transactions sent via rpc service")
+            )
+            .arg(
+                Arg::with_name("default_max_retries")
+                    .long("default-max-retries")
+                    .value_name("NUMBER")
+                    .takes_value(true)
+                    .validator(is_parsable::<usize>)
+                    .help("The maximum number of transaction broadcast retries when unspecified by the request")
+            )
+            .arg(
+                Arg::with_name("service_max_retries")
+                    .long("service-max-retries")
+                    .value_name("NUMBER")
+                    .takes_value(true)
+                    .validator(is_parsable::<usize>)
+                    .help("The maximum number of transaction broadcast retries, regardless of requested value")
+            )
+            .after_help("Note: settings that are not specified are left unchanged, and changes \
+                         only apply to the currently running validator instance")
+        )
         .subcommand(
             SubCommand::with_name("set-identity")
             .about("Set the validator identity")
@@ -1731,6 +1907,28 @@ pub fn main() {
             )
             .after_help("Note: the new filter only applies to the currently running validator instance")
         )
+        .subcommand(
+            SubCommand::with_name("snapshot")
+            .about("Request a snapshot of the next root, regardless of the snapshot intervals")
+            .arg(
+                Arg::with_name("incremental")
+                    .long("incremental")
+                    .takes_value(false)
+                    .help("Take an incremental snapshot based on the latest full snapshot")
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("tower")
+            .about("Display the validator's current tower")
+            .arg(
+                Arg::with_name("output")
+                    .long("output")
+                    .takes_value(true)
+                    .value_name("MODE")
+                    .possible_values(&["json", "json-compact"])
+                    .help("Output display mode")
+            )
+        )
         .subcommand(
             SubCommand::with_name("wait-for-restart-window")
             .about("Monitor the validator for a good time to restart")
@@ -1770,6 +1968,26 @@ pub fn main() {
 
     let operation = match matches.subcommand() {
         ("", _) | ("run", _) => Operation::Run,
+        ("accounts-index", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let occupancy = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.accounts_index_stats().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Accounts index stats query failed: {}", err);
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!("{}", serde_json::to_string_pretty(&occupancy).unwrap()),
+                    "json-compact" => print!("{}", serde_json::to_string(&occupancy).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{}", occupancy);
+            }
+            return;
+        }
         ("authorized-voter", Some(authorized_voter_subcommand_matches)) => {
             match authorized_voter_subcommand_matches.subcommand() {
                 ("add", Some(subcommand_matches)) => {
@@ -1842,6 +2060,38 @@ pub fn main() {
             return;
         }
         ("init", _) => Operation::Initialize,
+        ("known-validators", Some(known_validators_subcommand_matches)) => {
+            let validators = match known_validators_subcommand_matches.subcommand() {
+                ("get", _) => {
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let known_validators = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.known_validators().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("knownValidators request failed: {}", err);
+                            exit(1);
+                        });
+                    print_validators_set("Known validators", known_validators);
+                    return;
+                }
+                ("set", Some(subcommand_matches)) => Some(
+                    values_t_or_exit!(subcommand_matches, "validators", Pubkey)
+                        .into_iter()
+                        .map(|pubkey| pubkey.to_string())
+                        .collect(),
+                ),
+                ("clear", _) => None,
+                _ => unreachable!(),
+            };
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.set_known_validators(validators).await })
+                .unwrap_or_else(|err| {
+                    eprintln!("setKnownValidators request failed: {}", err);
+                    exit(1);
+                });
+            println!("Known validators updated");
+            return;
+        }
         ("exit", Some(subcommand_matches)) => {
             let min_idle_time = value_t_or_exit!(subcommand_matches, "min_idle_time", usize);
             let force = subcommand_matches.is_present("force");
@@ -1875,6 +2125,70 @@ pub fn main() {
             monitor_validator(&ledger_path);
             return;
         }
+        ("repair-validators", Some(repair_validators_subcommand_matches)) => {
+            let validators = match repair_validators_subcommand_matches.subcommand() {
+                ("get", _) => {
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let repair_validators = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.repair_validators().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("repairValidators request failed: {}", err);
+                            exit(1);
+                        });
+                    print_validators_set("Repair validators", repair_validators);
+                    return;
+                }
+                ("set", Some(subcommand_matches)) => Some(
+                    values_t_or_exit!(subcommand_matches, "validators", Pubkey)
+                        .into_iter()
+                        .map(|pubkey| pubkey.to_string())
+                        .collect(),
+                ),
+                ("clear", _) => None,
+                _ => unreachable!(),
+            };
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(
+                    async move { admin_client.await?.set_repair_validators(validators).await },
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("setRepairValidators request failed: {}", err);
+                    exit(1);
+                });
+            println!("Repair validators updated");
+            return;
+        }
+        ("send-transaction-service", Some(subcommand_matches)) => {
+            let config = admin_rpc_service::AdminRpcSendTransactionServiceConfig {
+                retry_rate_ms: value_of(subcommand_matches, "retry_ms"),
+                leader_forward_count: value_of(subcommand_matches, "leader_forward_count"),
+                default_max_retries: value_of(subcommand_matches, "default_max_retries"),
+                service_max_retries: value_of(subcommand_matches, "service_max_retries"),
+            };
+            let update = config.retry_rate_ms.is_some()
+                || config.leader_forward_count.is_some()
+                || config.default_max_retries.is_some()
+                || config.service_max_retries.is_some();
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let config = admin_rpc_service::runtime()
+                .block_on(async move {
+                    let admin_client = admin_client.await?;
+                    if update {
+                        admin_client
+                            .set_send_transaction_service_config(config)
+                            .await
+                    } else {
+                        admin_client.send_transaction_service_config().await
+                    }
+                })
+                .unwrap_or_else(|err| {
+                    eprintln!("Send transaction service config request failed: {}", err);
+                    exit(1);
+                });
+            print!("{}", config);
+            return;
+        }
         ("set-identity", Some(subcommand_matches)) => {
             let identity_keypair = value_t_or_exit!(subcommand_matches, "identity", String);
 
@@ -1909,6 +2223,41 @@ pub fn main() {
                 });
             return;
         }
+        ("snapshot", Some(subcommand_matches)) => {
+            let incremental = subcommand_matches.is_present("incremental");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.request_snapshot(incremental).await })
+                .unwrap_or_else(|err| {
+                    eprintln!("requestSnapshot request failed: {}", err);
+                    exit(1);
+                });
+            println!(
+                "{} snapshot requested for the next root",
+                if incremental { "Incremental" } else { "Full" }
+            );
+            return;
+        }
+        ("tower", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let tower_state = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.tower_state().await })
+                .unwrap_or_else(|err| {
+                    eprintln!("Tower state query failed: {}", err);
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!("{}", serde_json::to_string_pretty(&tower_state).unwrap()),
+                    "json-compact" => print!("{}", serde_json::to_string(&tower_state).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{}", tower_state);
+            }
+            return;
+        }
         ("wait-for-restart-window", Some(subcommand_matches)) => {
             let min_idle_time = value_t_or_exit!(subcommand_matches, "min_idle_time", usize);
             let identity = pubkey_of(subcommand_matches, "identity");
@@ -1982,6 +2331,7 @@ pub fn main() {
             u64
         ),
         incremental_snapshot_fetch: matches.is_present("incremental_snapshots"),
+        known_validator_snapshot_failover: matches.is_present("known_validator_snapshot_failover"),
     };
 
     let private_rpc = matches.is_present("private_rpc");
@@ -2134,6 +2484,15 @@ pub fn main() {
         accounts_index_config.index_limit_mb = Some(limit);
     }
 
+    if let Some(limit) = value_t!(matches, "accounts_index_memory_limit_bytes", usize).ok() {
+        accounts_index_config.index_limit_bytes = Some(limit);
+    }
+
+    if matches.is_present("accounts_index_persist") {
+        accounts_index_config.persisted_index_path =
+            Some(ledger_path.join("accounts_index_persisted"));
+    }
+
     {
         let mut accounts_index_paths: Vec<PathBuf> = if matches.is_present("accounts_index_path") {
             values_t_or_exit!(matches, "accounts_index_path", String)
@@ -2163,6 +2522,9 @@ pub fn main() {
         write_cache_limit_bytes: value_t!(matches, "accounts_db_cache_limit_mb", u64)
             .ok()
             .map(|mb| mb * MB as u64),
+        incremental_accounts_hash: matches.is_present("accounts_hash_incremental"),
+        compress_cold_storages: matches.is_present("accounts_db_compress_cold_storages"),
+        pack_ancient_storages: matches.is_present("accounts_db_pack_ancient_storages"),
         ..AccountsDbConfig::default()
     };
 
@@ -2256,6 +2618,8 @@ pub fn main() {
         }),
         pubsub_config: PubSubConfig {
             enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
             max_active_subscriptions: value_t_or_exit!(
                 matches,
@@ -2421,6 +2785,7 @@ pub fn main() {
             "bz2" => ArchiveFormat::TarBzip2,
             "gzip" => ArchiveFormat::TarGzip,
             "zstd" => ArchiveFormat::TarZstd,
+            "zstd-chunked" => ArchiveFormat::TarZstdChunked,
             "tar" | "none" => ArchiveFormat::Tar,
             _ => panic!("Archive format not recognized: {}", archive_format_str),
         }
@@ -2522,7 +2887,7 @@ pub fn main() {
     let _ledger_write_guard = lock_ledger(&ledger_path, &mut ledger_lock);
 
     let start_progress = Arc::new(RwLock::new(ValidatorStartProgress::default()));
-    let admin_service_cluster_info = Arc::new(RwLock::new(None));
+    let admin_service_post_init = Arc::new(RwLock::new(None));
     admin_rpc_service::run(
         &ledger_path,
         admin_rpc_service::AdminRpcRequestMetadata {
@@ -2531,8 +2896,8 @@ pub fn main() {
             validator_exit: validator_config.validator_exit.clone(),
             start_progress: start_progress.clone(),
             authorized_voter_keypairs: authorized_voter_keypairs.clone(),
-            cluster_info: admin_service_cluster_info.clone(),
             tower_storage: validator_config.tower_storage.clone(),
+            post_init: admin_service_post_init.clone(),
         },
     );
 
@@ -2674,7 +3039,7 @@ pub fn main() {
         start_progress,
         socket_addr_space,
     );
-    *admin_service_cluster_info.write().unwrap() = Some(validator.cluster_info.clone());
+    *admin_service_post_init.write().unwrap() = Some((&validator).into());
 
     if let Some(filename) = init_complete_file {
         File::create(filename).unwrap_or_else(|_| {